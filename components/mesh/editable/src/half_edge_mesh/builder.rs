@@ -28,7 +28,7 @@ impl<M: HalfEdgeMeshData> BuildingVertex<M> {
 }
 
 /// use this handle to create unbound error early to debug
-fn uninit_handle<T>() -> Handle<T> {
+pub(crate) fn uninit_handle<T>() -> Handle<T> {
   Handle::from_raw_parts(usize::MAX, u64::MAX)
 }
 
@@ -45,6 +45,10 @@ pub enum NoneManifoldError {
   BowtieVertex,
   DanglingPoint,
   DanglingEdge,
+  /// the common neighbor of the edge's two end points is not only the opposite vertices
+  LinkConditionViolated,
+  /// two faces will have the same vertices
+  DuplicateFace,
 }
 use HalfEdgeBuildError::*;
 use NoneManifoldError::*;
@@ -248,3 +252,118 @@ impl<'a, M: HalfEdgeMeshData> HalfEdgeMeshBuilder<'a, M> {
     Ok((a.0, b.0, c.0).into())
   }
 }
+
+/// The result of [HalfEdgeMesh::from_polygons]. The handles are indexed by the input vertex index
+/// and polygon index. Unreferenced vertices and degenerated polygons are not inserted.
+pub struct HalfEdgeMeshBuildResult<M: HalfEdgeMeshData> {
+  pub mesh: HalfEdgeMesh<M>,
+  pub vertices: Vec<Option<Handle<HalfEdgeVertex<M>>>>,
+  pub faces: Vec<Option<Handle<HalfEdgeFace<M>>>>,
+}
+
+impl<M: HalfEdgeMeshData> HalfEdgeMesh<M> {
+  /// Build the mesh from an indexed triangle list in one pass, see [HalfEdgeMesh::from_polygons].
+  pub fn from_triangles(
+    vertices: impl IntoIterator<Item = M::Vertex>,
+    triangles: impl IntoIterator<Item = [usize; 3]>,
+  ) -> HalfEdgeMeshBuildResult<M> {
+    Self::from_polygons(vertices, triangles)
+  }
+
+  /// Build the mesh from indexed polygons in one pass.
+  ///
+  /// Unlike [HalfEdgeMeshBuilder], this not require the faces to be added in a manifold
+  /// preserving order, and never fails. The edges shared by more than two faces, or shared by two
+  /// faces in inconsistent winding are left unpaired, so the vertices around these edges may not
+  /// be correctly iterated. User could detect these vertices by comparing the face count.
+  pub fn from_polygons<P: AsRef<[usize]>>(
+    vertices: impl IntoIterator<Item = M::Vertex>,
+    polygons: impl IntoIterator<Item = P>,
+  ) -> HalfEdgeMeshBuildResult<M> {
+    let mut mesh = Self::new();
+    let mut vertex_handles: Vec<_> = vertices
+      .into_iter()
+      .map(|data| {
+        let vertex = HalfEdgeVertex {
+          data,
+          edge: uninit_handle(),
+        };
+        Some(mesh.vertices.insert(vertex))
+      })
+      .collect();
+
+    let mut directed_edges = std::collections::HashMap::new();
+    let faces = polygons
+      .into_iter()
+      .map(|polygon| {
+        let polygon = polygon.as_ref();
+        let has_repeated = polygon
+          .iter()
+          .enumerate()
+          .any(|(i, v)| polygon[i + 1..].contains(v));
+        if polygon.len() < 3 || has_repeated {
+          return None;
+        }
+        let polygon = polygon
+          .iter()
+          .map(|&v| vertex_handles.get(v).copied().flatten())
+          .collect::<Option<Vec<_>>>()?;
+
+        let face = mesh.faces.insert(HalfEdgeFace {
+          data: M::Face::default(),
+          edge: uninit_handle(),
+        });
+
+        let n = polygon.len();
+        let edges: Vec<_> = (0..n)
+          .map(|i| {
+            let (from, to) = (polygon[i], polygon[(i + 1) % n]);
+            let edge = mesh.half_edges.insert(HalfEdge {
+              data: M::HalfEdge::default(),
+              vert: from,
+              pair: None,
+              face,
+              next: uninit_handle(),
+              prev: uninit_handle(),
+            });
+            let vertex = mesh.vertices.get_mut(from).unwrap();
+            if vertex.edge == uninit_handle() {
+              vertex.edge = edge;
+            }
+            directed_edges.entry((from, to)).or_insert(edge);
+            edge
+          })
+          .collect();
+
+        for i in 0..n {
+          let edge = mesh.half_edges.get_mut(edges[i]).unwrap();
+          edge.next = edges[(i + 1) % n];
+          edge.prev = edges[(i + n - 1) % n];
+        }
+        mesh.faces.get_mut(face).unwrap().edge = edges[0];
+
+        Some(face)
+      })
+      .collect();
+
+    for (&(from, to), &edge) in directed_edges.iter() {
+      if let Some(&pair) = directed_edges.get(&(to, from)) {
+        mesh.half_edges.get_mut(edge).unwrap().pair = Some(pair);
+      }
+    }
+
+    for handle in vertex_handles.iter_mut() {
+      let h = handle.unwrap();
+      if mesh.vertices[h].edge == uninit_handle() {
+        mesh.vertices.remove(h);
+        *handle = None;
+      }
+    }
+
+    HalfEdgeMeshBuildResult {
+      mesh,
+      vertices: vertex_handles,
+      faces,
+    }
+  }
+}
@@ -0,0 +1,178 @@
+use arena::Handle;
+use HalfEdgeEditError::*;
+use NoneManifoldError::*;
+
+use crate::*;
+
+impl<M: HalfEdgeMeshData> HalfEdgeMesh<M> {
+  /// return the next and prev half edge if the half edge's face is a triangle
+  pub(crate) fn triangle_half_edges(
+    &self,
+    edge: Handle<HalfEdge<M>>,
+  ) -> Result<(Handle<HalfEdge<M>>, Handle<HalfEdge<M>>), HalfEdgeEditError> {
+    let e = self.half_edges.get(edge).ok_or(InvalidHandle)?;
+    if self.half_edges[e.next].next != e.prev {
+      return Err(NonTriangleFace);
+    }
+    Ok((e.next, e.prev))
+  }
+
+  /// Check if the edge could be collapsed without breaking the manifold property.
+  ///
+  /// The faces adjacent to the edge should be triangles, and the common neighbor vertices of
+  /// the two end points should be exactly the opposite vertices of the adjacent faces (the link
+  /// condition). An interior edge that connects two boundary vertices can not be collapsed because
+  /// it will pinch the mesh.
+  pub fn check_edge_collapse(&self, edge: Handle<HalfEdge<M>>) -> Result<(), HalfEdgeEditError> {
+    let e = self.half_edges.get(edge).ok_or(InvalidHandle)?;
+    let from = e.vert;
+    let to = e.end(self);
+
+    let mut opposites = Vec::with_capacity(2);
+    let mut removed_faces = Vec::with_capacity(2);
+    for side in std::iter::once(edge).chain(e.pair) {
+      let (next, prev) = self.triangle_half_edges(side)?;
+      if self.half_edges[next].pair.is_none() && self.half_edges[prev].pair.is_none() {
+        return Err(NonManifoldOperation(DanglingPoint));
+      }
+      opposites.push(self.half_edges[prev].vert);
+      removed_faces.push(self.half_edges[side].face);
+    }
+
+    if e.pair.is_some() {
+      if opposites[0] == opposites[1] {
+        return Err(NonManifoldOperation(LinkConditionViolated));
+      }
+      if self.vertices[from].is_boundary_vertex(self) && self.vertices[to].is_boundary_vertex(self)
+      {
+        return Err(NonManifoldOperation(BowtieVertex));
+      }
+    }
+
+    let neighbors = |v: Handle<HalfEdgeVertex<M>>| {
+      let mut n: Vec<_> = self.vertices[v].iter_neighbor_vertex(self).collect();
+      n.sort();
+      n.dedup();
+      n
+    };
+    let to_neighbors = neighbors(to);
+    let mut common: Vec<_> = neighbors(from)
+      .into_iter()
+      .filter(|v| to_neighbors.binary_search(v).is_ok())
+      .collect();
+    common.sort();
+    opposites.sort();
+    if common != opposites {
+      return Err(NonManifoldOperation(LinkConditionViolated));
+    }
+
+    // the faces that not removed should not become the same face after the collapse.
+    let face_keys = |v: Handle<HalfEdgeVertex<M>>| {
+      self.vertices[v]
+        .iter_face(self)
+        .filter(|(_, f)| !removed_faces.contains(f))
+        .map(|(face, _)| {
+          let mut key: Vec<_> = face
+            .iter_half_edge(self)
+            .map(|(e, _)| if e.vert == to { from } else { e.vert })
+            .collect();
+          key.sort();
+          key
+        })
+        .collect::<Vec<_>>()
+    };
+    let from_faces = face_keys(from);
+    if face_keys(to).iter().any(|key| from_faces.contains(key)) {
+      return Err(NonManifoldOperation(DuplicateFace));
+    }
+
+    Ok(())
+  }
+
+  /// Collapse the edge into one of its end points, the other end point will be removed, and the
+  /// adjacent triangle faces will be removed.
+  ///
+  /// The data of the kept vertex is not modified, user should update it(for example, the position)
+  /// by themselves.
+  pub fn collapse_edge(
+    &mut self,
+    edge: Handle<HalfEdge<M>>,
+    kept: Handle<HalfEdgeVertex<M>>,
+  ) -> Result<Handle<HalfEdgeVertex<M>>, HalfEdgeEditError> {
+    let e = self.half_edges.get(edge).ok_or(InvalidHandle)?;
+    let (from, to, pair) = (e.vert, e.end(self), e.pair);
+    let removed = if kept == from {
+      to
+    } else if kept == to {
+      from
+    } else {
+      return Err(InvalidHandle);
+    };
+
+    self.check_edge_collapse(edge)?;
+
+    let moving: Vec<_> = self.vertices[removed]
+      .iter_out_half_edge(self)
+      .map(|(_, h)| h)
+      .collect();
+    let kept_edges: Vec<_> = self.vertices[kept]
+      .iter_out_half_edge(self)
+      .map(|(_, h)| h)
+      .collect();
+
+    let mut removed_edges = Vec::with_capacity(6);
+    let mut removed_faces = Vec::with_capacity(2);
+    let mut opposite_vertex_edges = Vec::with_capacity(2);
+    for side in std::iter::once(edge).chain(pair) {
+      let e = &self.half_edges[side];
+      let (next, prev, face) = (e.next, e.prev, e.face);
+      let outer_next = self.half_edges[next].pair;
+      let outer_prev = self.half_edges[prev].pair;
+      if let Some(h) = outer_next {
+        self.half_edges.get_mut(h).unwrap().pair = outer_prev;
+      }
+      if let Some(h) = outer_prev {
+        self.half_edges.get_mut(h).unwrap().pair = outer_next;
+      }
+
+      // the opposite vertex lost its out half edge(prev), outer_next is start from it, or
+      // the face of outer_prev contains it.
+      let opposite = self.half_edges[prev].vert;
+      let replace = outer_next.unwrap_or_else(|| self.half_edges[outer_prev.unwrap()].next);
+      opposite_vertex_edges.push((opposite, replace));
+
+      removed_edges.extend([side, next, prev]);
+      removed_faces.push(face);
+    }
+
+    for &h in &moving {
+      if !removed_edges.contains(&h) {
+        self.half_edges.get_mut(h).unwrap().vert = kept;
+      }
+    }
+
+    let kept_edge = kept_edges
+      .iter()
+      .chain(moving.iter())
+      .find(|h| !removed_edges.contains(h))
+      .copied()
+      .unwrap();
+    self.vertices.get_mut(kept).unwrap().edge = kept_edge;
+    for (opposite, replace) in opposite_vertex_edges {
+      let v = self.vertices.get_mut(opposite).unwrap();
+      if removed_edges.contains(&v.edge) {
+        v.edge = replace;
+      }
+    }
+
+    for h in removed_edges {
+      self.half_edges.remove(h);
+    }
+    for f in removed_faces {
+      self.faces.remove(f);
+    }
+    self.vertices.remove(removed);
+
+    Ok(kept)
+  }
+}
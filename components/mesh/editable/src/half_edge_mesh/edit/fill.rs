@@ -0,0 +1,100 @@
+use arena::Handle;
+use HalfEdgeEditError::*;
+
+use crate::*;
+
+impl<M: HalfEdgeMeshData> HalfEdgeMesh<M> {
+  /// Return the boundary loop that contains the boundary half edge, in the same direction of the
+  /// half edge.
+  pub fn boundary_loop(
+    &self,
+    edge: Handle<HalfEdge<M>>,
+  ) -> Result<Vec<Handle<HalfEdge<M>>>, HalfEdgeEditError> {
+    if !self.half_edges.get(edge).ok_or(InvalidHandle)?.is_border() {
+      return Err(NotBoundaryEdge);
+    }
+
+    let mut result = vec![edge];
+    let mut current = edge;
+    loop {
+      // rotate around the end vertex to find the next boundary half edge
+      let mut next = self.half_edges[current].next;
+      while let Some(pair) = self.half_edges[next].pair {
+        next = self.half_edges[pair].next;
+      }
+      if next == edge {
+        break;
+      }
+      result.push(next);
+      current = next;
+    }
+    Ok(result)
+  }
+
+  /// Fill the hole that bounded by the boundary half edge with a single polygon face.
+  pub fn fill_hole(
+    &mut self,
+    edge: Handle<HalfEdge<M>>,
+  ) -> Result<Handle<HalfEdgeFace<M>>, HalfEdgeEditError> {
+    let boundary = self.boundary_loop(edge)?;
+    if boundary.len() < 3 {
+      return Err(DegeneratedFace);
+    }
+
+    let face = self.faces.insert(HalfEdgeFace {
+      data: M::Face::default(),
+      edge: uninit_handle(),
+    });
+
+    let inner: Vec<_> = boundary
+      .iter()
+      .map(|&h| {
+        let vert = self.half_edges[h].end(self);
+        self.half_edges.insert(HalfEdge {
+          data: M::HalfEdge::default(),
+          vert,
+          pair: Some(h),
+          face,
+          next: h,
+          prev: h,
+        })
+      })
+      .collect();
+
+    // the boundary loop is v0 -> v1 -> v2, the inner loop is v2 -> v1 -> v0
+    let n = inner.len();
+    for i in 0..n {
+      let e = self.half_edges.get_mut(inner[i]).unwrap();
+      e.next = inner[(i + n - 1) % n];
+      e.prev = inner[(i + 1) % n];
+      self.half_edges.get_mut(boundary[i]).unwrap().pair = Some(inner[i]);
+    }
+    self.faces.get_mut(face).unwrap().edge = inner[0];
+
+    Ok(face)
+  }
+
+  /// Triangulate the face by connecting the first vertex to all other vertices.
+  ///
+  /// This is a pure topology operation, the result may be not good for concave faces.
+  pub fn triangulate_face(
+    &mut self,
+    face: Handle<HalfEdgeFace<M>>,
+  ) -> Result<Vec<Handle<HalfEdgeFace<M>>>, HalfEdgeEditError> {
+    let vertices: Vec<_> = self
+      .faces
+      .get(face)
+      .ok_or(InvalidHandle)?
+      .iter_half_edge(self)
+      .map(|(e, _)| e.vert)
+      .collect();
+
+    let mut result = vec![face];
+    // split off the last triangle each time, the remaining face keeps the first vertex
+    for i in (2..vertices.len().saturating_sub(1)).rev() {
+      let created = self.split_face(face, vertices[i], vertices[0])?;
+      result.push(created);
+    }
+    Ok(result)
+  }
+}
@@ -0,0 +1,72 @@
+use arena::Handle;
+use HalfEdgeEditError::*;
+
+use crate::*;
+
+impl<M: HalfEdgeMeshData> HalfEdgeMesh<M> {
+  /// Rotate the edge shared by two triangles, so that it connects the two opposite vertices.
+  ///
+  /// ```text
+  ///      c                c
+  ///    /   \            / | \
+  ///   a --- b    =>    a  |  b
+  ///    \   /            \ | /
+  ///      d                d
+  /// ```
+  pub fn flip_edge(&mut self, edge: Handle<HalfEdge<M>>) -> Result<(), HalfEdgeEditError> {
+    let h = edge;
+    let t = self
+      .half_edges
+      .get(h)
+      .ok_or(InvalidHandle)?
+      .pair
+      .ok_or(BoundaryEdge)?;
+    let (hn, hp) = self.triangle_half_edges(h)?;
+    let (tn, tp) = self.triangle_half_edges(t)?;
+
+    let a = self.half_edges[h].vert;
+    let b = self.half_edges[t].vert;
+    let c = self.half_edges[hp].vert;
+    let d = self.half_edges[tp].vert;
+    if c == d {
+      return Err(DegeneratedFace);
+    }
+    if HalfEdge::get_by_two_points(self, c, d).is_some()
+      || HalfEdge::get_by_two_points(self, d, c).is_some()
+    {
+      return Err(AlreadyConnected);
+    }
+
+    let f0 = self.half_edges[h].face;
+    let f1 = self.half_edges[t].face;
+
+    // f0: c -> d -> b, f1: d -> c -> a
+    let mut link = |edge, vert, face, prev, next| {
+      let e = self.half_edges.get_mut(edge).unwrap();
+      e.vert = vert;
+      e.face = face;
+      e.prev = prev;
+      e.next = next;
+    };
+    link(h, c, f0, hn, tp);
+    link(tp, d, f0, h, hn);
+    link(hn, b, f0, tp, h);
+    link(t, d, f1, tn, hp);
+    link(hp, c, f1, t, tn);
+    link(tn, a, f1, hp, t);
+
+    self.faces.get_mut(f0).unwrap().edge = h;
+    self.faces.get_mut(f1).unwrap().edge = t;
+
+    let a = self.vertices.get_mut(a).unwrap();
+    if a.edge == h {
+      a.edge = tn;
+    }
+    let b = self.vertices.get_mut(b).unwrap();
+    if b.edge == t {
+      b.edge = hn;
+    }
+
+    Ok(())
+  }
+}
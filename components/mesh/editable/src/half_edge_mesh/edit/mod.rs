@@ -0,0 +1,30 @@
+mod collapse;
+mod fill;
+mod flip;
+mod remove;
+mod split;
+
+pub use collapse::*;
+pub use fill::*;
+pub use flip::*;
+pub use remove::*;
+pub use split::*;
+
+use crate::NoneManifoldError;
+
+#[derive(Debug, PartialEq)]
+pub enum HalfEdgeEditError {
+  /// the handle is not exist in mesh, or not matches the operation
+  InvalidHandle,
+  /// the operation only supports triangle faces
+  NonTriangleFace,
+  /// the operation requires an edge that has faces on both sides
+  BoundaryEdge,
+  /// the operation requires an edge that on the boundary
+  NotBoundaryEdge,
+  /// the two vertices are already connected by an edge
+  AlreadyConnected,
+  /// the operation will create a face that has less than three sides
+  DegeneratedFace,
+  NonManifoldOperation(NoneManifoldError),
+}
@@ -0,0 +1,122 @@
+use arena::Handle;
+use HalfEdgeEditError::*;
+use NoneManifoldError::*;
+
+use crate::*;
+
+impl<M: HalfEdgeMeshData> HalfEdgeMesh<M> {
+  /// Return the half edges start from the vertex in rotation order. If the vertex is on boundary,
+  /// the order starts from the boundary half edge.
+  pub fn ordered_out_half_edges(
+    &self,
+    vertex: Handle<HalfEdgeVertex<M>>,
+  ) -> Vec<Handle<HalfEdge<M>>> {
+    let v = &self.vertices[vertex];
+    let start = v
+      .iter_out_half_edge(self)
+      .find(|(e, _)| e.is_border())
+      .map(|(_, h)| h)
+      .unwrap_or(v.edge);
+
+    let mut result = vec![start];
+    let mut current = start;
+    while let Some(next) = self.half_edges[self.half_edges[current].prev].pair {
+      if next == start {
+        break;
+      }
+      result.push(next);
+      current = next;
+    }
+    result
+  }
+
+  pub fn remove_face(&mut self, face: Handle<HalfEdgeFace<M>>) -> Result<(), HalfEdgeEditError> {
+    self.remove_faces(&[face])
+  }
+
+  /// Remove the vertex and all faces around it, this leaves a hole in the mesh.
+  pub fn remove_vertex(
+    &mut self,
+    vertex: Handle<HalfEdgeVertex<M>>,
+  ) -> Result<(), HalfEdgeEditError> {
+    let faces: Vec<_> = self
+      .vertices
+      .get(vertex)
+      .ok_or(InvalidHandle)?
+      .iter_face(self)
+      .map(|(_, f)| f)
+      .collect();
+    self.remove_faces(&faces)?;
+    self.vertices.remove(vertex);
+    Ok(())
+  }
+
+  /// Remove the faces, the vertices that not connected to any face after the removal are also
+  /// removed.
+  ///
+  /// The removal is rejected if any vertex's remaining faces are not connected, which makes the
+  /// vertex a bowtie.
+  pub fn remove_faces(
+    &mut self,
+    faces: &[Handle<HalfEdgeFace<M>>],
+  ) -> Result<(), HalfEdgeEditError> {
+    let mut faces = faces.to_vec();
+    faces.sort();
+    faces.dedup();
+
+    let mut affected = Vec::new();
+    for &f in &faces {
+      let face = self.faces.get(f).ok_or(InvalidHandle)?;
+      affected.extend(face.iter_half_edge(self).map(|(e, _)| e.vert));
+    }
+    affected.sort();
+    affected.dedup();
+
+    let mut vertex_edges = Vec::with_capacity(affected.len());
+    for v in affected {
+      let fan = self.ordered_out_half_edges(v);
+      let closed = !self.half_edges[fan[0]].is_border();
+      let kept: Vec<_> = fan
+        .iter()
+        .map(|h| faces.binary_search(&self.half_edges[*h].face).is_err())
+        .collect();
+
+      let n = kept.len();
+      let mut kept_runs = (0..n)
+        .filter(|&i| kept[i] && (i == 0 || !kept[i - 1]))
+        .count();
+      if closed && n > 1 && kept[0] && kept[n - 1] {
+        kept_runs -= 1;
+      }
+      if kept_runs > 1 {
+        return Err(NonManifoldOperation(BowtieVertex));
+      }
+
+      let new_edge = fan.iter().zip(kept.iter()).find(|(_, k)| **k);
+      vertex_edges.push((v, new_edge.map(|(h, _)| *h)));
+    }
+
+    for f in faces {
+      let edges: Vec<_> = self.faces[f].iter_half_edge(self).map(|(_, h)| h).collect();
+      for h in edges {
+        if let Some(pair) = self.half_edges[h].pair {
+          if let Some(pair) = self.half_edges.get_mut(pair) {
+            pair.pair = None;
+          }
+        }
+        self.half_edges.remove(h);
+      }
+      self.faces.remove(f);
+    }
+
+    for (v, edge) in vertex_edges {
+      if let Some(edge) = edge {
+        self.vertices.get_mut(v).unwrap().edge = edge;
+      } else {
+        self.vertices.remove(v);
+      }
+    }
+
+    Ok(())
+  }
+}
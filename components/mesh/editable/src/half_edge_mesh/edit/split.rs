@@ -0,0 +1,145 @@
+use arena::Handle;
+use HalfEdgeEditError::*;
+
+use crate::*;
+
+impl<M: HalfEdgeMeshData> HalfEdgeMesh<M> {
+  /// Insert a new vertex in the middle of the edge. The adjacent triangle faces are split into two
+  /// triangles by connecting the new vertex and the opposite vertex, other faces just have one
+  /// more side.
+  pub fn split_edge(
+    &mut self,
+    edge: Handle<HalfEdge<M>>,
+    vertex: M::Vertex,
+  ) -> Result<Handle<HalfEdgeVertex<M>>, HalfEdgeEditError> {
+    let pair = self.half_edges.get(edge).ok_or(InvalidHandle)?.pair;
+    let triangles: Vec<_> = std::iter::once(edge)
+      .chain(pair)
+      .filter(|&h| self.triangle_half_edges(h).is_ok())
+      .map(|h| {
+        (
+          self.half_edges[h].face,
+          self.half_edges[self.half_edges[h].prev].vert,
+        )
+      })
+      .collect();
+
+    let middle = self.insert_vertex_on_edge(edge, vertex);
+
+    for (face, opposite) in triangles {
+      self.split_face(face, middle, opposite)?;
+    }
+
+    Ok(middle)
+  }
+
+  /// a -> b becomes a -> m -> b, and the pair b -> a becomes b -> m -> a
+  fn insert_vertex_on_edge(
+    &mut self,
+    edge: Handle<HalfEdge<M>>,
+    vertex: M::Vertex,
+  ) -> Handle<HalfEdgeVertex<M>> {
+    let middle = self.vertices.insert(HalfEdgeVertex { data: vertex, edge });
+
+    let mut insert_after = |mesh: &mut Self, h: Handle<HalfEdge<M>>| {
+      let e = &mesh.half_edges[h];
+      let (next, face) = (e.next, e.face);
+      let inserted = mesh.half_edges.insert(HalfEdge {
+        data: M::HalfEdge::default(),
+        vert: middle,
+        pair: None,
+        face,
+        next,
+        prev: h,
+      });
+      mesh.half_edges.get_mut(h).unwrap().next = inserted;
+      mesh.half_edges.get_mut(next).unwrap().prev = inserted;
+      inserted
+    };
+
+    let h2 = insert_after(self, edge);
+    self.vertices.get_mut(middle).unwrap().edge = h2;
+
+    if let Some(t) = self.half_edges[edge].pair {
+      let t2 = insert_after(self, t);
+      self.half_edges.get_mut(edge).unwrap().pair = Some(t2);
+      self.half_edges.get_mut(t2).unwrap().pair = Some(edge);
+      self.half_edges.get_mut(t).unwrap().pair = Some(h2);
+      self.half_edges.get_mut(h2).unwrap().pair = Some(t);
+    }
+
+    middle
+  }
+
+  /// Split the face into two faces by connecting two vertices on the face. The newly created
+  /// face(contains the `from` vertex's out half edge) is returned.
+  pub fn split_face(
+    &mut self,
+    face: Handle<HalfEdgeFace<M>>,
+    from: Handle<HalfEdgeVertex<M>>,
+    to: Handle<HalfEdgeVertex<M>>,
+  ) -> Result<Handle<HalfEdgeFace<M>>, HalfEdgeEditError> {
+    let f = self.faces.get(face).ok_or(InvalidHandle)?;
+    let find_out = |v| {
+      f.iter_half_edge(self)
+        .find(|(e, _)| e.vert == v)
+        .map(|(_, h)| h)
+    };
+    let out_from = find_out(from).ok_or(InvalidHandle)?;
+    let out_to = find_out(to).ok_or(InvalidHandle)?;
+
+    let in_from = self.half_edges[out_from].prev;
+    let in_to = self.half_edges[out_to].prev;
+    if from == to
+      || self.half_edges[out_from].next == out_to
+      || self.half_edges[out_to].next == out_from
+    {
+      return Err(DegeneratedFace);
+    }
+    if HalfEdge::get_by_two_points(self, from, to).is_some()
+      || HalfEdge::get_by_two_points(self, to, from).is_some()
+    {
+      return Err(AlreadyConnected);
+    }
+
+    let new_face = self.faces.insert(HalfEdgeFace {
+      data: M::Face::default(),
+      edge: out_from,
+    });
+
+    // the old face: from -> to -> ... -> from
+    let e1 = self.half_edges.insert(HalfEdge {
+      data: M::HalfEdge::default(),
+      vert: from,
+      pair: None,
+      face,
+      next: out_to,
+      prev: in_from,
+    });
+    // the new face: to -> from -> ... -> to
+    let e2 = self.half_edges.insert(HalfEdge {
+      data: M::HalfEdge::default(),
+      vert: to,
+      pair: Some(e1),
+      face: new_face,
+      next: out_from,
+      prev: in_to,
+    });
+    self.half_edges.get_mut(e1).unwrap().pair = Some(e2);
+
+    self.half_edges.get_mut(in_from).unwrap().next = e1;
+    self.half_edges.get_mut(out_to).unwrap().prev = e1;
+    self.half_edges.get_mut(in_to).unwrap().next = e2;
+    self.half_edges.get_mut(out_from).unwrap().prev = e2;
+
+    let mut current = out_from;
+    while current != e2 {
+      let e = self.half_edges.get_mut(current).unwrap();
+      e.face = new_face;
+      current = e.next;
+    }
+    self.faces.get_mut(face).unwrap().edge = e1;
+
+    Ok(new_face)
+  }
+}
@@ -38,10 +38,10 @@ impl<'a, M: HalfEdgeMeshData> Iterator for FaceToHalfEdgeIter<'a, M> {
 
   fn next(&mut self) -> Option<Self::Item> {
     if let Some(last) = self.last {
-      let edge = &self.mesh[last];
-      if edge.next != self.start {
-        self.last = Some(edge.next);
-        Some((edge, edge.next))
+      let next = self.mesh[last].next;
+      if next != self.start {
+        self.last = Some(next);
+        Some((&self.mesh[next], next))
       } else {
         None
       }
@@ -1,7 +1,10 @@
 pub mod builder;
 pub mod edge;
+pub mod edit;
 pub mod face;
-mod test;
+#[cfg(test)]
+pub(crate) mod test;
+pub mod validate;
 pub mod vertex;
 
 use std::ops::Index;
@@ -9,7 +12,9 @@ use std::ops::Index;
 use arena::*;
 pub use builder::*;
 pub use edge::*;
+pub use edit::*;
 pub use face::*;
+pub use validate::*;
 pub use vertex::*;
 
 pub trait HalfEdgeMeshData {
@@ -61,21 +66,21 @@ impl<M: HalfEdgeMeshData> HalfEdgeMesh<M> {
     self.faces.len()
   }
 
-  // pub fn iter_vertex(&mut self) -> impl Iterator<Item = &HalfEdgeVertex<M>> {
-  //   self.vertices.iter()
-  // }
-
-  // pub fn remove_face(&mut self, face: &mut HalfEdgeFace<M>) {
-  //   face.visit_around_edge_mut(|edge| unsafe { self.remove_edge(edge) })
-  // }
-  // pub unsafe fn remove_edge(&mut self, edge: &mut HalfEdge<M>) {
-  //   if let Some(pair) = edge.pair_mut() {
-  //     pair.delete_pair();
-  //   }
-  //   let id = edge.id();
-  //   {
-  //     let _ = Box::from_raw(*&self.edges[id]);
-  //   }
-  //   self.edges.swap_remove(id);
-  // }
+  pub fn vertex_count(&self) -> usize {
+    self.vertices.len()
+  }
+
+  pub fn iter_vertex(
+    &self,
+  ) -> impl Iterator<Item = (Handle<HalfEdgeVertex<M>>, &HalfEdgeVertex<M>)> {
+    self.vertices.iter()
+  }
+
+  pub fn iter_face(&self) -> impl Iterator<Item = (Handle<HalfEdgeFace<M>>, &HalfEdgeFace<M>)> {
+    self.faces.iter()
+  }
+
+  pub fn iter_half_edge(&self) -> impl Iterator<Item = (Handle<HalfEdge<M>>, &HalfEdge<M>)> {
+    self.half_edges.iter()
+  }
 }
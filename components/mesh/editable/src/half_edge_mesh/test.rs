@@ -41,10 +41,9 @@ fn build_mesh() {
   assert_eq!(mesh[b].half_edge_connected_count(&mesh), 2);
   assert_eq!(mesh[c].half_edge_connected_count(&mesh), 2);
 
-  // todo fix bug
-  // assert_eq!(mesh[a].face_connected_count(&mesh), 1);
-  // assert_eq!(mesh[b].face_connected_count(&mesh), 1);
-  // assert_eq!(mesh[c].face_connected_count(&mesh), 1);
+  assert_eq!(mesh[a].face_connected_count(&mesh), 1);
+  assert_eq!(mesh[b].face_connected_count(&mesh), 1);
+  assert_eq!(mesh[c].face_connected_count(&mesh), 1);
 
   assert_eq!(mesh[a].is_boundary_vertex(&mesh), true);
   assert_eq!(mesh[b].is_boundary_vertex(&mesh), true);
@@ -66,6 +65,11 @@ fn build_mesh() {
   assert_eq!(mesh[c].half_edge_connected_count(&mesh), 2);
   assert_eq!(mesh[d].half_edge_connected_count(&mesh), 2);
 
+  assert_eq!(mesh[a].face_connected_count(&mesh), 2);
+  assert_eq!(mesh[b].face_connected_count(&mesh), 2);
+  assert_eq!(mesh[c].face_connected_count(&mesh), 1);
+  assert_eq!(mesh[d].face_connected_count(&mesh), 1);
+
   assert_eq!(mesh[a].is_boundary_vertex(&mesh), true);
   assert_eq!(mesh[b].is_boundary_vertex(&mesh), true);
   assert_eq!(mesh[c].is_boundary_vertex(&mesh), true);
@@ -96,3 +100,170 @@ fn build_mesh() {
   ));
   assert_eq!(err.is_err(), true);
 }
+
+/// the triangles of the n x n grid, the vertex (x, y) is at index y * (n + 1) + x
+pub(crate) fn grid_triangles(n: usize) -> Vec<[usize; 3]> {
+  let mut triangles = Vec::new();
+  for y in 0..n {
+    for x in 0..n {
+      let a = y * (n + 1) + x;
+      let b = a + 1;
+      let c = a + n + 1;
+      let d = c + 1;
+      triangles.push([a, b, d]);
+      triangles.push([a, d, c]);
+    }
+  }
+  triangles
+}
+
+/// build a n x n grid, the vertex (x, y) is at index y * (n + 1) + x
+fn build_grid(
+  n: usize,
+) -> (
+  HalfEdgeMesh<TestMeshSchema>,
+  Vec<arena::Handle<HalfEdgeVertex<TestMeshSchema>>>,
+) {
+  let vertices = (0..(n + 1) * (n + 1)).map(|_| "v");
+  let result = HalfEdgeMesh::from_triangles(vertices, grid_triangles(n));
+  let vertices = result.vertices.into_iter().map(|v| v.unwrap()).collect();
+  (result.mesh, vertices)
+}
+
+fn edge_between(
+  mesh: &HalfEdgeMesh<TestMeshSchema>,
+  a: arena::Handle<HalfEdgeVertex<TestMeshSchema>>,
+  b: arena::Handle<HalfEdgeVertex<TestMeshSchema>>,
+) -> arena::Handle<HalfEdge<TestMeshSchema>> {
+  HalfEdge::get_by_two_points(mesh, a, b).unwrap()
+}
+
+#[test]
+fn build_from_triangles() {
+  let (mesh, v) = build_grid(3);
+  assert_eq!(mesh.validate(), Ok(()));
+  assert_eq!(mesh.face_count(), 18);
+  assert_eq!(mesh.vertex_count(), 16);
+  assert_eq!(mesh[v[5]].face_connected_count(&mesh), 6);
+  assert!(!mesh[v[5]].is_boundary_vertex(&mesh));
+  assert_eq!(mesh.ordered_out_half_edges(v[0]).len(), 2);
+}
+
+#[test]
+fn remove_face_and_vertex() {
+  let (mut mesh, v) = build_grid(3);
+  let face = mesh[edge_between(&mesh, v[5], v[6])].face();
+  mesh.remove_face(face).unwrap();
+  assert_eq!(mesh.validate(), Ok(()));
+  assert_eq!(mesh.face_count(), 17);
+  assert!(mesh[v[5]].is_boundary_vertex(&mesh));
+
+  // the vertex 5 already has a gap, another gap makes it a bowtie
+  let corner_face = mesh[edge_between(&mesh, v[0], v[1])].face();
+  assert_eq!(
+    mesh.remove_face(corner_face),
+    Err(HalfEdgeEditError::NonManifoldOperation(BowtieVertex))
+  );
+  assert_eq!(mesh.validate(), Ok(()));
+
+  // remove the face at the grid corner will leave the corner vertex isolated, and removed
+  let corner_face = mesh[edge_between(&mesh, v[2], v[3])].face();
+  mesh.remove_face(corner_face).unwrap();
+  assert_eq!(mesh.validate(), Ok(()));
+  assert_eq!(mesh.vertex_count(), 15);
+
+  // the vertex 1 has a face on each side, removing the middle face makes it a bowtie
+  let (mut mesh, v) = build_grid(3);
+  let middle = mesh[edge_between(&mesh, v[1], v[6])].face();
+  assert_eq!(
+    mesh.remove_face(middle),
+    Err(HalfEdgeEditError::NonManifoldOperation(BowtieVertex))
+  );
+  assert_eq!(mesh.validate(), Ok(()));
+
+  let (mut mesh, v) = build_grid(3);
+  mesh.remove_vertex(v[5]).unwrap();
+  assert_eq!(mesh.validate(), Ok(()));
+  assert_eq!(mesh.face_count(), 12);
+  // the corner vertex 0 only connects to the faces around vertex 5
+  assert_eq!(mesh.vertex_count(), 14);
+}
+
+#[test]
+fn flip_edge() {
+  let (mut mesh, v) = build_grid(3);
+  let edge = edge_between(&mesh, v[5], v[10]);
+  mesh.flip_edge(edge).unwrap();
+  assert_eq!(mesh.validate(), Ok(()));
+  assert_eq!(mesh.face_count(), 18);
+  assert!(HalfEdge::get_by_two_points(&mesh, v[5], v[10]).is_none());
+  assert!(HalfEdge::get_by_two_points(&mesh, v[6], v[9]).is_some());
+  assert_eq!(mesh[v[5]].face_connected_count(&mesh), 5);
+  assert_eq!(mesh[v[6]].face_connected_count(&mesh), 7);
+
+  let border = edge_between(&mesh, v[0], v[1]);
+  assert_eq!(mesh.flip_edge(border), Err(HalfEdgeEditError::BoundaryEdge));
+}
+
+#[test]
+fn split_edge_and_face() {
+  let (mut mesh, v) = build_grid(3);
+  let middle = mesh
+    .split_edge(edge_between(&mesh, v[5], v[10]), "m")
+    .unwrap();
+  assert_eq!(mesh.validate(), Ok(()));
+  assert_eq!(mesh.face_count(), 20);
+  assert_eq!(mesh[middle].face_connected_count(&mesh), 4);
+
+  mesh
+    .split_edge(edge_between(&mesh, v[0], v[1]), "m")
+    .unwrap();
+  assert_eq!(mesh.validate(), Ok(()));
+  assert_eq!(mesh.face_count(), 21);
+
+  let face = mesh[edge_between(&mesh, v[1], v[2])].face();
+  assert_eq!(
+    mesh.split_face(face, v[1], v[2]),
+    Err(HalfEdgeEditError::DegeneratedFace)
+  );
+}
+
+#[test]
+fn collapse_edge() {
+  let (mut mesh, v) = build_grid(3);
+  let edge = edge_between(&mesh, v[5], v[6]);
+  mesh.collapse_edge(edge, v[5]).unwrap();
+  assert_eq!(mesh.validate(), Ok(()));
+  assert_eq!(mesh.face_count(), 16);
+  assert_eq!(mesh.vertex_count(), 15);
+
+  // the opposite corner vertex 3 only has one face, collapse will make it dangling
+  let (mut mesh, v) = build_grid(3);
+  let edge = edge_between(&mesh, v[2], v[7]);
+  assert_eq!(
+    mesh.collapse_edge(edge, v[2]),
+    Err(HalfEdgeEditError::NonManifoldOperation(DanglingPoint))
+  );
+}
+
+#[test]
+fn fill_hole() {
+  let (mut mesh, v) = build_grid(4);
+  mesh.remove_vertex(v[12]).unwrap();
+  assert_eq!(mesh.validate(), Ok(()));
+
+  let border = edge_between(&mesh, v[0], v[1]);
+  assert_eq!(mesh.boundary_loop(border).unwrap().len(), 16);
+
+  let hole_edge = edge_between(&mesh, v[7], v[6]);
+  assert_eq!(mesh.boundary_loop(hole_edge).unwrap().len(), 6);
+  let face = mesh.fill_hole(hole_edge).unwrap();
+  assert_eq!(mesh.validate(), Ok(()));
+  assert_eq!(mesh[face].side_count(&mesh), 6);
+
+  let faces = mesh.triangulate_face(face).unwrap();
+  assert_eq!(faces.len(), 4);
+  assert_eq!(mesh.validate(), Ok(()));
+  assert_eq!(mesh.face_count(), 30);
+  assert_eq!(mesh.boundary_loop(border).unwrap().len(), 16,);
+}
@@ -0,0 +1,91 @@
+use crate::*;
+
+#[derive(Debug, PartialEq)]
+pub enum HalfEdgeMeshValidationError {
+  /// some handle references to a removed or not exist element
+  InvalidHandle,
+  /// the half edge's next's prev is not itself, or prev's next is not itself
+  NextPrevMismatch,
+  /// the half edges in a face loop have different face
+  FaceLoopMismatch,
+  /// the face has less than three sides
+  DegeneratedFace,
+  /// the pair is not symmetric or not in opposite direction
+  PairMismatch,
+  /// more than one half edge has the same start and end vertex
+  DuplicateHalfEdge,
+  /// the vertex's half edge is not start from it
+  VertexEdgeMismatch,
+  /// the half edges of the vertex can not be visited by rotating around the vertex
+  NonManifoldVertex,
+}
+
+use HalfEdgeMeshValidationError::*;
+
+impl<M: HalfEdgeMeshData> HalfEdgeMesh<M> {
+  /// Check all the half edge mesh invariants, this is useful for debugging the editing
+  /// operations.
+  pub fn validate(&self) -> Result<(), HalfEdgeMeshValidationError> {
+    let mut directed = fast_hash_collection::FastHashSet::default();
+    let mut out_edge_counts = fast_hash_collection::FastHashMap::default();
+
+    for (h, e) in self.half_edges.iter() {
+      let next = self.half_edges.get(e.next).ok_or(InvalidHandle)?;
+      let prev = self.half_edges.get(e.prev).ok_or(InvalidHandle)?;
+      if next.prev != h || prev.next != h {
+        return Err(NextPrevMismatch);
+      }
+      if next.face != e.face {
+        return Err(FaceLoopMismatch);
+      }
+      if !self.faces.contains(e.face) || !self.vertices.contains(e.vert) {
+        return Err(InvalidHandle);
+      }
+      if let Some(pair) = e.pair {
+        let p = self.half_edges.get(pair).ok_or(InvalidHandle)?;
+        if pair == h || p.pair != Some(h) || p.vert != next.vert || e.vert != p.end(self) {
+          return Err(PairMismatch);
+        }
+      }
+      if !directed.insert((e.vert, next.vert)) {
+        return Err(DuplicateHalfEdge);
+      }
+      *out_edge_counts.entry(e.vert).or_insert(0) += 1;
+    }
+
+    for (f, face) in self.faces.iter() {
+      let start = self.half_edges.get(face.edge).ok_or(InvalidHandle)?;
+      if start.face != f {
+        return Err(FaceLoopMismatch);
+      }
+      let mut count = 0;
+      let mut current = face.edge;
+      loop {
+        count += 1;
+        current = self.half_edges[current].next;
+        if current == face.edge {
+          break;
+        }
+        if count > self.half_edges.len() {
+          return Err(FaceLoopMismatch);
+        }
+      }
+      if count < 3 {
+        return Err(DegeneratedFace);
+      }
+    }
+
+    for (v, vertex) in self.vertices.iter() {
+      let edge = self.half_edges.get(vertex.edge).ok_or(InvalidHandle)?;
+      if edge.vert != v {
+        return Err(VertexEdgeMismatch);
+      }
+      let visited = vertex.iter_out_half_edge(self).count();
+      if out_edge_counts.get(&v).copied().unwrap_or(0) != visited {
+        return Err(NonManifoldVertex);
+      }
+    }
+
+    Ok(())
+  }
+}
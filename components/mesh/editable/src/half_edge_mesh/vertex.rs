@@ -84,26 +84,23 @@ impl<'a, M: HalfEdgeMeshData> Iterator for VertexToHalfEdgeIter<'a, M> {
 
 pub type FaceIterItem<'a, M> = (&'a HalfEdgeFace<M>, Handle<HalfEdgeFace<M>>);
 
+/// An iterator that iterate all faces around this vertex. Every face around the vertex
+/// has exactly one half edge emanating from the vertex, so we visit those half edges.
 pub struct VertexToFaceIter<'a, M: HalfEdgeMeshData> {
   inner: VertexToHalfEdgeIter<'a, M>,
-  has_visited_start: bool,
 }
 
 impl<'a, M: HalfEdgeMeshData> Iterator for VertexToFaceIter<'a, M> {
   type Item = FaceIterItem<'a, M>;
 
   fn next(&mut self) -> Option<Self::Item> {
-    if !self.has_visited_start {
-      self.has_visited_start = true;
-      let face = self.inner.start.0.face();
-      return Some((self.inner.mesh.faces.get(face).unwrap(), face));
-    }
-
+    let mesh = self.inner.mesh;
+    let self_vert = self.inner.self_vert;
     self
       .inner
-      .next()
-      .and_then(|_| self.inner.next())
-      .map(|(edge, _)| (self.inner.mesh.faces.get(edge.face).unwrap(), edge.face))
+      .by_ref()
+      .find(|(edge, _)| std::ptr::eq(&mesh.vertices[edge.vert], self_vert))
+      .map(|(edge, _)| (mesh.faces.get(edge.face).unwrap(), edge.face))
   }
 }
 
@@ -142,7 +139,30 @@ impl<M: HalfEdgeMeshData> HalfEdgeVertex<M> {
   pub fn iter_face<'a>(&'a self, mesh: &'a HalfEdgeMesh<M>) -> VertexToFaceIter<'a, M> {
     VertexToFaceIter {
       inner: self.iter_half_edge(mesh),
-      has_visited_start: false,
     }
   }
+
+  /// iterate the half edges start from this vertex
+  pub fn iter_out_half_edge<'a>(
+    &'a self,
+    mesh: &'a HalfEdgeMesh<M>,
+  ) -> impl Iterator<Item = EdgeIterItem<'a, M>> + 'a {
+    self
+      .iter_half_edge(mesh)
+      .filter(move |(edge, _)| std::ptr::eq(&mesh.vertices[edge.vert], self))
+  }
+
+  /// iterate the vertices that connected to this vertex by an edge
+  pub fn iter_neighbor_vertex<'a>(
+    &'a self,
+    mesh: &'a HalfEdgeMesh<M>,
+  ) -> impl Iterator<Item = Handle<HalfEdgeVertex<M>>> + 'a {
+    self.iter_half_edge(mesh).map(move |(edge, _)| {
+      if std::ptr::eq(&mesh.vertices[edge.vert], self) {
+        edge.end(mesh)
+      } else {
+        edge.vert
+      }
+    })
+  }
 }
@@ -4,6 +4,8 @@
 
 pub mod half_edge_mesh;
 pub mod simplification;
+pub mod subdivision;
 
 pub use half_edge_mesh::*;
 pub use simplification::*;
+pub use subdivision::*;
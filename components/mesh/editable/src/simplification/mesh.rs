@@ -1,7 +1,9 @@
 use std::cell::Cell;
 
+use fast_hash_collection::FastHashMap;
 use rendiation_algebra::*;
 use rendiation_geometry::{Plane, Triangle};
+use rendiation_renderable_mesh::{vertex::Vertex as MeshVertex, IndexedMesh, TriangleList};
 
 use super::qem::QEM;
 use crate::{
@@ -17,83 +19,202 @@ impl HalfEdgeMeshData for SimplificationMeshData {
   type Vertex = VertexData;
 }
 
+pub type SimplificationSourceMesh = IndexedMesh<TriangleList, Vec<MeshVertex>, Vec<u32>>;
+
 pub(super) type Mesh = HalfEdgeMesh<SimplificationMeshData>;
 pub(super) type Vertex = HalfEdgeVertex<SimplificationMeshData>;
-// pub(super) type HEdge = HalfEdge<SimplificationMeshData>;
+pub(super) type HEdge = HalfEdge<SimplificationMeshData>;
 pub(super) type Face = HalfEdgeFace<SimplificationMeshData>;
 
-impl From<&Face> for Triangle {
-  fn from(_face: &Face) -> Self {
-    todo!()
-    // unsafe {
-    //   let edge_a = face.edge();
-    //   let vert_a = (*edge_a.vert().vertex_data.get()).positions;
-    //   let edge_b = edge_a.next();
-    //   let vert_b = (*edge_b.vert().vertex_data.get()).positions;
-    //   let edge_c = edge_b.next();
-    //   let vert_c = (*edge_c.vert().vertex_data.get()).positions;
-    //   Triangle::new(vert_a, vert_b, vert_c)
-    // }
-  }
+/// The vertex classification that decides how the vertex could be collapsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexKind {
+  /// interior vertex with continuous attributes
+  Manifold,
+  /// vertex on the mesh boundary
+  Border,
+  /// interior vertex that has discontinuous attributes(for example the uv seam)
+  Seam,
+  /// vertex that is not manifold, never touched in simplification
+  Locked,
 }
 
 pub struct VertexData {
   pub positions: Vec3<f32>,
   pub qem: Cell<QEM>,
+  pub kind: VertexKind,
 }
 
 pub struct EdgeData {
   pub update_id: Cell<u32>,
+  /// the attribute vertex index of the face corner at the start of this half edge
+  pub wedge: u32,
 }
 
 impl Default for EdgeData {
   fn default() -> Self {
     Self {
       update_id: Cell::new(0),
+      wedge: 0,
     }
   }
 }
 
+/// the penalty weight of the quadric that keeps the boundary edge from moving
+const BOUNDARY_QEM_WEIGHT: f32 = 10.;
+
 impl Mesh {
-  pub fn from_buffer(positions: &Vec<f32>, indices: &Vec<u32>) -> Self {
-    todo!()
-    // let mut vertices = Vec::new();
-    // let mut faces = Vec::new();
-    // let mut edges = Vec::new();
-
-    // let mut edge_pairs = EdgePairFinder::new();
-
-    // for v in 0..positions.len() / 3 {
-    //   let vert = HalfEdgeVertex::new(
-    //     VertexData {
-    //       positions: Vec3::new(positions[3 * v], positions[3 * v + 1], positions[3 * v + 2]),
-    //       normal: Vec3::new(1.0, 0.0, 0.0),
-    //       qem: QEM::zero(),
-    //     },
-    //     vertices.len(),
-    //   );
-    //   let vert = Box::into_raw(Box::new(vert));
-    //   vertices.push(vert);
-    // }
-
-    // for f in 0..indices.len() / 3 {
-    //   let face = HalfEdgeFace::new_tri(
-    //     vertices[indices[3 * f] as usize],
-    //     vertices[indices[3 * f + 1] as usize],
-    //     vertices[indices[3 * f + 2] as usize],
-    //     &mut edges,
-    //     &mut edge_pairs,
-    //     vertices.len(),
-    //   );
-    //   faces.push(Box::into_raw(Box::new(face)));
-    // }
-
-    // edge_pairs.find_edge_pairs(&mut edges);
-
-    // Self {
-    //   edges,
-    //   faces,
-    //   vertices,
-    // }
+  pub(super) fn face_triangle(&self, face: &Face) -> Triangle {
+    let mut iter = face
+      .iter_half_edge(self)
+      .map(|(e, _)| self.vertices[e.vert()].data.positions);
+    Triangle::new(
+      iter.next().unwrap(),
+      iter.next().unwrap(),
+      iter.next().unwrap(),
+    )
+  }
+
+  /// Build the mesh from an indexed triangle mesh. The vertices that have the same position are
+  /// welded together, and the source vertices are kept as the corner attributes(wedge).
+  pub fn from_indexed_mesh(source: &SimplificationSourceMesh) -> Self {
+    let mut welded = FastHashMap::default();
+    let mut positions = Vec::new();
+    let remapping: Vec<usize> = source
+      .vertex
+      .iter()
+      .map(|v| {
+        let key = [
+          v.position.x.to_bits(),
+          v.position.y.to_bits(),
+          v.position.z.to_bits(),
+        ];
+        *welded.entry(key).or_insert_with(|| {
+          positions.push(v.position);
+          positions.len() - 1
+        })
+      })
+      .collect();
+
+    let triangles: Vec<[u32; 3]> = source
+      .index
+      .chunks_exact(3)
+      .map(|t| [t[0], t[1], t[2]])
+      .collect();
+
+    let vertices = positions.iter().map(|&positions| VertexData {
+      positions,
+      qem: Cell::new(QEM::zero()),
+      kind: VertexKind::Manifold,
+    });
+    let result = Mesh::from_triangles(
+      vertices,
+      triangles.iter().map(|t| t.map(|i| remapping[i as usize])),
+    );
+    let mut mesh = result.mesh;
+
+    let mut face_counts = vec![0; positions.len()];
+    let mut wedges = vec![Vec::new(); positions.len()];
+    for (triangle, face) in triangles.iter().zip(result.faces.iter()) {
+      let face = if let Some(face) = face {
+        *face
+      } else {
+        continue;
+      };
+      let edges: Vec<_> = mesh.faces[face]
+        .iter_half_edge(&mesh)
+        .map(|(_, h)| h)
+        .collect();
+      // the first half edge of the face starts from the first vertex of the triangle
+      for (&h, &wedge) in edges.iter().zip(triangle.iter()) {
+        mesh.half_edges.get_mut(h).unwrap().data.wedge = wedge;
+        let welded = remapping[wedge as usize];
+        face_counts[welded] += 1;
+        wedges[welded].push(wedge);
+      }
+    }
+
+    let has_attribute_discontinuity = |wedges: &[u32]| {
+      let first = source.vertex[wedges[0] as usize];
+      wedges.iter().any(|&w| {
+        let v = source.vertex[w as usize];
+        v.normal != first.normal || v.uv != first.uv
+      })
+    };
+
+    for (i, handle) in result.vertices.iter().enumerate() {
+      let handle = if let Some(handle) = handle {
+        *handle
+      } else {
+        continue;
+      };
+      let vertex = &mesh.vertices[handle];
+      let kind = if vertex.half_edge_connected_count(&mesh) != face_counts[i] * 2 {
+        VertexKind::Locked
+      } else if vertex.is_boundary_vertex(&mesh) {
+        VertexKind::Border
+      } else if has_attribute_discontinuity(&wedges[i]) {
+        VertexKind::Seam
+      } else {
+        VertexKind::Manifold
+      };
+      mesh.vertices.get_mut(handle).unwrap().data.kind = kind;
+    }
+
+    mesh.compute_all_vertices_qem();
+    mesh
+  }
+
+  fn compute_all_vertices_qem(&self) {
+    for (_, face) in self.faces.iter() {
+      let triangle = self.face_triangle(face);
+      let normal = (triangle.b - triangle.a).cross(triangle.c - triangle.a);
+      let double_area = normal.length();
+      if double_area <= f32::EPSILON {
+        continue;
+      }
+      let face_qem = QEM::from(Plane::from(triangle)) * (double_area * 0.5);
+
+      for (edge, _) in face.iter_half_edge(self) {
+        let vertex = &self.vertices[edge.vert()].data;
+        vertex.qem.set(vertex.qem.get() + face_qem);
+
+        // add a perpendicular plane on the boundary edge to prevent the boundary from shrinking
+        if edge.is_border() {
+          let start = vertex.positions;
+          let end = self.vertices[edge.end(self)].data.positions;
+          let edge_dir = end - start;
+          let edge_length_sq = edge_dir.length2();
+          if edge_length_sq <= f32::EPSILON {
+            continue;
+          }
+          let plane = Plane::from_normal_and_plane_point(edge_dir.cross(normal), start);
+          let boundary_qem = QEM::from(plane) * (edge_length_sq * BOUNDARY_QEM_WEIGHT);
+          for v in [edge.vert(), edge.end(self)] {
+            let v = &self.vertices[v].data;
+            v.qem.set(v.qem.get() + boundary_qem);
+          }
+        }
+      }
+    }
+  }
+
+  /// Create the triangle mesh from current faces. Only the referenced wedges are kept.
+  pub fn build_indexed_mesh(&self, wedges: &[MeshVertex]) -> SimplificationSourceMesh {
+    let mut remapping = FastHashMap::default();
+    let mut vertices = Vec::new();
+    let mut indices = Vec::with_capacity(self.face_count() * 3);
+    for (_, face) in self.faces.iter() {
+      for (edge, _) in face.iter_half_edge(self) {
+        let index = *remapping.entry(edge.data.wedge).or_insert_with(|| {
+          let mut vertex = wedges[edge.data.wedge as usize];
+          vertex.position = self.vertices[edge.vert()].data.positions;
+          vertices.push(vertex);
+          vertices.len() as u32 - 1
+        });
+        indices.push(index);
+      }
+    }
+    IndexedMesh::new(vertices, indices)
   }
 }
@@ -2,35 +2,70 @@ use std::collections::BinaryHeap;
 
 use arena::Handle;
 use mesh::Mesh;
-use rendiation_algebra::Vec3;
-use rendiation_geometry::{Plane, Triangle};
+use rendiation_algebra::*;
+use rendiation_renderable_mesh::vertex::Vertex as MeshVertex;
 
-use self::{mesh::SimplificationMeshData, qem::QEM};
-use crate::HalfEdge;
+use self::{
+  mesh::{SimplificationMeshData, VertexKind},
+  qem::QEM,
+};
+use crate::{HalfEdge, HalfEdgeVertex};
 
 pub mod mesh;
 pub mod qem;
 
+pub use mesh::SimplificationSourceMesh;
+
+type EdgeHandle = Handle<HalfEdge<SimplificationMeshData>>;
+type VertexHandle = Handle<HalfEdgeVertex<SimplificationMeshData>>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SimplificationConfig {
+  /// the boundary vertices will never be moved or removed
+  pub preserve_boundary: bool,
+  /// the vertices that have discontinuous attributes(uv seam, hard normal edge) will never be
+  /// moved or removed
+  pub preserve_uv_seam: bool,
+  /// the collapse is rejected if any face normal rotated more than this angle, in radian
+  pub max_normal_deviation: f32,
+}
+
+impl Default for SimplificationConfig {
+  fn default() -> Self {
+    Self {
+      preserve_boundary: true,
+      preserve_uv_seam: true,
+      max_normal_deviation: std::f32::consts::FRAC_PI_2,
+    }
+  }
+}
+
 pub struct SimplificationCtx {
   mesh: Mesh,
+  /// the source vertices, they are the attributes of face corners
+  wedges: Vec<MeshVertex>,
   edge_choices: BinaryHeap<EdgeChoice>,
+  config: SimplificationConfig,
 }
 
+#[derive(Debug)]
 pub enum SimplificationError {
   NotEnoughEdgeForDecimation,
 }
 use SimplificationError::*;
 
 pub struct EdgeChoice {
-  edge: Handle<HalfEdge<SimplificationMeshData>>,
+  edge: EdgeHandle,
+  /// the vertex keep after the collapse, the other one is removed
+  kept: VertexHandle,
   dirty_id: u32,
   error: f32,
-  _new_merge_vertex_position: Vec3<f32>,
+  new_merge_vertex_position: Vec3<f32>,
 }
 
 impl PartialOrd for EdgeChoice {
   fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-    self.error.partial_cmp(&other.error)
+    Some(self.cmp(other))
   }
 }
 
@@ -43,31 +78,160 @@ impl PartialEq for EdgeChoice {
 impl Eq for EdgeChoice {}
 
 impl Ord for EdgeChoice {
+  /// reversed, so the binary heap pop the edge with minimal error first
   fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-    self.partial_cmp(&other).unwrap()
+    other.error.total_cmp(&self.error)
   }
 }
 
 impl SimplificationCtx {
-  pub fn new(positions: &Vec<f32>, indices: &Vec<u32>) -> Self {
-    let mesh = Mesh::from_buffer(positions, indices);
-
-    // compute_all_vertices_qem
-    mesh.vertices.iter().for_each(|(_, v)| {
-      let mut vert_qem = QEM::zero();
-      v.iter_face(&mesh).for_each(|(f, _)| {
-        let face3 = Triangle::from(f);
-        let plane = Plane::from(face3);
-        let face_qem = QEM::from(plane);
-        vert_qem += face_qem;
-      });
-      v.data.qem.set(vert_qem)
-    });
+  pub fn new(source: &SimplificationSourceMesh, config: SimplificationConfig) -> Self {
+    let mesh = Mesh::from_indexed_mesh(source);
 
-    Self {
+    let mut ctx = Self {
       mesh,
+      wedges: source.vertex.clone(),
       edge_choices: BinaryHeap::new(),
+      config,
+    };
+
+    let edges: Vec<_> = ctx
+      .mesh
+      .half_edges
+      .iter()
+      .filter(|(h, e)| e.pair().map(|pair| pair > *h).unwrap_or(true))
+      .map(|(h, _)| h)
+      .collect();
+    for edge in edges {
+      ctx.push_edge_choice(edge);
     }
+
+    ctx
+  }
+
+  pub fn face_count(&self) -> usize {
+    self.mesh.face_count()
+  }
+
+  /// Create the simplified triangle mesh
+  pub fn build_mesh(&self) -> SimplificationSourceMesh {
+    self.mesh.build_indexed_mesh(&self.wedges)
+  }
+
+  fn push_edge_choice(&mut self, edge: EdgeHandle) {
+    if let Some(choice) = self.compute_edge_choice(edge) {
+      self.edge_choices.push(choice);
+    }
+  }
+
+  /// decide the collapse direction and the new position of the edge, return None if the edge
+  /// could not be collapsed in any direction.
+  fn compute_edge_choice(&self, edge: EdgeHandle) -> Option<EdgeChoice> {
+    let e = &self.mesh.half_edges[edge];
+    let (from, to) = (e.vert(), e.end(&self.mesh));
+
+    [(from, to), (to, from)]
+      .into_iter()
+      .filter_map(|(kept, removed)| {
+        let (error, position) = self.evaluate_collapse(edge, kept, removed)?;
+        Some(EdgeChoice {
+          edge,
+          kept,
+          dirty_id: e.data.update_id.get(),
+          error,
+          new_merge_vertex_position: position,
+        })
+      })
+      .min_by(|a, b| a.error.total_cmp(&b.error))
+  }
+
+  fn is_vertex_fixed(&self, kind: VertexKind) -> bool {
+    match kind {
+      VertexKind::Manifold => false,
+      VertexKind::Border => self.config.preserve_boundary,
+      VertexKind::Seam => self.config.preserve_uv_seam,
+      VertexKind::Locked => true,
+    }
+  }
+
+  /// return the error and new position if the removed vertex could be merged into the kept
+  /// vertex
+  fn evaluate_collapse(
+    &self,
+    edge: EdgeHandle,
+    kept: VertexHandle,
+    removed: VertexHandle,
+  ) -> Option<(f32, Vec3<f32>)> {
+    let kept_v = &self.mesh.vertices[kept].data;
+    let removed_v = &self.mesh.vertices[removed].data;
+
+    if kept_v.kind == VertexKind::Locked || self.is_vertex_fixed(removed_v.kind) {
+      return None;
+    }
+    // border vertex only collapse along the border
+    if removed_v.kind == VertexKind::Border && !self.mesh.half_edges[edge].is_border() {
+      return None;
+    }
+
+    let qem = kept_v.qem.get() + removed_v.qem.get();
+    let position = if self.is_vertex_fixed(kept_v.kind) {
+      kept_v.positions
+    } else {
+      let a = kept_v.positions;
+      let b = removed_v.positions;
+      let middle = (a + b) * 0.5;
+      let edge_length = (a - b).length();
+      qem
+        .compute_optimal_position()
+        .filter(|p| (*p - middle).length() <= edge_length)
+        .into_iter()
+        .chain([a, b, middle])
+        .min_by(|x, y| qem.error(*x).total_cmp(&qem.error(*y)))
+        .unwrap()
+    };
+
+    if self.is_face_flipped(kept, removed, position)
+      || self.is_face_flipped(removed, kept, position)
+    {
+      return None;
+    }
+
+    Some((qem.error(position).max(0.), position))
+  }
+
+  /// check if the faces around the vertex (except the faces will be removed) will flip or
+  /// rotated too much if the vertex moved to the new position
+  fn is_face_flipped(
+    &self,
+    vertex: VertexHandle,
+    other: VertexHandle,
+    new_position: Vec3<f32>,
+  ) -> bool {
+    let mesh = &self.mesh;
+    let min_cos = self.config.max_normal_deviation.cos();
+    mesh.vertices[vertex].iter_face(mesh).any(|(face, _)| {
+      let mut positions = [Vec3::zero(); 3];
+      let mut moved = [Vec3::zero(); 3];
+      for (i, (edge, _)) in face.iter_half_edge(mesh).enumerate() {
+        if edge.vert() == other {
+          // face adjacent to the collapsed edge, will be removed
+          return false;
+        }
+        positions[i] = mesh.vertices[edge.vert()].data.positions;
+        moved[i] = if edge.vert() == vertex {
+          new_position
+        } else {
+          positions[i]
+        };
+      }
+      let normal = (positions[1] - positions[0]).cross(positions[2] - positions[0]);
+      let new_normal = (moved[1] - moved[0]).cross(moved[2] - moved[0]);
+      let new_length = new_normal.length();
+      if new_length <= f32::EPSILON {
+        return true;
+      }
+      normal.normalize().dot(new_normal / new_length) < min_cos
+    })
   }
 
   /// remove a edge in mesh
@@ -81,14 +245,111 @@ impl SimplificationCtx {
       if edge.data.update_id.get() != edge_record.dirty_id {
         continue;
       }
-      // todo
-      // merge edge
-      // update qem and dirty id;
+      let kept = edge_record.kept;
+      let removed = if edge.vert() == kept {
+        edge.end(&self.mesh)
+      } else {
+        edge.vert()
+      };
+
+      // the neighbor faces may changed by other collapses and not marked dirty
+      if self.mesh.check_edge_collapse(edge_record.edge).is_err()
+        || self.is_face_flipped(kept, removed, edge_record.new_merge_vertex_position)
+        || self.is_face_flipped(removed, kept, edge_record.new_merge_vertex_position)
+      {
+        continue;
+      }
+
+      self.collapse(edge_record, removed);
       return true;
     }
     false
   }
 
+  fn collapse(&mut self, choice: EdgeChoice, removed: VertexHandle) {
+    let EdgeChoice {
+      edge,
+      kept,
+      new_merge_vertex_position: position,
+      ..
+    } = choice;
+
+    // decide how the attributes of the removed vertex are replaced by the kept vertex's.
+    // the corners in the collapsed faces tell the correspondence.
+    let mut wedge_mapping = Vec::with_capacity(2);
+    for side in std::iter::once(edge).chain(self.mesh.half_edges[edge].pair()) {
+      let mut kept_wedge = None;
+      let mut removed_wedge = None;
+      for (e, _) in self.mesh.faces[self.mesh.half_edges[side].face()].iter_half_edge(&self.mesh) {
+        if e.vert() == kept {
+          kept_wedge = Some(e.data.wedge);
+        } else if e.vert() == removed {
+          removed_wedge = Some(e.data.wedge);
+        }
+      }
+      wedge_mapping.push((removed_wedge.unwrap(), kept_wedge.unwrap()));
+    }
+    let default_wedge = wedge_mapping[0].1;
+
+    // interpolate the kept attributes by the projected position on the edge
+    let kept_position = self.mesh.vertices[kept].data.positions;
+    let removed_position = self.mesh.vertices[removed].data.positions;
+    let dir = removed_position - kept_position;
+    let t = ((position - kept_position).dot(dir) / dir.length2().max(f32::EPSILON)).clamp(0., 1.);
+    if t > 0. {
+      let mut updated = Vec::with_capacity(2);
+      for &(removed_wedge, kept_wedge) in &wedge_mapping {
+        if updated.contains(&kept_wedge) {
+          continue;
+        }
+        updated.push(kept_wedge);
+        let source = self.wedges[removed_wedge as usize];
+        let target = &mut self.wedges[kept_wedge as usize];
+        target.normal = target.normal.lerp(source.normal, t).normalize();
+        target.uv = target.uv.lerp(source.uv, t);
+      }
+    }
+
+    let moving: Vec<_> = self.mesh.vertices[removed]
+      .iter_out_half_edge(&self.mesh)
+      .map(|(_, h)| h)
+      .collect();
+
+    let qem = self.mesh.vertices[kept].data.qem.get() + self.mesh.vertices[removed].data.qem.get();
+    self.mesh.collapse_edge(edge, kept).unwrap();
+
+    let kept_vertex = self.mesh.vertices.get_mut(kept).unwrap();
+    kept_vertex.data.positions = position;
+    kept_vertex.data.qem.set(qem);
+
+    for h in moving {
+      if let Some(e) = self.mesh.half_edges.get_mut(h) {
+        e.data.wedge = wedge_mapping
+          .iter()
+          .find(|(removed_wedge, _)| *removed_wedge == e.data.wedge)
+          .map(|(_, kept_wedge)| *kept_wedge)
+          .unwrap_or(default_wedge);
+      }
+    }
+
+    self.update_edges_around(kept);
+  }
+
+  /// mark the edges around the vertex dirty and recompute their choices
+  fn update_edges_around(&mut self, vertex: VertexHandle) {
+    let mut edges = Vec::new();
+    for (e, h) in self.mesh.vertices[vertex].iter_half_edge(&self.mesh) {
+      e.data.update_id.set(e.data.update_id.get() + 1);
+      // each edge is only pushed once, by the out half edge, or the border in half edge
+      if e.vert() == vertex || e.is_border() {
+        edges.push(h);
+      }
+    }
+    for edge in edges {
+      self.push_edge_choice(edge);
+    }
+  }
+
   pub fn simplify(&mut self, target_face_count: usize) -> Result<(), SimplificationError> {
     while self.mesh.face_count() > target_face_count {
       if !self.decimate_edge() {
@@ -98,3 +359,173 @@ impl SimplificationCtx {
     Ok(())
   }
 }
+
+/// Simplify the triangle mesh to the target face count by quadric error edge collapse.
+///
+/// If the mesh can not be simplified to the target count (for example, all remaining edges are
+/// constrained), the mesh simplified as much as possible is returned.
+pub fn simplify_mesh(
+  source: &SimplificationSourceMesh,
+  target_face_count: usize,
+  config: SimplificationConfig,
+) -> SimplificationSourceMesh {
+  let mut ctx = SimplificationCtx::new(source, config);
+  ctx.simplify(target_face_count).ok();
+  ctx.build_mesh()
+}
+
+#[test]
+fn simplify_grid() {
+  // a flat grid with 10x10 quads
+  let size = 10;
+  let mut vertices = Vec::new();
+  for y in 0..=size {
+    for x in 0..=size {
+      let position = Vec3::new(x as f32, y as f32, 0.);
+      let uv = Vec2::new(x as f32, y as f32) / size as f32;
+      vertices.push(MeshVertex::new(position, Vec3::new(0., 0., 1.), uv));
+    }
+  }
+  let mut indices = Vec::new();
+  for y in 0..size {
+    for x in 0..size {
+      let a = y * (size + 1) + x;
+      let b = a + 1;
+      let c = a + size + 1;
+      let d = c + 1;
+      indices.extend([a, b, d, a, d, c]);
+    }
+  }
+  let source = SimplificationSourceMesh::new(vertices, indices);
+
+  let config = SimplificationConfig::default();
+  let result = simplify_mesh(&source, 60, config);
+  let face_count = result.index.len() / 3;
+  assert!(face_count <= 60);
+  // the 40 boundary vertices are kept
+  assert!(face_count >= 38);
+
+  // the boundary is preserved, so the corners are kept and the mesh is still flat
+  for v in &result.vertex {
+    assert!(v.position.z.abs() < 1e-4);
+  }
+  let has_corner = |x: f32, y: f32| {
+    result
+      .vertex
+      .iter()
+      .any(|v| v.position == Vec3::new(x, y, 0.))
+  };
+  assert!(has_corner(0., 0.));
+  assert!(has_corner(10., 10.));
+
+  // the face winding is not flipped
+  for t in result.index.chunks_exact(3) {
+    let [a, b, c] = [t[0], t[1], t[2]].map(|i| result.vertex[i as usize].position);
+    assert!((b - a).cross(c - a).z > 0.);
+  }
+}
+
+#[cfg(test)]
+fn build_cube_grid(n: u32) -> SimplificationSourceMesh {
+  let mut vertices = Vec::new();
+  let mut indices = Vec::new();
+  let half = n as f32 * 0.5;
+  for axis in 0..3 {
+    for side in [0., n as f32] {
+      let mut normal = Vec3::zero();
+      normal[axis] = if side == 0. { -1. } else { 1. };
+      let start = vertices.len() as u32;
+      for s in 0..=n {
+        for t in 0..=n {
+          let mut position = Vec3::zero();
+          position[axis] = side;
+          position[(axis + 1) % 3] = s as f32;
+          position[(axis + 2) % 3] = t as f32;
+          let uv = Vec2::new(s as f32, t as f32) / n as f32;
+          vertices.push(MeshVertex::new(position, normal, uv));
+        }
+      }
+      for s in 0..n {
+        for t in 0..n {
+          let a = start + s * (n + 1) + t;
+          let b = a + 1;
+          let c = a + n + 1;
+          let d = c + 1;
+          for [x, y, z] in [[a, c, d], [a, d, b]] {
+            let [px, py, pz] = [x, y, z].map(|i| vertices[i as usize].position);
+            let center = (px + py + pz) / 3. - Vec3::splat(half);
+            if (py - px).cross(pz - px).dot(center) > 0. {
+              indices.extend([x, y, z]);
+            } else {
+              indices.extend([x, z, y]);
+            }
+          }
+        }
+      }
+    }
+  }
+  SimplificationSourceMesh::new(vertices, indices)
+}
+
+#[cfg(test)]
+fn signed_volume(mesh: &SimplificationSourceMesh) -> f32 {
+  mesh
+    .index
+    .chunks_exact(3)
+    .map(|t| {
+      let [a, b, c] = [t[0], t[1], t[2]].map(|i| mesh.vertex[i as usize].position);
+      a.dot(b.cross(c)) / 6.
+    })
+    .sum()
+}
+
+#[test]
+fn simplify_closed_mesh() {
+  let source = build_cube_grid(6);
+  assert_eq!(source.index.len() / 3, 6 * 6 * 6 * 2);
+  let volume = signed_volume(&source);
+
+  let config = SimplificationConfig {
+    preserve_uv_seam: false,
+    ..Default::default()
+  };
+  let result = simplify_mesh(&source, 24, config);
+  let face_count = result.index.len() / 3;
+  assert!(face_count <= 24);
+  assert!((signed_volume(&result) - volume).abs() / volume < 0.01);
+
+  // the mesh is still closed: every welded edge is shared by two faces in opposite direction
+  let mut edges = std::collections::HashMap::new();
+  for t in result.index.chunks_exact(3) {
+    let p = [t[0], t[1], t[2]].map(|i| {
+      let p = result.vertex[i as usize].position;
+      [p.x.to_bits(), p.y.to_bits(), p.z.to_bits()]
+    });
+    for i in 0..3 {
+      *edges.entry((p[i], p[(i + 1) % 3])).or_insert(0) += 1;
+    }
+  }
+  for (&(a, b), &count) in edges.iter() {
+    assert_eq!(count, 1);
+    assert_eq!(edges.get(&(b, a)), Some(&1));
+  }
+}
+
+#[test]
+fn simplify_preserve_seam() {
+  let source = build_cube_grid(6);
+  let result = simplify_mesh(&source, 24, SimplificationConfig::default());
+
+  // all vertices on the cube edges have discontinuous normals, so they are kept
+  let on_cube_edge = |p: Vec3<f32>| {
+    let on_side = |v: f32| v == 0. || v == 6.;
+    [p.x, p.y, p.z].into_iter().filter(|v| on_side(*v)).count() >= 2
+  };
+  let kept = source
+    .vertex
+    .iter()
+    .filter(|v| on_cube_edge(v.position))
+    .all(|v| result.vertex.iter().any(|r| r.position == v.position));
+  assert!(kept);
+  assert!(result.index.len() / 3 < source.index.len() / 3);
+}
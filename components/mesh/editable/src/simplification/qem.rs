@@ -1,4 +1,4 @@
-use std::ops::{Add, AddAssign};
+use std::ops::{Add, AddAssign, Mul};
 
 use rendiation_algebra::*;
 use rendiation_geometry::Plane;
@@ -15,12 +15,23 @@ impl QEM {
     QEM { mat: Mat4::zero() }
   }
 
+  /// the quadric error of the given position
+  pub fn error(&self, position: Vec3<f32>) -> f32 {
+    let v = Vec4::new(position.x, position.y, position.z, 1.0);
+    v.dot(self.mat * v)
+  }
+
+  /// solve the position that minimize the error, return None if the quadric is singular.
   pub fn compute_optimal_position(&self) -> Option<Vec3<f32>> {
-    let mut mat = self.mat.clone();
-    mat.c1 = 0.0;
-    mat.c2 = 0.0;
-    mat.c3 = 0.0;
-    mat.c4 = 1.0;
+    let mut mat = self.mat;
+    // replace the last row with (0, 0, 0, 1)
+    mat.a4 = 0.0;
+    mat.b4 = 0.0;
+    mat.c4 = 0.0;
+    mat.d4 = 1.0;
+    if mat.det().abs() < 1e-10 {
+      return None;
+    }
     mat
       .inverse()
       .map(|m| (m * Vec4::new(0.0, 0.0, 0.0, 1.0)).xyz())
@@ -33,7 +44,7 @@ impl From<Plane> for QEM {
     let a = plane.normal.x;
     let b = plane.normal.y;
     let c = plane.normal.z;
-    let d = -plane.constant;
+    let d = plane.constant;
 
     let mat = Mat4::new(
         a*a, a*b, a*c, a*d,
@@ -61,3 +72,13 @@ impl AddAssign for QEM {
     *self = *self + rhs
   }
 }
+
+impl Mul<f32> for QEM {
+  type Output = Self;
+
+  fn mul(self, weight: f32) -> Self {
+    Self {
+      mat: self.mat * weight,
+    }
+  }
+}
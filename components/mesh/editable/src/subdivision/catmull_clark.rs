@@ -0,0 +1,98 @@
+use super::*;
+
+impl<M> HalfEdgeMesh<M>
+where
+  M: HalfEdgeMeshData,
+  M::Vertex: SubdivisionVertex,
+  M::HalfEdge: SubdivisionEdge,
+{
+  /// Catmull-Clark subdivision, every n-gon is split into n quads.
+  ///
+  /// The boundary and sharp edges use the crease rule, and the vertex that connects more than two
+  /// sharp edges is kept as a corner.
+  ///
+  /// https://en.wikipedia.org/wiki/Catmull%E2%80%93Clark_subdivision_surface
+  pub fn catmull_clark_subdivide(&self) -> Self {
+    let data = |v: Handle<HalfEdgeVertex<M>>| &self.vertices[v].data;
+
+    let mut face_index = FastHashMap::default();
+    let face_points: Vec<_> = self
+      .faces
+      .iter()
+      .map(|(handle, face)| {
+        face_index.insert(handle, face_index.len());
+        let n = face.side_count(self) as f32;
+        M::Vertex::weighted_sum(
+          face
+            .iter_half_edge(self)
+            .map(|(e, _)| (data(e.vert()), 1. / n)),
+        )
+      })
+      .collect();
+
+    let mut vertex_index = FastHashMap::default();
+    let mut vertices =
+      Vec::with_capacity(self.vertices.len() + self.half_edges.len() / 2 + self.faces.len());
+    for (handle, vertex) in self.vertices.iter() {
+      // (n - 2) / n * P + 1 / n * avg(neighbors) + 1 / n * avg(face points)
+      let point = sharp_vertex_point(self, handle).unwrap_or_else(|| {
+        let edges: Vec<_> = vertex.iter_out_half_edge(self).map(|(e, _)| e).collect();
+        let n = edges.len() as f32;
+        let weight = 1. / (n * n);
+        let neighbors = edges.iter().map(|e| (data(e.end(self)), weight));
+        let faces = edges
+          .iter()
+          .map(|e| (&face_points[face_index[&e.face()]], weight));
+        let center = (&vertex.data, (n - 2.) / n);
+        M::Vertex::weighted_sum(neighbors.chain(faces).chain([center]))
+      });
+      vertex_index.insert(handle, vertices.len());
+      vertices.push(point);
+    }
+
+    let edge_index = self.index_edges(vertices.len());
+    for (handle, edge) in self.half_edges.iter() {
+      if edge_index[&handle] != vertices.len() {
+        continue;
+      }
+      let (start, end) = (edge.vert(), edge.end(self));
+      let point = if self.is_sharp_edge(edge) {
+        M::Vertex::weighted_sum([(data(start), 0.5), (data(end), 0.5)])
+      } else {
+        let pair = &self.half_edges[edge.pair().unwrap()];
+        M::Vertex::weighted_sum([
+          (data(start), 0.25),
+          (data(end), 0.25),
+          (&face_points[face_index[&edge.face()]], 0.25),
+          (&face_points[face_index[&pair.face()]], 0.25),
+        ])
+      };
+      vertices.push(point);
+    }
+
+    let face_start = vertices.len();
+    vertices.extend(face_points);
+
+    let polygons = self
+      .faces
+      .iter()
+      .flat_map(|(handle, face)| {
+        let center = face_start + face_index[&handle];
+        face
+          .iter_half_edge(self)
+          .map(|(edge, handle)| {
+            let previous = edge_index[&edge.prev()];
+            vec![
+              vertex_index[&edge.vert()],
+              edge_index[&handle],
+              center,
+              previous,
+            ]
+          })
+          .collect::<Vec<_>>()
+      })
+      .collect();
+
+    self.build_subdivided(vertices, polygons, &vertex_index, &edge_index)
+  }
+}
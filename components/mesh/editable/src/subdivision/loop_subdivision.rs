@@ -0,0 +1,84 @@
+use std::f32::consts::PI;
+
+use super::*;
+
+impl<M> HalfEdgeMesh<M>
+where
+  M: HalfEdgeMeshData,
+  M::Vertex: SubdivisionVertex,
+  M::HalfEdge: SubdivisionEdge,
+{
+  /// Loop subdivision, every triangle is split into four, the mesh must be a triangle mesh.
+  ///
+  /// The boundary and sharp edges use the crease rule, and the vertex that connects more than two
+  /// sharp edges is kept as a corner.
+  ///
+  /// https://www.microsoft.com/en-us/research/wp-content/uploads/2016/02/thesis-10.pdf
+  pub fn loop_subdivide(&self) -> Result<Self, SubdivisionError> {
+    if self.faces.iter().any(|(_, f)| f.side_count(self) != 3) {
+      return Err(SubdivisionError::NonTriangleFace);
+    }
+    let data = |v: Handle<HalfEdgeVertex<M>>| &self.vertices[v].data;
+
+    let mut vertex_index = FastHashMap::default();
+    let mut vertices = Vec::with_capacity(self.vertices.len() + self.half_edges.len() / 2);
+    for (handle, vertex) in self.vertices.iter() {
+      let point = sharp_vertex_point(self, handle).unwrap_or_else(|| {
+        let neighbors: Vec<_> = vertex
+          .iter_out_half_edge(self)
+          .map(|(e, _)| e.end(self))
+          .collect();
+        let n = neighbors.len() as f32;
+        let beta = (0.625 - (0.375 + 0.25 * (2. * PI / n).cos()).powi(2)) / n;
+        let neighbors = neighbors.iter().map(|&v| (data(v), beta));
+        M::Vertex::weighted_sum(neighbors.chain([(&vertex.data, 1. - n * beta)]))
+      });
+      vertex_index.insert(handle, vertices.len());
+      vertices.push(point);
+    }
+
+    let edge_index = self.index_edges(vertices.len());
+    for (handle, edge) in self.half_edges.iter() {
+      if edge_index[&handle] != vertices.len() {
+        continue;
+      }
+      let (start, end) = (edge.vert(), edge.end(self));
+      let point = if self.is_sharp_edge(edge) {
+        M::Vertex::weighted_sum([(data(start), 0.5), (data(end), 0.5)])
+      } else {
+        let opposite = |e: &HalfEdge<M>| self.half_edges[e.prev()].vert();
+        let pair = &self.half_edges[edge.pair().unwrap()];
+        M::Vertex::weighted_sum([
+          (data(start), 0.375),
+          (data(end), 0.375),
+          (data(opposite(edge)), 0.125),
+          (data(opposite(pair)), 0.125),
+        ])
+      };
+      vertices.push(point);
+    }
+
+    let polygons = self
+      .faces
+      .iter()
+      .flat_map(|(_, face)| {
+        let mut corners = [0; 3];
+        let mut middles = [0; 3];
+        for (i, (edge, handle)) in face.iter_half_edge(self).enumerate() {
+          corners[i] = vertex_index[&edge.vert()];
+          middles[i] = edge_index[&handle];
+        }
+        let [a, b, c] = corners;
+        let [ab, bc, ca] = middles;
+        [
+          vec![a, ab, ca],
+          vec![ab, b, bc],
+          vec![ca, bc, c],
+          vec![ab, bc, ca],
+        ]
+      })
+      .collect();
+
+    Ok(self.build_subdivided(vertices, polygons, &vertex_index, &edge_index))
+  }
+}
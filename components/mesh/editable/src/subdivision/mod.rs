@@ -0,0 +1,176 @@
+use arena::Handle;
+use fast_hash_collection::FastHashMap;
+use rendiation_algebra::*;
+use rendiation_renderable_mesh::vertex::Vertex as MeshVertex;
+
+use crate::*;
+
+mod catmull_clark;
+mod loop_subdivision;
+
+pub use catmull_clark::*;
+pub use loop_subdivision::*;
+
+/// The vertex payload that could be interpolated by subdivision.
+pub trait SubdivisionVertex: Sized {
+  /// Create the vertex by the weighted sum of the source vertices, the weights always sum to one.
+  fn weighted_sum<'a>(vertices: impl IntoIterator<Item = (&'a Self, f32)>) -> Self
+  where
+    Self: 'a;
+}
+
+impl SubdivisionVertex for Vec3<f32> {
+  fn weighted_sum<'a>(vertices: impl IntoIterator<Item = (&'a Self, f32)>) -> Self {
+    vertices
+      .into_iter()
+      .fold(Vec3::zero(), |sum, (v, weight)| sum + *v * weight)
+  }
+}
+
+impl SubdivisionVertex for MeshVertex {
+  fn weighted_sum<'a>(vertices: impl IntoIterator<Item = (&'a Self, f32)>) -> Self {
+    let mut result = MeshVertex::new(Vec3::zero(), Vec3::zero(), Vec2::zero());
+    for (v, weight) in vertices {
+      result.position = result.position + v.position * weight;
+      result.normal = result.normal + v.normal * weight;
+      result.uv = result.uv + v.uv * weight;
+    }
+    result.normal = result.normal.normalize();
+    result
+  }
+}
+
+/// The half edge payload that could mark the edge as sharp(crease) in subdivision.
+///
+/// The boundary edges are always treated as sharp.
+pub trait SubdivisionEdge: Default {
+  fn is_sharp(&self) -> bool {
+    false
+  }
+
+  /// Create the data of the child half edges that split from the parent half edge. The sharpness
+  /// should be inherited here to keep the crease in the next subdivision level.
+  fn split_from(_parent: &Self) -> Self {
+    Self::default()
+  }
+}
+
+impl SubdivisionEdge for () {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubdivisionError {
+  NonTriangleFace,
+}
+
+/// How the vertex position is updated, decided by the sharp edges around the vertex.
+enum VertexRule<M: HalfEdgeMeshData> {
+  Smooth,
+  /// the vertex is on a crease line, the two vertices are the neighbors on the line
+  Crease(Handle<HalfEdgeVertex<M>>, Handle<HalfEdgeVertex<M>>),
+  Corner,
+}
+
+impl<M> HalfEdgeMesh<M>
+where
+  M: HalfEdgeMeshData,
+  M::HalfEdge: SubdivisionEdge,
+{
+  fn is_sharp_edge(&self, edge: &HalfEdge<M>) -> bool {
+    match edge.pair() {
+      Some(pair) => edge.data.is_sharp() || self.half_edges[pair].data.is_sharp(),
+      None => true,
+    }
+  }
+
+  fn vertex_rule(&self, vertex: Handle<HalfEdgeVertex<M>>) -> VertexRule<M> {
+    let mut sharp_neighbors = Vec::new();
+    let mut is_boundary = false;
+    for (edge, _) in self.vertices[vertex].iter_half_edge(self) {
+      is_boundary |= edge.is_border();
+      if !self.is_sharp_edge(edge) {
+        continue;
+      }
+      let neighbor = if edge.vert() == vertex {
+        edge.end(self)
+      } else {
+        edge.vert()
+      };
+      if !sharp_neighbors.contains(&neighbor) {
+        sharp_neighbors.push(neighbor);
+      }
+    }
+
+    match sharp_neighbors.as_slice() {
+      &[a, b] => VertexRule::Crease(a, b),
+      // a boundary vertex always has two boundary edges unless the mesh is broken
+      _ if sharp_neighbors.len() > 2 || is_boundary => VertexRule::Corner,
+      _ => VertexRule::Smooth,
+    }
+  }
+
+  /// Assign an index to every edge, the pair half edges share the same index.
+  fn index_edges(&self, start: usize) -> FastHashMap<Handle<HalfEdge<M>>, usize> {
+    let mut edge_index = FastHashMap::default();
+    let mut next_index = start;
+    for (handle, edge) in self.half_edges.iter() {
+      let paired = edge.pair().and_then(|pair| edge_index.get(&pair).copied());
+      let index = paired.unwrap_or_else(|| {
+        next_index += 1;
+        next_index - 1
+      });
+      edge_index.insert(handle, index);
+    }
+    edge_index
+  }
+
+  /// Build the subdivided mesh, and set the data of the child half edges that lie on the parent
+  /// half edges.
+  fn build_subdivided(
+    &self,
+    vertices: Vec<M::Vertex>,
+    polygons: Vec<Vec<usize>>,
+    vertex_index: &FastHashMap<Handle<HalfEdgeVertex<M>>, usize>,
+    edge_index: &FastHashMap<Handle<HalfEdge<M>>, usize>,
+  ) -> Self {
+    let result = Self::from_polygons(vertices, polygons);
+    let mut mesh = result.mesh;
+    let new_vertex = |index: usize| result.vertices[index].unwrap();
+
+    for (handle, edge) in self.half_edges.iter() {
+      let start = new_vertex(vertex_index[&edge.vert()]);
+      let end = new_vertex(vertex_index[&edge.end(self)]);
+      let middle = new_vertex(edge_index[&handle]);
+      for (from, to) in [(start, middle), (middle, end)] {
+        let child = HalfEdge::get_by_two_points(&mesh, from, to).unwrap();
+        mesh.half_edges.get_mut(child).unwrap().data = M::HalfEdge::split_from(&edge.data);
+      }
+    }
+
+    mesh
+  }
+}
+
+/// the position update of the vertex on the crease or corner, return None if the vertex is smooth
+fn sharp_vertex_point<M>(
+  mesh: &HalfEdgeMesh<M>,
+  vertex: Handle<HalfEdgeVertex<M>>,
+) -> Option<M::Vertex>
+where
+  M: HalfEdgeMeshData,
+  M::Vertex: SubdivisionVertex,
+  M::HalfEdge: SubdivisionEdge,
+{
+  let data = |v: Handle<HalfEdgeVertex<M>>| &mesh.vertices[v].data;
+  match mesh.vertex_rule(vertex) {
+    VertexRule::Smooth => None,
+    VertexRule::Crease(a, b) => Some(M::Vertex::weighted_sum([
+      (data(vertex), 0.75),
+      (data(a), 0.125),
+      (data(b), 0.125),
+    ])),
+    VertexRule::Corner => Some(M::Vertex::weighted_sum([(data(vertex), 1.)])),
+  }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,134 @@
+use super::*;
+
+pub struct TestSubdivisionMesh;
+
+impl HalfEdgeMeshData for TestSubdivisionMesh {
+  type Face = ();
+  type HalfEdge = TestEdge;
+  type Vertex = Vec3<f32>;
+}
+
+#[derive(Default)]
+pub struct TestEdge {
+  pub sharp: bool,
+}
+
+impl SubdivisionEdge for TestEdge {
+  fn is_sharp(&self) -> bool {
+    self.sharp
+  }
+  fn split_from(parent: &Self) -> Self {
+    Self {
+      sharp: parent.sharp,
+    }
+  }
+}
+
+pub type Mesh = HalfEdgeMesh<TestSubdivisionMesh>;
+
+/// the cube in [-1, 1]
+pub fn build_cube() -> Mesh {
+  let vertices = (0..8).map(|i| {
+    let s = |bit: usize| if i & bit != 0 { 1. } else { -1. };
+    Vec3::new(s(1), s(2), s(4))
+  });
+  let quads = [
+    [0, 2, 3, 1],
+    [4, 5, 7, 6],
+    [0, 1, 5, 4],
+    [2, 6, 7, 3],
+    [0, 4, 6, 2],
+    [1, 3, 7, 5],
+  ];
+  Mesh::from_polygons(vertices, quads).mesh
+}
+
+/// the flat n x n grid of triangles on xy plane
+pub fn build_grid(n: usize) -> Mesh {
+  let vertices = (0..=n).flat_map(|y| (0..=n).map(move |x| Vec3::new(x as f32, y as f32, 0.)));
+  Mesh::from_triangles(vertices, half_edge_mesh::test::grid_triangles(n)).mesh
+}
+
+pub fn mark_all_sharp(mesh: &mut Mesh) {
+  let handles: Vec<_> = mesh.iter_half_edge().map(|(h, _)| h).collect();
+  for h in handles {
+    mesh.half_edges.get_mut(h).unwrap().data.sharp = true;
+  }
+}
+
+fn max_norm(v: Vec3<f32>) -> f32 {
+  v.x.abs().max(v.y.abs()).max(v.z.abs())
+}
+
+#[test]
+fn loop_subdivide_grid() {
+  let mesh = build_grid(2);
+  let result = mesh.loop_subdivide().unwrap();
+  assert_eq!(result.validate(), Ok(()));
+  assert_eq!(result.face_count(), 32);
+  assert_eq!(result.vertex_count(), 9 + 16);
+
+  // the flat mesh keeps flat, and the boundary edge points are on the boundary lines
+  assert!(result.iter_vertex().all(|(_, v)| v.data.z == 0.));
+  assert!(result
+    .iter_vertex()
+    .any(|(_, v)| v.data == Vec3::new(0.5, 0., 0.)));
+
+  // the interior vertex with the regular valence keeps in place on the flat uniform grid
+  let center = result
+    .iter_vertex()
+    .find(|(_, v)| v.data == Vec3::new(1., 1., 0.));
+  assert!(center.is_some());
+
+  let quads = build_cube();
+  assert_eq!(
+    quads.loop_subdivide().err(),
+    Some(SubdivisionError::NonTriangleFace)
+  );
+}
+
+#[test]
+fn catmull_clark_cube() {
+  let mesh = build_cube();
+  let result = mesh.catmull_clark_subdivide();
+  assert_eq!(result.validate(), Ok(()));
+  assert_eq!(result.face_count(), 24);
+  assert_eq!(result.vertex_count(), 8 + 12 + 6);
+  assert!(result.iter_face().all(|(_, f)| f.side_count(&result) == 4));
+
+  let corner = 5. / 9.;
+  let corners = result
+    .iter_vertex()
+    .filter(|(_, v)| (max_norm(v.data) - corner).abs() < 1e-5)
+    .count();
+  assert_eq!(corners, 8);
+
+  // the surface converges to a smooth closed shape inside the cube
+  let result = result.catmull_clark_subdivide().catmull_clark_subdivide();
+  assert_eq!(result.validate(), Ok(()));
+  assert_eq!(result.face_count(), 24 * 16);
+  assert!(result.iter_vertex().all(|(_, v)| max_norm(v.data) < 1.));
+}
+
+#[test]
+fn sharp_edges() {
+  // all edges are sharp, the cube corners are kept and the edges stay straight
+  let mut mesh = build_cube();
+  mark_all_sharp(&mut mesh);
+  let result = mesh.catmull_clark_subdivide().catmull_clark_subdivide();
+  assert_eq!(result.validate(), Ok(()));
+  assert!(result
+    .iter_vertex()
+    .all(|(_, v)| (max_norm(v.data) - 1.).abs() < 1e-5));
+  assert!(result
+    .iter_vertex()
+    .any(|(_, v)| v.data == Vec3::new(1., 1., 1.)));
+
+  let mut mesh = build_grid(2);
+  mark_all_sharp(&mut mesh);
+  let result = mesh.loop_subdivide().unwrap().loop_subdivide().unwrap();
+  assert_eq!(result.validate(), Ok(()));
+  assert!(result
+    .iter_vertex()
+    .any(|(_, v)| v.data == Vec3::new(2., 2., 0.)));
+}
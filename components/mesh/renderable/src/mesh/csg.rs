@@ -0,0 +1,123 @@
+use fast_hash_collection::FastHashMap;
+use rendiation_geometry::Triangle;
+use space_algorithm::bsp::csg::{CSGMesh, CSGVertex};
+
+use super::{AbstractMesh, IndexedMesh, TriangleList};
+use crate::vertex::Vertex;
+
+impl From<Vertex> for CSGVertex {
+  fn from(v: Vertex) -> Self {
+    CSGVertex::new(v.position, v.normal, v.uv)
+  }
+}
+
+impl From<CSGVertex> for Vertex {
+  fn from(v: CSGVertex) -> Self {
+    Vertex::new(v.position, v.normal, v.uv)
+  }
+}
+
+pub trait CSGMeshBuildAbleMesh {
+  fn build_csg_mesh(&self) -> CSGMesh;
+}
+
+impl<G> CSGMeshBuildAbleMesh for G
+where
+  G: AbstractMesh,
+  G::Primitive: Into<Triangle<Vertex>>,
+{
+  fn build_csg_mesh(&self) -> CSGMesh {
+    CSGMesh::from_triangles(self.primitive_iter().map(|t| {
+      let t: Triangle<Vertex> = t.into();
+      [t.a.into(), t.b.into(), t.c.into()]
+    }))
+  }
+}
+
+impl From<&CSGMesh> for IndexedMesh<TriangleList, Vec<Vertex>, Vec<u32>> {
+  /// The vertices that have the same attributes are merged.
+  fn from(mesh: &CSGMesh) -> Self {
+    let mut deduplicate = FastHashMap::<Vertex, u32>::default();
+    let mut vertices = Vec::new();
+    let indices = mesh
+      .triangles()
+      .flatten()
+      .map(|v| {
+        *deduplicate.entry(v.into()).or_insert_with(|| {
+          vertices.push(v.into());
+          vertices.len() as u32 - 1
+        })
+      })
+      .collect();
+    IndexedMesh::new(vertices, indices)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use rendiation_algebra::*;
+
+  use super::*;
+
+  #[test]
+  fn csg_indexed_mesh_conversion() {
+    // two triangles of the quad on xy plane
+    let vertices = vec![
+      Vertex::new(
+        Vec3::new(0., 0., 0.),
+        Vec3::new(0., 0., 1.),
+        Vec2::new(0., 0.),
+      ),
+      Vertex::new(
+        Vec3::new(1., 0., 0.),
+        Vec3::new(0., 0., 1.),
+        Vec2::new(1., 0.),
+      ),
+      Vertex::new(
+        Vec3::new(1., 1., 0.),
+        Vec3::new(0., 0., 1.),
+        Vec2::new(1., 1.),
+      ),
+      Vertex::new(
+        Vec3::new(0., 1., 0.),
+        Vec3::new(0., 0., 1.),
+        Vec2::new(0., 1.),
+      ),
+    ];
+    let mesh: IndexedMesh<TriangleList, Vec<Vertex>, Vec<u32>> =
+      IndexedMesh::new(vertices.clone(), vec![0, 1, 2, 0, 2, 3]);
+
+    let csg = mesh.build_csg_mesh();
+    assert_eq!(csg.polygon_count(), 2);
+
+    // the shared vertices of the triangles are merged
+    let result: IndexedMesh<TriangleList, Vec<Vertex>, Vec<u32>> = (&csg).into();
+    assert_eq!(result.vertex.len(), 4);
+    assert_eq!(result.index.len(), 6);
+    for v in &result.vertex {
+      assert!(vertices.contains(v));
+    }
+    let triangles: Vec<_> = result
+      .index
+      .chunks_exact(3)
+      .map(|t| [0, 1, 2].map(|i| result.vertex[t[i] as usize].position))
+      .collect();
+    for t in mesh.index.chunks_exact(3) {
+      let source = [0, 1, 2].map(|i| vertices[t[i] as usize].position);
+      assert!(triangles.contains(&source));
+    }
+
+    // the vertices that only differ in normal are not merged
+    let mut vertices = vertices;
+    vertices.push(Vertex::new(
+      Vec3::new(0., 0., 0.),
+      Vec3::new(0., 0.6, 0.8),
+      Vec2::new(0., 0.),
+    ));
+    let mesh: IndexedMesh<TriangleList, Vec<Vertex>, Vec<u32>> =
+      IndexedMesh::new(vertices, vec![0, 1, 2, 4, 2, 3]);
+    let result: IndexedMesh<TriangleList, Vec<Vertex>, Vec<u32>> = (&mesh.build_csg_mesh()).into();
+    assert_eq!(result.vertex.len(), 5);
+    assert_eq!(result.index.len(), 6);
+  }
+}
@@ -1,9 +1,11 @@
 pub mod bvh;
 pub mod container;
 pub mod conversion;
+pub mod csg;
 pub mod intersection;
 pub mod primitive;
 pub use bvh::*;
 pub use container::*;
+pub use csg::*;
 pub use intersection::*;
 pub use primitive::*;
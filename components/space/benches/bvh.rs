@@ -1,38 +1,92 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rendiation_algebra::Vec3;
+use rendiation_geometry::{Box3, IntersectAble, Ray3};
 use space_algorithm::{
-  bvh::test::bvh_build, bvh::BalanceTree, bvh::SAH, utils::generate_boxes_in_space,
-  utils::TreeBuildOption,
+  bvh::test::bvh_build, bvh::BalanceTree, bvh::BinnedSAH, bvh::FlattenBVH, bvh::SAH,
+  utils::generate_boxes_in_space, utils::TreeBuildOption,
 };
 
+fn build_option() -> TreeBuildOption {
+  TreeBuildOption {
+    max_tree_depth: 15,
+    bin_size: 10,
+  }
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
   let boxes = generate_boxes_in_space(black_box(20000), black_box(10000.), black_box(1.));
 
   c.bench_function("balance bvh build perf", |b| {
-    b.iter(|| {
-      bvh_build(
-        &boxes,
-        &mut BalanceTree,
-        &TreeBuildOption {
-          max_tree_depth: 15,
-          bin_size: 10,
-        },
-      )
-    })
+    b.iter(|| bvh_build(&boxes, &mut BalanceTree, &build_option()))
   });
 
   c.bench_function("sah bvh build perf", |b| {
-    b.iter(|| {
-      bvh_build(
-        &boxes,
-        &mut SAH::new(4),
-        &TreeBuildOption {
-          max_tree_depth: 15,
-          bin_size: 10,
-        },
-      )
-    })
+    b.iter(|| bvh_build(&boxes, &mut SAH::new(4), &build_option()))
+  });
+
+  c.bench_function("binned sah bvh build perf", |b| {
+    b.iter(|| bvh_build(&boxes, &mut BinnedSAH::new(16), &build_option()))
+  });
+
+  #[cfg(feature = "parallel")]
+  c.bench_function("parallel binned sah bvh build perf", |b| {
+    b.iter(|| FlattenBVH::new_parallel(boxes.iter().cloned(), &BinnedSAH::new(16), &build_option()))
   });
 }
 
-criterion_group!(benches, criterion_benchmark);
+/// count the visited nodes and tested primitives of the rays, as the traversal cost
+fn traverse_rays(bvh: &FlattenBVH<Box3>, boxes: &[Box3], rays: &[Ray3]) -> usize {
+  let mut node_visited = 0;
+  let mut primitive_tested = 0;
+  for ray in rays {
+    bvh.traverse(
+      |node| {
+        node_visited += 1;
+        node.bounding.intersect(ray, &())
+      },
+      |leaf| {
+        for &i in leaf.iter_primitive(bvh) {
+          primitive_tested += 1;
+          black_box::<bool>(boxes[i].intersect(ray, &()));
+        }
+        true
+      },
+    );
+  }
+  node_visited + primitive_tested
+}
+
+fn traverse_benchmark(c: &mut Criterion) {
+  let boxes = generate_boxes_in_space(20000, 10000., 10.);
+  let targets = generate_boxes_in_space(1000, 10000., 1.);
+  let rays: Vec<_> = targets
+    .iter()
+    .map(|target| Ray3::from_point_to_point(Vec3::new(-100., 5000., 5000.), target.center()))
+    .collect();
+
+  let trees = [
+    (
+      "balance",
+      bvh_build(&boxes, &mut BalanceTree, &build_option()),
+    ),
+    ("sah", bvh_build(&boxes, &mut SAH::new(4), &build_option())),
+    (
+      "binned sah",
+      bvh_build(&boxes, &mut BinnedSAH::new(16), &build_option()),
+    ),
+  ];
+
+  for (name, bvh) in &trees {
+    println!(
+      "{name} bvh sah cost: {}, ray traversal cost: {}",
+      bvh.sah_cost(),
+      traverse_rays(bvh, &boxes, &rays)
+    );
+    c.bench_function(&format!("{name} bvh ray traverse perf"), |b| {
+      b.iter(|| traverse_rays(bvh, &boxes, &rays))
+    });
+  }
+}
+
+criterion_group!(benches, criterion_benchmark, traverse_benchmark);
 criterion_main!(benches);
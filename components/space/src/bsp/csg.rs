@@ -1,61 +1,112 @@
 use rendiation_algebra::*;
-use rendiation_geometry::{Plane, Triangle};
+use rendiation_geometry::{DistanceTo, Plane};
 
-use super::BspNode;
 use crate::*;
 
-type CSGNode = BspNode<Polygon>;
+/// The default distance tolerance to classify a point onto the plane.
+pub const CSG_DEFAULT_EPSILON: f32 = 1e-5;
 
 #[derive(Clone)]
 pub struct CSGMesh {
   polygons: Vec<Polygon>,
+  epsilon: f32,
 }
 
 impl CSGMesh {
-  fn from_polygons(polygons: Vec<Polygon>) -> Self {
-    Self { polygons }
+  fn from_polygons(polygons: Vec<Polygon>, epsilon: f32) -> Self {
+    Self { polygons, epsilon }
+  }
+
+  /// Create the mesh from triangles, the triangle that has no area is skipped.
+  ///
+  /// The triangles should form closed solids, the front face is counter clockwise.
+  pub fn from_triangles(triangles: impl IntoIterator<Item = [CSGVertex; 3]>) -> Self {
+    Self::from_convex_polygons(triangles.into_iter().map(Vec::from))
+  }
+
+  /// Create the mesh from convex planar polygons, the polygon that has no area is skipped.
+  pub fn from_convex_polygons(polygons: impl IntoIterator<Item = Vec<CSGVertex>>) -> Self {
+    let polygons = polygons.into_iter().filter_map(Polygon::new).collect();
+    Self::from_polygons(polygons, CSG_DEFAULT_EPSILON)
+  }
+
+  /// Set the distance tolerance to classify a vertex onto a splitting plane. The larger epsilon
+  /// produces less slivers but more snapping error. The result of the boolean operations keeps the
+  /// epsilon of self.
+  #[must_use]
+  pub fn with_epsilon(mut self, epsilon: f32) -> Self {
+    self.epsilon = epsilon;
+    self
+  }
+
+  pub fn epsilon(&self) -> f32 {
+    self.epsilon
+  }
+
+  pub fn polygon_count(&self) -> usize {
+    self.polygons.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.polygons.is_empty()
+  }
+
+  pub fn iter_polygons(&self) -> impl Iterator<Item = &[CSGVertex]> {
+    self.polygons.iter().map(|p| p.vertices.as_slice())
+  }
+
+  /// Triangulate the convex polygons by fan, the vertex normals are normalized.
+  pub fn triangles(&self) -> impl Iterator<Item = [CSGVertex; 3]> + '_ {
+    self.polygons.iter().flat_map(|polygon| {
+      let vertices = &polygon.vertices;
+      (1..vertices.len() - 1)
+        .map(move |i| [vertices[0], vertices[i], vertices[i + 1]].map(CSGVertex::normalized))
+    })
   }
 
   #[must_use]
   pub fn union(&self, other: Self) -> Self {
-    let mut a = CSGNode::from_polygons(self.polygons.clone());
-    let mut b = CSGNode::from_polygons(other.polygons);
-    a.clip_to(&b);
-    b.clip_to(&a);
+    let epsilon = self.epsilon;
+    let mut a = CSGNode::from_polygons(self.polygons.clone(), epsilon);
+    let mut b = CSGNode::from_polygons(other.polygons, epsilon);
+    a.clip_to(&b, epsilon);
+    b.clip_to(&a, epsilon);
     b.invert();
-    b.clip_to(&a);
+    b.clip_to(&a, epsilon);
     b.invert();
-    a.build(b.all_polygons());
-    Self::from_polygons(a.all_polygons())
+    a.build(b.all_polygons(), epsilon);
+    Self::from_polygons(a.all_polygons(), epsilon)
   }
 
   #[must_use]
   pub fn subtract(&self, other: Self) -> Self {
-    let mut a = CSGNode::from_polygons(self.polygons.clone());
-    let mut b = CSGNode::from_polygons(other.polygons);
+    let epsilon = self.epsilon;
+    let mut a = CSGNode::from_polygons(self.polygons.clone(), epsilon);
+    let mut b = CSGNode::from_polygons(other.polygons, epsilon);
     a.invert();
-    a.clip_to(&b);
-    b.clip_to(&a);
+    a.clip_to(&b, epsilon);
+    b.clip_to(&a, epsilon);
     b.invert();
-    b.clip_to(&a);
+    b.clip_to(&a, epsilon);
     b.invert();
-    a.build(b.all_polygons());
+    a.build(b.all_polygons(), epsilon);
     a.invert();
-    Self::from_polygons(a.all_polygons())
+    Self::from_polygons(a.all_polygons(), epsilon)
   }
 
   #[must_use]
   pub fn intersect(&self, other: Self) -> Self {
-    let mut a = CSGNode::from_polygons(self.polygons.clone());
-    let mut b = CSGNode::from_polygons(other.polygons);
+    let epsilon = self.epsilon;
+    let mut a = CSGNode::from_polygons(self.polygons.clone(), epsilon);
+    let mut b = CSGNode::from_polygons(other.polygons, epsilon);
     a.invert();
-    b.clip_to(&a);
+    b.clip_to(&a, epsilon);
     b.invert();
-    a.clip_to(&b);
-    b.clip_to(&a);
-    a.build(b.all_polygons());
+    a.clip_to(&b, epsilon);
+    b.clip_to(&a, epsilon);
+    a.build(b.all_polygons(), epsilon);
     a.invert();
-    Self::from_polygons(a.all_polygons())
+    Self::from_polygons(a.all_polygons(), epsilon)
   }
 
   #[must_use]
@@ -68,12 +119,47 @@ impl CSGMesh {
   }
 }
 
+/// The bsp node of csg. Unlike [super::BspNode], the plane is kept even if all the coplanar
+/// polygons are clipped away, because the plane still partitions the space.
+#[derive(Default)]
+struct CSGNode {
+  plane: Option<Plane>,
+  coplanar: Vec<Polygon>,
+  front: Option<Box<CSGNode>>,
+  back: Option<Box<CSGNode>>,
+}
+
+impl AbstractTreeNode for CSGNode {
+  fn visit_children(&self, mut visitor: impl FnMut(&Self)) {
+    if let Some(n) = self.front.as_ref() {
+      visitor(n.as_ref())
+    }
+    if let Some(n) = self.back.as_ref() {
+      visitor(n.as_ref())
+    }
+  }
+}
+
+impl AbstractTreeMutNode for CSGNode {
+  fn visit_children_mut(&mut self, mut visitor: impl FnMut(&mut Self)) {
+    if let Some(n) = self.front.as_mut() {
+      visitor(n.as_mut())
+    }
+    if let Some(n) = self.back.as_mut() {
+      visitor(n.as_mut())
+    }
+  }
+}
+
 impl CSGNode {
   /// Convert solid space to empty space and empty space to solid space.
   fn invert(&mut self) {
     for polygon in &mut self.coplanar {
       polygon.flip();
     }
+    if let Some(plane) = &mut self.plane {
+      plane.flip();
+    }
     if let Some(front) = &mut self.front {
       front.invert();
     }
@@ -84,44 +170,45 @@ impl CSGNode {
   }
 
   /// Recursively remove all polygons in `polygons` that are inside this BSP tree.
-  #[allow(clippy::ptr_arg)]
-  fn clip_polygons(&self, polygons: &Vec<Polygon>) -> Vec<Polygon> {
-    if let Some(first) = &self.coplanar.first() {
-      let plane = first.plane;
+  fn clip_polygons(&self, polygons: &[Polygon], epsilon: f32) -> Vec<Polygon> {
+    let plane = if let Some(plane) = self.plane {
+      plane
+    } else {
+      return polygons.to_vec();
+    };
+
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    for polygon in polygons {
       let mut coplanar_front = Vec::new();
       let mut coplanar_back = Vec::new();
-      let mut front = Vec::new();
-      let mut back = Vec::new();
-
-      for polygon in &self.coplanar {
-        polygon.split(
-          plane,
-          &mut coplanar_front,
-          &mut coplanar_back,
-          &mut front,
-          &mut back,
-        );
-      }
-
-      let mut result = Vec::new();
+      polygon.split(
+        plane,
+        epsilon,
+        &mut coplanar_front,
+        &mut coplanar_back,
+        &mut front,
+        &mut back,
+      );
+      front.extend(coplanar_front);
+      back.extend(coplanar_back);
+    }
 
-      if let Some(front_node) = &self.front {
-        result.extend(front_node.clip_polygons(&coplanar_front));
-        result.extend(front_node.clip_polygons(&front));
-      }
-      if let Some(back_node) = &self.back {
-        result.extend(back_node.clip_polygons(&coplanar_back));
-        result.extend(back_node.clip_polygons(&back));
-      }
-      result
+    let mut result = if let Some(front_node) = &self.front {
+      front_node.clip_polygons(&front, epsilon)
     } else {
-      polygons.clone()
+      front
+    };
+    // the back of the leaf plane is inside the solid
+    if let Some(back_node) = &self.back {
+      result.extend(back_node.clip_polygons(&back, epsilon));
     }
+    result
   }
 
   /// Remove all polygons in this BSP tree that are inside the other BSP tree `bsp`.
-  fn clip_to(&mut self, bsp: &Self) {
-    self.traverse_mut(&mut |n| n.coplanar = bsp.clip_polygons(&n.coplanar));
+  fn clip_to(&mut self, bsp: &Self, epsilon: f32) {
+    self.traverse_mut(&mut |n| n.coplanar = bsp.clip_polygons(&n.coplanar, epsilon));
   }
 
   /// Return a list of all polygons in this BSP tree.
@@ -135,12 +222,12 @@ impl CSGNode {
   /// new polygons are filtered down to the bottom of the tree and become new
   /// nodes there. Each set of polygons is partitioned using the first polygon
   /// (no heuristic is used to pick a good split).
-  fn build(&mut self, polygons: Vec<Polygon>) {
+  fn build(&mut self, polygons: Vec<Polygon>, epsilon: f32) {
     if polygons.is_empty() {
       return;
     }
 
-    let plane = self.coplanar.first().unwrap_or(&polygons[0]).plane;
+    let plane = *self.plane.get_or_insert(polygons[0].plane);
 
     let mut front = Vec::new();
     let mut back = Vec::new();
@@ -150,6 +237,7 @@ impl CSGNode {
     for polygon in polygons {
       polygon.split(
         plane,
+        epsilon,
         &mut self.coplanar,
         &mut other_coplanar,
         &mut front,
@@ -159,28 +247,43 @@ impl CSGNode {
 
     self.coplanar.extend(other_coplanar);
 
-    self.front.get_or_insert_default().build(front);
-    self.back.get_or_insert_default().build(back);
+    // only create the child when required, the missing back child means the solid space
+    if !front.is_empty() {
+      self.front.get_or_insert_default().build(front, epsilon);
+    }
+    if !back.is_empty() {
+      self.back.get_or_insert_default().build(back, epsilon);
+    }
   }
 
-  fn from_polygons(polygons: Vec<Polygon>) -> Self {
+  fn from_polygons(polygons: Vec<Polygon>, epsilon: f32) -> Self {
     let mut node = Self::default();
-    node.build(polygons);
+    node.build(polygons, epsilon);
     node
   }
 }
 
-#[derive(Clone, Copy)]
-struct Vertex {
+/// The vertex of the csg mesh, the attributes are interpolated when the polygon is split.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CSGVertex {
   pub position: Vec3<f32>,
   pub normal: Vec3<f32>,
-  pub uv: Vec3<f32>,
+  pub uv: Vec2<f32>,
 }
 
-impl Vertex {
+impl CSGVertex {
+  pub fn new(position: Vec3<f32>, normal: Vec3<f32>, uv: Vec2<f32>) -> Self {
+    Self {
+      position,
+      normal,
+      uv,
+    }
+  }
+
   fn flip(&mut self) {
     self.normal = self.normal.reverse();
   }
+
   fn lerp(&self, other: Self, t: f32) -> Self {
     Self {
       position: self.position.lerp(other.position, t),
@@ -188,26 +291,57 @@ impl Vertex {
       uv: self.uv.lerp(other.uv, t),
     }
   }
+
+  fn normalized(mut self) -> Self {
+    if self.normal.length2() > 0. {
+      self.normal = self.normal.normalize();
+    }
+    self
+  }
 }
 
-/// Represents a convex polygon. The vertices used to initialize a polygon must
-/// be coplanar and form a convex loop. They do not have to be `Vertex`
-/// instances but they must behave similarly (duck typing can be used for
-/// customization).
+/// Represents a convex polygon. The vertices must be coplanar and form a convex loop.
 #[derive(Clone)]
 struct Polygon {
   plane: Plane,
-  vertices: Vec<Vertex>,
+  vertices: Vec<CSGVertex>,
 }
 
 const COPLANAR: u8 = 0;
 const FRONT: u8 = 1;
 const BACK: u8 = 2;
 const SPANNING: u8 = 3;
-const EPSILON: f32 = 1e-5;
 
 impl Polygon {
+  /// Create the polygon, the plane is computed by Newell's method so the collinear vertices are
+  /// allowed. Return None if the polygon has no area.
+  fn new(vertices: Vec<CSGVertex>) -> Option<Self> {
+    if vertices.len() < 3 {
+      return None;
+    }
+    let mut normal = Vec3::zero();
+    let mut center = Vec3::zero();
+    for (i, current) in vertices.iter().enumerate() {
+      let current = current.position;
+      let next = vertices[(i + 1) % vertices.len()].position;
+      normal.x += (current.y - next.y) * (current.z + next.z);
+      normal.y += (current.z - next.z) * (current.x + next.x);
+      normal.z += (current.x - next.x) * (current.y + next.y);
+      center += current;
+    }
+    if normal.length2() <= f32::EPSILON * f32::EPSILON {
+      return None;
+    }
+    center /= vertices.len() as f32;
+    Self {
+      plane: Plane::from_normal_and_plane_point(normal, center),
+      vertices,
+    }
+    .into()
+  }
+
   fn flip(&mut self) {
+    self.vertices.reverse();
     for v in &mut self.vertices {
       v.flip()
     }
@@ -222,6 +356,7 @@ impl Polygon {
   fn split(
     &self,
     plane: Plane,
+    epsilon: f32,
     coplanar_front: &mut Vec<Polygon>,
     coplanar_back: &mut Vec<Polygon>,
     front: &mut Vec<Polygon>,
@@ -230,18 +365,20 @@ impl Polygon {
     // Classify each point as well as the entire polygon into one of the above
     // four classes.
     let mut polygon_type = 0;
-    let mut types = Vec::new();
+    let mut types = Vec::with_capacity(self.vertices.len());
+    let mut distances = Vec::with_capacity(self.vertices.len());
     for vertex in &self.vertices {
-      let t = plane.normal.dot(vertex.position) - plane.constant;
-      let ty = if t < -EPSILON {
+      let t = plane.distance_to(&vertex.position);
+      let ty = if t < -epsilon {
         BACK
-      } else if t > EPSILON {
+      } else if t > epsilon {
         FRONT
       } else {
         COPLANAR
       };
       polygon_type |= ty;
       types.push(ty);
+      distances.push(t);
     }
 
     // Put the polygon in the correct list, splitting it when necessary.
@@ -273,33 +410,27 @@ impl Polygon {
             b.push(vi);
           }
           if (ti | tj) == SPANNING {
-            let t = (plane.constant - plane.normal.dot(vi.position))
-              / plane.normal.dot(vj.position - vi.position);
+            let t = distances[i] / (distances[i] - distances[j]);
             let v = vi.lerp(vj, t);
             f.push(v);
             b.push(v);
           }
         }
+        // the fragments keep the plane of the source polygon to avoid the precision loss
         if f.len() >= 3 {
-          front.push(Polygon::new(f));
+          front.push(Polygon {
+            plane: self.plane,
+            vertices: f,
+          });
         }
         if b.len() >= 3 {
-          back.push(Polygon::new(b));
+          back.push(Polygon {
+            plane: self.plane,
+            vertices: b,
+          });
         }
       }
       _ => unreachable!(),
     }
   }
-
-  fn new(vertices: Vec<Vertex>) -> Self {
-    let triangle = Triangle {
-      a: vertices[0].position,
-      b: vertices[1].position,
-      c: vertices[2].position,
-    };
-    Self {
-      plane: triangle.into(),
-      vertices,
-    }
-  }
 }
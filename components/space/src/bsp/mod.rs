@@ -7,6 +7,8 @@
 use crate::*;
 
 pub mod csg;
+#[cfg(test)]
+mod test;
 
 impl<T> AbstractTreeNode for BspNode<T> {
   fn visit_children(&self, mut visitor: impl FnMut(&Self)) {
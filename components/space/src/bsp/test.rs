@@ -0,0 +1,97 @@
+use rendiation_algebra::*;
+
+use super::csg::{CSGMesh, CSGVertex};
+
+fn cube(min: Vec3<f32>, max: Vec3<f32>) -> CSGMesh {
+  // axis, direction, the two tangent axes in counter clockwise order
+  let faces = [
+    (0, max.x, 1, 2),
+    (0, min.x, 2, 1),
+    (1, max.y, 2, 0),
+    (1, min.y, 0, 2),
+    (2, max.z, 0, 1),
+    (2, min.z, 1, 0),
+  ];
+  CSGMesh::from_convex_polygons(faces.iter().map(|&(axis, value, u, v)| {
+    let mut normal = Vec3::zero();
+    normal[axis] = if value == max[axis] { 1. } else { -1. };
+    [(0., 0.), (1., 0.), (1., 1.), (0., 1.)]
+      .iter()
+      .map(|&(s, t)| {
+        let mut position = Vec3::zero();
+        position[axis] = value;
+        position[u] = min[u] + (max[u] - min[u]) * s;
+        position[v] = min[v] + (max[v] - min[v]) * t;
+        CSGVertex::new(position, normal, Vec2::new(s, t))
+      })
+      .collect()
+  }))
+}
+
+fn volume(mesh: &CSGMesh) -> f32 {
+  mesh
+    .triangles()
+    .map(|[a, b, c]| a.position.dot(b.position.cross(c.position)) / 6.)
+    .sum()
+}
+
+#[test]
+fn csg_boolean() {
+  let a = cube(Vec3::splat(0.), Vec3::splat(2.));
+  let b = cube(Vec3::splat(1.), Vec3::splat(3.));
+  assert!((volume(&a) - 8.).abs() < 1e-4);
+
+  assert!((volume(&a.union(b.clone())) - 15.).abs() < 1e-3);
+  assert!((volume(&a.subtract(b.clone())) - 7.).abs() < 1e-3);
+  assert!((volume(&a.intersect(b)) - 1.).abs() < 1e-3);
+  assert!((volume(&a.inverse()) + 8.).abs() < 1e-4);
+
+  // disjoint
+  let c = cube(Vec3::splat(5.), Vec3::splat(6.));
+  assert!((volume(&a.union(c.clone())) - 9.).abs() < 1e-3);
+  assert!(a.intersect(c).is_empty());
+
+  // the coplanar faces are merged
+  let d = cube(Vec3::new(2., 0., 0.), Vec3::new(4., 2., 2.));
+  assert!((volume(&a.union(d)) - 16.).abs() < 1e-3);
+}
+
+#[test]
+fn csg_attribute_interpolation() {
+  let a = cube(Vec3::splat(0.), Vec3::splat(2.));
+  let b = cube(Vec3::splat(1.), Vec3::splat(3.));
+  let result = a.subtract(b);
+  for [a, b, c] in result.triangles() {
+    for v in [a, b, c] {
+      assert!((v.normal.length() - 1.).abs() < 1e-4);
+      assert!(v.uv.x >= -1e-4 && v.uv.x <= 1. + 1e-4);
+      assert!(v.uv.y >= -1e-4 && v.uv.y <= 1. + 1e-4);
+    }
+    // the fan triangulation keeps the winding
+    let normal = (b.position - a.position).cross(c.position - a.position);
+    assert!(normal.dot(a.normal) > 0.);
+  }
+
+  // the split vertex on the face of cube a at z = 2 has the interpolated uv
+  let has_middle_uv = result.triangles().flatten().any(|v| {
+    let position = v.position;
+    position.z == 2.
+      && position.y == 0.
+      && (position.x - 1.).abs() < 1e-5
+      && (v.uv.x - 0.5).abs() < 1e-5
+  });
+  assert!(has_middle_uv);
+}
+
+#[test]
+fn csg_degenerated_input() {
+  let p = |x: f32| CSGVertex::new(Vec3::new(x, 0., 0.), Vec3::zero(), Vec2::zero());
+  let mesh = CSGMesh::from_triangles([[p(0.), p(1.), p(2.)]]);
+  assert!(mesh.is_empty());
+
+  let a = cube(Vec3::splat(0.), Vec3::splat(2.)).with_epsilon(1e-3);
+  let b = cube(Vec3::splat(1.), Vec3::new(3., 3., 2.0005));
+  let result = a.union(b);
+  assert_eq!(result.epsilon(), 1e-3);
+  assert!((volume(&result) - 11.).abs() < 1e-2);
+}
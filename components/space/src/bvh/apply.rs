@@ -3,7 +3,7 @@ use std::{cmp::Ordering, ops::Range};
 use rendiation_algebra::Vec3;
 use rendiation_geometry::{Axis3, Box3};
 
-use super::{BVHBounding, BalanceTreeBounding, SAHBounding};
+use super::{BVHBounding, BalanceTreeBounding, BinnedSAHBounding, SAHBounding};
 use crate::utils::BuildPrimitive;
 
 impl BVHBounding for Box3 {
@@ -19,7 +19,7 @@ impl BalanceTreeBounding for Box3 {
   fn median_partition_at_axis(
     range: Range<usize>,
     build_source: &[BuildPrimitive<Self>],
-    index_source: &mut Vec<usize>,
+    index_source: &mut [usize],
     axis: Self::AxisType,
   ) {
     let range_middle = (range.end - range.start) / 2;
@@ -76,3 +76,9 @@ impl SAHBounding for Box3 {
     self.union(other)
   }
 }
+
+impl BinnedSAHBounding for Box3 {
+  fn partition_axes() -> &'static [Axis3] {
+    &[Axis3::X, Axis3::Y, Axis3::Z]
+  }
+}
@@ -0,0 +1,154 @@
+use std::ops::Range;
+
+use super::{BVHBuildStrategy, BalanceTree, FlattenBVHNode, SAHBounding};
+use crate::utils::{bounding_from_build_source, BuildPrimitive};
+
+pub trait BinnedSAHBounding: SAHBounding {
+  /// All the axes that the primitives could be partitioned along.
+  fn partition_axes() -> &'static [Self::AxisType];
+}
+
+#[derive(Clone)]
+struct Bin<B> {
+  bounding: B,
+  primitive_count: usize,
+}
+
+/// The SAH build strategy that bins the primitives by their center along every axis, and
+/// chooses the split with the lowest cost among all the bin boundaries.
+///
+/// Compared with [super::SAH], the bins are placed in the range of primitive centers instead of
+/// the node bounding, and the bin cost is swept so the bin count could be large.
+#[derive(Clone)]
+pub struct BinnedSAH<B: SAHBounding> {
+  bins: Vec<Bin<B>>,
+  /// the cost and the primitive count of the bins on the right side of the boundary
+  right_cost: Vec<(f32, usize)>,
+}
+
+impl<B: SAHBounding> BinnedSAH<B> {
+  pub fn new(bin_count: usize) -> Self {
+    assert!(bin_count >= 2, "binned sah requires at least two bins");
+    Self {
+      bins: vec![
+        Bin {
+          bounding: B::empty(),
+          primitive_count: 0,
+        };
+        bin_count
+      ],
+      right_cost: vec![(0., 0); bin_count],
+    }
+  }
+
+  fn bin_count(&self) -> usize {
+    self.bins.len()
+  }
+}
+
+/// the bin mapping of the primitive center on the axis
+#[derive(Clone, Copy)]
+struct BinMapping {
+  start: f32,
+  scale: f32,
+  bin_count: usize,
+}
+
+impl BinMapping {
+  fn bin_index(&self, value: f32) -> usize {
+    (((value - self.start) * self.scale) as usize).min(self.bin_count - 1)
+  }
+}
+
+impl<B> BVHBuildStrategy<B> for BinnedSAH<B>
+where
+  B: BinnedSAHBounding,
+  B::AxisType: 'static,
+{
+  fn split(
+    &mut self,
+    parent_node: &FlattenBVHNode<B>,
+    build_source: &[BuildPrimitive<B>],
+    index_source: &mut [usize],
+  ) -> ((B, Range<usize>), B::AxisType, (B, Range<usize>)) {
+    let range = parent_node.primitive_range.clone();
+    let bin_count = self.bin_count();
+    let center =
+      |index: usize, axis| B::get_unit_from_center_by_axis(&build_source[index].center, axis);
+
+    // axis, mapping, the first bin of the right side, cost
+    let mut best: Option<(B::AxisType, BinMapping, usize, f32)> = None;
+    for &axis in B::partition_axes() {
+      let (min, max) = index_source[range.clone()]
+        .iter()
+        .map(|&index| center(index, axis))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| {
+          (min.min(v), max.max(v))
+        });
+      if max <= min {
+        continue;
+      }
+      let mapping = BinMapping {
+        start: min,
+        scale: bin_count as f32 / (max - min),
+        bin_count,
+      };
+
+      self.bins.iter_mut().for_each(|bin| {
+        bin.bounding = B::empty();
+        bin.primitive_count = 0;
+      });
+      for &index in &index_source[range.clone()] {
+        let bin = &mut self.bins[mapping.bin_index(center(index, axis))];
+        bin.bounding.union(build_source[index].bounding);
+        bin.primitive_count += 1;
+      }
+
+      let mut bounding = B::empty();
+      let mut primitive_count = 0;
+      for split in (1..bin_count).rev() {
+        bounding.union(self.bins[split].bounding);
+        primitive_count += self.bins[split].primitive_count;
+        let cost = bounding.surface_area() * primitive_count as f32;
+        self.right_cost[split] = (cost, primitive_count);
+      }
+
+      let mut bounding = B::empty();
+      let mut primitive_count = 0;
+      for split in 1..bin_count {
+        bounding.union(self.bins[split - 1].bounding);
+        primitive_count += self.bins[split - 1].primitive_count;
+        let (right_cost, right_count) = self.right_cost[split];
+        if primitive_count == 0 || right_count == 0 {
+          continue;
+        }
+        let cost = bounding.surface_area() * primitive_count as f32 + right_cost;
+        if best.map_or(true, |(.., best_cost)| cost < best_cost) {
+          best = Some((axis, mapping, split, cost));
+        }
+      }
+    }
+
+    // all the primitive centers are at the same place
+    let (axis, mapping, split, _) = match best {
+      Some(best) => best,
+      None => return BalanceTree.split(parent_node, build_source, index_source),
+    };
+
+    let ranged_index = &mut index_source[range.clone()];
+    let mut left_count = 0;
+    for i in 0..ranged_index.len() {
+      if mapping.bin_index(center(ranged_index[i], axis)) < split {
+        ranged_index.swap(i, left_count);
+        left_count += 1;
+      }
+    }
+
+    let left_range = range.start..range.start + left_count;
+    let right_range = range.start + left_count..range.end;
+    let left_bbox = bounding_from_build_source(index_source, build_source, left_range.clone());
+    let right_bbox = bounding_from_build_source(index_source, build_source, right_range.clone());
+
+    ((left_bbox, left_range), axis, (right_bbox, right_range))
+  }
+}
@@ -1,12 +1,19 @@
 mod apply;
+mod binned;
 mod node;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod query;
 mod strategy;
+mod update;
 
 pub mod test;
 
 use std::iter::FromIterator;
 
+pub use binned::*;
 pub use node::*;
+pub use query::*;
 use rendiation_abstract_tree::NextTraverseVisit;
 use rendiation_geometry::SolidEntity;
 pub use strategy::*;
@@ -61,11 +68,7 @@ impl<B: BVHBounding> FlattenBVH<B> {
     strategy: &mut S,
     option: &TreeBuildOption,
   ) -> Self {
-    // prepare build source;
-    let (mut index_list, primitives): (Vec<usize>, Vec<BuildPrimitive<B>>) = source
-      .enumerate()
-      .map(|(i, b)| (i, BuildPrimitive::new(b)))
-      .unzip();
+    let (mut index_list, primitives) = Self::prepare_build_source(source);
 
     // prepare root
     let root_bbox =
@@ -82,6 +85,13 @@ impl<B: BVHBounding> FlattenBVH<B> {
     }
   }
 
+  fn prepare_build_source(source: impl Iterator<Item = B>) -> (Vec<usize>, Vec<BuildPrimitive<B>>) {
+    source
+      .enumerate()
+      .map(|(i, b)| (i, BuildPrimitive::new(b)))
+      .unzip()
+  }
+
   fn create_node_ref(&self, index: usize) -> BVHTreeNodeRef<B> {
     BVHTreeNodeRef {
       tree: self,
@@ -0,0 +1,108 @@
+use super::{BVHBounding, BVHBuildStrategy, FlattenBVH, FlattenBVHNode, FlattenBVHNodeChildInfo};
+use crate::utils::{
+  bounding_from_build_source, BuildPrimitive, CenterAblePrimitive, TreeBuildOption,
+};
+
+/// The subtree that has fewer primitives is built in the current thread.
+const PARALLEL_BUILD_THRESHOLD: usize = 4096;
+
+impl<B> FlattenBVH<B>
+where
+  B: BVHBounding + Send + Sync,
+  B::AxisType: Send,
+  <B as CenterAblePrimitive>::Center: Sync,
+{
+  /// Build the tree the same as [FlattenBVH::new], but the subtrees are built in parallel. Every
+  /// parallel task uses a clone of the strategy.
+  pub fn new_parallel<S: BVHBuildStrategy<B> + Clone + Send>(
+    source: impl Iterator<Item = B>,
+    strategy: &S,
+    option: &TreeBuildOption,
+  ) -> Self {
+    let (mut index_list, primitives) = Self::prepare_build_source(source);
+    let root_bbox =
+      bounding_from_build_source(&index_list, primitives.as_slice(), 0..index_list.len());
+
+    let nodes = build_subtree(
+      &mut strategy.clone(),
+      option,
+      &primitives,
+      &mut index_list,
+      root_bbox,
+      0,
+    );
+
+    Self {
+      nodes,
+      sorted_primitive_index: index_list,
+    }
+  }
+}
+
+/// Build the subtree of the index source, the primitive range of the returned nodes are relative
+/// to the index source.
+fn build_subtree<B, S>(
+  strategy: &mut S,
+  option: &TreeBuildOption,
+  build_source: &[BuildPrimitive<B>],
+  index_source: &mut [usize],
+  bounding: B,
+  depth: usize,
+) -> Vec<FlattenBVHNode<B>>
+where
+  B: BVHBounding + Send + Sync,
+  B::AxisType: Send,
+  <B as CenterAblePrimitive>::Center: Sync,
+  S: BVHBuildStrategy<B> + Clone + Send,
+{
+  let count = index_source.len();
+  let mut nodes = vec![FlattenBVHNode::new(bounding, 0..count, 0)];
+  if count <= PARALLEL_BUILD_THRESHOLD || !option.should_continue(count, depth) {
+    strategy.build(option, build_source, index_source, &mut nodes, depth);
+    return nodes;
+  }
+
+  let ((left_bbox, left_range), split_axis, (right_bbox, _)) =
+    strategy.split(&nodes[0], build_source, index_source);
+  let (left_source, right_source) = index_source.split_at_mut(left_range.end);
+  let mut right_strategy = strategy.clone();
+  let (left, right) = rayon::join(
+    || {
+      build_subtree(
+        strategy,
+        option,
+        build_source,
+        left_source,
+        left_bbox,
+        depth + 1,
+      )
+    },
+    || {
+      build_subtree(
+        &mut right_strategy,
+        option,
+        build_source,
+        right_source,
+        right_bbox,
+        depth + 1,
+      )
+    },
+  );
+
+  nodes[0].child = Some(FlattenBVHNodeChildInfo {
+    left_count: left.len(),
+    split_axis,
+  });
+  let left_count = left.len();
+  nodes.extend(left.into_iter().map(|mut node| {
+    node.self_index += 1;
+    node
+  }));
+  nodes.extend(right.into_iter().map(|mut node| {
+    node.self_index += left_count + 1;
+    node.primitive_range.start += left_range.end;
+    node.primitive_range.end += left_range.end;
+    node
+  }));
+  nodes
+}
@@ -0,0 +1,252 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use rendiation_geometry::{DistanceSquareTo, Frustum, IntersectAble};
+
+use super::{BVHBounding, FlattenBVH};
+
+/// The primitive found by the nearest query, and the squared distance to the query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NearestPrimitive {
+  pub primitive: usize,
+  pub distance_sq: f32,
+}
+
+/// The node in the nearest query queue, the node nearer to the query is popped first.
+struct NodeDistance {
+  node: usize,
+  distance_sq: f32,
+}
+
+impl PartialEq for NodeDistance {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for NodeDistance {}
+
+impl PartialOrd for NodeDistance {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for NodeDistance {
+  fn cmp(&self, other: &Self) -> Ordering {
+    other.distance_sq.total_cmp(&self.distance_sq)
+  }
+}
+
+impl<B: BVHBounding> FlattenBVH<B> {
+  fn is_empty(&self) -> bool {
+    self.nodes[0].primitive_range.is_empty()
+  }
+
+  /// Find the nearest primitive to the query.
+  ///
+  /// The primitive_distance_sq returns the squared distance from the query to the primitive, or
+  /// None if the primitive should be ignored. The squared distance to the bounding should never
+  /// be larger than the squared distance to the primitives inside.
+  pub fn nearest<Q: DistanceSquareTo<B, f32>>(
+    &self,
+    query: &Q,
+    primitive_distance_sq: impl FnMut(usize) -> Option<f32>,
+  ) -> Option<NearestPrimitive> {
+    self.k_nearest(query, 1, primitive_distance_sq).pop()
+  }
+
+  /// Find at most k nearest primitives to the query, sorted from near to far.
+  ///
+  /// See [FlattenBVH::nearest] for the requirement of the distance.
+  pub fn k_nearest<Q: DistanceSquareTo<B, f32>>(
+    &self,
+    query: &Q,
+    k: usize,
+    mut primitive_distance_sq: impl FnMut(usize) -> Option<f32>,
+  ) -> Vec<NearestPrimitive> {
+    let mut result: Vec<NearestPrimitive> = Vec::with_capacity(k);
+    if k == 0 || self.is_empty() {
+      return result;
+    }
+
+    let mut queue = BinaryHeap::new();
+    queue.push(NodeDistance {
+      node: 0,
+      distance_sq: query.distance_sq_to(&self.nodes[0].bounding),
+    });
+
+    while let Some(NodeDistance { node, distance_sq }) = queue.pop() {
+      if result.len() == k && distance_sq > result[k - 1].distance_sq {
+        break;
+      }
+      let node = &self.nodes[node];
+      if let (Some(left), Some(right)) = (node.left_child_offset(), node.right_child_offset()) {
+        for child in [left, right] {
+          let distance_sq = query.distance_sq_to(&self.nodes[child].bounding);
+          queue.push(NodeDistance {
+            node: child,
+            distance_sq,
+          });
+        }
+        continue;
+      }
+
+      for &primitive in node.iter_primitive(self) {
+        let distance_sq = match primitive_distance_sq(primitive) {
+          Some(distance_sq) => distance_sq,
+          None => continue,
+        };
+        if result.len() == k && distance_sq >= result[k - 1].distance_sq {
+          continue;
+        }
+        let position = result.partition_point(|r| r.distance_sq <= distance_sq);
+        result.insert(
+          position,
+          NearestPrimitive {
+            primitive,
+            distance_sq,
+          },
+        );
+        result.truncate(k);
+      }
+    }
+
+    result
+  }
+
+  /// Collect the primitives that pass the primitive_test, in the leaves whose bounding passes the
+  /// bounding_test along the path.
+  pub fn query_primitives(
+    &self,
+    mut bounding_test: impl FnMut(&B) -> bool,
+    mut primitive_test: impl FnMut(usize) -> bool,
+  ) -> Vec<usize> {
+    let mut result = Vec::new();
+    self.traverse(
+      |node| bounding_test(&node.bounding),
+      |leaf| {
+        leaf
+          .iter_primitive(self)
+          .filter(|&&primitive| primitive_test(primitive))
+          .for_each(|&primitive| result.push(primitive));
+        true
+      },
+    );
+    result
+  }
+
+  /// Collect the primitives intersected with the frustum. The bounding is tested against the
+  /// frustum first, the primitive_test decides the primitive in the visible leaves.
+  pub fn query_frustum(
+    &self,
+    frustum: &Frustum,
+    primitive_test: impl FnMut(usize) -> bool,
+  ) -> Vec<usize>
+  where
+    Frustum: IntersectAble<B, bool>,
+  {
+    self.query_primitives(|b| frustum.intersect(b, &()), primitive_test)
+  }
+
+  /// Collect the primitives overlapped with the bounding. The primitive_test decides the primitive
+  /// in the overlapped leaves.
+  pub fn query_overlap(&self, bounding: &B, primitive_test: impl FnMut(usize) -> bool) -> Vec<usize>
+  where
+    B: IntersectAble<B, bool>,
+  {
+    self.query_primitives(|b| b.intersect(bounding, &()), primitive_test)
+  }
+
+  /// Enumerate the primitive pairs in this tree that pass the primitive_test, every pair is
+  /// visited once. Only the primitives in the overlapped leaves are tested.
+  pub fn overlap_pairs(
+    &self,
+    mut primitive_test: impl FnMut(usize, usize) -> bool,
+  ) -> Vec<(usize, usize)>
+  where
+    B: IntersectAble<B, bool>,
+  {
+    let mut result = Vec::new();
+    if self.is_empty() {
+      return result;
+    }
+
+    let mut self_stack = vec![0];
+    let mut pair_stack = Vec::new();
+    while let Some(node) = self_stack.pop() {
+      let node = &self.nodes[node];
+      if let (Some(left), Some(right)) = (node.left_child_offset(), node.right_child_offset()) {
+        self_stack.push(left);
+        self_stack.push(right);
+        pair_stack.push((left, right));
+        continue;
+      }
+      let primitives = &self.sorted_primitive_index[node.primitive_range.clone()];
+      for (i, &a) in primitives.iter().enumerate() {
+        for &b in &primitives[i + 1..] {
+          if primitive_test(a, b) {
+            result.push((a, b));
+          }
+        }
+      }
+    }
+
+    overlap_node_pairs(self, self, pair_stack, primitive_test, &mut result);
+    result
+  }
+
+  /// Enumerate the primitive pairs between this tree and the other tree that pass the
+  /// primitive_test. The first primitive of the pair is in this tree.
+  pub fn overlap_pairs_with(
+    &self,
+    other: &Self,
+    primitive_test: impl FnMut(usize, usize) -> bool,
+  ) -> Vec<(usize, usize)>
+  where
+    B: IntersectAble<B, bool>,
+  {
+    let mut result = Vec::new();
+    if !self.is_empty() && !other.is_empty() {
+      overlap_node_pairs(self, other, vec![(0, 0)], primitive_test, &mut result);
+    }
+    result
+  }
+}
+
+/// Descend the node pairs with overlapped bounding, the node with more primitives is split first.
+fn overlap_node_pairs<B: BVHBounding + IntersectAble<B, bool>>(
+  tree_a: &FlattenBVH<B>,
+  tree_b: &FlattenBVH<B>,
+  mut stack: Vec<(usize, usize)>,
+  mut primitive_test: impl FnMut(usize, usize) -> bool,
+  result: &mut Vec<(usize, usize)>,
+) {
+  while let Some((a, b)) = stack.pop() {
+    let (a, b) = (&tree_a.nodes[a], &tree_b.nodes[b]);
+    if !a.bounding.intersect(&b.bounding, &()) {
+      continue;
+    }
+
+    let split_a =
+      !a.is_leaf() && (b.is_leaf() || a.primitive_range.len() >= b.primitive_range.len());
+    if split_a {
+      let (left, right) = (
+        a.left_child_offset().unwrap(),
+        a.right_child_offset().unwrap(),
+      );
+      stack.push((left, b.self_index));
+      stack.push((right, b.self_index));
+    } else if let (Some(left), Some(right)) = (b.left_child_offset(), b.right_child_offset()) {
+      stack.push((a.self_index, left));
+      stack.push((a.self_index, right));
+    } else {
+      for &primitive_a in a.iter_primitive(tree_a) {
+        for &primitive_b in b.iter_primitive(tree_b) {
+          if primitive_test(primitive_a, primitive_b) {
+            result.push((primitive_a, primitive_b));
+          }
+        }
+      }
+    }
+  }
+}
@@ -12,7 +12,7 @@ pub trait BVHBuildStrategy<B: BVHBounding> {
     &mut self,
     option: &TreeBuildOption,
     build_source: &[BuildPrimitive<B>],
-    index_source: &mut Vec<usize>,
+    index_source: &mut [usize],
     nodes: &mut Vec<FlattenBVHNode<B>>,
     depth: usize,
   ) -> usize {
@@ -48,7 +48,7 @@ pub trait BVHBuildStrategy<B: BVHBounding> {
     &mut self,
     parent_node: &FlattenBVHNode<B>,
     build_source: &[BuildPrimitive<B>],
-    index_source: &mut Vec<usize>,
+    index_source: &mut [usize],
   ) -> ((B, Range<usize>), B::AxisType, (B, Range<usize>));
 }
 
@@ -58,7 +58,7 @@ pub trait BalanceTreeBounding: BVHBounding {
   fn median_partition_at_axis(
     range: Range<usize>,
     build_source: &[BuildPrimitive<Self>],
-    index_source: &mut Vec<usize>,
+    index_source: &mut [usize],
     axis: Self::AxisType,
   );
 }
@@ -68,7 +68,7 @@ impl<B: BalanceTreeBounding> BVHBuildStrategy<B> for BalanceTree {
     &mut self,
     parent_node: &FlattenBVHNode<B>,
     build_source: &[BuildPrimitive<B>],
-    index_source: &mut Vec<usize>,
+    index_source: &mut [usize],
   ) -> ((B, Range<usize>), B::AxisType, (B, Range<usize>)) {
     let axis = parent_node.bounding.get_partition_axis();
 
@@ -203,7 +203,7 @@ impl<B: SAHBounding> BVHBuildStrategy<B> for SAH<B> {
     &mut self,
     parent_node: &FlattenBVHNode<B>,
     build_source: &[BuildPrimitive<B>],
-    index_source: &mut Vec<usize>,
+    index_source: &mut [usize],
   ) -> ((B, Range<usize>), B::AxisType, (B, Range<usize>)) {
     // step 1, update pre_partition_check_cache
     let range = parent_node.primitive_range.clone();
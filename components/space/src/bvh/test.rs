@@ -33,3 +33,277 @@ pub fn test_bvh_build() {
     },
   );
 }
+
+#[cfg(test)]
+fn check_bvh(bvh: &FlattenBVH<Box3>, boxes: &[Box3], primitive_count: usize) {
+  use rendiation_geometry::ContainAble;
+
+  let mut primitives = bvh.sorted_primitive_index.clone();
+  primitives.sort_unstable();
+  primitives.dedup();
+  assert_eq!(primitives.len(), primitive_count);
+  assert_eq!(bvh.sorted_primitive_index.len(), primitive_count);
+
+  for (index, node) in bvh.nodes.iter().enumerate() {
+    assert_eq!(node.self_index, index);
+    if let (Some(left), Some(right)) = (node.left_child_offset(), node.right_child_offset()) {
+      let (left, right) = (&bvh.nodes[left], &bvh.nodes[right]);
+      assert_eq!(left.primitive_range.start, node.primitive_range.start);
+      assert_eq!(left.primitive_range.end, right.primitive_range.start);
+      assert_eq!(right.primitive_range.end, node.primitive_range.end);
+      assert!(node.bounding.contains(&left.bounding));
+      assert!(node.bounding.contains(&right.bounding));
+    } else {
+      assert!(node
+        .iter_primitive(bvh)
+        .all(|&p| node.bounding.contains(&boxes[p])));
+    }
+  }
+}
+
+#[test]
+fn test_bvh_refit() {
+  use rendiation_algebra::{Vec3, Vector};
+
+  use super::SAH;
+  use crate::utils::*;
+  let mut boxes = generate_boxes_in_space(200, 1000., 1.);
+  let option = TreeBuildOption {
+    max_tree_depth: 15,
+    bin_size: 4,
+  };
+  let mut bvh = bvh_build(&boxes, &mut SAH::new(4), &option);
+  check_bvh(&bvh, &boxes, 200);
+  let build_cost = bvh.sah_cost();
+  assert!(build_cost > 1.);
+
+  boxes.iter_mut().step_by(10).for_each(|b| {
+    b.min += Vec3::splat(50.);
+    b.max += Vec3::splat(50.);
+  });
+  bvh.refit(&boxes);
+  check_bvh(&bvh, &boxes, 200);
+
+  // move all boxes to a far place, the tree is still valid but much worse
+  boxes.iter_mut().step_by(2).for_each(|b| {
+    b.min.x += 5000.;
+    b.max.x += 5000.;
+  });
+  bvh.refit(&boxes);
+  check_bvh(&bvh, &boxes, 200);
+  assert!(bvh.sah_cost() > build_cost);
+}
+
+#[test]
+fn test_bvh_insert_remove() {
+  use super::SAH;
+  use crate::utils::*;
+  let boxes = generate_boxes_in_space(300, 1000., 1.);
+  let option = TreeBuildOption {
+    max_tree_depth: 15,
+    bin_size: 4,
+  };
+  let mut bvh = bvh_build(&boxes[..100], &mut SAH::new(4), &option);
+  check_bvh(&bvh, &boxes, 100);
+
+  let mut sah = SAH::new(4);
+  for i in 100..300 {
+    bvh.insert(i, &boxes, &mut sah, &option);
+    check_bvh(&bvh, &boxes, i + 1);
+  }
+  assert!(bvh.sah_cost() > 0.);
+
+  for i in (0..300).step_by(3) {
+    assert!(bvh.remove(i));
+    assert!(!bvh.remove(i));
+  }
+  check_bvh(&bvh, &boxes, 200);
+
+  for i in 0..300 {
+    bvh.remove(i);
+  }
+  check_bvh(&bvh, &boxes, 0);
+  assert_eq!(bvh.nodes.len(), 1);
+  assert_eq!(bvh.sah_cost(), 0.);
+
+  bvh.insert(0, &boxes, &mut sah, &option);
+  check_bvh(&bvh, &boxes, 1);
+}
+
+#[test]
+fn test_bvh_insert_split_leaf() {
+  use rendiation_algebra::Vec3;
+
+  use super::SAH;
+  use crate::utils::*;
+  let mut boxes = generate_boxes_in_space(100, 1000., 1.);
+  let point = Vec3::new(100., 200., 300.);
+  boxes.extend((0..500).map(|_| Box3::new3(point, point)));
+  let option = TreeBuildOption {
+    max_tree_depth: 30,
+    bin_size: 4,
+  };
+  let mut sah = SAH::new(4);
+  let mut bvh = bvh_build(&boxes[..100], &mut sah, &option);
+
+  for i in 100..600 {
+    bvh.insert(i, &boxes, &mut sah, &option);
+  }
+  check_bvh(&bvh, &boxes, 600);
+
+  let max_leaf_size = bvh
+    .nodes
+    .iter()
+    .filter(|node| node.is_leaf())
+    .map(|node| node.primitive_range.len())
+    .max()
+    .unwrap();
+  assert!(max_leaf_size <= option.bin_size);
+}
+
+#[test]
+fn test_bvh_nearest() {
+  use rendiation_algebra::Vec3;
+  use rendiation_geometry::DistanceSquareTo;
+
+  use super::SAH;
+  use crate::utils::*;
+  let boxes = generate_boxes_in_space(500, 1000., 5.);
+  let bvh = bvh_build(&boxes, &mut SAH::new(4), &TreeBuildOption::default());
+
+  let boxes = &boxes;
+  let distance = |point: Vec3<f32>| move |i: usize| Some(point.distance_sq_to(&boxes[i]));
+  for point in [Vec3::new(500., 500., 500.), Vec3::new(-100., 20., 1200.)] {
+    let mut expect: Vec<_> = (0..boxes.len())
+      .map(|i| (i, distance(point)(i).unwrap()))
+      .collect();
+    expect.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let nearest = bvh.nearest(&point, distance(point)).unwrap();
+    assert_eq!(nearest.distance_sq, expect[0].1);
+
+    let k_nearest = bvh.k_nearest(&point, 10, distance(point));
+    assert_eq!(k_nearest.len(), 10);
+    for (result, expect) in k_nearest.iter().zip(&expect) {
+      assert_eq!(result.distance_sq, expect.1);
+    }
+  }
+
+  // the ignored primitive is never returned
+  let point = Vec3::new(500., 500., 500.);
+  let nearest = bvh.nearest(&point, |i| {
+    (i % 2 == 0).then(|| point.distance_sq_to(&boxes[i]))
+  });
+  assert_eq!(nearest.unwrap().primitive % 2, 0);
+}
+
+#[test]
+fn test_bvh_query() {
+  use rendiation_algebra::*;
+  use rendiation_geometry::{Frustum, IntersectAble};
+
+  use super::SAH;
+  use crate::utils::*;
+  let boxes = generate_boxes_in_space(500, 1000., 10.);
+  let bvh = bvh_build(&boxes, &mut SAH::new(4), &TreeBuildOption::default());
+
+  let area = Box3::new3(Vec3::splat(200.), Vec3::splat(600.));
+  let mut result = bvh.query_overlap(&area, |i| boxes[i].intersect(&area, &()));
+  result.sort_unstable();
+  let expect: Vec<_> = (0..boxes.len())
+    .filter(|&i| boxes[i].intersect(&area, &()))
+    .collect();
+  assert!(!expect.is_empty());
+  assert_eq!(result, expect);
+
+  let camera = Mat4::translate((500., 500., 2000.)).inverse_or_identity();
+  let projection = Mat4::perspective_fov_aspect::<OpenGL>(0.5, 1., 1., 3000.);
+  let mut frustum = Frustum::new();
+  frustum.set_from_matrix(projection * camera);
+  let mut result = bvh.query_frustum(&frustum, |i| frustum.intersect(&boxes[i], &()));
+  result.sort_unstable();
+  let expect: Vec<_> = (0..boxes.len())
+    .filter(|&i| frustum.intersect(&boxes[i], &()))
+    .collect();
+  assert!(!expect.is_empty() && expect.len() < boxes.len());
+  assert_eq!(result, expect);
+
+  let overlap = |a: usize, b: usize| boxes[a].intersect(&boxes[b], &());
+  let mut result = bvh.overlap_pairs(overlap);
+  result.iter_mut().for_each(|(a, b)| {
+    if a > b {
+      std::mem::swap(a, b)
+    }
+  });
+  result.sort_unstable();
+  let mut expect = Vec::new();
+  for a in 0..boxes.len() {
+    for b in a + 1..boxes.len() {
+      if overlap(a, b) {
+        expect.push((a, b));
+      }
+    }
+  }
+  assert!(!expect.is_empty());
+  assert_eq!(result, expect);
+
+  let others = generate_boxes_in_space(300, 1000., 20.);
+  let other_bvh = bvh_build(&others, &mut SAH::new(4), &TreeBuildOption::default());
+  let overlap = |a: usize, b: usize| boxes[a].intersect(&others[b], &());
+  let mut result = bvh.overlap_pairs_with(&other_bvh, overlap);
+  result.sort_unstable();
+  let mut expect = Vec::new();
+  for a in 0..boxes.len() {
+    for b in 0..others.len() {
+      if overlap(a, b) {
+        expect.push((a, b));
+      }
+    }
+  }
+  assert!(!expect.is_empty());
+  assert_eq!(result, expect);
+}
+
+#[test]
+fn test_bvh_binned_build() {
+  use super::{BalanceTree, BinnedSAH};
+  use crate::utils::*;
+  let boxes = generate_boxes_in_space(2000, 1000., 1.);
+  let option = TreeBuildOption {
+    max_tree_depth: 15,
+    bin_size: 4,
+  };
+  let binned = bvh_build(&boxes, &mut BinnedSAH::new(16), &option);
+  check_bvh(&binned, &boxes, 2000);
+  let balance = bvh_build(&boxes, &mut BalanceTree, &option);
+  assert!(binned.sah_cost() < balance.sah_cost());
+
+  // all centers at the same place
+  let boxes = vec![Box3::new3((0., 0., 0.).into(), (1., 1., 1.).into()); 100];
+  let bvh = bvh_build(&boxes, &mut BinnedSAH::new(16), &option);
+  check_bvh(&bvh, &boxes, 100);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_bvh_parallel_build() {
+  use super::BinnedSAH;
+  use crate::utils::*;
+  let boxes = generate_boxes_in_space(20000, 1000., 1.);
+  let option = TreeBuildOption {
+    max_tree_depth: 20,
+    bin_size: 4,
+  };
+  let sequential = bvh_build(&boxes, &mut BinnedSAH::new(16), &option);
+  let parallel = FlattenBVH::new_parallel(boxes.iter().cloned(), &BinnedSAH::new(16), &option);
+  check_bvh(&parallel, &boxes, 20000);
+  assert_eq!(
+    sequential.sorted_primitive_index,
+    parallel.sorted_primitive_index
+  );
+  assert_eq!(sequential.nodes.len(), parallel.nodes.len());
+  for (a, b) in sequential.nodes.iter().zip(&parallel.nodes) {
+    assert_eq!(a.primitive_range, b.primitive_range);
+    assert_eq!(a.bounding, b.bounding);
+  }
+}
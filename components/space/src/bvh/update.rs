@@ -0,0 +1,364 @@
+use super::{BVHBounding, BVHBuildStrategy, FlattenBVH, FlattenBVHNode, SAHBounding};
+use crate::utils::{BuildPrimitive, TreeBuildOption};
+
+fn union<B: BVHBounding>(a: B, b: B) -> B {
+  [a, b].into_iter().collect()
+}
+
+/// swap the first block and the last block of the slice, the middle part keeps its place.
+fn swap_blocks<T>(slice: &mut [T], first_len: usize, last_len: usize) {
+  slice.rotate_left(first_len);
+  let len = slice.len();
+  slice[..len - first_len].rotate_right(last_len);
+}
+
+/// The tree rotation that swaps a child of a node with a grandchild on the other side.
+#[derive(Clone, Copy)]
+enum Rotation {
+  LeftWithRightLeft,
+  LeftWithRightRight,
+  RightWithLeftLeft,
+  RightWithLeftRight,
+}
+
+impl<B: BVHBounding> FlattenBVH<B> {
+  fn child_pair(&self, index: usize) -> Option<(usize, usize)> {
+    let node = &self.nodes[index];
+    Some((node.left_child_offset()?, node.right_child_offset()?))
+  }
+
+  /// The node count of the subtree, only the left count is used so the self index of the nodes
+  /// in the subtree could be outdated.
+  fn subtree_size(&self, mut index: usize) -> usize {
+    let mut size = 0;
+    loop {
+      size += 1;
+      match &self.nodes[index].child {
+        Some(child) => {
+          size += child.left_count;
+          index += child.left_count + 1;
+        }
+        None => return size,
+      }
+    }
+  }
+
+  /// Update the bounding of every node by the new primitive bounding, the tree structure is not
+  /// changed.
+  ///
+  /// The bounding is indexed by the primitive index, the same as the source used in building. The
+  /// refit is cheap, but the tree quality degrades when the primitives move a lot, check the
+  /// [FlattenBVH::sah_cost] to decide when to rebuild.
+  pub fn refit(&mut self, bounding: &[B]) {
+    // the children always have larger index than their parent
+    for index in (0..self.nodes.len()).rev() {
+      let new_bounding = match self.child_pair(index) {
+        Some((left, right)) => union(self.nodes[left].bounding, self.nodes[right].bounding),
+        None => self.nodes[index]
+          .iter_primitive(self)
+          .map(|&primitive| bounding[primitive])
+          .collect(),
+      };
+      self.nodes[index].bounding = new_bounding;
+    }
+  }
+
+  /// Recompute the self index and primitive range in the subtree by the node order.
+  ///
+  /// The leaves keep their primitive count, and the leaves in the node order are packed in the
+  /// primitive order.
+  fn fix_layout(&mut self, root: usize) {
+    let end = root + self.subtree_size(root);
+    let mut cursor = self.nodes[root].primitive_range.start;
+    for index in root..end {
+      let node = &mut self.nodes[index];
+      node.self_index = index;
+      if node.is_leaf() {
+        let count = node.primitive_range.len();
+        node.primitive_range = cursor..cursor + count;
+        cursor += count;
+      }
+    }
+    for index in (root..end).rev() {
+      if let Some((left, right)) = self.child_pair(index) {
+        let range = self.nodes[left].primitive_range.start..self.nodes[right].primitive_range.end;
+        self.nodes[index].primitive_range = range;
+      }
+    }
+  }
+}
+
+impl<B: SAHBounding> FlattenBVH<B> {
+  /// The surface area heuristic cost of the tree relative to the root, the cost of traversing a
+  /// node and testing a primitive are both treated as one.
+  ///
+  /// The cost right after the build is a good baseline. When the cost grows much larger than the
+  /// baseline after refit, insertion or removal, a full rebuild is worth it.
+  pub fn sah_cost(&self) -> f32 {
+    let root = &self.nodes[0];
+    let root_area = root.bounding.surface_area();
+    if root.primitive_range.is_empty() || root_area <= 0. {
+      return 0.;
+    }
+    self
+      .nodes
+      .iter()
+      .map(|node| {
+        let cost = if node.is_leaf() {
+          node.primitive_range.len() as f32
+        } else {
+          1.
+        };
+        cost * node.bounding.surface_area()
+      })
+      .sum::<f32>()
+      / root_area
+  }
+
+  /// Insert the primitive into the leaf that causes the least surface area growth, split the leaf
+  /// by the strategy if it exceeds the option, then rotate the nodes on the path to improve the
+  /// tree quality.
+  ///
+  /// The bounding is indexed by the primitive index, the same as [FlattenBVH::refit], and the
+  /// primitive index should not exist in the tree.
+  ///
+  /// The nodes and primitives are stored in the depth first order, so the insertion shifts the
+  /// elements behind the leaf, the cost is linear to the tree size. For a large batch of changes,
+  /// a full rebuild is cheaper.
+  pub fn insert<S: BVHBuildStrategy<B>>(
+    &mut self,
+    primitive: usize,
+    bounding: &[B],
+    strategy: &mut S,
+    option: &TreeBuildOption,
+  ) {
+    let new_bounding = bounding[primitive];
+    let mut path = vec![0];
+    while let Some((left, right)) = self.child_pair(*path.last().unwrap()) {
+      let growth = |child: usize| {
+        let child = self.nodes[child].bounding;
+        union(child, new_bounding).surface_area() - child.surface_area()
+      };
+      let count = |child: usize| self.nodes[child].primitive_range.len();
+      // prefer the smaller subtree when the growth is the same, or the overlapped primitives
+      // always go into the same branch and the tree degenerates into a list
+      let (left_growth, right_growth) = (growth(left), growth(right));
+      let go_left =
+        left_growth < right_growth || (left_growth == right_growth && count(left) <= count(right));
+      path.push(if go_left { left } else { right });
+    }
+
+    let leaf = *path.last().unwrap();
+    let position = self.nodes[leaf].primitive_range.end;
+    self.sorted_primitive_index.insert(position, primitive);
+    for &index in &path {
+      let node = &mut self.nodes[index];
+      node.primitive_range.end += 1;
+      node.bounding = union(node.bounding, new_bounding);
+    }
+    for node in &mut self.nodes[leaf + 1..] {
+      node.primitive_range.start += 1;
+      node.primitive_range.end += 1;
+    }
+
+    self.split_leaf(&path, bounding, strategy, option);
+
+    path
+      .iter()
+      .rev()
+      .skip(1)
+      .for_each(|&index| self.rotate(index));
+  }
+
+  /// Split the leaf at the end of the path by the strategy if the option allows, the new subtree
+  /// replaces the leaf in place.
+  fn split_leaf<S: BVHBuildStrategy<B>>(
+    &mut self,
+    path: &[usize],
+    bounding: &[B],
+    strategy: &mut S,
+    option: &TreeBuildOption,
+  ) {
+    let leaf = *path.last().unwrap();
+    let depth = path.len() - 1;
+    let range = self.nodes[leaf].primitive_range.clone();
+    if !option.should_continue(range.len(), depth) {
+      return;
+    }
+
+    // build the subtree by the local index of the leaf primitives
+    let primitives = self.sorted_primitive_index[range.clone()].to_vec();
+    let build_source: Vec<_> = primitives
+      .iter()
+      .map(|&p| BuildPrimitive::new(bounding[p]))
+      .collect();
+    let mut index_list: Vec<_> = (0..primitives.len()).collect();
+    let root = FlattenBVHNode::new(self.nodes[leaf].bounding, 0..primitives.len(), 0);
+    let mut subtree = vec![root];
+    strategy.build(option, &build_source, &mut index_list, &mut subtree, depth);
+
+    for (target, &local) in self.sorted_primitive_index[range.clone()]
+      .iter_mut()
+      .zip(index_list.iter())
+    {
+      *target = primitives[local];
+    }
+    for node in &mut subtree {
+      node.self_index += leaf;
+      node.primitive_range =
+        node.primitive_range.start + range.start..node.primitive_range.end + range.start;
+    }
+
+    let added = subtree.len() - 1;
+    self.nodes.splice(leaf..leaf + 1, subtree);
+    for node in &mut self.nodes[leaf + added + 1..] {
+      node.self_index += added;
+    }
+    for (&ancestor, &next) in path.iter().zip(path.iter().skip(1)) {
+      if next == ancestor + 1 {
+        self.nodes[ancestor].child.as_mut().unwrap().left_count += added;
+      }
+    }
+  }
+
+  /// Remove the primitive from the tree, return if the primitive is found. The empty leaf is
+  /// removed with its parent, and the nodes on the path are rotated to improve the tree quality.
+  ///
+  /// The bounding of the leaf that contains the primitive is not shrunk, use refit to tighten it.
+  /// Like [FlattenBVH::insert], the cost is linear to the tree size.
+  pub fn remove(&mut self, primitive: usize) -> bool {
+    let position = match self
+      .sorted_primitive_index
+      .iter()
+      .position(|&p| p == primitive)
+    {
+      Some(position) => position,
+      None => return false,
+    };
+
+    let mut path = vec![0];
+    while let Some((left, right)) = self.child_pair(*path.last().unwrap()) {
+      path.push(if self.nodes[left].primitive_range.contains(&position) {
+        left
+      } else {
+        right
+      });
+    }
+
+    let leaf = *path.last().unwrap();
+    self.sorted_primitive_index.remove(position);
+    for &index in &path {
+      self.nodes[index].primitive_range.end -= 1;
+    }
+    for node in &mut self.nodes[leaf + 1..] {
+      node.primitive_range.start -= 1;
+      node.primitive_range.end -= 1;
+    }
+
+    if self.nodes[leaf].primitive_range.is_empty() && path.len() > 1 {
+      // replace the parent by the sibling
+      path.pop();
+      let parent = path.pop().unwrap();
+      self.nodes.remove(leaf);
+      self.nodes.remove(parent);
+      for (index, node) in self.nodes.iter_mut().enumerate().skip(parent) {
+        node.self_index = index;
+      }
+      for (&ancestor, &next) in path.iter().zip(path.iter().skip(1).chain([&parent])) {
+        if next == ancestor + 1 {
+          self.nodes[ancestor].child.as_mut().unwrap().left_count -= 2;
+        }
+      }
+    }
+
+    for &index in path.iter().rev() {
+      if let Some((left, right)) = self.child_pair(index) {
+        self.nodes[index].bounding = union(self.nodes[left].bounding, self.nodes[right].bounding);
+        self.rotate(index);
+      }
+    }
+    true
+  }
+
+  /// Apply the rotation that reduces the surface area of the children most, if any.
+  fn rotate(&mut self, index: usize) {
+    let (left, right) = match self.child_pair(index) {
+      Some(pair) => pair,
+      None => return,
+    };
+    let area = |node: usize| self.nodes[node].bounding.surface_area();
+    let bounding = |node: usize| self.nodes[node].bounding;
+
+    let mut best = None;
+    let mut best_gain = 0.;
+    let mut check = |rotation, changed: usize, new_bounding: B| {
+      let gain = area(changed) - new_bounding.surface_area();
+      if gain > best_gain {
+        best_gain = gain;
+        best = Some((rotation, new_bounding));
+      }
+    };
+    if let Some((right_left, right_right)) = self.child_pair(right) {
+      let with_right_left = union(bounding(left), bounding(right_right));
+      check(Rotation::LeftWithRightLeft, right, with_right_left);
+      let with_right_right = union(bounding(left), bounding(right_left));
+      check(Rotation::LeftWithRightRight, right, with_right_right);
+    }
+    if let Some((left_left, left_right)) = self.child_pair(left) {
+      let with_left_left = union(bounding(right), bounding(left_right));
+      check(Rotation::RightWithLeftLeft, left, with_left_left);
+      let with_left_right = union(bounding(left_left), bounding(right));
+      check(Rotation::RightWithLeftRight, left, with_left_right);
+    }
+
+    if let Some((rotation, new_bounding)) = best {
+      self.apply_rotation(index, rotation, new_bounding);
+    }
+  }
+
+  /// Swap the subtrees in place. The node order and primitive order are both the depth first
+  /// order, so the swap is moving the two blocks of the subtrees in both arrays.
+  fn apply_rotation(&mut self, index: usize, rotation: Rotation, new_bounding: B) {
+    let (left, right) = self.child_pair(index).unwrap();
+    let (a, b) = match rotation {
+      Rotation::LeftWithRightLeft => (left, self.child_pair(right).unwrap().0),
+      Rotation::LeftWithRightRight => (left, self.child_pair(right).unwrap().1),
+      Rotation::RightWithLeftLeft => (self.child_pair(left).unwrap().0, right),
+      Rotation::RightWithLeftRight => (self.child_pair(left).unwrap().1, right),
+    };
+    let size = |node: usize| self.subtree_size(node);
+    let (size_a, size_b) = (size(a), size(b));
+
+    // the left count of the node and the child that changed
+    let (node_left_count, changed, changed_left_count) = match rotation {
+      Rotation::LeftWithRightLeft => (size_b, index + 1 + size_b, size_a),
+      Rotation::LeftWithRightRight => {
+        let right_left = self.child_pair(right).unwrap().0;
+        (size_b, index + 1 + size_b, size(right_left))
+      }
+      Rotation::RightWithLeftLeft => {
+        let left_right = self.child_pair(left).unwrap().1;
+        (1 + size_b + size(left_right), left, size_b)
+      }
+      Rotation::RightWithLeftRight => {
+        let left_left = self.child_pair(left).unwrap().0;
+        (1 + size(left_left) + size_b, left, size(left_left))
+      }
+    };
+
+    swap_blocks(&mut self.nodes[a..b + size_b], size_a, size_b);
+    let range_a = self.nodes[b + size_b - size_a].primitive_range.clone();
+    let range_b = self.nodes[a].primitive_range.clone();
+    swap_blocks(
+      &mut self.sorted_primitive_index[range_a.start..range_b.end],
+      range_a.len(),
+      range_b.len(),
+    );
+
+    self.nodes[index].child.as_mut().unwrap().left_count = node_left_count;
+    let changed = &mut self.nodes[changed];
+    changed.child.as_mut().unwrap().left_count = changed_left_count;
+    changed.bounding = new_bounding;
+    self.fix_layout(index);
+  }
+}
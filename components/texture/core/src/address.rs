@@ -1,5 +1,5 @@
 use incremental::*;
-use rendiation_algebra::Scalar;
+use rendiation_algebra::{Scalar, Vec4};
 
 /// How edges should be handled in texture addressing.
 #[repr(C)]
@@ -20,24 +20,78 @@ pub enum AddressMode {
   /// -0.25 -> 0.25
   /// 1.25 -> 0.75
   MirrorRepeat = 2,
+  /// Texels outside the texture are the border color of the sampler
+  ///
+  /// -0.25 -> border
+  /// 1.25  -> border
+  ClampToBorder = 3,
 }
 
 clone_self_incremental!(AddressMode);
 
+/// Color of the texels outside the texture when address mode is [AddressMode::ClampToBorder]
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub enum SamplerBorderColor {
+  /// [0, 0, 0, 0]
+  #[default]
+  TransparentBlack,
+  /// [0, 0, 0, 1]
+  OpaqueBlack,
+  /// [1, 1, 1, 1]
+  OpaqueWhite,
+}
+
+clone_self_incremental!(SamplerBorderColor);
+
+impl SamplerBorderColor {
+  pub fn to_rgba(&self) -> Vec4<f32> {
+    match self {
+      SamplerBorderColor::TransparentBlack => Vec4::new(0., 0., 0., 0.),
+      SamplerBorderColor::OpaqueBlack => Vec4::new(0., 0., 0., 1.),
+      SamplerBorderColor::OpaqueWhite => Vec4::new(1., 1., 1., 1.),
+    }
+  }
+}
+
 impl AddressMode {
   pub fn correct<T: Scalar>(&self, uv: T) -> T {
     match self {
       AddressMode::ClampToEdge => ClampToEdge::correct(uv),
       AddressMode::Repeat => Repeat::correct(uv),
       AddressMode::MirrorRepeat => MirrorRepeat::correct(uv),
+      AddressMode::ClampToBorder => ClampToBorder::correct(uv),
     }
   }
+
+  /// Address the texel index like the gpu, return None if the texel is the border.
+  pub fn texel_index(&self, index: isize, size: usize) -> Option<usize> {
+    let size = size as isize;
+    let index = match self {
+      AddressMode::ClampToEdge => index.clamp(0, size - 1),
+      AddressMode::Repeat => index.rem_euclid(size),
+      AddressMode::MirrorRepeat => {
+        let period = index.rem_euclid(size * 2);
+        if period < size {
+          period
+        } else {
+          size * 2 - 1 - period
+        }
+      }
+      AddressMode::ClampToBorder => {
+        if index < 0 || index >= size {
+          return None;
+        }
+        index
+      }
+    };
+    Some(index as usize)
+  }
 }
 
 /// How edges should be handled in texture addressing.
 pub trait TextureAddressMode {
   const ENUM: AddressMode;
-  /// correct uv to [0, 1]
+  /// correct uv to [0, 1], except the [ClampToBorder] which keeps the uv outside
   fn correct<T: Scalar>(uv: T) -> T;
 }
 
@@ -85,13 +139,51 @@ fn repeat() {
 pub struct MirrorRepeat;
 impl TextureAddressMode for MirrorRepeat {
   const ENUM: AddressMode = AddressMode::MirrorRepeat;
-  fn correct<T: Scalar>(_uv: T) -> T {
-    todo!()
+  fn correct<T: Scalar>(uv: T) -> T {
+    let period = uv - (uv * T::half()).floor() * T::two();
+    if period > T::one() {
+      T::two() - period
+    } else {
+      period
+    }
   }
 }
 
-// #[test]
-// fn mirror_repeat() {
-//   assert_eq!(MirrorRepeat::correct(-0.25), 0.25);
-//   assert_eq!(MirrorRepeat::correct(1.25), 0.75);
-// }
+#[test]
+fn mirror_repeat() {
+  assert_eq!(MirrorRepeat::correct(-0.25), 0.25);
+  assert_eq!(MirrorRepeat::correct(1.25), 0.75);
+  assert_eq!(MirrorRepeat::correct(2.25), 0.25);
+  assert_eq!(MirrorRepeat::correct(-1.25), 0.75);
+}
+
+/// Texels outside the texture are the border color of the sampler, the uv is not corrected
+/// because the border is not in the texture.
+///
+/// -0.25 -> border
+/// 1.25  -> border
+pub struct ClampToBorder;
+impl TextureAddressMode for ClampToBorder {
+  const ENUM: AddressMode = AddressMode::ClampToBorder;
+  fn correct<T: Scalar>(uv: T) -> T {
+    uv
+  }
+}
+
+#[test]
+fn texel_address() {
+  let address = |mode: AddressMode| [-5, -1, 0, 3, 4, 9].map(|i| mode.texel_index(i, 4));
+  assert_eq!(
+    address(AddressMode::ClampToEdge),
+    [0, 0, 0, 3, 3, 3].map(Some)
+  );
+  assert_eq!(address(AddressMode::Repeat), [3, 3, 0, 3, 0, 1].map(Some));
+  assert_eq!(
+    address(AddressMode::MirrorRepeat),
+    [3, 0, 0, 3, 3, 1].map(Some)
+  );
+  assert_eq!(
+    address(AddressMode::ClampToBorder),
+    [None, None, Some(0), Some(3), None, None]
+  );
+}
@@ -0,0 +1,83 @@
+use crate::*;
+
+/// The layered 2d textures, all the layers have the same size and there is at least one layer.
+pub trait Texture2DArray {
+  type Layer: Texture2D;
+
+  fn layer(&self, index: usize) -> &Self::Layer;
+  fn layer_mut(&mut self, index: usize) -> &mut Self::Layer;
+  fn layer_count(&self) -> usize;
+
+  fn size(&self) -> Size {
+    self.layer(0).size()
+  }
+}
+
+/// The layers of the same size, there is at least one layer, so the array size and the layer
+/// clamping are always defined.
+#[derive(Debug, Clone)]
+pub struct Texture2DLayers<T> {
+  layers: Vec<T>,
+}
+
+impl<T: Texture2D> Texture2DLayers<T> {
+  /// Return None if the layers are empty or not in the same size.
+  pub fn new(layers: Vec<T>) -> Option<Self> {
+    let size = layers.first()?.size();
+    layers
+      .iter()
+      .all(|layer| layer.size() == size)
+      .then_some(Self { layers })
+  }
+
+  pub fn into_layers(self) -> Vec<T> {
+    self.layers
+  }
+}
+
+impl<T: Texture2D> Texture2DArray for Texture2DLayers<T> {
+  type Layer = T;
+
+  fn layer(&self, index: usize) -> &Self::Layer {
+    &self.layers[index]
+  }
+
+  fn layer_mut(&mut self, index: usize) -> &mut Self::Layer {
+    &mut self.layers[index]
+  }
+
+  fn layer_count(&self) -> usize {
+    self.layers.len()
+  }
+}
+
+#[test]
+fn texture_array_layered_sample() {
+  use rendiation_algebra::Vec2;
+
+  let size = Size::from_usize_pair_min_one((2, 2));
+  let layers: Vec<_> = (0..3)
+    .map(|i| Texture2DBuffer::init_with(size, i as f32))
+    .collect();
+  let layers = Texture2DLayers::new(layers).unwrap();
+  assert_eq!(layers.size(), size);
+  assert_eq!(layers.layer_count(), 3);
+
+  let sampler = TextureSampler::tri_linear_repeat();
+  let uv = Vec2::new(0.3, 0.6);
+  assert_eq!(sampler.sample_2d_array(&layers, uv, 1).x, 1.);
+  // the layer is clamped
+  assert_eq!(sampler.sample_2d_array(&layers, uv, 10).x, 2.);
+}
+
+#[test]
+fn texture_array_invalid_layers() {
+  let layers: Vec<Texture2DBuffer<f32>> = Vec::new();
+  assert!(Texture2DLayers::new(layers).is_none());
+
+  let layers = vec![
+    Texture2DBuffer::init_with(Size::from_usize_pair_min_one((2, 2)), 0.),
+    Texture2DBuffer::init_with(Size::from_usize_pair_min_one((4, 2)), 0.),
+  ];
+  assert!(Texture2DLayers::new(layers).is_none());
+}
@@ -0,0 +1,325 @@
+//! The BC1, BC3, BC4 and BC5 block compression.
+//!
+//! https://learn.microsoft.com/en-us/windows/win32/direct3d10/d3d10-graphics-programming-guide-resources-block-compression
+
+/// The 4x4 texels of a block in rgba, row by row.
+pub type TexelBlock = [[u8; 4]; 16];
+
+fn expand_565(color: u16) -> [u8; 3] {
+  let r = ((color >> 11) & 31) as u8;
+  let g = ((color >> 5) & 63) as u8;
+  let b = (color & 31) as u8;
+  [
+    (r << 3) | (r >> 2),
+    (g << 2) | (g >> 4),
+    (b << 3) | (b >> 2),
+  ]
+}
+
+fn quantize_565(color: [f32; 3]) -> u16 {
+  let q = |v: f32, max: f32| (v.clamp(0., 255.) * max / 255. + 0.5) as u16;
+  (q(color[0], 31.) << 11) | (q(color[1], 63.) << 5) | q(color[2], 31.)
+}
+
+fn distance_sq(a: [u8; 3], b: [u8; 3]) -> u32 {
+  a.iter()
+    .zip(b)
+    .map(|(&a, b)| (a as i32 - b as i32).pow(2) as u32)
+    .sum()
+}
+
+/// The palette of the color block, the second value is whether the 4th color is transparent.
+fn bc1_palette(color0: u16, color1: u16, force_four_color: bool) -> ([[u8; 3]; 4], bool) {
+  let c0 = expand_565(color0);
+  let c1 = expand_565(color1);
+  let mix = |w0: u32, w1: u32| {
+    let mut c = [0; 3];
+    for i in 0..3 {
+      c[i] = ((c0[i] as u32 * w0 + c1[i] as u32 * w1) / (w0 + w1)) as u8;
+    }
+    c
+  };
+  if color0 > color1 || force_four_color {
+    ([c0, c1, mix(2, 1), mix(1, 2)], false)
+  } else {
+    ([c0, c1, mix(1, 1), [0; 3]], true)
+  }
+}
+
+fn decode_color_block(block: &[u8; 8], force_four_color: bool, texels: &mut TexelBlock) {
+  let color0 = u16::from_le_bytes([block[0], block[1]]);
+  let color1 = u16::from_le_bytes([block[2], block[3]]);
+  let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+  let (palette, has_transparent) = bc1_palette(color0, color1, force_four_color);
+  for (i, texel) in texels.iter_mut().enumerate() {
+    let index = ((indices >> (i * 2)) & 3) as usize;
+    let [r, g, b] = palette[index];
+    texel[0] = r;
+    texel[1] = g;
+    texel[2] = b;
+    texel[3] = if has_transparent && index == 3 {
+      0
+    } else {
+      255
+    };
+  }
+}
+
+/// Find the principal axis of the colors by power iteration, and return the extreme points of
+/// the colors projected on the axis.
+fn principal_endpoints(colors: &[[f32; 3]]) -> ([f32; 3], [f32; 3]) {
+  let count = colors.len() as f32;
+  let mut mean = [0.; 3];
+  colors
+    .iter()
+    .for_each(|c| (0..3).for_each(|i| mean[i] += c[i] / count));
+
+  let mut covariance = [[0.; 3]; 3];
+  for c in colors {
+    let d = [c[0] - mean[0], c[1] - mean[1], c[2] - mean[2]];
+    for i in 0..3 {
+      for j in 0..3 {
+        covariance[i][j] += d[i] * d[j];
+      }
+    }
+  }
+
+  let mut axis = [1., 1., 1.];
+  for _ in 0..8 {
+    let mut next = [0.; 3];
+    for i in 0..3 {
+      next[i] = (0..3).map(|j| covariance[i][j] * axis[j]).sum();
+    }
+    let length = next.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if length < f32::EPSILON {
+      break;
+    }
+    axis = next.map(|v| v / length);
+  }
+
+  let project = |c: &[f32; 3]| (0..3).map(|i| (c[i] - mean[i]) * axis[i]).sum::<f32>();
+  let (min, max) = colors
+    .iter()
+    .map(project)
+    .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| {
+      (min.min(v), max.max(v))
+    });
+  let point = |t: f32| [0, 1, 2].map(|i| mean[i] + axis[i] * t);
+  (point(max), point(min))
+}
+
+fn encode_color_block(texels: &TexelBlock, allow_transparent: bool) -> [u8; 8] {
+  let transparent = |t: &[u8; 4]| allow_transparent && t[3] < 128;
+  let colors: Vec<[f32; 3]> = texels
+    .iter()
+    .filter(|t| !transparent(t))
+    .map(|t| [t[0] as f32, t[1] as f32, t[2] as f32])
+    .collect();
+  let has_transparent = colors.len() < 16;
+
+  let (mut color0, mut color1) = if colors.is_empty() {
+    (0, 0)
+  } else {
+    let (max, min) = principal_endpoints(&colors);
+    (quantize_565(max), quantize_565(min))
+  };
+
+  // the order of the endpoints decides the block mode
+  if has_transparent != (color0 <= color1) {
+    std::mem::swap(&mut color0, &mut color1);
+  }
+  let (palette, _) = bc1_palette(color0, color1, !allow_transparent);
+  let selectable = if has_transparent || (color0 == color1 && allow_transparent) {
+    3
+  } else {
+    4
+  };
+
+  let mut indices = 0_u32;
+  for (i, t) in texels.iter().enumerate() {
+    let index = if transparent(t) {
+      3
+    } else {
+      let color = [t[0], t[1], t[2]];
+      (0..selectable)
+        .min_by_key(|&index| distance_sq(palette[index], color))
+        .unwrap()
+    };
+    indices |= (index as u32) << (i * 2);
+  }
+
+  let mut block = [0; 8];
+  block[0..2].copy_from_slice(&color0.to_le_bytes());
+  block[2..4].copy_from_slice(&color1.to_le_bytes());
+  block[4..8].copy_from_slice(&indices.to_le_bytes());
+  block
+}
+
+fn single_channel_palette(value0: u8, value1: u8) -> [u8; 8] {
+  let (v0, v1) = (value0 as u32, value1 as u32);
+  let mut palette = [value0, value1, 0, 0, 0, 0, 0, 255];
+  if value0 > value1 {
+    for i in 1..7 {
+      palette[i as usize + 1] = (((7 - i) * v0 + i * v1) / 7) as u8;
+    }
+  } else {
+    for i in 1..5 {
+      palette[i as usize + 1] = (((5 - i) * v0 + i * v1) / 5) as u8;
+    }
+    palette[6] = 0;
+  }
+  palette
+}
+
+fn decode_single_channel_block(block: &[u8; 8], texels: &mut TexelBlock, channel: usize) {
+  let palette = single_channel_palette(block[0], block[1]);
+  let mut indices = [0; 8];
+  indices[..6].copy_from_slice(&block[2..8]);
+  let indices = u64::from_le_bytes(indices);
+  for (i, texel) in texels.iter_mut().enumerate() {
+    texel[channel] = palette[((indices >> (i * 3)) & 7) as usize];
+  }
+}
+
+fn encode_single_channel_block(texels: &TexelBlock, channel: usize) -> [u8; 8] {
+  let (min, max) = texels
+    .iter()
+    .map(|t| t[channel])
+    .fold((u8::MAX, u8::MIN), |(min, max), v| (min.min(v), max.max(v)));
+
+  let mut block = [0; 8];
+  block[0] = max;
+  block[1] = min;
+  if max == min {
+    return block;
+  }
+
+  let palette = single_channel_palette(max, min);
+  let mut indices = 0_u64;
+  for (i, t) in texels.iter().enumerate() {
+    let index = (0..8)
+      .min_by_key(|&index| (palette[index] as i32 - t[channel] as i32).abs())
+      .unwrap();
+    indices |= (index as u64) << (i * 3);
+  }
+  block[2..8].copy_from_slice(&indices.to_le_bytes()[..6]);
+  block
+}
+
+fn split_block<const N: usize>(block: &[u8]) -> [u8; N] {
+  block[..N].try_into().unwrap()
+}
+
+pub fn encode_bc1_block(texels: &TexelBlock) -> [u8; 8] {
+  encode_color_block(texels, true)
+}
+
+pub fn decode_bc1_block(block: &[u8; 8]) -> TexelBlock {
+  let mut texels = [[0; 4]; 16];
+  decode_color_block(block, false, &mut texels);
+  texels
+}
+
+pub fn encode_bc3_block(texels: &TexelBlock) -> [u8; 16] {
+  let mut block = [0; 16];
+  block[..8].copy_from_slice(&encode_single_channel_block(texels, 3));
+  block[8..].copy_from_slice(&encode_color_block(texels, false));
+  block
+}
+
+pub fn decode_bc3_block(block: &[u8; 16]) -> TexelBlock {
+  let mut texels = [[0; 4]; 16];
+  decode_color_block(&split_block(&block[8..]), true, &mut texels);
+  decode_single_channel_block(&split_block(block), &mut texels, 3);
+  texels
+}
+
+/// Only the red channel is encoded.
+pub fn encode_bc4_block(texels: &TexelBlock) -> [u8; 8] {
+  encode_single_channel_block(texels, 0)
+}
+
+/// The texels are decoded as (r, 0, 0, 255).
+pub fn decode_bc4_block(block: &[u8; 8]) -> TexelBlock {
+  let mut texels = [[0, 0, 0, 255]; 16];
+  decode_single_channel_block(block, &mut texels, 0);
+  texels
+}
+
+/// Only the red and green channels are encoded.
+pub fn encode_bc5_block(texels: &TexelBlock) -> [u8; 16] {
+  let mut block = [0; 16];
+  block[..8].copy_from_slice(&encode_single_channel_block(texels, 0));
+  block[8..].copy_from_slice(&encode_single_channel_block(texels, 1));
+  block
+}
+
+/// The texels are decoded as (r, g, 0, 255).
+pub fn decode_bc5_block(block: &[u8; 16]) -> TexelBlock {
+  let mut texels = [[0, 0, 0, 255]; 16];
+  decode_single_channel_block(&split_block(block), &mut texels, 0);
+  decode_single_channel_block(&split_block(&block[8..]), &mut texels, 1);
+  texels
+}
+
+#[cfg(test)]
+pub(crate) fn max_channel_error(a: &TexelBlock, b: &TexelBlock) -> u8 {
+  a.iter()
+    .zip(b)
+    .flat_map(|(a, b)| a.iter().zip(b).map(|(a, b)| a.abs_diff(*b)))
+    .max()
+    .unwrap()
+}
+
+#[cfg(test)]
+pub(crate) fn gradient_block() -> TexelBlock {
+  let mut texels = [[0; 4]; 16];
+  for (i, t) in texels.iter_mut().enumerate() {
+    let v = (i * 4) as u8;
+    *t = [64 + v, 32 + v / 2, 200 - v, 255 - v];
+  }
+  texels
+}
+
+#[test]
+fn bc1_round_trip() {
+  let texels = gradient_block();
+  let decoded = decode_bc1_block(&encode_bc1_block(&texels));
+  assert!(decoded.iter().all(|t| t[3] == 255));
+  let opaque = texels.map(|t| [t[0], t[1], t[2], 255]);
+  assert!(max_channel_error(&opaque, &decoded) <= 16);
+
+  // the transparent texels use the three color mode
+  let mut texels = [[200, 100, 50, 255]; 16];
+  texels[5][3] = 0;
+  let decoded = decode_bc1_block(&encode_bc1_block(&texels));
+  assert_eq!(decoded[5][3], 0);
+  assert!(max_channel_error(&[decoded[0]; 16], &[texels[0]; 16]) <= 4);
+}
+
+#[test]
+fn bc3_round_trip() {
+  let texels = gradient_block();
+  let decoded = decode_bc3_block(&encode_bc3_block(&texels));
+  assert!(max_channel_error(&texels, &decoded) <= 16);
+
+  let solid = [[10, 20, 30, 40]; 16];
+  let decoded = decode_bc3_block(&encode_bc3_block(&solid));
+  assert!(max_channel_error(&solid, &decoded) <= 4);
+}
+
+#[test]
+fn bc4_bc5_round_trip() {
+  let texels = gradient_block();
+  let decoded = decode_bc4_block(&encode_bc4_block(&texels));
+  assert!(texels
+    .iter()
+    .zip(&decoded)
+    .all(|(a, b)| a[0].abs_diff(b[0]) <= 6 && b[1] == 0 && b[3] == 255));
+
+  let decoded = decode_bc5_block(&encode_bc5_block(&texels));
+  assert!(texels
+    .iter()
+    .zip(&decoded)
+    .all(|(a, b)| a[0].abs_diff(b[0]) <= 6 && a[1].abs_diff(b[1]) <= 6 && b[2] == 0));
+}
@@ -0,0 +1,454 @@
+//! The BC7 block compression, all the modes are decoded, the encoder only uses the mode 6.
+//!
+//! https://learn.microsoft.com/en-us/windows/win32/direct3d11/bc7-format
+
+use crate::TexelBlock;
+
+struct ModeInfo {
+  subset_count: usize,
+  partition_bits: usize,
+  rotation_bits: usize,
+  index_selection_bits: usize,
+  color_bits: usize,
+  alpha_bits: usize,
+  endpoint_p_bits: bool,
+  shared_p_bits: bool,
+  index_bits: usize,
+  secondary_index_bits: usize,
+}
+
+#[allow(clippy::too_many_arguments)]
+const fn mode(
+  subset_count: usize,
+  partition_bits: usize,
+  rotation_bits: usize,
+  index_selection_bits: usize,
+  color_bits: usize,
+  alpha_bits: usize,
+  endpoint_p_bits: bool,
+  shared_p_bits: bool,
+  index_bits: usize,
+  secondary_index_bits: usize,
+) -> ModeInfo {
+  ModeInfo {
+    subset_count,
+    partition_bits,
+    rotation_bits,
+    index_selection_bits,
+    color_bits,
+    alpha_bits,
+    endpoint_p_bits,
+    shared_p_bits,
+    index_bits,
+    secondary_index_bits,
+  }
+}
+
+#[rustfmt::skip]
+const MODES: [ModeInfo; 8] = [
+  mode(3, 4, 0, 0, 4, 0, true, false, 3, 0),
+  mode(2, 6, 0, 0, 6, 0, false, true, 3, 0),
+  mode(3, 6, 0, 0, 5, 0, false, false, 2, 0),
+  mode(2, 6, 0, 0, 7, 0, true, false, 2, 0),
+  mode(1, 0, 2, 1, 5, 6, false, false, 2, 3),
+  mode(1, 0, 2, 0, 7, 8, false, false, 2, 2),
+  mode(1, 0, 0, 0, 7, 7, true, false, 4, 0),
+  mode(2, 6, 0, 0, 5, 5, true, false, 2, 0),
+];
+
+/// bit i is the subset of the texel i
+#[rustfmt::skip]
+const PARTITION_2: [u16; 64] = [
+  0xcccc, 0x8888, 0xeeee, 0xecc8, 0xc880, 0xfeec, 0xfec8, 0xec80,
+  0xc800, 0xffec, 0xfe80, 0xe800, 0xffe8, 0xff00, 0xfff0, 0xf000,
+  0xf710, 0x008e, 0x7100, 0x08ce, 0x008c, 0x7310, 0x3100, 0x8cce,
+  0x088c, 0x3110, 0x6666, 0x366c, 0x17e8, 0x0ff0, 0x718e, 0x399c,
+  0xaaaa, 0xf0f0, 0x5a5a, 0x33cc, 0x3c3c, 0x55aa, 0x9696, 0xa55a,
+  0x73ce, 0x13c8, 0x324c, 0x3bdc, 0x6996, 0xc33c, 0x9966, 0x0660,
+  0x0272, 0x04e4, 0x4e40, 0x2720, 0xc936, 0x936c, 0x39c6, 0x639c,
+  0x9336, 0x9cc6, 0x817e, 0xe718, 0xccf0, 0x0fcc, 0x7744, 0xee22,
+];
+
+/// bits 2i..2i+2 is the subset of the texel i
+#[rustfmt::skip]
+const PARTITION_3: [u32; 64] = [
+  0xaa685050, 0x6a5a5040, 0x5a5a4200, 0x5450a0a8, 0xa5a50000, 0xa0a05050, 0x5555a0a0, 0x5a5a5050,
+  0xaa550000, 0xaa555500, 0xaaaa5500, 0x90909090, 0x94949494, 0xa4a4a4a4, 0xa9a59450, 0x2a0a4250,
+  0xa5945040, 0x0a425054, 0xa5a5a500, 0x55a0a0a0, 0xa8a85454, 0x6a6a4040, 0xa4a45000, 0x1a1a0500,
+  0x0050a4a4, 0xaaa59090, 0x14696914, 0x69691400, 0xa08585a0, 0xaa821414, 0x50a4a450, 0x6a5a0200,
+  0xa9a58000, 0x5090a0a8, 0xa8a09050, 0x24242424, 0x00aa5500, 0x24924924, 0x24499224, 0x50a50a50,
+  0x500aa550, 0xaaaa4444, 0x66660000, 0xa5a0a5a0, 0x50a050a0, 0x69286928, 0x44aaaa44, 0x66666600,
+  0xaa444444, 0x54a854a8, 0x95809580, 0x96969600, 0xa85454a8, 0x80959580, 0xaa141414, 0x96960000,
+  0xaaaa1414, 0xa05050a0, 0xa0a5a5a0, 0x96000000, 0x40804080, 0xa9a8a9a8, 0xaaaaaa44, 0x2a4a5254,
+];
+
+/// the anchor texel of the second subset in the two subsets partition
+#[rustfmt::skip]
+const ANCHOR_2_OF_2: [u8; 64] = [
+  15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15,
+  15, 2, 8, 2, 2, 8, 8, 15, 2, 8, 2, 2, 8, 8, 2, 2,
+  15, 15, 6, 8, 2, 8, 15, 15, 2, 8, 2, 2, 2, 15, 15, 6,
+  6, 2, 6, 8, 15, 15, 2, 2, 15, 15, 15, 15, 15, 2, 2, 15,
+];
+
+/// the anchor texel of the second subset in the three subsets partition
+#[rustfmt::skip]
+const ANCHOR_2_OF_3: [u8; 64] = [
+  3, 3, 15, 15, 8, 3, 15, 15, 8, 8, 6, 6, 6, 5, 3, 3,
+  3, 3, 8, 15, 3, 3, 6, 10, 5, 8, 8, 6, 8, 5, 15, 15,
+  8, 15, 3, 5, 6, 10, 8, 15, 15, 3, 15, 5, 15, 15, 15, 15,
+  3, 15, 5, 5, 5, 8, 5, 10, 5, 10, 8, 13, 15, 12, 3, 3,
+];
+
+/// the anchor texel of the third subset in the three subsets partition
+#[rustfmt::skip]
+const ANCHOR_3_OF_3: [u8; 64] = [
+  15, 8, 8, 3, 15, 15, 3, 8, 15, 15, 15, 15, 15, 15, 15, 8,
+  15, 8, 15, 3, 15, 8, 15, 8, 3, 15, 6, 10, 15, 15, 10, 8,
+  15, 3, 15, 10, 10, 8, 9, 10, 6, 15, 8, 15, 3, 6, 6, 8,
+  15, 3, 15, 15, 15, 15, 15, 15, 15, 15, 15, 15, 3, 15, 15, 8,
+];
+
+const WEIGHTS_2: [u32; 4] = [0, 21, 43, 64];
+const WEIGHTS_3: [u32; 8] = [0, 9, 18, 27, 37, 46, 55, 64];
+const WEIGHTS_4: [u32; 16] = [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+fn weights(index_bits: usize) -> &'static [u32] {
+  match index_bits {
+    2 => &WEIGHTS_2,
+    3 => &WEIGHTS_3,
+    _ => &WEIGHTS_4,
+  }
+}
+
+fn interpolate(e0: u8, e1: u8, weight: u32) -> u8 {
+  (((64 - weight) * e0 as u32 + weight * e1 as u32 + 32) >> 6) as u8
+}
+
+fn subset_of(subset_count: usize, partition: usize, texel: usize) -> usize {
+  match subset_count {
+    2 => ((PARTITION_2[partition] >> texel) & 1) as usize,
+    3 => ((PARTITION_3[partition] >> (texel * 2)) & 3) as usize,
+    _ => 0,
+  }
+}
+
+fn is_anchor(subset_count: usize, partition: usize, texel: usize) -> bool {
+  texel == 0
+    || match subset_count {
+      2 => ANCHOR_2_OF_2[partition] as usize == texel,
+      3 => ANCHOR_2_OF_3[partition] as usize == texel || ANCHOR_3_OF_3[partition] as usize == texel,
+      _ => false,
+    }
+}
+
+struct BitReader<'a> {
+  block: &'a [u8; 16],
+  position: usize,
+}
+
+impl<'a> BitReader<'a> {
+  fn read(&mut self, count: usize) -> u8 {
+    let mut value = 0;
+    for i in 0..count {
+      let bit = self.position + i;
+      value |= ((self.block[bit / 8] >> (bit % 8)) & 1) << i;
+    }
+    self.position += count;
+    value
+  }
+}
+
+struct BitWriter {
+  block: [u8; 16],
+  position: usize,
+}
+
+impl BitWriter {
+  fn write(&mut self, value: u8, count: usize) {
+    for i in 0..count {
+      let bit = self.position + i;
+      self.block[bit / 8] |= ((value >> i) & 1) << (bit % 8);
+    }
+    self.position += count;
+  }
+}
+
+/// Expand the endpoint of the bits count to 8 bits.
+fn unquantize(value: u8, bits: usize) -> u8 {
+  if bits == 8 {
+    return value;
+  }
+  let value = value << (8 - bits);
+  value | (value >> bits)
+}
+
+pub fn decode_bc7_block(block: &[u8; 16]) -> TexelBlock {
+  let mode = match block[0].trailing_zeros() as usize {
+    mode if mode < 8 => mode,
+    // reserved mode
+    _ => return [[0; 4]; 16],
+  };
+  let info = &MODES[mode];
+  let mut reader = BitReader {
+    block,
+    position: mode + 1,
+  };
+
+  let partition = reader.read(info.partition_bits) as usize;
+  let rotation = reader.read(info.rotation_bits);
+  let index_selection = reader.read(info.index_selection_bits);
+
+  let endpoint_count = info.subset_count * 2;
+  let mut endpoints = [[0_u8; 4]; 6];
+  for channel in 0..3 {
+    for endpoint in endpoints.iter_mut().take(endpoint_count) {
+      endpoint[channel] = reader.read(info.color_bits);
+    }
+  }
+  for endpoint in endpoints.iter_mut().take(endpoint_count) {
+    endpoint[3] = reader.read(info.alpha_bits);
+  }
+
+  let (mut color_bits, mut alpha_bits) = (info.color_bits, info.alpha_bits);
+  if info.endpoint_p_bits || info.shared_p_bits {
+    let p_bits: Vec<u8> = if info.endpoint_p_bits {
+      (0..endpoint_count).map(|_| reader.read(1)).collect()
+    } else {
+      (0..info.subset_count)
+        .flat_map(|_| {
+          let p = reader.read(1);
+          [p, p]
+        })
+        .collect()
+    };
+    for (endpoint, p) in endpoints.iter_mut().zip(p_bits) {
+      endpoint.iter_mut().for_each(|v| *v = (*v << 1) | p);
+    }
+    color_bits += 1;
+    if alpha_bits > 0 {
+      alpha_bits += 1;
+    }
+  }
+  for endpoint in endpoints.iter_mut().take(endpoint_count) {
+    for v in endpoint.iter_mut().take(3) {
+      *v = unquantize(*v, color_bits);
+    }
+    endpoint[3] = if alpha_bits > 0 {
+      unquantize(endpoint[3], alpha_bits)
+    } else {
+      255
+    };
+  }
+
+  let mut read_indices = |bits: usize| {
+    let mut indices = [0; 16];
+    for (texel, index) in indices.iter_mut().enumerate() {
+      let anchor = is_anchor(info.subset_count, partition, texel);
+      *index = reader.read(if anchor { bits - 1 } else { bits }) as usize;
+    }
+    indices
+  };
+  let primary = read_indices(info.index_bits);
+  let secondary = (info.secondary_index_bits > 0).then(|| read_indices(info.secondary_index_bits));
+
+  let mut texels = [[0; 4]; 16];
+  for (texel, output) in texels.iter_mut().enumerate() {
+    let subset = subset_of(info.subset_count, partition, texel);
+    let (e0, e1) = (endpoints[subset * 2], endpoints[subset * 2 + 1]);
+
+    let (color_index, color_weights, alpha_index, alpha_weights) = match secondary {
+      Some(secondary) if index_selection == 1 => (
+        secondary[texel],
+        weights(info.secondary_index_bits),
+        primary[texel],
+        weights(info.index_bits),
+      ),
+      Some(secondary) => (
+        primary[texel],
+        weights(info.index_bits),
+        secondary[texel],
+        weights(info.secondary_index_bits),
+      ),
+      None => {
+        let w = weights(info.index_bits);
+        (primary[texel], w, primary[texel], w)
+      }
+    };
+
+    for channel in 0..3 {
+      output[channel] = interpolate(e0[channel], e1[channel], color_weights[color_index]);
+    }
+    output[3] = interpolate(e0[3], e1[3], alpha_weights[alpha_index]);
+
+    match rotation {
+      1 => output.swap(0, 3),
+      2 => output.swap(1, 3),
+      3 => output.swap(2, 3),
+      _ => {}
+    }
+  }
+  texels
+}
+
+/// Find the endpoints along the principal axis of the texels in rgba space.
+fn principal_endpoints(texels: &TexelBlock) -> ([f32; 4], [f32; 4]) {
+  let mut mean = [0.; 4];
+  texels
+    .iter()
+    .for_each(|t| (0..4).for_each(|i| mean[i] += t[i] as f32 / 16.));
+
+  let mut covariance = [[0.; 4]; 4];
+  for t in texels {
+    let d = [0, 1, 2, 3].map(|i| t[i] as f32 - mean[i]);
+    for i in 0..4 {
+      for j in 0..4 {
+        covariance[i][j] += d[i] * d[j];
+      }
+    }
+  }
+
+  let mut axis = [1., 1., 1., 1.];
+  for _ in 0..8 {
+    let next = [0, 1, 2, 3].map(|i| (0..4).map(|j| covariance[i][j] * axis[j]).sum::<f32>());
+    let length = next.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if length < f32::EPSILON {
+      break;
+    }
+    axis = next.map(|v| v / length);
+  }
+
+  let project = |t: &[u8; 4]| {
+    (0..4)
+      .map(|i| (t[i] as f32 - mean[i]) * axis[i])
+      .sum::<f32>()
+  };
+  let (min, max) = texels
+    .iter()
+    .map(project)
+    .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| {
+      (min.min(v), max.max(v))
+    });
+  let point = |t: f32| [0, 1, 2, 3].map(|i| mean[i] + axis[i] * t);
+  (point(min), point(max))
+}
+
+/// Quantize the endpoint to 7 bits with the p bit that has less error.
+fn quantize_mode6_endpoint(endpoint: [f32; 4]) -> ([u8; 4], u8) {
+  (0..2)
+    .map(|p| {
+      let quantized = endpoint.map(|v| ((v - p as f32) / 2.).round().clamp(0., 127.) as u8);
+      let error: f32 = quantized
+        .iter()
+        .zip(endpoint)
+        .map(|(&q, v)| (((q << 1) | p) as f32 - v).powi(2))
+        .sum();
+      (quantized, p, error)
+    })
+    .min_by(|a, b| a.2.total_cmp(&b.2))
+    .map(|(quantized, p, _)| (quantized, p))
+    .unwrap()
+}
+
+pub fn encode_bc7_block(texels: &TexelBlock) -> [u8; 16] {
+  let (min, max) = principal_endpoints(texels);
+  let mut endpoints = [quantize_mode6_endpoint(min), quantize_mode6_endpoint(max)];
+  let unquantized = |(e, p): ([u8; 4], u8)| e.map(|v| (v << 1) | p);
+
+  let select = |endpoints: &[([u8; 4], u8); 2]| {
+    let (e0, e1) = (unquantized(endpoints[0]), unquantized(endpoints[1]));
+    let palette: Vec<[u8; 4]> = WEIGHTS_4
+      .iter()
+      .map(|&w| [0, 1, 2, 3].map(|c| interpolate(e0[c], e1[c], w)))
+      .collect();
+    texels.map(|t| {
+      (0..16)
+        .min_by_key(|&i| {
+          (0..4)
+            .map(|c| (palette[i][c] as i32 - t[c] as i32).pow(2))
+            .sum::<i32>()
+        })
+        .unwrap()
+    })
+  };
+
+  let mut indices = select(&endpoints);
+  // the msb of the anchor index is implicitly zero
+  if indices[0] >= 8 {
+    endpoints.swap(0, 1);
+    indices = indices.map(|i| 15 - i);
+  }
+
+  let mut writer = BitWriter {
+    block: [0; 16],
+    position: 0,
+  };
+  writer.write(1 << 6, 7);
+  for channel in 0..4 {
+    for (endpoint, _) in &endpoints {
+      writer.write(endpoint[channel], 7);
+    }
+  }
+  for (_, p) in &endpoints {
+    writer.write(*p, 1);
+  }
+  for (texel, &index) in indices.iter().enumerate() {
+    writer.write(index as u8, if texel == 0 { 3 } else { 4 });
+  }
+  writer.block
+}
+
+#[test]
+fn bc7_partition_anchors() {
+  for partition in 0..64 {
+    assert_eq!(subset_of(2, partition, 0), 0);
+    assert_eq!(
+      subset_of(2, partition, ANCHOR_2_OF_2[partition] as usize),
+      1
+    );
+    assert_eq!(subset_of(3, partition, 0), 0);
+    assert_eq!(
+      subset_of(3, partition, ANCHOR_2_OF_3[partition] as usize),
+      1
+    );
+    assert_eq!(
+      subset_of(3, partition, ANCHOR_3_OF_3[partition] as usize),
+      2
+    );
+  }
+}
+
+#[test]
+fn bc7_round_trip() {
+  use crate::bc::{gradient_block, max_channel_error};
+
+  let texels = gradient_block();
+  let decoded = decode_bc7_block(&encode_bc7_block(&texels));
+  assert!(max_channel_error(&texels, &decoded) <= 4);
+
+  let solid = [[10, 20, 30, 40]; 16];
+  let decoded = decode_bc7_block(&encode_bc7_block(&solid));
+  assert!(max_channel_error(&solid, &decoded) <= 1);
+}
+
+#[test]
+fn bc7_decode_other_modes() {
+  // mode 5 with the solid endpoints: the decoded texels are the same as the endpoints
+  let mut writer = BitWriter {
+    block: [0; 16],
+    position: 0,
+  };
+  writer.write(1 << 5, 6);
+  writer.write(0, 2); // rotation
+  for color in [100_u8, 50, 24] {
+    writer.write(color >> 1, 7);
+    writer.write(color >> 1, 7);
+  }
+  writer.write(200, 8);
+  writer.write(200, 8);
+  let decoded = decode_bc7_block(&writer.block);
+  assert_eq!(decoded, [[100, 50, 24, 200]; 16]);
+
+  // the reserved mode decodes to zero
+  assert_eq!(decode_bc7_block(&[0; 16]), [[0; 4]; 16]);
+}
@@ -0,0 +1,152 @@
+use crate::*;
+
+type BlockEncoder = fn(&TexelBlock, &mut Vec<u8>);
+type BlockDecoder = fn(&[u8]) -> TexelBlock;
+
+fn block_codec(format: TextureFormat) -> Option<(BlockEncoder, BlockDecoder)> {
+  fn block<const N: usize>(data: &[u8]) -> &[u8; N] {
+    data.try_into().unwrap()
+  }
+
+  use TextureFormat::*;
+  let codec: (BlockEncoder, BlockDecoder) = match format {
+    Bc1RgbaUnorm | Bc1RgbaUnormSrgb => (
+      |t, out| out.extend(encode_bc1_block(t)),
+      |data| decode_bc1_block(block(data)),
+    ),
+    Bc3RgbaUnorm | Bc3RgbaUnormSrgb => (
+      |t, out| out.extend(encode_bc3_block(t)),
+      |data| decode_bc3_block(block(data)),
+    ),
+    Bc4RUnorm => (
+      |t, out| out.extend(encode_bc4_block(t)),
+      |data| decode_bc4_block(block(data)),
+    ),
+    Bc5RgUnorm => (
+      |t, out| out.extend(encode_bc5_block(t)),
+      |data| decode_bc5_block(block(data)),
+    ),
+    Bc7RgbaUnorm | Bc7RgbaUnormSrgb => (
+      |t, out| out.extend(encode_bc7_block(t)),
+      |data| decode_bc7_block(block(data)),
+    ),
+    _ => return None,
+  };
+  Some(codec)
+}
+
+/// The block compressed formats that could be encoded and decoded by [compress_texture] and
+/// [decompress_texture].
+pub fn is_cpu_compress_supported(format: TextureFormat) -> bool {
+  block_codec(format).is_some()
+}
+
+/// Encode the texture into the block compressed format, the texels outside the texture in the
+/// edge blocks are clamped. The texels are encoded as they are stored, so the sRGB formats expect
+/// the sRGB encoded texels. Return None if the format is not supported.
+pub fn compress_texture<T>(texture: &T, format: TextureFormat) -> Option<GPUBufferImage>
+where
+  T: Texture2D,
+  T::Pixel: MipMapFilterAblePixel,
+{
+  let (encoder, _) = block_codec(format)?;
+  let size = texture.size();
+  let (width, height) = size.into_usize();
+
+  let mut data = Vec::with_capacity(texture_byte_size(format, size)?);
+  for block_y in (0..height).step_by(4) {
+    for block_x in (0..width).step_by(4) {
+      let mut texels = [[0; 4]; 16];
+      for (i, texel) in texels.iter_mut().enumerate() {
+        let x = (block_x + i % 4).min(width - 1);
+        let y = (block_y + i / 4).min(height - 1);
+        *texel = Rgba::<u8>::from_rgba(texture.read((x, y)).to_rgba()).0;
+      }
+      encoder(&texels, &mut data);
+    }
+  }
+
+  GPUBufferImage { data, format, size }.into()
+}
+
+/// Decode the block compressed image to rgba8. Return None if the format is not supported or the
+/// data size mismatches.
+pub fn decompress_texture(image: &GPUBufferImage) -> Option<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+  let (_, decoder) = block_codec(image.format)?;
+  if texture_byte_size(image.format, image.size)? != image.data.len() {
+    return None;
+  }
+  let block_size = image.format.block_size(None)? as usize;
+  let (width, height) = image.size.into_usize();
+  let blocks_x = (width + 3) / 4;
+
+  let mut result = ImageBuffer::new(width as u32, height as u32);
+  for (block_index, block) in image.data.chunks_exact(block_size).enumerate() {
+    let block_x = (block_index % blocks_x) * 4;
+    let block_y = (block_index / blocks_x) * 4;
+    for (i, texel) in decoder(block).iter().enumerate() {
+      let (x, y) = (block_x + i % 4, block_y + i / 4);
+      if x < width && y < height {
+        result.put_pixel(x as u32, y as u32, Rgba(*texel));
+      }
+    }
+  }
+  result.into()
+}
+
+/// Encode every level of the mipmap into the block compressed format.
+pub fn compress_mipmap<T>(mipmap: &MipMap<T>, format: TextureFormat) -> Option<GPUBufferTexture>
+where
+  T: Texture2D,
+  T::Pixel: MipMapFilterAblePixel,
+{
+  let levels = mipmap
+    .levels()
+    .iter()
+    .map(|level| compress_texture(level, format).map(|image| vec![image.data]))
+    .collect::<Option<Vec<_>>>()?;
+
+  GPUBufferTexture {
+    format,
+    size: mipmap.main_layer().size(),
+    face_count: 1,
+    levels,
+  }
+  .into()
+}
+
+#[test]
+fn compress_npot_texture() {
+  let mut texture = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(7, 5);
+  // the colors in a block are on a line, so the error is only from the quantization
+  texture.fill_by(|p| {
+    let v = p.x * 30 + p.y * 10;
+    Rgba([v as u8, (v / 2) as u8, 128, 255])
+  });
+
+  for format in [
+    TextureFormat::Bc1RgbaUnorm,
+    TextureFormat::Bc3RgbaUnorm,
+    TextureFormat::Bc7RgbaUnormSrgb,
+  ] {
+    let compressed = compress_texture(&texture, format).unwrap();
+    assert_eq!(
+      compressed.data.len(),
+      2 * 2 * format.block_size(None).unwrap() as usize
+    );
+
+    let decoded = decompress_texture(&compressed).unwrap();
+    assert_eq!(decoded.dimensions(), (7, 5));
+    assert!(texture
+      .pixels()
+      .zip(decoded.pixels())
+      .all(|(a, b)| { a.0.iter().zip(b.0).all(|(a, b)| a.abs_diff(b) <= 24) }));
+  }
+
+  assert!(compress_texture(&texture, TextureFormat::Rgba8Unorm).is_none());
+
+  let mipmap = MipMap::generate(texture, &Default::default());
+  let compressed = compress_mipmap(&mipmap, TextureFormat::Bc7RgbaUnorm).unwrap();
+  assert_eq!(compressed.level_count(), 3);
+  assert!(compressed.validate());
+}
@@ -36,7 +36,8 @@ impl<P: Clone> Texture2DBuffer<P> {
   }
 
   pub fn as_byte_buffer(&self) -> &[u8] {
-    unsafe { std::mem::transmute(self.data.as_slice()) }
+    let byte_len = std::mem::size_of_val(self.data.as_slice());
+    unsafe { std::slice::from_raw_parts(self.data.as_ptr() as *const u8, byte_len) }
   }
 }
 
@@ -69,8 +70,8 @@ impl<P: Copy + Default> Texture2dInitAble for Texture2DBuffer<P> {
   fn init_not_care(size: Size) -> Self {
     let width = usize::from(size.width);
     let height = usize::from(size.height);
-    let mut buffer = Vec::with_capacity(width * height * 4);
-    unsafe { buffer.set_len(width * height * 4) };
+    let mut buffer = Vec::with_capacity(width * height);
+    unsafe { buffer.set_len(width * height) };
     Self { data: buffer, size }
   }
 }
@@ -82,6 +83,64 @@ pub struct GPUBufferImage {
   pub size: Size,
 }
 
+/// The byte size of the texture data in the format, the size is rounded up to the block size.
+pub fn texture_byte_size(format: TextureFormat, size: Size) -> Option<usize> {
+  let (block_width, block_height) = format.block_dimensions();
+  let block_size = format.block_size(None)? as usize;
+  let (width, height) = size.into_usize();
+  let blocks_x = (width + block_width as usize - 1) / block_width as usize;
+  let blocks_y = (height + block_height as usize - 1) / block_height as usize;
+  Some(blocks_x * blocks_y * block_size)
+}
+
+/// The data of all the mip levels and cube faces, in the layout that could be uploaded to the
+/// GPU directly.
+#[derive(Debug, Clone)]
+pub struct GPUBufferTexture {
+  pub format: TextureFormat,
+  /// the size of the base level
+  pub size: Size,
+  /// 1 for the 2d texture, 6 for the cube texture in the order of [CubeTextureFace]
+  pub face_count: usize,
+  /// indexed by the level and then the face
+  pub levels: Vec<Vec<Vec<u8>>>,
+}
+
+impl GPUBufferTexture {
+  pub fn is_cube(&self) -> bool {
+    self.face_count == 6
+  }
+
+  pub fn level_count(&self) -> usize {
+    self.levels.len()
+  }
+
+  pub fn level_size(&self, level: usize) -> Size {
+    let (width, height) = self.size.into_usize();
+    Size::from_usize_pair_min_one((width >> level, height >> level))
+  }
+
+  pub fn image(&self, level: usize, face: usize) -> GPUBufferImage {
+    GPUBufferImage {
+      data: self.levels[level][face].clone(),
+      format: self.format,
+      size: self.level_size(level),
+    }
+  }
+
+  /// Check the face count of every level, and the data size of every image.
+  pub fn validate(&self) -> bool {
+    !self.levels.is_empty()
+      && self.levels.iter().enumerate().all(|(level, faces)| {
+        let byte_size = texture_byte_size(self.format, self.level_size(level));
+        faces.len() == self.face_count
+          && faces
+            .iter()
+            .all(|face| byte_size.map_or(false, |size| size == face.len()))
+      })
+  }
+}
+
 pub fn create_padding_buffer(
   input: &[u8],
   step_read_byte_count: usize,
@@ -1,6 +1,9 @@
-use rendiation_algebra::{Lerp, NormalizedVector, Scalar, Vec2, Vec3};
+use rendiation_algebra::{InnerProductSpace, Lerp, NormalizedVector, Scalar, Vec2, Vec3, Vec4};
 
-use crate::{AddressMode, FilterMode, Texture2D, Texture2DSampleAble};
+use crate::{
+  sample_bilinear, AddressMode, CubeTextureFace, FilterMode, MipMapFilterAblePixel, Size,
+  Texture2D, Texture2DBuffer, Texture2DSampleAble,
+};
 
 // https://github.com/Hyper3D/hyper3d-envmapgen/blob/master/rust/src/cubemap.rs
 
@@ -60,3 +63,174 @@ where
     face.sample_dyn(pixel_position, address, filter)
   }
 }
+
+/// The faces in the gpu cube texture layer order.
+pub const CUBE_FACES: [CubeTextureFace; 6] = [
+  CubeTextureFace::PositiveX,
+  CubeTextureFace::NegativeX,
+  CubeTextureFace::PositiveY,
+  CubeTextureFace::NegativeY,
+  CubeTextureFace::PositiveZ,
+  CubeTextureFace::NegativeZ,
+];
+
+/// Map the uv on the face to the direction(not normalized), following the gpu cube texture
+/// convention, the v axis points down on the side faces.
+pub fn cube_face_uv_to_direction(face: CubeTextureFace, uv: Vec2<f32>) -> Vec3<f32> {
+  let s = uv.x * 2. - 1.;
+  let t = uv.y * 2. - 1.;
+  match face {
+    CubeTextureFace::PositiveX => Vec3::new(1., -t, -s),
+    CubeTextureFace::NegativeX => Vec3::new(-1., -t, s),
+    CubeTextureFace::PositiveY => Vec3::new(s, 1., t),
+    CubeTextureFace::NegativeY => Vec3::new(s, -1., -t),
+    CubeTextureFace::PositiveZ => Vec3::new(s, -t, 1.),
+    CubeTextureFace::NegativeZ => Vec3::new(-s, -t, -1.),
+  }
+}
+
+/// The inverse of [cube_face_uv_to_direction], the direction is not required to be normalized.
+pub fn cube_direction_to_face_uv(direction: Vec3<f32>) -> (CubeTextureFace, Vec2<f32>) {
+  let Vec3 { x, y, z } = direction;
+  let (face, s, t, major) = if x.abs() >= y.abs() && x.abs() >= z.abs() {
+    if x >= 0. {
+      (CubeTextureFace::PositiveX, -z, -y, x)
+    } else {
+      (CubeTextureFace::NegativeX, z, -y, -x)
+    }
+  } else if y.abs() >= z.abs() {
+    if y >= 0. {
+      (CubeTextureFace::PositiveY, x, z, y)
+    } else {
+      (CubeTextureFace::NegativeY, x, -z, -y)
+    }
+  } else if z >= 0. {
+    (CubeTextureFace::PositiveZ, x, -y, z)
+  } else {
+    (CubeTextureFace::NegativeZ, -x, -y, -z)
+  };
+  let re_range = |v: f32| (v / major + 1.) * 0.5;
+  (face, Vec2::new(re_range(s), re_range(t)))
+}
+
+/// The solid angle covered by the texel at (x, y) on a face with the size * size texels.
+///
+/// https://www.rorydriscoll.com/2012/01/15/cubemap-texel-solid-angle/
+pub fn cube_texel_solid_angle(x: usize, y: usize, size: usize) -> f32 {
+  // the signed solid angle from the face center to the point on the face plane
+  let area = |x: f32, y: f32| (x * y).atan2((x * x + y * y + 1.).sqrt());
+  let to_face = |v: usize| v as f32 / size as f32 * 2. - 1.;
+  let (x0, y0, x1, y1) = (to_face(x), to_face(y), to_face(x + 1), to_face(y + 1));
+  area(x0, y0) - area(x0, y1) - area(x1, y0) + area(x1, y1)
+}
+
+impl<P, T> CubeTexture<P, T>
+where
+  T: Texture2D<Pixel = P>,
+{
+  pub fn from_faces(faces: [T; 6]) -> Self {
+    let [positive_x, negative_x, positive_y, negative_y, positive_z, negative_z] = faces;
+    Self {
+      positive_x,
+      negative_x,
+      positive_y,
+      negative_y,
+      positive_z,
+      negative_z,
+    }
+  }
+
+  pub fn face(&self, face: CubeTextureFace) -> &T {
+    match face {
+      CubeTextureFace::PositiveX => &self.positive_x,
+      CubeTextureFace::NegativeX => &self.negative_x,
+      CubeTextureFace::PositiveY => &self.positive_y,
+      CubeTextureFace::NegativeY => &self.negative_y,
+      CubeTextureFace::PositiveZ => &self.positive_z,
+      CubeTextureFace::NegativeZ => &self.negative_z,
+    }
+  }
+
+  /// The faces in the gpu cube texture layer order.
+  pub fn faces(&self) -> [&T; 6] {
+    CUBE_FACES.map(|face| self.face(face))
+  }
+
+  pub fn into_faces(self) -> [T; 6] {
+    [
+      self.positive_x,
+      self.negative_x,
+      self.positive_y,
+      self.negative_y,
+      self.positive_z,
+      self.negative_z,
+    ]
+  }
+
+  /// The faces are square, so the width of a face is the size.
+  pub fn face_size(&self) -> usize {
+    self.positive_x.width()
+  }
+}
+
+impl<P: Copy> CubeTexture<P, Texture2DBuffer<P>> {
+  /// Create the cube texture by computing the texel of every face from the normalized direction
+  /// of the texel center.
+  pub fn from_direction_fn(size: usize, mut texel: impl FnMut(Vec3<f32>) -> P) -> Self {
+    Self::from_faces(CUBE_FACES.map(|face| {
+      let mut data = Vec::with_capacity(size * size);
+      for y in 0..size {
+        for x in 0..size {
+          let uv = Vec2::new(x as f32 + 0.5, y as f32 + 0.5) / size as f32;
+          let direction = cube_face_uv_to_direction(face, uv).normalize();
+          data.push(texel(direction));
+        }
+      }
+      Texture2DBuffer::from_raw(data, Size::from_usize_pair_min_one((size, size)))
+    }))
+  }
+}
+
+impl<P, T> CubeTexture<P, T>
+where
+  T: Texture2D<Pixel = P>,
+  P: MipMapFilterAblePixel,
+{
+  /// Bilinear sample in the float rgba, the face edges are clamped.
+  pub fn sample_linear(&self, direction: Vec3<f32>) -> Vec4<f32> {
+    let (face, uv) = cube_direction_to_face_uv(direction);
+    sample_bilinear(self.face(face), uv, AddressMode::ClampToEdge)
+  }
+}
+
+#[test]
+fn cube_face_direction_mapping() {
+  for face in CUBE_FACES {
+    for uv in [
+      Vec2::new(0.5, 0.5),
+      Vec2::new(0.1, 0.8),
+      Vec2::new(0.9, 0.3),
+    ] {
+      let direction = cube_face_uv_to_direction(face, uv);
+      let (mapped_face, mapped_uv) = cube_direction_to_face_uv(direction * 3.);
+      assert_eq!(face, mapped_face);
+      assert!((mapped_uv - uv).length() < 1e-5);
+    }
+  }
+
+  // +x face: u goes to -z, v goes to -y
+  let direction = cube_face_uv_to_direction(CubeTextureFace::PositiveX, Vec2::new(1., 1.));
+  assert_eq!(direction, Vec3::new(1., -1., -1.));
+}
+
+#[test]
+fn cube_solid_angle_sum() {
+  let size = 8;
+  let mut sum = 0.;
+  for y in 0..size {
+    for x in 0..size {
+      sum += cube_texel_solid_angle(x, y, size);
+    }
+  }
+  assert!((sum * 6. - 4. * std::f32::consts::PI).abs() < 1e-4);
+}
@@ -0,0 +1,339 @@
+//! https://learn.microsoft.com/en-us/windows/win32/direct3ddds/dx-graphics-dds-pguide
+
+use std::{
+  io::{Error, ErrorKind},
+  path::Path,
+};
+
+use crate::*;
+
+pub struct DDS;
+
+const MAGIC: &[u8; 4] = b"DDS ";
+const HEADER_SIZE: u32 = 124;
+const PIXEL_FORMAT_SIZE: u32 = 32;
+
+const DDSD_CAPS: u32 = 0x1;
+const DDSD_HEIGHT: u32 = 0x2;
+const DDSD_WIDTH: u32 = 0x4;
+const DDSD_PITCH: u32 = 0x8;
+const DDSD_PIXELFORMAT: u32 = 0x1000;
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDSD_LINEARSIZE: u32 = 0x80000;
+
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+
+const DDSCAPS_COMPLEX: u32 = 0x8;
+const DDSCAPS_TEXTURE: u32 = 0x1000;
+const DDSCAPS_MIPMAP: u32 = 0x400000;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_CUBEMAP_ALL_FACES: u32 = 0xFC00;
+
+const D3D10_RESOURCE_DIMENSION_TEXTURE2D: u32 = 3;
+const D3D10_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+/// https://learn.microsoft.com/en-us/windows/win32/api/dxgiformat/ne-dxgiformat-dxgi_format
+const DXGI_FORMATS: &[(TextureFormat, u32)] = &[
+  (TextureFormat::Rgba32Float, 2),
+  (TextureFormat::Rgba16Float, 10),
+  (TextureFormat::Rgba8Unorm, 28),
+  (TextureFormat::Rgba8UnormSrgb, 29),
+  (TextureFormat::R32Float, 41),
+  (TextureFormat::Rg8Unorm, 49),
+  (TextureFormat::R8Unorm, 61),
+  (TextureFormat::Bc1RgbaUnorm, 71),
+  (TextureFormat::Bc1RgbaUnormSrgb, 72),
+  (TextureFormat::Bc2RgbaUnorm, 74),
+  (TextureFormat::Bc2RgbaUnormSrgb, 75),
+  (TextureFormat::Bc3RgbaUnorm, 77),
+  (TextureFormat::Bc3RgbaUnormSrgb, 78),
+  (TextureFormat::Bc4RUnorm, 80),
+  (TextureFormat::Bc5RgUnorm, 83),
+  (TextureFormat::Bgra8Unorm, 87),
+  (TextureFormat::Bgra8UnormSrgb, 91),
+  (TextureFormat::Bc7RgbaUnorm, 98),
+  (TextureFormat::Bc7RgbaUnormSrgb, 99),
+];
+
+/// The legacy formats that are described by the four cc code.
+const FOUR_CC_FORMATS: &[(TextureFormat, &[u8; 4])] = &[
+  (TextureFormat::Bc1RgbaUnorm, b"DXT1"),
+  (TextureFormat::Bc2RgbaUnorm, b"DXT3"),
+  (TextureFormat::Bc3RgbaUnorm, b"DXT5"),
+  (TextureFormat::Bc4RUnorm, b"ATI1"),
+  (TextureFormat::Bc4RUnorm, b"BC4U"),
+  (TextureFormat::Bc5RgUnorm, b"ATI2"),
+  (TextureFormat::Bc5RgUnorm, b"BC5U"),
+];
+
+pub(crate) fn invalid_data(message: &str) -> Error {
+  Error::new(ErrorKind::InvalidData, message)
+}
+
+pub(crate) fn unsupported_format(format: TextureFormat) -> Error {
+  Error::new(
+    ErrorKind::Unsupported,
+    format!("texture format {format:?} is not supported by the container"),
+  )
+}
+
+/// The little endian reader of the container file.
+pub(crate) struct ByteReader<'a> {
+  pub bytes: &'a [u8],
+  pub position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+  pub fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], Error> {
+    let bytes = self
+      .bytes
+      .get(self.position..self.position + count)
+      .ok_or_else(|| invalid_data("unexpected end of the texture file"))?;
+    self.position += count;
+    Ok(bytes)
+  }
+
+  pub fn read_u32(&mut self) -> Result<u32, Error> {
+    Ok(u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()))
+  }
+
+  pub fn read_u64(&mut self) -> Result<u64, Error> {
+    Ok(u64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap()))
+  }
+}
+
+fn write_u32s(output: &mut Vec<u8>, values: &[u32]) {
+  values
+    .iter()
+    .for_each(|v| output.extend_from_slice(&v.to_le_bytes()));
+}
+
+impl GPUBufferTexture {
+  /// Encode into the dds file, the format is always described by the DX10 header extension.
+  pub fn write_dds(&self) -> Result<Vec<u8>, Error> {
+    let dxgi_format = DXGI_FORMATS
+      .iter()
+      .find(|(format, _)| *format == self.format)
+      .map(|(_, dxgi)| *dxgi)
+      .ok_or_else(|| unsupported_format(self.format))?;
+    if !self.validate() {
+      return Err(Error::new(
+        ErrorKind::InvalidInput,
+        "the texture data size mismatches the format",
+      ));
+    }
+
+    let (width, height) = self.size.into_usize();
+    let (pitch_flag, pitch_or_linear_size) = if self.format.is_compressed() {
+      (DDSD_LINEARSIZE, self.levels[0][0].len())
+    } else {
+      let block_size = self.format.block_size(None).unwrap() as usize;
+      (DDSD_PITCH, width * block_size)
+    };
+    let mut caps = DDSCAPS_TEXTURE;
+    if self.level_count() > 1 {
+      caps |= DDSCAPS_COMPLEX | DDSCAPS_MIPMAP;
+    }
+    let (caps2, misc_flag) = if self.is_cube() {
+      caps |= DDSCAPS_COMPLEX;
+      (
+        DDSCAPS2_CUBEMAP | DDSCAPS2_CUBEMAP_ALL_FACES,
+        D3D10_RESOURCE_MISC_TEXTURECUBE,
+      )
+    } else {
+      (0, 0)
+    };
+
+    let mut output = Vec::new();
+    output.extend_from_slice(MAGIC);
+    write_u32s(
+      &mut output,
+      &[
+        HEADER_SIZE,
+        DDSD_CAPS | DDSD_HEIGHT | DDSD_WIDTH | DDSD_PIXELFORMAT | DDSD_MIPMAPCOUNT | pitch_flag,
+        height as u32,
+        width as u32,
+        pitch_or_linear_size as u32,
+        0,
+        self.level_count() as u32,
+      ],
+    );
+    write_u32s(&mut output, &[0; 11]);
+    write_u32s(&mut output, &[PIXEL_FORMAT_SIZE, DDPF_FOURCC]);
+    output.extend_from_slice(b"DX10");
+    write_u32s(&mut output, &[0; 5]);
+    write_u32s(&mut output, &[caps, caps2, 0, 0, 0]);
+    write_u32s(
+      &mut output,
+      &[
+        dxgi_format,
+        D3D10_RESOURCE_DIMENSION_TEXTURE2D,
+        misc_flag,
+        1,
+        0,
+      ],
+    );
+
+    // the data is stored face by face, every face contains all the levels
+    for face in 0..self.face_count {
+      for level in &self.levels {
+        output.extend_from_slice(&level[face]);
+      }
+    }
+    Ok(output)
+  }
+
+  pub fn read_dds(bytes: &[u8]) -> Result<Self, Error> {
+    let mut reader = ByteReader { bytes, position: 0 };
+    if reader.read_bytes(4)? != MAGIC {
+      return Err(invalid_data("not a dds file"));
+    }
+    if reader.read_u32()? != HEADER_SIZE {
+      return Err(invalid_data("invalid dds header size"));
+    }
+    let _flags = reader.read_u32()?;
+    let height = reader.read_u32()?;
+    let width = reader.read_u32()?;
+    let _pitch_or_linear_size = reader.read_u32()?;
+    let depth = reader.read_u32()?;
+    let level_count = reader.read_u32()?.max(1) as usize;
+    reader.read_bytes(4 * 11)?;
+
+    let _pixel_format_size = reader.read_u32()?;
+    let pixel_format_flags = reader.read_u32()?;
+    let four_cc: [u8; 4] = reader.read_bytes(4)?.try_into().unwrap();
+    let rgb_bit_count = reader.read_u32()?;
+    let masks = [
+      reader.read_u32()?,
+      reader.read_u32()?,
+      reader.read_u32()?,
+      reader.read_u32()?,
+    ];
+    let _caps = reader.read_u32()?;
+    let caps2 = reader.read_u32()?;
+    reader.read_bytes(4 * 3)?;
+
+    if depth > 1 {
+      return Err(invalid_data("volume dds texture is not supported"));
+    }
+
+    let mut is_cube = caps2 & DDSCAPS2_CUBEMAP != 0;
+    if is_cube && caps2 & DDSCAPS2_CUBEMAP_ALL_FACES != DDSCAPS2_CUBEMAP_ALL_FACES {
+      return Err(invalid_data("partial cube dds texture is not supported"));
+    }
+
+    let format = if pixel_format_flags & DDPF_FOURCC != 0 && &four_cc == b"DX10" {
+      let dxgi_format = reader.read_u32()?;
+      let resource_dimension = reader.read_u32()?;
+      let misc_flag = reader.read_u32()?;
+      let array_size = reader.read_u32()?;
+      let _misc_flags2 = reader.read_u32()?;
+      if resource_dimension != D3D10_RESOURCE_DIMENSION_TEXTURE2D || array_size > 1 {
+        return Err(invalid_data(
+          "only the single 2d or cube dds texture is supported",
+        ));
+      }
+      is_cube |= misc_flag & D3D10_RESOURCE_MISC_TEXTURECUBE != 0;
+      DXGI_FORMATS
+        .iter()
+        .find(|(_, dxgi)| *dxgi == dxgi_format)
+        .map(|(format, _)| *format)
+    } else if pixel_format_flags & DDPF_FOURCC != 0 {
+      FOUR_CC_FORMATS
+        .iter()
+        .find(|(_, code)| **code == four_cc)
+        .map(|(format, _)| *format)
+    } else if pixel_format_flags & DDPF_RGB != 0 && rgb_bit_count == 32 {
+      let has_alpha = pixel_format_flags & DDPF_ALPHAPIXELS != 0;
+      match (masks[0], masks[1], masks[2]) {
+        (0xff, 0xff00, 0xff0000) if has_alpha => Some(TextureFormat::Rgba8Unorm),
+        (0xff0000, 0xff00, 0xff) if has_alpha => Some(TextureFormat::Bgra8Unorm),
+        _ => None,
+      }
+    } else {
+      None
+    }
+    .ok_or_else(|| invalid_data("unsupported dds pixel format"))?;
+
+    let size = Size::from_u32_pair_min_one((width, height));
+    let face_count = if is_cube { 6 } else { 1 };
+    let mut levels = vec![Vec::with_capacity(face_count); level_count];
+    for _ in 0..face_count {
+      for (level, faces) in levels.iter_mut().enumerate() {
+        let (width, height) = size.into_usize();
+        let level_size = Size::from_usize_pair_min_one((width >> level, height >> level));
+        let byte_size = texture_byte_size(format, level_size).unwrap();
+        faces.push(reader.read_bytes(byte_size)?.to_vec());
+      }
+    }
+
+    Ok(Self {
+      format,
+      size,
+      face_count,
+      levels,
+    })
+  }
+}
+
+impl TextureIO<DDS> for GPUBufferTexture {
+  fn save_to_file(&self, path: &dyn AsRef<Path>) -> Result<(), Error> {
+    std::fs::write(path, self.write_dds()?)
+  }
+}
+
+#[cfg(test)]
+pub(crate) fn test_cube_texture() -> GPUBufferTexture {
+  let size = Size::from_usize_pair_min_one((8, 4));
+  let format = TextureFormat::Bc1RgbaUnorm;
+  let levels = (0..size.mip_level_count())
+    .map(|level| {
+      let level_size = Size::from_usize_pair_min_one((8 >> level, 4 >> level));
+      let byte_size = texture_byte_size(format, level_size).unwrap();
+      (0..6)
+        .map(|face| vec![(level * 6 + face) as u8; byte_size])
+        .collect()
+    })
+    .collect();
+  GPUBufferTexture {
+    format,
+    size,
+    face_count: 6,
+    levels,
+  }
+}
+
+#[test]
+fn dds_round_trip() {
+  let texture = test_cube_texture();
+  assert!(texture.validate());
+  let file = texture.write_dds().unwrap();
+  let read = GPUBufferTexture::read_dds(&file).unwrap();
+  assert_eq!(read.format, texture.format);
+  assert_eq!(read.size, texture.size);
+  assert_eq!(read.face_count, 6);
+  assert_eq!(read.levels, texture.levels);
+
+  assert!(GPUBufferTexture::read_dds(&file[..file.len() - 1]).is_err());
+  assert!(GPUBufferTexture::read_dds(b"KTX 20").is_err());
+}
+
+#[test]
+fn dds_legacy_header() {
+  let texture = test_cube_texture();
+  let mut file = texture.write_dds().unwrap();
+  // replace the DX10 header by the four cc, and remove the cube flag
+  file[84..88].copy_from_slice(b"DXT1");
+  file[112..116].copy_from_slice(&0_u32.to_le_bytes());
+  file.drain(128..148);
+  let level_bytes: usize = texture.levels.iter().map(|l| l[0].len()).sum();
+  file.truncate(128 + level_bytes);
+
+  let read = GPUBufferTexture::read_dds(&file).unwrap();
+  assert_eq!(read.format, TextureFormat::Bc1RgbaUnorm);
+  assert_eq!(read.face_count, 1);
+  assert_eq!(read.level_count(), texture.level_count());
+  assert_eq!(read.levels[2][0], texture.levels[2][0]);
+}
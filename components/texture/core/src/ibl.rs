@@ -0,0 +1,360 @@
+//! The cpu side precomputation for the image based lighting, the results could be baked into
+//! assets by [cube_levels_to_gpu_texture] and the container writers.
+//!
+//! https://cdn2.unrealengine.com/Resources/files/2013SiggraphPresentationsNotes-26915738.pdf
+
+use std::f32::consts::PI;
+
+use rendiation_algebra::*;
+
+use crate::*;
+
+/// The cube texture in the float rgba, the result of the precomputation.
+pub type FloatCubeTexture = CubeTexture<Vec4<f32>, Texture2DBuffer<Vec4<f32>>>;
+
+/// The panorama is in the latitude-longitude layout, the top row is +y, and the u is the angle
+/// from the +x axis to the +z axis.
+pub fn equirectangular_to_cube<T>(panorama: &T, face_size: usize) -> FloatCubeTexture
+where
+  T: Texture2D,
+  T::Pixel: MipMapFilterAblePixel,
+{
+  let half_texel = 0.5 / panorama.height() as f32;
+  FloatCubeTexture::from_direction_fn(face_size, |direction| {
+    let u = direction.z.atan2(direction.x) / (2. * PI) + 0.5;
+    let v = direction.y.clamp(-1., 1.).acos() / PI;
+    // the longitude is repeated, the latitude is clamped to the texel centers so the repeat
+    // never reaches the other pole
+    let v = v.clamp(half_texel, 1. - half_texel);
+    sample_bilinear(panorama, Vec2::new(u, v), AddressMode::Repeat)
+  })
+}
+
+fn hammersley(index: usize, count: usize) -> Vec2<f32> {
+  let radical_inverse = (index as u32).reverse_bits() as f32 / 4_294_967_296.;
+  Vec2::new(index as f32 / count as f32, radical_inverse)
+}
+
+/// Return the half vector around the normal.
+fn importance_sample_ggx(xi: Vec2<f32>, roughness: f32, normal: Vec3<f32>) -> Vec3<f32> {
+  let a = roughness * roughness;
+  let phi = 2. * PI * xi.x;
+  let cos_theta = ((1. - xi.y) / (1. + (a * a - 1.) * xi.y)).sqrt();
+  let sin_theta = (1. - cos_theta * cos_theta).sqrt();
+
+  let up = if normal.z.abs() < 0.999 {
+    Vec3::new(0., 0., 1.)
+  } else {
+    Vec3::new(1., 0., 0.)
+  };
+  let tangent_x = up.cross(normal).normalize();
+  let tangent_y = normal.cross(tangent_x);
+  tangent_x * (sin_theta * phi.cos()) + tangent_y * (sin_theta * phi.sin()) + normal * cos_theta
+}
+
+fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+  let a2 = roughness.powi(4);
+  let d = n_dot_h * n_dot_h * (a2 - 1.) + 1.;
+  a2 / (PI * d * d)
+}
+
+fn geometry_smith_ibl(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32 {
+  let k = roughness * roughness / 2.;
+  let g1 = |n_dot: f32| n_dot / (n_dot * (1. - k) + k);
+  g1(n_dot_v) * g1(n_dot_l)
+}
+
+pub struct SpecularPrefilterOption {
+  /// The face size of the first level, the following levels are halved.
+  pub face_size: usize,
+  /// The roughness of the level i is i / (level_count - 1).
+  pub level_count: usize,
+  pub sample_count: usize,
+}
+
+impl Default for SpecularPrefilterOption {
+  fn default() -> Self {
+    Self {
+      face_size: 128,
+      level_count: 6,
+      sample_count: 256,
+    }
+  }
+}
+
+/// Prefilter the environment by the GGX distribution for every roughness level, the result is
+/// the mip chain of the specular cube texture.
+///
+/// The samples read the mipmap of the environment by the sample density to reduce the noise.
+///
+/// https://developer.nvidia.com/gpugems/gpugems3/part-iii-rendering/chapter-20-gpu-based-importance-sampling
+pub fn prefilter_specular<P, T>(
+  environment: &CubeTexture<P, T>,
+  option: &SpecularPrefilterOption,
+) -> Vec<FloatCubeTexture>
+where
+  T: Texture2D<Pixel = P>,
+  P: MipMapFilterAblePixel,
+{
+  let source = environment.faces().map(|face| {
+    MipMap::generate(
+      face.map::<Texture2DBuffer<Vec4<f32>>>(|p| p.to_rgba()),
+      &Default::default(),
+    )
+  });
+  let sample_source = |direction: Vec3<f32>, lod: f32| {
+    let (face, uv) = cube_direction_to_face_uv(direction);
+    source[face as usize].sample(uv, lod, AddressMode::ClampToEdge)
+  };
+  let source_size = environment.face_size() as f32;
+  let texel_solid_angle = 4. * PI / (6. * source_size * source_size);
+
+  let level_count = option.level_count.max(1);
+  (0..level_count)
+    .map(|level| {
+      let face_size = (option.face_size >> level).max(1);
+      let roughness = if level_count == 1 {
+        0.
+      } else {
+        level as f32 / (level_count - 1) as f32
+      };
+
+      FloatCubeTexture::from_direction_fn(face_size, |normal| {
+        if roughness == 0. {
+          return sample_source(normal, 0.);
+        }
+
+        // assume the view direction is the normal
+        let mut sum = Vec3::zero();
+        let mut weight = 0.;
+        for i in 0..option.sample_count {
+          let h = importance_sample_ggx(hammersley(i, option.sample_count), roughness, normal);
+          let n_dot_h = normal.dot(h);
+          let l = h * 2. * n_dot_h - normal;
+          let n_dot_l = normal.dot(l);
+          if n_dot_l > 0. {
+            let pdf = distribution_ggx(n_dot_h, roughness) / 4.;
+            let sample_solid_angle = 1. / (option.sample_count as f32 * pdf + 0.0001);
+            let lod = 0.5 * (sample_solid_angle / texel_solid_angle).log2() + 1.;
+            sum += sample_source(l, lod.max(0.)).xyz() * n_dot_l;
+            weight += n_dot_l;
+          }
+        }
+        let color = sum / weight.max(f32::EPSILON);
+        Vec4::new(color.x, color.y, color.z, 1.)
+      })
+    })
+    .collect()
+}
+
+/// Store the cube levels(for example the result of [prefilter_specular]) in Rgba32Float.
+pub fn cube_levels_to_gpu_texture(levels: &[FloatCubeTexture]) -> GPUBufferTexture {
+  let size = levels[0].positive_x.size();
+  let levels = levels
+    .iter()
+    .map(|level| {
+      level
+        .faces()
+        .iter()
+        .map(|face| face.as_byte_buffer().to_vec())
+        .collect()
+    })
+    .collect();
+
+  GPUBufferTexture {
+    format: TextureFormat::Rgba32Float,
+    size,
+    face_count: 6,
+    levels,
+  }
+}
+
+/// The L2 (9 coefficients) spherical harmonics of the rgb radiance.
+///
+/// https://graphics.stanford.edu/papers/envmap/envmap.pdf
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SphericalHarmonicsL2 {
+  pub coefficients: [Vec3<f32>; 9],
+}
+
+impl SphericalHarmonicsL2 {
+  pub fn basis(direction: Vec3<f32>) -> [f32; 9] {
+    let Vec3 { x, y, z } = direction;
+    [
+      0.282_095,
+      0.488_603 * y,
+      0.488_603 * z,
+      0.488_603 * x,
+      1.092_548 * x * y,
+      1.092_548 * y * z,
+      0.315_392 * (3. * z * z - 1.),
+      1.092_548 * x * z,
+      0.546_274 * (x * x - y * y),
+    ]
+  }
+
+  /// Project the radiance of the environment, every texel is weighted by its solid angle.
+  pub fn project_cube<P, T>(environment: &CubeTexture<P, T>) -> Self
+  where
+    T: Texture2D<Pixel = P>,
+    P: MipMapFilterAblePixel,
+  {
+    let size = environment.face_size();
+    let mut coefficients = [Vec3::zero(); 9];
+    let mut total_solid_angle = 0.;
+    for face in CUBE_FACES {
+      let texture = environment.face(face);
+      for y in 0..size {
+        for x in 0..size {
+          let uv = Vec2::new(x as f32 + 0.5, y as f32 + 0.5) / size as f32;
+          let direction = cube_face_uv_to_direction(face, uv).normalize();
+          let solid_angle = cube_texel_solid_angle(x, y, size);
+          let radiance = texture.read((x, y)).to_rgba().xyz();
+          for (c, basis) in coefficients.iter_mut().zip(Self::basis(direction)) {
+            *c += radiance * (basis * solid_angle);
+          }
+          total_solid_angle += solid_angle;
+        }
+      }
+    }
+
+    // correct the numerical error of the solid angles
+    let correction = 4. * PI / total_solid_angle;
+    Self {
+      coefficients: coefficients.map(|c| c * correction),
+    }
+  }
+
+  /// Reconstruct the radiance in the direction.
+  pub fn evaluate(&self, direction: Vec3<f32>) -> Vec3<f32> {
+    self
+      .coefficients
+      .iter()
+      .zip(Self::basis(direction))
+      .fold(Vec3::zero(), |sum, (&c, basis)| sum + c * basis)
+  }
+
+  /// Convolve with the clamped cosine lobe, after that [Self::evaluate] returns the irradiance.
+  /// The lambertian diffuse is the irradiance multiplied by albedo / PI.
+  pub fn convolve_cosine(&self) -> Self {
+    let band = [PI, 2. * PI / 3., PI / 4.];
+    let mut coefficients = self.coefficients;
+    for (i, c) in coefficients.iter_mut().enumerate() {
+      let l = match i {
+        0 => 0,
+        1..=3 => 1,
+        _ => 2,
+      };
+      *c *= band[l];
+    }
+    Self { coefficients }
+  }
+
+  pub fn irradiance(&self, normal: Vec3<f32>) -> Vec3<f32> {
+    self.convolve_cosine().evaluate(normal)
+  }
+}
+
+/// The split sum BRDF integration lut. The x axis is the n dot v, the y axis(from the top row) is
+/// the roughness, the texel is the scale and bias to the f0.
+pub fn generate_brdf_lut(size: usize, sample_count: usize) -> Texture2DBuffer<Vec2<f32>> {
+  let normal = Vec3::new(0., 0., 1.);
+  let mut data = Vec::with_capacity(size * size);
+  for y in 0..size {
+    for x in 0..size {
+      let n_dot_v = (x as f32 + 0.5) / size as f32;
+      let roughness = (y as f32 + 0.5) / size as f32;
+      let v = Vec3::new((1. - n_dot_v * n_dot_v).sqrt(), 0., n_dot_v);
+
+      let mut scale_bias = Vec2::zero();
+      for i in 0..sample_count {
+        let h = importance_sample_ggx(hammersley(i, sample_count), roughness, normal);
+        let v_dot_h = v.dot(h);
+        let l = h * 2. * v_dot_h - v;
+        let n_dot_l = l.z;
+        let n_dot_h = h.z;
+        if n_dot_l > 0. {
+          let g = geometry_smith_ibl(n_dot_v, n_dot_l, roughness);
+          let g_vis = g * v_dot_h.max(0.) / (n_dot_h * n_dot_v);
+          let fc = (1. - v_dot_h.max(0.)).powi(5);
+          scale_bias += Vec2::new((1. - fc) * g_vis, fc * g_vis);
+        }
+      }
+      data.push(scale_bias / sample_count as f32);
+    }
+  }
+  Texture2DBuffer::from_raw(data, Size::from_usize_pair_min_one((size, size)))
+}
+
+#[cfg(test)]
+fn constant_environment(color: Vec4<f32>) -> FloatCubeTexture {
+  FloatCubeTexture::from_direction_fn(8, |_| color)
+}
+
+#[test]
+fn equirectangular_conversion() {
+  // the upper half is red and the lower half is blue
+  let mut panorama = Texture2DBuffer::init_not_care(Size::from_usize_pair_min_one((16, 8)));
+  panorama.fill_by(|p| {
+    if p.y < 4 {
+      Vec4::new(1., 0., 0., 1.)
+    } else {
+      Vec4::new(0., 0., 1., 1.)
+    }
+  });
+  let cube = equirectangular_to_cube(&panorama, 4);
+  assert_eq!(cube.face_size(), 4);
+  assert_eq!(cube.positive_y.read((1, 2)), Vec4::new(1., 0., 0., 1.));
+  assert_eq!(cube.negative_y.read((3, 0)), Vec4::new(0., 0., 1., 1.));
+  assert_eq!(cube.positive_z.read((0, 0)), Vec4::new(1., 0., 0., 1.));
+}
+
+#[test]
+fn specular_prefilter() {
+  let color = Vec4::new(0.5, 2., 4., 1.);
+  let option = SpecularPrefilterOption {
+    face_size: 8,
+    level_count: 3,
+    sample_count: 32,
+  };
+  let levels = prefilter_specular(&constant_environment(color), &option);
+  assert_eq!(levels.len(), 3);
+  assert_eq!(levels[2].face_size(), 2);
+  for level in &levels {
+    for face in level.faces() {
+      assert!(face.iter().all(|(&p, _)| (p - color).length() < 1e-4));
+    }
+  }
+
+  let texture = cube_levels_to_gpu_texture(&levels);
+  assert!(texture.is_cube());
+  assert!(texture.validate());
+}
+
+#[test]
+fn spherical_harmonics_irradiance() {
+  let sh = SphericalHarmonicsL2::project_cube(&constant_environment(Vec4::new(1., 0.5, 0., 1.)));
+  let irradiance = sh.irradiance(Vec3::new(0., 1., 0.));
+  assert!((irradiance - Vec3::new(PI, PI / 2., 0.)).length() < 1e-3);
+
+  // the radiance only comes from the +y hemisphere
+  let environment =
+    FloatCubeTexture::from_direction_fn(16, |d| Vec4::splat(if d.y > 0. { 1. } else { 0. }));
+  let sh = SphericalHarmonicsL2::project_cube(&environment);
+  assert!((sh.irradiance(Vec3::new(0., 1., 0.)).x - PI).abs() < 0.2);
+  assert!(sh.irradiance(Vec3::new(0., -1., 0.)).x.abs() < 0.2);
+}
+
+#[test]
+fn brdf_lut() {
+  let lut = generate_brdf_lut(16, 128);
+  // the smooth surface viewed from the normal reflects all the energy
+  let smooth = lut.read((15, 0));
+  assert!((smooth.x + smooth.y - 1.).abs() < 0.05);
+  assert!(lut
+    .iter()
+    .all(|(p, _)| p.x >= 0. && p.y >= 0. && p.x + p.y <= 1.01));
+  // the rough surface loses energy
+  let rough = lut.read((8, 15));
+  assert!(rough.x + rough.y < smooth.x + smooth.y);
+}
@@ -1,5 +1,5 @@
 use std::{
-  io::Error,
+  io::{Error, ErrorKind},
   ops::{Deref, DerefMut},
   path::Path,
 };
@@ -9,10 +9,17 @@ pub trait TextureIO<T> {
 }
 
 pub struct PNG;
+pub struct JPEG;
+/// The Radiance rgbe image
+pub struct HDR;
+pub struct OpenEXR;
 
 use fast_hash_collection::FastHashMap;
-use image::{EncodableLayout, ImageBuffer, Pixel, PixelWithColorType};
+use image::{EncodableLayout, ImageBuffer, ImageFormat, Pixel, PixelWithColorType};
+use rendiation_algebra::Vec4;
 use rendiation_texture_types::Size;
+
+use crate::{MipMapFilterAblePixel, Texture2DBuffer};
 impl<P, C> TextureIO<PNG> for ImageBuffer<P, C>
 where
   P: Pixel + PixelWithColorType + 'static,
@@ -31,9 +38,77 @@ where
 }
 
 pub trait AbstractTextureLoader<P> {
-  fn load(&self, on_pixel: &mut dyn FnMut()) -> Result<Size, Error>;
+  /// Check if the file could be loaded by the magic bytes at the start of the file.
+  fn could_load(&self, bytes: &[u8]) -> bool;
+  fn load(&self, bytes: &[u8]) -> Result<Texture2DBuffer<P>, Error>;
+}
+
+fn image_error_to_io(error: image::ImageError) -> Error {
+  match error {
+    image::ImageError::IoError(io) => io,
+    other => Error::new(ErrorKind::InvalidData, other),
+  }
+}
+
+/// Decode by the image crate, the pixels are converted from the float rgba. The ldr images are
+/// normalized to [0, 1] without the color space conversion.
+fn load_by_image_crate<P: MipMapFilterAblePixel>(
+  bytes: &[u8],
+  format: ImageFormat,
+) -> Result<Texture2DBuffer<P>, Error> {
+  let image = image::load_from_memory_with_format(bytes, format)
+    .map_err(image_error_to_io)?
+    .into_rgba32f();
+  let size = Size::from_u32_pair_min_one(image.dimensions());
+  let data = image
+    .pixels()
+    .map(|p| P::from_rgba(Vec4::new(p[0], p[1], p[2], p[3])))
+    .collect();
+  Ok(Texture2DBuffer::from_raw(data, size))
+}
+
+/// The generic decode path of the image crate tone maps the radiance image into ldr, so the
+/// radiance decoder is used directly to keep the float range.
+fn load_radiance<P: MipMapFilterAblePixel>(bytes: &[u8]) -> Result<Texture2DBuffer<P>, Error> {
+  let decoder = image::codecs::hdr::HdrDecoder::new(bytes).map_err(image_error_to_io)?;
+  let meta = decoder.metadata();
+  let size = Size::from_u32_pair_min_one((meta.width, meta.height));
+  let data = decoder
+    .read_image_hdr()
+    .map_err(image_error_to_io)?
+    .into_iter()
+    .map(|p| P::from_rgba(Vec4::new(p[0], p[1], p[2], 1.)))
+    .collect();
+  Ok(Texture2DBuffer::from_raw(data, size))
 }
 
+/// Implement the loader that recognizes the file by any of the magic bytes.
+macro_rules! magic_bytes_loader {
+  ($loader: ty, [$($magic: expr),*], |$bytes: ident| $load: expr) => {
+    impl<P: MipMapFilterAblePixel> AbstractTextureLoader<P> for $loader {
+      fn could_load(&self, bytes: &[u8]) -> bool {
+        $(bytes.starts_with($magic))||*
+      }
+      fn load(&self, $bytes: &[u8]) -> Result<Texture2DBuffer<P>, Error> {
+        $load
+      }
+    }
+  };
+}
+
+magic_bytes_loader!(PNG, [b"\x89PNG\r\n\x1a\n"], |bytes| {
+  load_by_image_crate(bytes, ImageFormat::Png)
+});
+magic_bytes_loader!(JPEG, [b"\xFF\xD8\xFF"], |bytes| {
+  load_by_image_crate(bytes, ImageFormat::Jpeg)
+});
+magic_bytes_loader!(HDR, [b"#?RADIANCE", b"#?RGBE"], |bytes| {
+  load_radiance(bytes)
+});
+magic_bytes_loader!(OpenEXR, [b"\x76\x2F\x31\x01"], |bytes| {
+  load_by_image_crate(bytes, ImageFormat::OpenExr)
+});
+
 /// When user want to load arbitrary image files in different formats, it's
 /// convenient to use the so called dynamic loader which contains file format support
 /// as much as possible. However which specific formats a dynamic loader should support
@@ -42,6 +117,28 @@ pub struct DynamicTextureLoader<P> {
   supports: FastHashMap<&'static str, Box<dyn AbstractTextureLoader<P>>>,
 }
 
+impl<P> Default for DynamicTextureLoader<P> {
+  fn default() -> Self {
+    Self {
+      supports: Default::default(),
+    }
+  }
+}
+
+impl<P: MipMapFilterAblePixel + 'static> DynamicTextureLoader<P> {
+  /// Create the loader with png, jpeg, hdr and exr support.
+  pub fn with_builtin_loaders() -> Self {
+    let mut loader = Self::default();
+    loader
+      .register_loader("png", PNG)
+      .register_loader("jpg", JPEG)
+      .register_loader("jpeg", JPEG)
+      .register_loader("hdr", HDR)
+      .register_loader("exr", OpenEXR);
+    loader
+  }
+}
+
 impl<P> DynamicTextureLoader<P> {
   pub fn register_loader(
     &mut self,
@@ -51,4 +148,78 @@ impl<P> DynamicTextureLoader<P> {
     self.supports.insert(ext_name, Box::new(loader));
     self
   }
+
+  /// Load by the loader that recognizes the magic bytes of the file.
+  pub fn load(&self, bytes: &[u8]) -> Result<Texture2DBuffer<P>, Error> {
+    self
+      .supports
+      .values()
+      .find(|loader| loader.could_load(bytes))
+      .ok_or_else(|| Error::new(ErrorKind::Unsupported, "unknown texture file format"))?
+      .load(bytes)
+  }
+
+  /// Load by the magic bytes of the file, if the format is not recognized, the loader is selected
+  /// by the extension name.
+  pub fn load_file(&self, path: impl AsRef<Path>) -> Result<Texture2DBuffer<P>, Error> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path)?;
+    if let Some(loader) = self.supports.values().find(|l| l.could_load(&bytes)) {
+      return loader.load(&bytes);
+    }
+
+    path
+      .extension()
+      .and_then(|ext| ext.to_str())
+      .and_then(|ext| self.supports.get(ext.to_lowercase().as_str()))
+      .ok_or_else(|| Error::new(ErrorKind::Unsupported, "unknown texture file format"))?
+      .load(&bytes)
+  }
+}
+
+#[test]
+fn dynamic_texture_loader() {
+  use crate::{Rgb, Rgba, Texture2D};
+
+  let mut png = Vec::new();
+  let image = ImageBuffer::from_fn(3, 2, |x, y| Rgba([x as u8 * 100, y as u8 * 200, 7, 255]));
+  image
+    .write_to(
+      &mut std::io::Cursor::new(&mut png),
+      image::ImageOutputFormat::Png,
+    )
+    .unwrap();
+
+  let loader = DynamicTextureLoader::<Rgba<u8>>::with_builtin_loaders();
+  let texture = loader.load(&png).unwrap();
+  assert_eq!(texture.size().into_usize(), (3, 2));
+  assert_eq!(texture.read((2, 1)), Rgba([200, 200, 7, 255]));
+
+  let mut hdr = Vec::new();
+  let pixels: Vec<_> = (0..6).map(|i| Rgb([i as f32 * 4., 0.5, 1.])).collect();
+  image::codecs::hdr::HdrEncoder::new(&mut hdr)
+    .encode(&pixels, 3, 2)
+    .unwrap();
+
+  let loader = DynamicTextureLoader::<Vec4<f32>>::with_builtin_loaders();
+  let texture = loader.load(&hdr).unwrap();
+  // rgbe keeps 8 bits mantissa
+  assert!((texture.read((2, 1)).x - 20.).abs() < 0.1);
+  assert_eq!(texture.read((2, 1)).w, 1.);
+
+  let mut exr = Vec::new();
+  let image = image::Rgba32FImage::from_fn(2, 2, |x, _| Rgba([x as f32 * 16., 2., 3., 0.5]));
+  image::DynamicImage::ImageRgba32F(image)
+    .write_to(
+      &mut std::io::Cursor::new(&mut exr),
+      image::ImageOutputFormat::OpenExr,
+    )
+    .unwrap();
+  let texture = loader.load(&exr).unwrap();
+  assert_eq!(texture.read((1, 0)), Vec4::new(16., 2., 3., 0.5));
+
+  assert!(loader.load(b"unknown").is_err());
+  assert!(DynamicTextureLoader::<Rgba<u8>>::default()
+    .load(&png)
+    .is_err());
 }
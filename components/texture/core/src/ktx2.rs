@@ -0,0 +1,369 @@
+//! https://registry.khronos.org/KTX/specs/2.0/ktxspec.v2.html
+
+use std::{
+  io::{Error, ErrorKind},
+  path::Path,
+};
+
+use crate::{
+  dds::{invalid_data, unsupported_format, ByteReader},
+  *,
+};
+
+pub struct KTX2;
+
+const IDENTIFIER: [u8; 12] = [
+  0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+
+/// identifier, header and index
+const LEVEL_INDEX_OFFSET: usize = 12 + 9 * 4 + 4 * 4 + 2 * 8;
+const LEVEL_INDEX_ENTRY_SIZE: usize = 3 * 8;
+
+/// https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VkFormat.html
+const VK_FORMATS: &[(TextureFormat, u32)] = &[
+  (TextureFormat::R8Unorm, 9),
+  (TextureFormat::Rg8Unorm, 16),
+  (TextureFormat::Rgba8Unorm, 37),
+  (TextureFormat::Rgba8UnormSrgb, 43),
+  (TextureFormat::Bgra8Unorm, 44),
+  (TextureFormat::Bgra8UnormSrgb, 50),
+  (TextureFormat::Rgba16Float, 97),
+  (TextureFormat::R32Float, 100),
+  (TextureFormat::Rgba32Float, 109),
+  (TextureFormat::Bc1RgbaUnorm, 133),
+  (TextureFormat::Bc1RgbaUnormSrgb, 134),
+  (TextureFormat::Bc2RgbaUnorm, 135),
+  (TextureFormat::Bc2RgbaUnormSrgb, 136),
+  (TextureFormat::Bc3RgbaUnorm, 137),
+  (TextureFormat::Bc3RgbaUnormSrgb, 138),
+  (TextureFormat::Bc4RUnorm, 139),
+  (TextureFormat::Bc5RgUnorm, 141),
+  (TextureFormat::Bc7RgbaUnorm, 145),
+  (TextureFormat::Bc7RgbaUnormSrgb, 146),
+];
+
+// the color models and channels of the data format descriptor
+const KHR_DF_MODEL_RGBSDA: u8 = 1;
+const KHR_DF_MODEL_BC1A: u8 = 128;
+const KHR_DF_MODEL_BC2: u8 = 129;
+const KHR_DF_MODEL_BC3: u8 = 130;
+const KHR_DF_MODEL_BC4: u8 = 131;
+const KHR_DF_MODEL_BC5: u8 = 132;
+const KHR_DF_MODEL_BC7: u8 = 134;
+const CHANNEL_R: u8 = 0;
+const CHANNEL_G: u8 = 1;
+const CHANNEL_B: u8 = 2;
+const CHANNEL_A: u8 = 15;
+const CHANNEL_BC1_ALPHA_PRESENT: u8 = 1;
+const SAMPLE_LINEAR: u8 = 0x10;
+const SAMPLE_SIGNED: u8 = 0x40;
+const SAMPLE_FLOAT: u8 = 0x80;
+
+/// The color model, and the (channel, bit length) of the samples in order.
+fn format_description(format: TextureFormat) -> (u8, &'static [(u8, u16)]) {
+  use TextureFormat::*;
+  match format {
+    R8Unorm => (KHR_DF_MODEL_RGBSDA, &[(CHANNEL_R, 8)]),
+    Rg8Unorm => (KHR_DF_MODEL_RGBSDA, &[(CHANNEL_R, 8), (CHANNEL_G, 8)]),
+    Rgba8Unorm | Rgba8UnormSrgb => (
+      KHR_DF_MODEL_RGBSDA,
+      &[
+        (CHANNEL_R, 8),
+        (CHANNEL_G, 8),
+        (CHANNEL_B, 8),
+        (CHANNEL_A, 8),
+      ],
+    ),
+    Bgra8Unorm | Bgra8UnormSrgb => (
+      KHR_DF_MODEL_RGBSDA,
+      &[
+        (CHANNEL_B, 8),
+        (CHANNEL_G, 8),
+        (CHANNEL_R, 8),
+        (CHANNEL_A, 8),
+      ],
+    ),
+    Rgba16Float => (
+      KHR_DF_MODEL_RGBSDA,
+      &[
+        (CHANNEL_R, 16),
+        (CHANNEL_G, 16),
+        (CHANNEL_B, 16),
+        (CHANNEL_A, 16),
+      ],
+    ),
+    R32Float => (KHR_DF_MODEL_RGBSDA, &[(CHANNEL_R, 32)]),
+    Rgba32Float => (
+      KHR_DF_MODEL_RGBSDA,
+      &[
+        (CHANNEL_R, 32),
+        (CHANNEL_G, 32),
+        (CHANNEL_B, 32),
+        (CHANNEL_A, 32),
+      ],
+    ),
+    Bc1RgbaUnorm | Bc1RgbaUnormSrgb => (KHR_DF_MODEL_BC1A, &[(CHANNEL_BC1_ALPHA_PRESENT, 64)]),
+    Bc2RgbaUnorm | Bc2RgbaUnormSrgb => (KHR_DF_MODEL_BC2, &[(CHANNEL_A, 64), (CHANNEL_R, 64)]),
+    Bc3RgbaUnorm | Bc3RgbaUnormSrgb => (KHR_DF_MODEL_BC3, &[(CHANNEL_A, 64), (CHANNEL_R, 64)]),
+    Bc4RUnorm => (KHR_DF_MODEL_BC4, &[(CHANNEL_R, 64)]),
+    Bc5RgUnorm => (KHR_DF_MODEL_BC5, &[(CHANNEL_R, 64), (CHANNEL_G, 64)]),
+    Bc7RgbaUnorm | Bc7RgbaUnormSrgb => (KHR_DF_MODEL_BC7, &[(CHANNEL_R, 128)]),
+    _ => unreachable!("the format is checked by the vk format table"),
+  }
+}
+
+/// Create the basic data format descriptor with the total size prefix.
+fn data_format_descriptor(format: TextureFormat) -> Vec<u8> {
+  let (color_model, samples) = format_description(format);
+  let is_float = matches!(
+    format,
+    TextureFormat::Rgba16Float | TextureFormat::R32Float | TextureFormat::Rgba32Float
+  );
+  let (block_width, block_height) = format.block_dimensions();
+  let block_size = format.block_size(None).unwrap();
+  let block_byte_size = 24 + 16 * samples.len() as u32;
+
+  let mut output = Vec::new();
+  output.extend_from_slice(&(4 + block_byte_size).to_le_bytes());
+  // vendor id and descriptor type are zero
+  output.extend_from_slice(&0_u32.to_le_bytes());
+  // version
+  output.extend_from_slice(&2_u16.to_le_bytes());
+  output.extend_from_slice(&(block_byte_size as u16).to_le_bytes());
+  // bt709 primaries, the transfer function is linear or sRGB, straight alpha
+  let transfer = if format.is_srgb() { 2 } else { 1 };
+  output.extend_from_slice(&[color_model, 1, transfer, 0]);
+  output.extend_from_slice(&[block_width as u8 - 1, block_height as u8 - 1, 0, 0]);
+  output.extend_from_slice(&[block_size as u8, 0, 0, 0, 0, 0, 0, 0]);
+
+  let mut bit_offset: u16 = 0;
+  for &(channel, bit_length) in samples {
+    let mut channel_type = channel;
+    if is_float {
+      channel_type |= SAMPLE_FLOAT | SAMPLE_SIGNED;
+    }
+    if channel == CHANNEL_A && format.is_srgb() && !format.is_compressed() {
+      channel_type |= SAMPLE_LINEAR;
+    }
+    let (lower, upper) = if is_float {
+      ((-1.0_f32).to_bits(), 1.0_f32.to_bits())
+    } else if bit_length >= 32 {
+      (0, u32::MAX)
+    } else {
+      (0, (1 << bit_length) - 1)
+    };
+
+    output.extend_from_slice(&bit_offset.to_le_bytes());
+    output.extend_from_slice(&[(bit_length - 1) as u8, channel_type]);
+    output.extend_from_slice(&[0; 4]);
+    output.extend_from_slice(&lower.to_le_bytes());
+    output.extend_from_slice(&upper.to_le_bytes());
+    bit_offset += bit_length;
+  }
+  output
+}
+
+/// The size of a single component for the type size field.
+fn type_size(format: TextureFormat) -> u32 {
+  match format {
+    TextureFormat::Rgba16Float => 2,
+    TextureFormat::R32Float | TextureFormat::Rgba32Float => 4,
+    _ => 1,
+  }
+}
+
+fn level_alignment(format: TextureFormat) -> usize {
+  let block_size = format.block_size(None).unwrap() as usize;
+  // the lcm of the block size and 4
+  match block_size % 4 {
+    0 => block_size,
+    2 => block_size * 2,
+    _ => block_size * 4,
+  }
+}
+
+fn align(value: usize, alignment: usize) -> usize {
+  (value + alignment - 1) / alignment * alignment
+}
+
+impl GPUBufferTexture {
+  /// Encode into the ktx2 file without the supercompression and key value data.
+  pub fn write_ktx2(&self) -> Result<Vec<u8>, Error> {
+    let vk_format = VK_FORMATS
+      .iter()
+      .find(|(format, _)| *format == self.format)
+      .map(|(_, vk)| *vk)
+      .ok_or_else(|| unsupported_format(self.format))?;
+    if !self.validate() {
+      return Err(Error::new(
+        ErrorKind::InvalidInput,
+        "the texture data size mismatches the format",
+      ));
+    }
+
+    let (width, height) = self.size.into_usize();
+    let level_count = self.level_count();
+    let dfd = data_format_descriptor(self.format);
+    let dfd_offset = LEVEL_INDEX_OFFSET + level_count * LEVEL_INDEX_ENTRY_SIZE;
+
+    // the levels are stored from the smallest one
+    let alignment = level_alignment(self.format);
+    let mut level_index = vec![(0, 0); level_count];
+    let mut offset = dfd_offset + dfd.len();
+    for level in (0..level_count).rev() {
+      offset = align(offset, alignment);
+      let byte_length: usize = self.levels[level].iter().map(|face| face.len()).sum();
+      level_index[level] = (offset, byte_length);
+      offset += byte_length;
+    }
+
+    let mut output = Vec::with_capacity(offset);
+    output.extend_from_slice(&IDENTIFIER);
+    for value in [
+      vk_format,
+      type_size(self.format),
+      width as u32,
+      height as u32,
+      0,
+      0,
+      self.face_count as u32,
+      level_count as u32,
+      0,
+    ] {
+      output.extend_from_slice(&value.to_le_bytes());
+    }
+    output.extend_from_slice(&(dfd_offset as u32).to_le_bytes());
+    output.extend_from_slice(&(dfd.len() as u32).to_le_bytes());
+    // no key value data and supercompression global data
+    output.extend_from_slice(&[0; 4 * 2 + 8 * 2]);
+    for &(offset, byte_length) in &level_index {
+      for value in [offset, byte_length, byte_length] {
+        output.extend_from_slice(&(value as u64).to_le_bytes());
+      }
+    }
+    output.extend_from_slice(&dfd);
+
+    for level in (0..level_count).rev() {
+      output.resize(level_index[level].0, 0);
+      self.levels[level]
+        .iter()
+        .for_each(|face| output.extend_from_slice(face));
+    }
+    Ok(output)
+  }
+
+  pub fn read_ktx2(bytes: &[u8]) -> Result<Self, Error> {
+    let mut reader = ByteReader { bytes, position: 0 };
+    if reader.read_bytes(12)? != IDENTIFIER {
+      return Err(invalid_data("not a ktx2 file"));
+    }
+    let vk_format = reader.read_u32()?;
+    let _type_size = reader.read_u32()?;
+    let width = reader.read_u32()?;
+    let height = reader.read_u32()?;
+    let depth = reader.read_u32()?;
+    let layer_count = reader.read_u32()?;
+    let face_count = reader.read_u32()? as usize;
+    // zero means the mip levels should be generated by the loader
+    let level_count = reader.read_u32()?.max(1) as usize;
+    let supercompression_scheme = reader.read_u32()?;
+
+    let format = VK_FORMATS
+      .iter()
+      .find(|(_, vk)| *vk == vk_format)
+      .map(|(format, _)| *format)
+      .ok_or_else(|| invalid_data("unsupported ktx2 vk format"))?;
+    if supercompression_scheme != 0 {
+      return Err(invalid_data("supercompressed ktx2 file is not supported"));
+    }
+    if depth > 0 || layer_count > 0 {
+      return Err(invalid_data(
+        "only the single 2d or cube ktx2 texture is supported",
+      ));
+    }
+    if face_count != 1 && face_count != 6 {
+      return Err(invalid_data("invalid ktx2 face count"));
+    }
+
+    reader.position = LEVEL_INDEX_OFFSET;
+    let level_index = (0..level_count)
+      .map(|_| {
+        let offset = reader.read_u64()? as usize;
+        let byte_length = reader.read_u64()? as usize;
+        let _uncompressed_byte_length = reader.read_u64()?;
+        Ok((offset, byte_length))
+      })
+      .collect::<Result<Vec<_>, Error>>()?;
+
+    let size = Size::from_u32_pair_min_one((width, height));
+    let levels = level_index
+      .iter()
+      .enumerate()
+      .map(|(level, &(offset, byte_length))| {
+        let (width, height) = size.into_usize();
+        let level_size = Size::from_usize_pair_min_one((width >> level, height >> level));
+        let face_size = texture_byte_size(format, level_size).unwrap();
+        if face_size * face_count != byte_length {
+          return Err(invalid_data("invalid ktx2 level size"));
+        }
+        reader.position = offset;
+        (0..face_count)
+          .map(|_| reader.read_bytes(face_size).map(|face| face.to_vec()))
+          .collect()
+      })
+      .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(Self {
+      format,
+      size,
+      face_count,
+      levels,
+    })
+  }
+}
+
+impl TextureIO<KTX2> for GPUBufferTexture {
+  fn save_to_file(&self, path: &dyn AsRef<Path>) -> Result<(), Error> {
+    std::fs::write(path, self.write_ktx2()?)
+  }
+}
+
+#[test]
+fn ktx2_round_trip() {
+  let texture = crate::dds::test_cube_texture();
+  let file = texture.write_ktx2().unwrap();
+  let read = GPUBufferTexture::read_ktx2(&file).unwrap();
+  assert_eq!(read.format, texture.format);
+  assert_eq!(read.size, texture.size);
+  assert_eq!(read.face_count, 6);
+  assert_eq!(read.levels, texture.levels);
+
+  // the levels are aligned and stored from the smallest one
+  let first_level_offset = u64::from_le_bytes(file[80..88].try_into().unwrap());
+  let last_level_offset = u64::from_le_bytes(file[80 + 72..88 + 72].try_into().unwrap());
+  assert!(first_level_offset > last_level_offset);
+  assert_eq!(first_level_offset % 8, 0);
+
+  assert!(GPUBufferTexture::read_ktx2(&file[..file.len() - 1]).is_err());
+}
+
+#[test]
+fn ktx2_uncompressed() {
+  let data = (0..4 * 3 * 4).map(|v| v as u8).collect();
+  let texture = GPUBufferTexture {
+    format: TextureFormat::Rgba8UnormSrgb,
+    size: Size::from_usize_pair_min_one((4, 3)),
+    face_count: 1,
+    levels: vec![vec![data]],
+  };
+  let read = GPUBufferTexture::read_ktx2(&texture.write_ktx2().unwrap()).unwrap();
+  assert_eq!(read.format, TextureFormat::Rgba8UnormSrgb);
+  assert_eq!(read.levels, texture.levels);
+
+  let texture = GPUBufferTexture {
+    format: TextureFormat::Depth32Float,
+    ..texture
+  };
+  assert!(texture.write_ktx2().is_err());
+}
@@ -9,12 +9,30 @@ use std::{
 pub use address::*;
 pub mod filter;
 pub use filter::*;
+pub mod array;
+pub use array::*;
 pub mod cube;
 pub use cube::*;
 pub mod sampler;
 pub use sampler::*;
+pub mod bc;
+pub use bc::*;
+pub mod bc7;
+pub use bc7::*;
+pub mod compress;
+pub use compress::*;
+pub mod dds;
+pub use dds::*;
+pub mod ktx2;
+pub use ktx2::*;
+pub mod ibl;
+pub use ibl::*;
+pub mod mipmap;
+pub use mipmap::*;
 pub mod iter;
 pub use iter::*;
+pub mod texture3d;
+pub use texture3d::*;
 pub mod util;
 pub use util::*;
 pub mod io;
@@ -25,7 +43,7 @@ pub use image::*;
 use incremental::*;
 use rendiation_algebra::{Lerp, Scalar, Vec2};
 pub use rendiation_texture_types::*;
-pub use wgpu_types::TextureFormat;
+pub use wgpu_types::{CompareFunction, TextureFormat};
 
 pub trait Texture2D: Sized {
   type Pixel: Copy;
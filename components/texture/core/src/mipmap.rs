@@ -1,32 +1,478 @@
+use rendiation_algebra::*;
+
+use crate::*;
+
+/// The pixel that could be converted from and to the float rgba, so it could be filtered.
+pub trait MipMapFilterAblePixel: Copy {
+  fn to_rgba(self) -> Vec4<f32>;
+  fn from_rgba(rgba: Vec4<f32>) -> Self;
+}
+
+fn unorm8_to_f32(v: u8) -> f32 {
+  v as f32 / 255.
+}
+
+fn f32_to_unorm8(v: f32) -> u8 {
+  (v.clamp(0., 1.) * 255. + 0.5) as u8
+}
+
+impl MipMapFilterAblePixel for Rgba<u8> {
+  fn to_rgba(self) -> Vec4<f32> {
+    let [r, g, b, a] = self.0.map(unorm8_to_f32);
+    Vec4::new(r, g, b, a)
+  }
+  fn from_rgba(rgba: Vec4<f32>) -> Self {
+    Rgba([rgba.x, rgba.y, rgba.z, rgba.w].map(f32_to_unorm8))
+  }
+}
+
+impl MipMapFilterAblePixel for Rgb<u8> {
+  fn to_rgba(self) -> Vec4<f32> {
+    let [r, g, b] = self.0.map(unorm8_to_f32);
+    Vec4::new(r, g, b, 1.)
+  }
+  fn from_rgba(rgba: Vec4<f32>) -> Self {
+    Rgb([rgba.x, rgba.y, rgba.z].map(f32_to_unorm8))
+  }
+}
+
+impl MipMapFilterAblePixel for Luma<u8> {
+  fn to_rgba(self) -> Vec4<f32> {
+    let l = unorm8_to_f32(self.0[0]);
+    Vec4::new(l, l, l, 1.)
+  }
+  fn from_rgba(rgba: Vec4<f32>) -> Self {
+    Luma([f32_to_unorm8(rgba.x)])
+  }
+}
+
+impl MipMapFilterAblePixel for Vec4<f32> {
+  fn to_rgba(self) -> Vec4<f32> {
+    self
+  }
+  fn from_rgba(rgba: Vec4<f32>) -> Self {
+    rgba
+  }
+}
+
+impl MipMapFilterAblePixel for Vec3<f32> {
+  fn to_rgba(self) -> Vec4<f32> {
+    Vec4::new(self.x, self.y, self.z, 1.)
+  }
+  fn from_rgba(rgba: Vec4<f32>) -> Self {
+    Vec3::new(rgba.x, rgba.y, rgba.z)
+  }
+}
+
+impl MipMapFilterAblePixel for f32 {
+  fn to_rgba(self) -> Vec4<f32> {
+    Vec4::new(self, self, self, 1.)
+  }
+  fn from_rgba(rgba: Vec4<f32>) -> Self {
+    rgba.x
+  }
+}
+
+/// The filter used to downsample the previous level to the next level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MipMapFilter {
+  /// Average the covered texels, weighted by the coverage. This is the classic 2x2 average for the
+  /// power of two size.
+  Box,
+  /// The sinc windowed by the kaiser window. The width is the half width of the window in the
+  /// target texel, the common choice is width 3 and alpha 4.
+  Kaiser { width: f32, alpha: f32 },
+  /// The lanczos resampling, the common choice of lobes is 3.
+  Lanczos { lobes: f32 },
+}
+
+impl MipMapFilter {
+  /// The radius of the filter in the target texel.
+  fn support(&self) -> f32 {
+    match self {
+      MipMapFilter::Box => 0.5,
+      MipMapFilter::Kaiser { width, .. } => *width,
+      MipMapFilter::Lanczos { lobes } => *lobes,
+    }
+  }
+
+  /// x is the distance to the target texel center, in the target texel.
+  fn weight(&self, x: f32) -> f32 {
+    match self {
+      MipMapFilter::Box => unreachable!("box filter is weighted by the coverage"),
+      MipMapFilter::Kaiser { width, alpha } => sinc(x) * kaiser(x / width, *alpha),
+      MipMapFilter::Lanczos { lobes } => lanczos(x, *lobes),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MipMapGenerateOption {
+  pub filter: MipMapFilter,
+  /// If the rgb channels are encoded in sRGB, they are decoded into linear space before filtering
+  /// and encoded back after. The alpha channel is always linear.
+  pub srgb: bool,
+}
+
+impl Default for MipMapGenerateOption {
+  fn default() -> Self {
+    Self {
+      filter: MipMapFilter::Box,
+      srgb: false,
+    }
+  }
+}
+
+/// https://en.wikipedia.org/wiki/SRGB#Transformation
+fn srgb_to_linear(v: f32) -> f32 {
+  if v <= 0.04045 {
+    v / 12.92
+  } else {
+    ((v + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+fn linear_to_srgb(v: f32) -> f32 {
+  if v <= 0.0031308 {
+    v * 12.92
+  } else {
+    1.055 * v.powf(1. / 2.4) - 0.055
+  }
+}
+
+/// The intermediate level in float rgba, the levels are downsampled from the previous float level
+/// to avoid accumulating the quantization error.
+struct FloatLevel {
+  width: usize,
+  height: usize,
+  data: Vec<Vec4<f32>>,
+}
+
+impl FloatLevel {
+  fn from_texture<T>(texture: &T, srgb: bool) -> Self
+  where
+    T: Texture2D,
+    T::Pixel: MipMapFilterAblePixel,
+  {
+    let (width, height) = texture.size().into_usize();
+    let mut data = Vec::with_capacity(width * height);
+    for y in 0..height {
+      for x in 0..width {
+        let mut rgba = texture.read((x, y)).to_rgba();
+        if srgb {
+          rgba = Vec4::new(
+            srgb_to_linear(rgba.x),
+            srgb_to_linear(rgba.y),
+            srgb_to_linear(rgba.z),
+            rgba.w,
+          );
+        }
+        data.push(rgba);
+      }
+    }
+    Self {
+      width,
+      height,
+      data,
+    }
+  }
+
+  fn to_texture<T>(&self, srgb: bool) -> T
+  where
+    T: Texture2dInitAble,
+    T::Pixel: MipMapFilterAblePixel,
+  {
+    let mut texture = T::init_not_care(Size::from_usize_pair_min_one((self.width, self.height)));
+    for (i, &rgba) in self.data.iter().enumerate() {
+      let rgba = if srgb {
+        Vec4::new(
+          linear_to_srgb(rgba.x),
+          linear_to_srgb(rgba.y),
+          linear_to_srgb(rgba.z),
+          rgba.w,
+        )
+      } else {
+        rgba
+      };
+      texture.write((i % self.width, i / self.width), T::Pixel::from_rgba(rgba));
+    }
+    texture
+  }
+
+  /// The separable downsample, horizontal first.
+  fn downsample(&self, width: usize, height: usize, filter: &MipMapFilter) -> Self {
+    let x_weights = resample_weights(self.width, width, filter);
+    let mut horizontal = Vec::with_capacity(width * self.height);
+    for y in 0..self.height {
+      let row = &self.data[y * self.width..(y + 1) * self.width];
+      horizontal.extend(
+        x_weights
+          .iter()
+          .map(|weights| weighted_sum(weights, |x| row[x])),
+      );
+    }
+
+    let y_weights = resample_weights(self.height, height, filter);
+    let mut data = Vec::with_capacity(width * height);
+    for weights in &y_weights {
+      data.extend((0..width).map(|x| weighted_sum(weights, |y| horizontal[y * width + x])));
+    }
+
+    Self {
+      width,
+      height,
+      data,
+    }
+  }
+}
+
+fn weighted_sum(weights: &[(usize, f32)], source: impl Fn(usize) -> Vec4<f32>) -> Vec4<f32> {
+  weights
+    .iter()
+    .fold(Vec4::zero(), |sum, &(i, weight)| sum + source(i) * weight)
+}
+
+/// Compute the normalized (source texel, weight) list of every target texel, the source outside
+/// the edge is clamped.
+fn resample_weights(source: usize, target: usize, filter: &MipMapFilter) -> Vec<Vec<(usize, f32)>> {
+  let scale = source as f32 / target as f32;
+  (0..target)
+    .map(|t| {
+      let mut weights: Vec<(usize, f32)> = Vec::new();
+      let mut push = |i: isize, weight: f32| {
+        let i = i.clamp(0, source as isize - 1) as usize;
+        match weights.iter_mut().find(|(index, _)| *index == i) {
+          Some((_, w)) => *w += weight,
+          None => weights.push((i, weight)),
+        }
+      };
+
+      if let MipMapFilter::Box = filter {
+        let (start, end) = (t as f32 * scale, (t + 1) as f32 * scale);
+        for i in start.floor() as isize..end.ceil() as isize {
+          let coverage = end.min((i + 1) as f32) - start.max(i as f32);
+          if coverage > 0. {
+            push(i, coverage);
+          }
+        }
+      } else {
+        let center = (t as f32 + 0.5) * scale;
+        let radius = filter.support() * scale;
+        for i in (center - radius).floor() as isize..=(center + radius).ceil() as isize {
+          let weight = filter.weight((i as f32 + 0.5 - center) / scale);
+          if weight != 0. {
+            push(i, weight);
+          }
+        }
+      }
+
+      let sum: f32 = weights.iter().map(|(_, w)| w).sum();
+      weights.iter_mut().for_each(|(_, w)| *w /= sum);
+      weights
+    })
+    .collect()
+}
+
+/// The texture and all the downsampled levels, the size of the next level is the half of the
+/// previous level rounded down, and at least one.
 pub struct MipMap<T> {
   levels: Vec<T>,
 }
 
+fn next_level_size(size: Size) -> Size {
+  Size::from_usize_pair_min_one((size.width_usize() / 2, size.height_usize() / 2))
+}
+
 impl<T: Texture2D> MipMap<T> {
+  pub fn from_levels(levels: Vec<T>) -> Self {
+    assert!(!levels.is_empty(), "mipmap requires at least one level");
+    Self { levels }
+  }
+
   pub fn level_count(&self) -> usize {
     self.levels.len()
   }
 
+  pub fn level(&self, level: usize) -> Option<&T> {
+    self.levels.get(level)
+  }
+
+  pub fn levels(&self) -> &[T] {
+    &self.levels
+  }
+
+  pub fn into_levels(self) -> Vec<T> {
+    self.levels
+  }
+
   pub fn main_layer(&self) -> &T {
     &self.levels[0]
   }
 
-  pub fn main_layer_mut(&self) -> &mut T {
+  pub fn main_layer_mut(&mut self) -> &mut T {
     &mut self.levels[0]
   }
 
   /// validate the mip arrays' each layer size is a valid mipmap array
   pub fn validate_size(&self) -> bool {
-    let mut previous_level = None;
+    self
+      .levels
+      .windows(2)
+      .all(|pair| next_level_size(pair[0].size()) == pair[1].size())
+  }
+}
 
-    for level in self.levels {
-      if let Some(previous) = previous_level {
-        if previous.width / 2 != level.width || previous.height / 2 != level.height {
-          return false;
-        }
-      };
-      previous_level = level.into.size().into()
+impl<T> MipMap<T>
+where
+  T: Texture2dInitAble,
+  T::Pixel: MipMapFilterAblePixel,
+{
+  /// Generate the full mip chain down to 1x1 from the base level.
+  pub fn generate(base: T, option: &MipMapGenerateOption) -> Self {
+    let level_count = base.size().mip_level_count();
+    let mut current = FloatLevel::from_texture(&base, option.srgb);
+    let mut levels = Vec::with_capacity(level_count);
+    levels.push(base);
+
+    for _ in 1..level_count {
+      let size = next_level_size(Size::from_usize_pair_min_one((
+        current.width,
+        current.height,
+      )));
+      let (width, height) = size.into_usize();
+      current = current.downsample(width, height, &option.filter);
+      levels.push(current.to_texture(option.srgb));
     }
-    true
+
+    Self { levels }
+  }
+
+  /// Bilinear sample the two nearest levels of the lod, and blend them linearly. The lod is
+  /// clamped into the level range. The pixels are interpolated as they are stored, no sRGB decode
+  /// is applied.
+  pub fn sample(&self, uv: Vec2<f32>, lod: f32, address: AddressMode) -> T::Pixel {
+    let lod = lod.clamp(0., (self.level_count() - 1) as f32);
+    let low = lod.floor() as usize;
+    let high = (low + 1).min(self.level_count() - 1);
+    let low_sample = sample_bilinear(&self.levels[low], uv, address);
+    let result = if high == low {
+      low_sample
+    } else {
+      low_sample.lerp(
+        sample_bilinear(&self.levels[high], uv, address),
+        lod - low as f32,
+      )
+    };
+    T::Pixel::from_rgba(result)
+  }
+}
+
+pub(crate) fn sample_bilinear<T>(texture: &T, uv: Vec2<f32>, address: AddressMode) -> Vec4<f32>
+where
+  T: Texture2D,
+  T::Pixel: MipMapFilterAblePixel,
+{
+  TextureSampler {
+    address_mode_u: address,
+    address_mode_v: address,
+    mag_filter: FilterMode::Linear,
+    ..Default::default()
+  }
+  .sample_2d(texture, uv)
+}
+
+#[cfg(test)]
+fn gradient(width: usize, height: usize) -> Texture2DBuffer<Vec4<f32>> {
+  let mut texture = Texture2DBuffer::init_not_care(Size::from_usize_pair_min_one((width, height)));
+  texture.fill_by(|p| Vec4::new(p.x as f32, p.y as f32, 1., 1.));
+  texture
+}
+
+#[test]
+fn mipmap_generate_npot() {
+  for filter in [
+    MipMapFilter::Box,
+    MipMapFilter::Kaiser {
+      width: 3.,
+      alpha: 4.,
+    },
+    MipMapFilter::Lanczos { lobes: 3. },
+  ] {
+    let option = MipMapGenerateOption {
+      filter,
+      srgb: false,
+    };
+    let mipmap = MipMap::generate(gradient(13, 6), &option);
+    assert_eq!(mipmap.level_count(), 4);
+    assert!(mipmap.validate_size());
+    let sizes: Vec<_> = mipmap
+      .levels()
+      .iter()
+      .map(|l| l.size().into_usize())
+      .collect();
+    assert_eq!(sizes, [(13, 6), (6, 3), (3, 1), (1, 1)]);
+
+    // constant channels are preserved, and the average is kept at the center
+    let last = mipmap.level(3).unwrap().read((0, 0));
+    assert!((last.z - 1.).abs() < 1e-4);
+    assert!((last.x - 6.).abs() < 1e-3);
+    assert!((last.y - 2.5).abs() < 1e-3);
   }
 }
+
+#[test]
+fn mipmap_box_filter() {
+  let mipmap = MipMap::generate(gradient(4, 4), &Default::default());
+  let level = mipmap.level(1).unwrap();
+  assert_eq!(level.read((0, 0)), Vec4::new(0.5, 0.5, 1., 1.));
+  assert_eq!(level.read((1, 1)), Vec4::new(2.5, 2.5, 1., 1.));
+
+  // 3 texels to 1 texel
+  let mipmap = MipMap::generate(gradient(3, 1), &Default::default());
+  assert_eq!(mipmap.level(1).unwrap().read((0, 0)).x, 1.);
+}
+
+#[test]
+fn mipmap_srgb() {
+  let mut texture = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(2, 1);
+  texture.write((0, 0), Rgba([0, 0, 0, 0]));
+  texture.write((1, 0), Rgba([255, 255, 255, 255]));
+
+  let linear = MipMap::generate(texture.clone(), &Default::default());
+  assert_eq!(
+    linear.level(1).unwrap().read((0, 0)),
+    Rgba([128, 128, 128, 128])
+  );
+
+  let option = MipMapGenerateOption {
+    srgb: true,
+    ..Default::default()
+  };
+  let srgb = MipMap::generate(texture, &option);
+  assert_eq!(
+    srgb.level(1).unwrap().read((0, 0)),
+    Rgba([188, 188, 188, 128])
+  );
+}
+
+#[test]
+fn mipmap_trilinear_sample() {
+  let mipmap = MipMap::generate(gradient(4, 4), &Default::default());
+  let center = Vec2::new(0.5, 0.5);
+  let sample = |lod| mipmap.sample(center, lod, AddressMode::ClampToEdge);
+  assert_eq!(sample(0.), Vec4::new(1.5, 1.5, 1., 1.));
+  assert_eq!(sample(2.), Vec4::new(1.5, 1.5, 1., 1.));
+  assert_eq!(sample(10.), sample(2.));
+
+  // blend between the levels
+  let corner = Vec2::new(0.125, 0.125);
+  let lod0 = mipmap.sample(corner, 0., AddressMode::ClampToEdge);
+  let lod1 = mipmap.sample(corner, 1., AddressMode::ClampToEdge);
+  let half = mipmap.sample(corner, 0.5, AddressMode::ClampToEdge);
+  assert_eq!(lod0.x, 0.);
+  assert_eq!(half.x, (lod0.x + lod1.x) / 2.);
+
+  // repeat wraps to the other side
+  let edge = mipmap.sample(Vec2::new(0., 0.5), 0., AddressMode::Repeat);
+  assert_eq!(edge.x, 1.5);
+}
@@ -1,3 +1,5 @@
+use rendiation_algebra::*;
+
 use crate::*;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Incremental)]
@@ -8,6 +10,8 @@ pub struct TextureSampler {
   pub mag_filter: FilterMode,
   pub min_filter: FilterMode,
   pub mipmap_filter: FilterMode,
+  /// Only used when the address mode is [AddressMode::ClampToBorder]
+  pub border_color: SamplerBorderColor,
 }
 
 impl TextureSampler {
@@ -19,8 +23,18 @@ impl TextureSampler {
       mag_filter: FilterMode::Linear,
       min_filter: FilterMode::Linear,
       mipmap_filter: FilterMode::Linear,
+      border_color: SamplerBorderColor::TransparentBlack,
     }
   }
+
+  pub fn use_border(&self) -> bool {
+    [
+      self.address_mode_u,
+      self.address_mode_v,
+      self.address_mode_w,
+    ]
+    .contains(&AddressMode::ClampToBorder)
+  }
 }
 
 impl Default for TextureSampler {
@@ -32,6 +46,200 @@ impl Default for TextureSampler {
       mag_filter: FilterMode::Nearest,
       min_filter: FilterMode::Nearest,
       mipmap_filter: FilterMode::Nearest,
+      border_color: SamplerBorderColor::TransparentBlack,
+    }
+  }
+}
+
+/// The texels and weights to filter on one axis, the None texel is the border.
+fn axis_taps(
+  coord: f32,
+  size: usize,
+  address: AddressMode,
+  filter: FilterMode,
+) -> [(Option<usize>, f32); 2] {
+  let position = coord * size as f32;
+  match filter {
+    FilterMode::Nearest => {
+      let texel = address.texel_index(position.floor() as isize, size);
+      [(texel, 1.), (texel, 0.)]
+    }
+    FilterMode::Linear => {
+      // texel centers are at the half integer
+      let position = position - 0.5;
+      let start = position.floor();
+      let t = position - start;
+      let start = start as isize;
+      [
+        (address.texel_index(start, size), 1. - t),
+        (address.texel_index(start + 1, size), t),
+      ]
+    }
+  }
+}
+
+/// The cpu sampling follows the gpu behavior, but the mipmap is not selected here, the mag filter
+/// is used for the filtering.
+impl TextureSampler {
+  pub fn sample_2d<T>(&self, texture: &T, uv: Vec2<f32>) -> Vec4<f32>
+  where
+    T: Texture2D,
+    T::Pixel: MipMapFilterAblePixel,
+  {
+    self.sample_2d_by(texture, uv, |texel| texel)
+  }
+
+  /// The depth comparison is done on the first channel of each texel before the filtering, the
+  /// result is the ratio of the passed texels. The reference is the left operand of the compare.
+  pub fn sample_2d_compare<T>(
+    &self,
+    texture: &T,
+    uv: Vec2<f32>,
+    reference: f32,
+    compare: CompareFunction,
+  ) -> f32
+  where
+    T: Texture2D,
+    T::Pixel: MipMapFilterAblePixel,
+  {
+    self
+      .sample_2d_by(texture, uv, |texel| {
+        let passed = match compare {
+          CompareFunction::Never => false,
+          CompareFunction::Less => reference < texel.x,
+          CompareFunction::Equal => reference == texel.x,
+          CompareFunction::LessEqual => reference <= texel.x,
+          CompareFunction::Greater => reference > texel.x,
+          CompareFunction::NotEqual => reference != texel.x,
+          CompareFunction::GreaterEqual => reference >= texel.x,
+          CompareFunction::Always => true,
+        };
+        Vec4::splat(if passed { 1. } else { 0. })
+      })
+      .x
+  }
+
+  fn sample_2d_by<T>(
+    &self,
+    texture: &T,
+    uv: Vec2<f32>,
+    texel_map: impl Fn(Vec4<f32>) -> Vec4<f32>,
+  ) -> Vec4<f32>
+  where
+    T: Texture2D,
+    T::Pixel: MipMapFilterAblePixel,
+  {
+    let (width, height) = texture.size().into_usize();
+    let u = axis_taps(uv.x, width, self.address_mode_u, self.mag_filter);
+    let v = axis_taps(uv.y, height, self.address_mode_v, self.mag_filter);
+    let border = texel_map(self.border_color.to_rgba());
+
+    let mut result = Vec4::zero();
+    for (y, wy) in v {
+      for (x, wx) in u {
+        let texel = match (x, y) {
+          (Some(x), Some(y)) => texel_map(texture.read((x, y)).to_rgba()),
+          _ => border,
+        };
+        result += texel * (wx * wy);
+      }
     }
+    result
+  }
+
+  /// The layer is clamped into the layer range.
+  pub fn sample_2d_array<T>(&self, texture: &T, uv: Vec2<f32>, layer: usize) -> Vec4<f32>
+  where
+    T: Texture2DArray,
+    <T::Layer as Texture2D>::Pixel: MipMapFilterAblePixel,
+  {
+    let layer = layer.min(texture.layer_count() - 1);
+    self.sample_2d(texture.layer(layer), uv)
   }
+
+  pub fn sample_3d<T>(&self, texture: &T, uvw: Vec3<f32>) -> Vec4<f32>
+  where
+    T: Texture3D,
+    T::Pixel: MipMapFilterAblePixel,
+  {
+    let (width, height) = texture.size().into_usize();
+    let u = axis_taps(uvw.x, width, self.address_mode_u, self.mag_filter);
+    let v = axis_taps(uvw.y, height, self.address_mode_v, self.mag_filter);
+    let w = axis_taps(uvw.z, texture.depth(), self.address_mode_w, self.mag_filter);
+    let border = self.border_color.to_rgba();
+
+    let mut result = Vec4::zero();
+    for (z, wz) in w {
+      for (y, wy) in v {
+        for (x, wx) in u {
+          let texel = match (x, y, z) {
+            (Some(x), Some(y), Some(z)) => texture.read((x, y, z)).to_rgba(),
+            _ => border,
+          };
+          result += texel * (wx * wy * wz);
+        }
+      }
+    }
+    result
+  }
+}
+
+#[test]
+fn sample_2d_address() {
+  let mut texture = Texture2DBuffer::init_not_care(Size::from_usize_pair_min_one((2, 1)));
+  texture.write((0, 0), 0.);
+  texture.write((1, 0), 1.);
+
+  let mut sampler = TextureSampler {
+    mag_filter: FilterMode::Linear,
+    ..Default::default()
+  };
+  let sample = |sampler: &TextureSampler, u: f32| sampler.sample_2d(&texture, Vec2::new(u, 0.5)).x;
+  assert_eq!(sample(&sampler, 0.5), 0.5);
+  assert_eq!(sample(&sampler, 0.), 0.);
+
+  sampler.address_mode_u = AddressMode::Repeat;
+  assert_eq!(sample(&sampler, 0.), 0.5);
+  assert_eq!(sample(&sampler, 1.25), 0.);
+
+  sampler.address_mode_u = AddressMode::MirrorRepeat;
+  assert_eq!(sample(&sampler, -0.25), 0.);
+  assert_eq!(sample(&sampler, 1.25), 1.);
+
+  sampler.address_mode_u = AddressMode::ClampToBorder;
+  sampler.border_color = SamplerBorderColor::OpaqueWhite;
+  assert_eq!(sample(&sampler, 0.), 0.5);
+  assert_eq!(
+    sampler.sample_2d(&texture, Vec2::new(-1., 0.5)),
+    Vec4::one()
+  );
+  sampler.border_color = SamplerBorderColor::TransparentBlack;
+  assert_eq!(sample(&sampler, 1.), 0.5);
+
+  sampler.mag_filter = FilterMode::Nearest;
+  assert_eq!(sample(&sampler, 0.6), 1.);
+}
+
+#[test]
+fn sample_2d_compare() {
+  let mut texture = Texture2DBuffer::init_not_care(Size::from_usize_pair_min_one((2, 1)));
+  texture.write((0, 0), 0.2);
+  texture.write((1, 0), 0.8);
+
+  let sampler = TextureSampler {
+    mag_filter: FilterMode::Linear,
+    ..Default::default()
+  };
+  let sample = |u: f32, reference: f32| {
+    sampler.sample_2d_compare(
+      &texture,
+      Vec2::new(u, 0.5),
+      reference,
+      CompareFunction::Less,
+    )
+  };
+  assert_eq!(sample(0.25, 0.5), 0.);
+  assert_eq!(sample(0.75, 0.5), 1.);
+  assert_eq!(sample(0.5, 0.5), 0.5);
+  assert_eq!(sample(0.5, 0.1), 1.);
 }
@@ -0,0 +1,136 @@
+use std::num::NonZeroUsize;
+
+use rendiation_algebra::Vec3;
+
+use crate::*;
+
+/// The volume texture, the texels are addressed by (x, y, z), z is the depth slice.
+pub trait Texture3D: Sized {
+  type Pixel: Copy;
+
+  fn get(&self, position: impl Into<Vec3<usize>>) -> &Self::Pixel;
+  fn get_mut(&mut self, position: impl Into<Vec3<usize>>) -> &mut Self::Pixel;
+
+  fn read(&self, position: impl Into<Vec3<usize>>) -> Self::Pixel {
+    *self.get(position)
+  }
+  fn write(&mut self, position: impl Into<Vec3<usize>>, v: Self::Pixel) {
+    *self.get_mut(position.into()) = v;
+  }
+
+  /// The size of a depth slice
+  fn size(&self) -> Size;
+  fn depth(&self) -> usize;
+
+  fn fill_by(&mut self, writer: impl Fn(Vec3<usize>) -> Self::Pixel) {
+    let (width, height) = self.size().into_usize();
+    for z in 0..self.depth() {
+      for y in 0..height {
+        for x in 0..width {
+          let position = Vec3::new(x, y, z);
+          self.write(position, writer(position));
+        }
+      }
+    }
+  }
+}
+
+#[derive(Clone)]
+pub struct Texture3DBuffer<P> {
+  data: Vec<P>,
+  size: Size,
+  depth: NonZeroUsize,
+}
+
+impl<P> Texture3DBuffer<P> {
+  /// The data is stored slice by slice, and row by row in a slice.
+  pub fn from_raw(data: Vec<P>, size: Size, depth: NonZeroUsize) -> Self {
+    assert_eq!(data.len(), size.area() * usize::from(depth));
+    Self { data, size, depth }
+  }
+
+  pub fn as_buffer(&self) -> &[P] {
+    self.data.as_slice()
+  }
+}
+
+impl<P: Clone> Texture3DBuffer<P> {
+  pub fn init_with(size: Size, depth: NonZeroUsize, pixel: P) -> Self {
+    Self {
+      data: vec![pixel; size.area() * usize::from(depth)],
+      size,
+      depth,
+    }
+  }
+}
+
+impl<T> core::fmt::Debug for Texture3DBuffer<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Texture3DBuffer")
+      .field("data", &"raw data skipped")
+      .field("size", &self.size)
+      .field("depth", &self.depth)
+      .finish()
+  }
+}
+
+impl<P: Copy> Texture3DBuffer<P> {
+  fn index(&self, position: Vec3<usize>) -> usize {
+    let (width, height) = self.size.into_usize();
+    (position.z * height + position.y) * width + position.x
+  }
+}
+
+impl<P: Copy> Texture3D for Texture3DBuffer<P> {
+  type Pixel = P;
+
+  fn get(&self, position: impl Into<Vec3<usize>>) -> &Self::Pixel {
+    &self.data[self.index(position.into())]
+  }
+
+  fn get_mut(&mut self, position: impl Into<Vec3<usize>>) -> &mut Self::Pixel {
+    let index = self.index(position.into());
+    &mut self.data[index]
+  }
+
+  fn size(&self) -> Size {
+    self.size
+  }
+
+  fn depth(&self) -> usize {
+    self.depth.into()
+  }
+}
+
+#[test]
+fn texture_3d_trilinear_sample() {
+  let size = Size::from_usize_pair_min_one((2, 2));
+  let mut texture = Texture3DBuffer::init_with(size, NonZeroUsize::new(2).unwrap(), 0.);
+  texture.fill_by(|p| (p.x + p.y * 2 + p.z * 4) as f32);
+  assert_eq!(texture.read((1, 0, 1)), 5.);
+
+  let sampler = TextureSampler {
+    mag_filter: FilterMode::Linear,
+    ..Default::default()
+  };
+  // the center is the average of all the texels
+  assert_eq!(sampler.sample_3d(&texture, Vec3::new(0.5, 0.5, 0.5)).x, 3.5);
+  assert_eq!(
+    sampler.sample_3d(&texture, Vec3::new(0.75, 0.25, 0.5)).x,
+    3.
+  );
+
+  let sampler = TextureSampler {
+    address_mode_w: AddressMode::ClampToBorder,
+    border_color: SamplerBorderColor::OpaqueBlack,
+    ..Default::default()
+  };
+  assert_eq!(
+    sampler.sample_3d(&texture, Vec3::new(0.75, 0.25, 1.5)).x,
+    0.
+  );
+  assert_eq!(
+    sampler.sample_3d(&texture, Vec3::new(0.75, 0.25, 0.9)).x,
+    5.
+  );
+}
@@ -35,3 +35,49 @@ fn lanczos_eval() {
   assert!(lanczos(1.0_f32, 1.) - 0. <= f32::EPSILON);
   assert!(lanczos(2.0_f32, 2.) - 0. <= f32::EPSILON);
 }
+
+/// https://en.wikipedia.org/wiki/Bessel_function#Modified_Bessel_functions
+///
+/// the zeroth order modified bessel function of the first kind, evaluated by power series
+pub fn bessel_i0<T: Scalar>(x: T) -> T {
+  let quarter_x2 = x * x / (T::two() * T::two());
+  let mut sum = T::one();
+  let mut term = T::one();
+  let mut k = T::one();
+  loop {
+    term = term * quarter_x2 / (k * k);
+    sum += term;
+    if term <= sum * T::epsilon() {
+      return sum;
+    }
+    k += T::one();
+  }
+}
+
+#[test]
+fn bessel_i0_eval() {
+  assert_eq!(bessel_i0(0.0_f64), 1.);
+  assert!((bessel_i0(1.0_f64) - 1.266_065_877_752_008_2).abs() <= 1e-7);
+  assert!((bessel_i0(4.0_f64) - 11.301_921_952_136_33).abs() <= 1e-6);
+}
+
+/// https://en.wikipedia.org/wiki/Kaiser_window
+///
+/// x is normalized by the half width of the window, alpha controls the trade off between the main
+/// lobe width and the side lobe level.
+pub fn kaiser<T: Scalar>(x: T, alpha: T) -> T {
+  if x.abs() > T::one() {
+    return T::zero();
+  }
+
+  let pi_alpha = T::PI() * alpha;
+  bessel_i0(pi_alpha * (T::one() - x * x).sqrt()) / bessel_i0(pi_alpha)
+}
+
+#[test]
+fn kaiser_eval() {
+  assert_eq!(kaiser(0.0_f32, 4.), 1.);
+  assert_eq!(kaiser(1.5_f32, 4.), 0.);
+  assert!(kaiser(1.0_f32, 4.) < 1e-4);
+  assert!(kaiser(0.5_f32, 4.) < 1.);
+}
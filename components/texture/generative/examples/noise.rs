@@ -0,0 +1,38 @@
+use rendiation_generative_texture::*;
+use rendiation_texture::*;
+
+fn main() {
+  let size = Size::from_u32_pair_min_one((512, 512));
+  let to_gray = |value: f32| {
+    let value = (value * 255.) as u8;
+    image::Rgba([value, value, value, 255])
+  };
+
+  let fbm = Fbm {
+    noise: PerlinNoise::new(42),
+    option: Default::default(),
+  };
+  let image: image::RgbaImage = NoiseTexture {
+    noise: &fbm,
+    frequency: 1. / 64.,
+  }
+  .map(to_gray)
+  .generate(size);
+  image.save("fbm.png").unwrap();
+
+  let warped = DomainWarp {
+    noise: &fbm,
+    warp: Fbm {
+      noise: SimplexNoise::new(7),
+      option: Default::default(),
+    },
+    strength: 2.,
+  };
+  let image: image::RgbaImage = NoiseTexture {
+    noise: warped,
+    frequency: 1. / 64.,
+  }
+  .map(to_gray)
+  .generate(size);
+  image.save("domain_warp.png").unwrap();
+}
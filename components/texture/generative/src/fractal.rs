@@ -0,0 +1,114 @@
+use crate::*;
+
+/// The sum of the octaves, the frequency of every octave is multiplied by the lacunarity and the
+/// amplitude is multiplied by the gain.
+#[derive(Clone, Copy)]
+pub struct FractalOption {
+  pub octaves: usize,
+  pub lacunarity: f32,
+  pub gain: f32,
+}
+
+impl Default for FractalOption {
+  fn default() -> Self {
+    Self {
+      octaves: 5,
+      lacunarity: 2.,
+      gain: 0.5,
+    }
+  }
+}
+
+impl FractalOption {
+  /// Sum the octaves, normalized by the sum of the amplitudes.
+  fn accumulate<P: NoisePoint>(&self, point: P, mut octave: impl FnMut(P) -> f32) -> f32 {
+    let mut frequency = 1.;
+    let mut amplitude = 1.;
+    let mut sum = 0.;
+    let mut amplitude_sum = 0.;
+    for _ in 0..self.octaves.max(1) {
+      sum += octave(point * frequency) * amplitude;
+      amplitude_sum += amplitude;
+      frequency *= self.lacunarity;
+      amplitude *= self.gain;
+    }
+    sum / amplitude_sum
+  }
+}
+
+/// Fractal brownian motion, the value is in the range of the source noise.
+pub struct Fbm<N> {
+  pub noise: N,
+  pub option: FractalOption,
+}
+
+impl<P: NoisePoint, N: Noise<P>> Noise<P> for Fbm<N> {
+  fn sample(&self, point: P) -> f32 {
+    self.option.accumulate(point, |p| self.noise.sample(p))
+  }
+}
+
+/// The octaves are folded to the sharp ridges by 1 - |n|, the value is in [0, 1].
+pub struct Ridged<N> {
+  pub noise: N,
+  pub option: FractalOption,
+}
+
+impl<P: NoisePoint, N: Noise<P>> Noise<P> for Ridged<N> {
+  fn sample(&self, point: P) -> f32 {
+    self.option.accumulate(point, |p| {
+      let ridge = 1. - self.noise.sample(p).abs();
+      ridge * ridge
+    })
+  }
+}
+
+/// The sum of the absolute octaves, the value is in [0, 1].
+pub struct Turbulence<N> {
+  pub noise: N,
+  pub option: FractalOption,
+}
+
+impl<P: NoisePoint, N: Noise<P>> Noise<P> for Turbulence<N> {
+  fn sample(&self, point: P) -> f32 {
+    self
+      .option
+      .accumulate(point, |p| self.noise.sample(p).abs())
+  }
+}
+
+/// Offset the sample point by the warp noise before sampling the noise.
+///
+/// https://iquilezles.org/articles/warp/
+pub struct DomainWarp<N, W> {
+  pub noise: N,
+  pub warp: W,
+  pub strength: f32,
+}
+
+impl<P: NoisePoint, N: Noise<P>, W: Noise<P>> Noise<P> for DomainWarp<N, W> {
+  fn sample(&self, point: P) -> f32 {
+    // every axis reads the warp noise at a different place to decorrelate the axes
+    let offset = P::from_fn(|axis| {
+      let shift = P::from_fn(|i| (axis * P::DIMENSION + i) as f32 * 5.2 + 1.3);
+      self.warp.sample(point + shift)
+    });
+    self.noise.sample(point + offset * self.strength)
+  }
+}
+
+/// Make any 4d noise tileable in 2d by mapping the two axes to two circles.
+pub struct Tileable2D<N> {
+  pub noise: N,
+  /// The size of the tile in the noise space.
+  pub period: f32,
+}
+
+impl<N: Noise<Vec4<f32>>> Noise<Vec2<f32>> for Tileable2D<N> {
+  fn sample(&self, point: Vec2<f32>) -> f32 {
+    let radius = self.period / (2. * std::f32::consts::PI);
+    let angle = point * (2. * std::f32::consts::PI / self.period);
+    let point = Vec4::new(angle.x.cos(), angle.x.sin(), angle.y.cos(), angle.y.sin()) * radius;
+    self.noise.sample(point)
+  }
+}
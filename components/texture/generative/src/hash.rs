@@ -0,0 +1,80 @@
+//! The float hashes from "Hash without Sine", they only use the float arithmetic so the shader
+//! side could produce the same result.
+//!
+//! https://www.shadertoy.com/view/4djSRW
+
+use rendiation_algebra::*;
+
+use crate::worley::Hasher;
+
+fn fract(v: f32) -> f32 {
+  v - v.floor()
+}
+
+pub fn hash22(p: Vec2<f32>) -> Vec2<f32> {
+  let p3 = Vec3::new(p.x, p.y, p.x) * Vec3::new(0.1031, 0.1030, 0.0973);
+  let p3 = p3.map(fract);
+  let p3 = p3 + Vec3::splat(p3.dot(Vec3::new(p3.y, p3.z, p3.x) + Vec3::splat(33.33)));
+  Vec2::new((p3.x + p3.y) * p3.z, (p3.x + p3.z) * p3.y).map(fract)
+}
+
+pub fn hash33(p: Vec3<f32>) -> Vec3<f32> {
+  let p3 = (p * Vec3::new(0.1031, 0.1030, 0.0973)).map(fract);
+  let p3 = p3 + Vec3::splat(p3.dot(Vec3::new(p3.y, p3.x, p3.z) + Vec3::splat(33.33)));
+  Vec3::new(
+    (p3.x + p3.y) * p3.z,
+    (p3.x + p3.x) * p3.y,
+    (p3.y + p3.x) * p3.x,
+  )
+  .map(fract)
+}
+
+pub fn hash44(p: Vec4<f32>) -> Vec4<f32> {
+  let p4 = (p * Vec4::new(0.1031, 0.1030, 0.0973, 0.1099)).map(fract);
+  let p4 = p4 + Vec4::splat(p4.dot(Vec4::new(p4.w, p4.z, p4.x, p4.y) + Vec4::splat(33.33)));
+  Vec4::new(
+    (p4.x + p4.y) * p4.z,
+    (p4.x + p4.z) * p4.y,
+    (p4.y + p4.z) * p4.w,
+    (p4.z + p4.w) * p4.x,
+  )
+  .map(fract)
+}
+
+/// The seed shifts the lattice by whole cells, so the seeded noise keeps the period.
+pub fn seed_offset(seed: usize) -> Vec4<f32> {
+  let hasher = Hasher::new();
+  let seed = seed as i32;
+  Vec4::new(
+    hasher.hash_f(seed),
+    hasher.hash_f(seed.wrapping_add(1_000)),
+    hasher.hash_f(seed.wrapping_add(2_000)),
+    hasher.hash_f(seed.wrapping_add(3_000)),
+  )
+  .map(|v| (v * 1024.).floor())
+}
+
+/// The unit gradient of the lattice point, the lattice point is already shifted by the seed.
+pub(crate) fn lattice_gradient<const N: usize>(lattice: [f32; N]) -> [f32; N] {
+  let mut gradient = [0.; N];
+  match N {
+    2 => {
+      let h = hash22(Vec2::new(lattice[0], lattice[1]));
+      let angle = h.x * 2. * std::f32::consts::PI;
+      gradient[0] = angle.cos();
+      gradient[1] = angle.sin();
+    }
+    3 => {
+      let h = hash33(Vec3::new(lattice[0], lattice[1], lattice[2]));
+      let h = (h * 2. - Vec3::one()).normalize();
+      gradient.copy_from_slice(&[h.x, h.y, h.z]);
+    }
+    4 => {
+      let h = hash44(Vec4::new(lattice[0], lattice[1], lattice[2], lattice[3]));
+      let h = (h * 2. - Vec4::one()).normalize();
+      gradient.copy_from_slice(&[h.x, h.y, h.z, h.w]);
+    }
+    _ => unreachable!("only 2d, 3d and 4d noise are supported"),
+  }
+  gradient
+}
@@ -1,10 +1,77 @@
-#![allow(unused)]
 use rendiation_algebra::*;
+use rendiation_texture::{Size, Texture2dInitAble};
+
+mod hash;
+pub use hash::*;
+mod noise;
+pub use noise::*;
+mod fractal;
+pub use fractal::*;
+mod shader;
+pub use shader::*;
 
 pub mod perlin;
+pub use perlin::*;
+pub mod simplex;
+pub use simplex::*;
 pub mod worley;
 
+#[cfg(test)]
+mod test;
+
 pub trait TextureGenerator {
   type Pixel;
   fn gen(&self, p: Vec2<usize>) -> Self::Pixel;
+
+  fn generate<T>(&self, size: Size) -> T
+  where
+    T: Texture2dInitAble<Pixel = Self::Pixel>,
+  {
+    let mut texture = T::init_not_care(size);
+    texture.fill_by(|p| self.gen(p));
+    texture
+  }
+
+  fn map<F, P>(self, mapper: F) -> MappedTextureGenerator<Self, F>
+  where
+    Self: Sized,
+    F: Fn(Self::Pixel) -> P,
+  {
+    MappedTextureGenerator {
+      inner: self,
+      mapper,
+    }
+  }
+}
+
+pub struct MappedTextureGenerator<G, F> {
+  inner: G,
+  mapper: F,
+}
+
+impl<G, F, P> TextureGenerator for MappedTextureGenerator<G, F>
+where
+  G: TextureGenerator,
+  F: Fn(G::Pixel) -> P,
+{
+  type Pixel = P;
+  fn gen(&self, p: Vec2<usize>) -> Self::Pixel {
+    (self.mapper)(self.inner.gen(p))
+  }
+}
+
+/// Sample the noise at the pixel centers scaled by the frequency, the value is remapped from
+/// [-1, 1] to [0, 1].
+pub struct NoiseTexture<N> {
+  pub noise: N,
+  /// The noise space size of a pixel
+  pub frequency: f32,
+}
+
+impl<N: Noise<Vec2<f32>>> TextureGenerator for NoiseTexture<N> {
+  type Pixel = f32;
+  fn gen(&self, p: Vec2<usize>) -> Self::Pixel {
+    let point = p.map(|v| v as f32 + 0.5) * self.frequency;
+    (self.noise.sample(point) * 0.5 + 0.5).clamp(0., 1.)
+  }
 }
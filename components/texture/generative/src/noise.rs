@@ -0,0 +1,52 @@
+use std::ops::{Add, Mul};
+
+use rendiation_algebra::*;
+
+/// The coherent noise that could be sampled at any point, the value is roughly in [-1, 1].
+pub trait Noise<P> {
+  fn sample(&self, point: P) -> f32;
+}
+
+impl<P, T: Noise<P>> Noise<P> for &T {
+  fn sample(&self, point: P) -> f32 {
+    (*self).sample(point)
+  }
+}
+
+/// The point type the noise could be sampled at, implemented for the f32 Vec2, Vec3 and Vec4.
+pub trait NoisePoint: Copy + Add<Output = Self> + Mul<f32, Output = Self> {
+  const DIMENSION: usize;
+  fn from_fn(f: impl FnMut(usize) -> f32) -> Self;
+}
+
+macro_rules! noise_point {
+  ($ty: ty, $dimension: tt) => {
+    impl NoisePoint for $ty {
+      const DIMENSION: usize = $dimension;
+      fn from_fn(f: impl FnMut(usize) -> f32) -> Self {
+        std::array::from_fn::<_, $dimension, _>(f).into()
+      }
+    }
+  };
+}
+
+noise_point!(Vec2<f32>, 2);
+noise_point!(Vec3<f32>, 3);
+noise_point!(Vec4<f32>, 4);
+
+/// Implement the noise for all the points by the generic dimension implementation.
+macro_rules! impl_noise_by_dimension {
+  ($noise: ty, $fn: ident) => {
+    impl_noise_by_dimension!($noise, $fn, Vec2<f32>, 2);
+    impl_noise_by_dimension!($noise, $fn, Vec3<f32>, 3);
+    impl_noise_by_dimension!($noise, $fn, Vec4<f32>, 4);
+  };
+  ($noise: ty, $fn: ident, $point: ty, $dimension: tt) => {
+    impl $crate::Noise<$point> for $noise {
+      fn sample(&self, point: $point) -> f32 {
+        self.$fn::<$dimension>(*point.as_ref())
+      }
+    }
+  };
+}
+pub(crate) use impl_noise_by_dimension;
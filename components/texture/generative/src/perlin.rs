@@ -1,27 +1,36 @@
 use rendiation_algebra::*;
 
-use crate::TextureGenerator;
+use crate::*;
 
+/// The gradient noise, the seeded gradients are from the float hash, so the shader side noise
+/// could be the same.
+///
 /// https://flafla2.github.io/2014/08/09/perlinnoise.html
+#[derive(Clone, Copy)]
 pub struct PerlinNoise {
-  seed: usize,
-  repeat: i32,
+  seed_offset: [f32; 4],
+  period: Option<u32>,
 }
 
-impl TextureGenerator for PerlinNoise {
-  type Pixel = f32;
-  fn gen(&self, p: Vec2<usize>) -> Self::Pixel {
-    todo!()
-  }
-}
+impl_noise_by_dimension!(PerlinNoise, sample_dimension);
 
 impl PerlinNoise {
   pub fn new(seed: usize) -> Self {
-    Self { seed, repeat: 1 }
+    Self {
+      seed_offset: seed_offset(seed).into(),
+      period: None,
+    }
+  }
+
+  /// The noise repeats every period in all the dimensions.
+  pub fn tileable(mut self, period: u32) -> Self {
+    self.period = Some(period.max(1));
+    self
   }
 
-  fn get_gradient_vec(corner_point: Vec3<i32>) -> Vec3<f32> {
-    todo!()
+  /// The lattice shift of the seed, pass it to the shader side noise to get the same result.
+  pub fn seed_offset(&self) -> Vec4<f32> {
+    self.seed_offset.into()
   }
 
   /// Fade function as defined by Ken Perlin.  
@@ -32,58 +41,39 @@ impl PerlinNoise {
     t * t * t * (t * (t * 6. - 15.) + 10.)
   }
 
+  /// The 3d noise remapped to [0, 1].
   pub fn get(&self, point: Vec3<f32>) -> f32 {
-    let near_corner = point.map(|v| v.floor());
-    let far_corner = near_corner + Vec3::one();
-
-    let near_corner_i = near_corner.map(|v| v as i32);
-    let far_corner_i = near_corner_i + Vec3::one();
-
-    let i000 = Vec3::new(near_corner_i.x, near_corner_i.y, near_corner_i.z);
-    let i100 = Vec3::new(far_corner_i.x, near_corner_i.y, near_corner_i.z);
-    let i010 = Vec3::new(near_corner_i.x, far_corner_i.y, near_corner_i.z);
-    let i110 = Vec3::new(far_corner_i.x, far_corner_i.y, near_corner_i.z);
-    let i001 = Vec3::new(near_corner_i.x, near_corner_i.y, far_corner_i.z);
-    let i101 = Vec3::new(far_corner_i.x, near_corner_i.y, far_corner_i.z);
-    let i011 = Vec3::new(near_corner_i.x, far_corner_i.y, far_corner_i.z);
-    let i111 = Vec3::new(far_corner_i.x, far_corner_i.y, far_corner_i.z);
-
-    let g000 = PerlinNoise::get_gradient_vec(i000);
-    let g100 = PerlinNoise::get_gradient_vec(i100);
-    let g010 = PerlinNoise::get_gradient_vec(i010);
-    let g110 = PerlinNoise::get_gradient_vec(i110);
-    let g001 = PerlinNoise::get_gradient_vec(i001);
-    let g101 = PerlinNoise::get_gradient_vec(i101);
-    let g011 = PerlinNoise::get_gradient_vec(i011);
-    let g111 = PerlinNoise::get_gradient_vec(i111);
-
-    let center = far_corner - point;
-
-    let f000 = (Vec3::new(near_corner.x, near_corner.y, near_corner.z) - center).dot(g000);
-    let f100 = (Vec3::new(far_corner.x, near_corner.y, near_corner.z) - center).dot(g100);
-    let f010 = (Vec3::new(near_corner.x, far_corner.y, near_corner.z) - center).dot(g010);
-    let f110 = (Vec3::new(far_corner.x, far_corner.y, near_corner.z) - center).dot(g110);
-    let f001 = (Vec3::new(near_corner.x, near_corner.y, far_corner.z) - center).dot(g001);
-    let f101 = (Vec3::new(far_corner.x, near_corner.y, far_corner.z) - center).dot(g101);
-    let f011 = (Vec3::new(near_corner.x, far_corner.y, far_corner.z) - center).dot(g011);
-    let f111 = (Vec3::new(far_corner.x, far_corner.y, far_corner.z) - center).dot(g111);
-
-    let u = PerlinNoise::fade(center.x);
-    let v = PerlinNoise::fade(center.y);
-    let w = PerlinNoise::fade(center.z);
-
-    let x1 = f000.lerp(f100, u);
-    let x2 = f010.lerp(f110, u);
-
-    let y1 = x1.lerp(x2, v);
-
-    let x1 = f001.lerp(f101, u);
-    let x2 = f011.lerp(f111, u);
-
-    let y2 = x1.lerp(x2, v);
-
-    let value = y1.lerp(y2, w);
+    0.5 * (self.sample(point) + 1.0)
+  }
 
-    0.5 * (value + 1.0)
+  fn sample_dimension<const N: usize>(&self, point: [f32; N]) -> f32 {
+    let cell = point.map(f32::floor);
+    let local: [f32; N] = std::array::from_fn(|i| point[i] - cell[i]);
+    let fade = local.map(Self::fade);
+
+    // the n-linear interpolation of the corners, the weight of a corner is the product of the
+    // fade weights of every axis
+    let mut value = 0.;
+    for corner in 0..(1 << N) {
+      let mut weight = 1.;
+      let mut lattice = [0.; N];
+      let mut offset = [0.; N];
+      for i in 0..N {
+        let far = (corner >> i) & 1 == 1;
+        offset[i] = if far { 1. } else { 0. };
+        weight *= if far { fade[i] } else { 1. - fade[i] };
+        let mut lattice_i = cell[i] + offset[i];
+        if let Some(period) = self.period {
+          lattice_i = lattice_i.rem_euclid(period as f32);
+        }
+        lattice[i] = lattice_i + self.seed_offset[i];
+      }
+      let gradient = lattice_gradient(lattice);
+      let dot: f32 = (0..N).map(|i| gradient[i] * (local[i] - offset[i])).sum();
+      value += weight * dot;
+    }
+
+    // the max value of the unit gradients is sqrt(n / 4)
+    value * 2. / (N as f32).sqrt()
   }
 }
@@ -0,0 +1,535 @@
+//! The shader side noise, the hashes and the formulas are the same as the cpu side, so the noise
+//! of the same seed offset is the same on the gpu (up to the float precision).
+
+use std::ops::{Add, Div, Mul, Sub};
+
+use rendiation_shader_api::*;
+
+use crate::*;
+
+pub fn shader_hash22(p: Node<Vec2<f32>>) -> Node<Vec2<f32>> {
+  let p3: Node<Vec3<f32>> = (p.x(), p.y(), p.x()).into();
+  let p3 = (p3 * val(Vec3::new(0.1031, 0.1030, 0.0973))).fract();
+  let yzx: Node<Vec3<f32>> = (p3.y(), p3.z(), p3.x()).into();
+  let p3 = p3 + p3.dot(yzx + val(Vec3::splat(33.33))).splat();
+  let result: Node<Vec2<f32>> = ((p3.x() + p3.y()) * p3.z(), (p3.x() + p3.z()) * p3.y()).into();
+  result.fract()
+}
+
+pub fn shader_hash33(p: Node<Vec3<f32>>) -> Node<Vec3<f32>> {
+  let p3 = (p * val(Vec3::new(0.1031, 0.1030, 0.0973))).fract();
+  let yxz: Node<Vec3<f32>> = (p3.y(), p3.x(), p3.z()).into();
+  let p3 = p3 + p3.dot(yxz + val(Vec3::splat(33.33))).splat();
+  let result: Node<Vec3<f32>> = (
+    (p3.x() + p3.y()) * p3.z(),
+    (p3.x() + p3.x()) * p3.y(),
+    (p3.y() + p3.x()) * p3.x(),
+  )
+    .into();
+  result.fract()
+}
+
+pub fn shader_hash44(p: Node<Vec4<f32>>) -> Node<Vec4<f32>> {
+  let p4 = (p * val(Vec4::new(0.1031, 0.1030, 0.0973, 0.1099))).fract();
+  let wzxy: Node<Vec4<f32>> = (p4.w(), p4.z(), p4.x(), p4.y()).into();
+  let p4 = p4 + p4.dot(wzxy + val(Vec4::splat(33.33))).splat();
+  let result: Node<Vec4<f32>> = (
+    (p4.x() + p4.y()) * p4.z(),
+    (p4.x() + p4.z()) * p4.y(),
+    (p4.y() + p4.z()) * p4.w(),
+    (p4.z() + p4.w()) * p4.x(),
+  )
+    .into();
+  result.fract()
+}
+
+fn gradient_2d(lattice: Node<Vec2<f32>>) -> Node<Vec2<f32>> {
+  let angle = shader_hash22(lattice).x() * val(2. * f32::PI());
+  (angle.cos(), angle.sin()).into()
+}
+
+fn gradient_3d(lattice: Node<Vec3<f32>>) -> Node<Vec3<f32>> {
+  (shader_hash33(lattice) * val(2.) - val(Vec3::one())).normalize()
+}
+
+fn gradient_4d(lattice: Node<Vec4<f32>>) -> Node<Vec4<f32>> {
+  (shader_hash44(lattice) * val(2.) - val(Vec4::one())).normalize()
+}
+
+fn component(v: Node<Vec4<f32>>, axis: usize) -> Node<f32> {
+  match axis {
+    0 => v.x(),
+    1 => v.y(),
+    2 => v.z(),
+    _ => v.w(),
+  }
+}
+
+fn fade<T>(t: Node<T>) -> Node<T>
+where
+  T: PrimitiveShaderNodeType + Vector<f32> + Mul<T, Output = T> + Mul<f32, Output = T>,
+  T: Add<T, Output = T> + Sub<T, Output = T>,
+{
+  t * t * t * (t * (t * val(6.) - val(T::splat(15.))) + val(T::splat(10.)))
+}
+
+fn wrap<T>(lattice: Node<T>, period: Option<Node<T>>) -> Node<T>
+where
+  T: PrimitiveShaderNodeType + Mul<T, Output = T> + Div<T, Output = T> + Sub<T, Output = T>,
+{
+  if let Some(period) = period {
+    lattice - (lattice / period).floor() * period
+  } else {
+    lattice
+  }
+}
+
+fn perlin_2d(
+  point: Node<Vec2<f32>>,
+  seed_offset: Node<Vec2<f32>>,
+  period: Option<Node<f32>>,
+) -> Node<f32> {
+  let cell = point.floor();
+  let local = point - cell;
+  let fade = fade(local);
+
+  let mut value = val(0.);
+  for corner in 0..4 {
+    let offset = Vec2::new((corner & 1) as f32, (corner >> 1) as f32);
+    let weight_x = if offset.x == 1. {
+      fade.x()
+    } else {
+      val(1.) - fade.x()
+    };
+    let weight_y = if offset.y == 1. {
+      fade.y()
+    } else {
+      val(1.) - fade.y()
+    };
+    let lattice = wrap(cell + val(offset), period.map(|p| p.splat())) + seed_offset;
+    let dot = gradient_2d(lattice).dot(local - val(offset));
+    value += weight_x * weight_y * dot;
+  }
+  value * val(2. / 2_f32.sqrt())
+}
+
+fn perlin_3d(
+  point: Node<Vec3<f32>>,
+  seed_offset: Node<Vec3<f32>>,
+  period: Option<Node<f32>>,
+) -> Node<f32> {
+  let cell = point.floor();
+  let local = point - cell;
+  let fade = fade(local);
+
+  let mut value = val(0.);
+  for corner in 0..8 {
+    let offset = Vec3::new(
+      (corner & 1) as f32,
+      ((corner >> 1) & 1) as f32,
+      (corner >> 2) as f32,
+    );
+    let weight = |far: f32, fade: Node<f32>| if far == 1. { fade } else { val(1.) - fade };
+    let weight =
+      weight(offset.x, fade.x()) * weight(offset.y, fade.y()) * weight(offset.z, fade.z());
+    let lattice = wrap(cell + val(offset), period.map(|p| p.splat())) + seed_offset;
+    let dot = gradient_3d(lattice).dot(local - val(offset));
+    value += weight * dot;
+  }
+  value * val(2. / 3_f32.sqrt())
+}
+
+fn perlin_4d(
+  point: Node<Vec4<f32>>,
+  seed_offset: Node<Vec4<f32>>,
+  period: Option<Node<f32>>,
+) -> Node<f32> {
+  let cell = point.floor();
+  let local = point - cell;
+  let fade = fade(local);
+
+  let mut value = val(0.);
+  for corner in 0..16 {
+    let offset = Vec4::new(
+      (corner & 1) as f32,
+      ((corner >> 1) & 1) as f32,
+      ((corner >> 2) & 1) as f32,
+      (corner >> 3) as f32,
+    );
+    let weight = (0..4)
+      .map(|axis| {
+        let fade = component(fade, axis);
+        if offset[axis] == 1. {
+          fade
+        } else {
+          val(1.) - fade
+        }
+      })
+      .reduce(|a, b| a * b)
+      .unwrap();
+    let lattice = wrap(cell + val(offset), period.map(|p| p.splat())) + seed_offset;
+    let dot = gradient_4d(lattice).dot(local - val(offset));
+    value += weight * dot;
+  }
+  value
+}
+
+/// The shader version of the 2d [PerlinNoise], the seed offset is the xy of
+/// [PerlinNoise::seed_offset].
+pub fn shader_perlin_noise_2d(point: Node<Vec2<f32>>, seed_offset: Node<Vec2<f32>>) -> Node<f32> {
+  get_shader_fn::<f32>(shader_fn_name(shader_perlin_noise_2d))
+    .or_define(|cx| {
+      let point = cx.push_fn_parameter_by(point);
+      let seed_offset = cx.push_fn_parameter_by(seed_offset);
+      cx.do_return(perlin_2d(point, seed_offset, None))
+    })
+    .prepare_parameters()
+    .push(point)
+    .push(seed_offset)
+    .call()
+}
+
+/// The shader version of the 2d tileable [PerlinNoise].
+pub fn shader_tileable_perlin_noise_2d(
+  point: Node<Vec2<f32>>,
+  seed_offset: Node<Vec2<f32>>,
+  period: Node<f32>,
+) -> Node<f32> {
+  get_shader_fn::<f32>(shader_fn_name(shader_tileable_perlin_noise_2d))
+    .or_define(|cx| {
+      let point = cx.push_fn_parameter_by(point);
+      let seed_offset = cx.push_fn_parameter_by(seed_offset);
+      let period = cx.push_fn_parameter_by(period);
+      cx.do_return(perlin_2d(point, seed_offset, Some(period)))
+    })
+    .prepare_parameters()
+    .push(point)
+    .push(seed_offset)
+    .push(period)
+    .call()
+}
+
+/// The shader version of the 3d [PerlinNoise], the seed offset is the xyz of
+/// [PerlinNoise::seed_offset].
+pub fn shader_perlin_noise_3d(point: Node<Vec3<f32>>, seed_offset: Node<Vec3<f32>>) -> Node<f32> {
+  get_shader_fn::<f32>(shader_fn_name(shader_perlin_noise_3d))
+    .or_define(|cx| {
+      let point = cx.push_fn_parameter_by(point);
+      let seed_offset = cx.push_fn_parameter_by(seed_offset);
+      cx.do_return(perlin_3d(point, seed_offset, None))
+    })
+    .prepare_parameters()
+    .push(point)
+    .push(seed_offset)
+    .call()
+}
+
+/// The shader version of the 3d tileable [PerlinNoise].
+pub fn shader_tileable_perlin_noise_3d(
+  point: Node<Vec3<f32>>,
+  seed_offset: Node<Vec3<f32>>,
+  period: Node<f32>,
+) -> Node<f32> {
+  get_shader_fn::<f32>(shader_fn_name(shader_tileable_perlin_noise_3d))
+    .or_define(|cx| {
+      let point = cx.push_fn_parameter_by(point);
+      let seed_offset = cx.push_fn_parameter_by(seed_offset);
+      let period = cx.push_fn_parameter_by(period);
+      cx.do_return(perlin_3d(point, seed_offset, Some(period)))
+    })
+    .prepare_parameters()
+    .push(point)
+    .push(seed_offset)
+    .push(period)
+    .call()
+}
+
+/// The shader version of the 4d [PerlinNoise], the seed offset is [PerlinNoise::seed_offset].
+pub fn shader_perlin_noise_4d(point: Node<Vec4<f32>>, seed_offset: Node<Vec4<f32>>) -> Node<f32> {
+  get_shader_fn::<f32>(shader_fn_name(shader_perlin_noise_4d))
+    .or_define(|cx| {
+      let point = cx.push_fn_parameter_by(point);
+      let seed_offset = cx.push_fn_parameter_by(seed_offset);
+      cx.do_return(perlin_4d(point, seed_offset, None))
+    })
+    .prepare_parameters()
+    .push(point)
+    .push(seed_offset)
+    .call()
+}
+
+/// The shader version of the 4d tileable [PerlinNoise].
+pub fn shader_tileable_perlin_noise_4d(
+  point: Node<Vec4<f32>>,
+  seed_offset: Node<Vec4<f32>>,
+  period: Node<f32>,
+) -> Node<f32> {
+  get_shader_fn::<f32>(shader_fn_name(shader_tileable_perlin_noise_4d))
+    .or_define(|cx| {
+      let point = cx.push_fn_parameter_by(point);
+      let seed_offset = cx.push_fn_parameter_by(seed_offset);
+      let period = cx.push_fn_parameter_by(period);
+      cx.do_return(perlin_4d(point, seed_offset, Some(period)))
+    })
+    .prepare_parameters()
+    .push(point)
+    .push(seed_offset)
+    .push(period)
+    .call()
+}
+
+fn simplex_contribution<T>(d: Node<T>, gradient: Node<T>) -> Node<f32>
+where
+  T: PrimitiveShaderNodeType + InnerProductSpace<f32>,
+{
+  let t = val(0.5) - d.dot(d);
+  let t4 = t * t * t * t;
+  t.greater_than(0.).select(t4 * gradient.dot(d), val(0.))
+}
+
+fn select_one(condition: Node<bool>) -> Node<f32> {
+  condition.select(val(1.), val(0.))
+}
+
+/// The shader version of the 2d [SimplexNoise], the seed offset is the xy of
+/// [SimplexNoise::seed_offset].
+pub fn shader_simplex_noise_2d(point: Node<Vec2<f32>>, seed_offset: Node<Vec2<f32>>) -> Node<f32> {
+  get_shader_fn::<f32>(shader_fn_name(shader_simplex_noise_2d))
+    .or_define(|cx| {
+      let point = cx.push_fn_parameter_by(point);
+      let seed_offset = cx.push_fn_parameter_by(seed_offset);
+
+      let skew_factor = (3_f32.sqrt() - 1.) / 2.;
+      let unskew_factor = (1. - 1. / 3_f32.sqrt()) / 2.;
+      let cell = (point + ((point.x() + point.y()) * val(skew_factor)).splat()).floor();
+      let unskew = (cell.x() + cell.y()) * val(unskew_factor);
+      let local = point - (cell - unskew.splat());
+
+      let x_first = select_one(local.x().greater_equal_than(local.y()));
+      let corners: [Node<Vec2<f32>>; 3] = [
+        val(Vec2::zero()),
+        (x_first, val(1.) - x_first).into(),
+        val(Vec2::one()),
+      ];
+
+      let mut value = val(0.);
+      for (k, corner) in corners.into_iter().enumerate() {
+        let d = local - corner + val(Vec2::splat(k as f32 * unskew_factor));
+        let gradient = gradient_2d(cell + corner + seed_offset);
+        value += simplex_contribution(d, gradient);
+      }
+      cx.do_return(value * val(SIMPLEX_SCALE[0]))
+    })
+    .prepare_parameters()
+    .push(point)
+    .push(seed_offset)
+    .call()
+}
+
+/// The shader version of the 3d [SimplexNoise], the seed offset is the xyz of
+/// [SimplexNoise::seed_offset].
+pub fn shader_simplex_noise_3d(point: Node<Vec3<f32>>, seed_offset: Node<Vec3<f32>>) -> Node<f32> {
+  get_shader_fn::<f32>(shader_fn_name(shader_simplex_noise_3d))
+    .or_define(|cx| {
+      let point = cx.push_fn_parameter_by(point);
+      let seed_offset = cx.push_fn_parameter_by(seed_offset);
+
+      let skew_factor = 1. / 3.;
+      let unskew_factor = 1. / 6.;
+      let skew = (point.x() + point.y() + point.z()) * val(skew_factor);
+      let cell = (point + skew.splat()).floor();
+      let unskew = (cell.x() + cell.y() + cell.z()) * val(unskew_factor);
+      let local = point - (cell - unskew.splat());
+
+      // the axis goes first if it's greater, or equal but with the smaller index
+      let x_ge_y = local.x().greater_equal_than(local.y());
+      let x_ge_z = local.x().greater_equal_than(local.z());
+      let y_ge_z = local.y().greater_equal_than(local.z());
+      let largest: Node<Vec3<f32>> = (
+        select_one(x_ge_y.and(x_ge_z)),
+        select_one(x_ge_y.not().and(y_ge_z)),
+        select_one(x_ge_z.not().and(y_ge_z.not())),
+      )
+        .into();
+      let smallest: Node<Vec3<f32>> = (
+        select_one(x_ge_y.not().and(x_ge_z.not())),
+        select_one(x_ge_y.and(y_ge_z.not())),
+        select_one(x_ge_z.and(y_ge_z)),
+      )
+        .into();
+      let corners = [
+        val(Vec3::zero()),
+        largest,
+        val(Vec3::one()) - smallest,
+        val(Vec3::one()),
+      ];
+
+      let mut value = val(0.);
+      for (k, corner) in corners.into_iter().enumerate() {
+        let d = local - corner + val(Vec3::splat(k as f32 * unskew_factor));
+        let gradient = gradient_3d(cell + corner + seed_offset);
+        value += simplex_contribution(d, gradient);
+      }
+      cx.do_return(value * val(SIMPLEX_SCALE[1]))
+    })
+    .prepare_parameters()
+    .push(point)
+    .push(seed_offset)
+    .call()
+}
+
+/// The shader version of the 4d [SimplexNoise], the seed offset is
+/// [SimplexNoise::seed_offset].
+pub fn shader_simplex_noise_4d(point: Node<Vec4<f32>>, seed_offset: Node<Vec4<f32>>) -> Node<f32> {
+  get_shader_fn::<f32>(shader_fn_name(shader_simplex_noise_4d))
+    .or_define(|cx| {
+      let point = cx.push_fn_parameter_by(point);
+      let seed_offset = cx.push_fn_parameter_by(seed_offset);
+
+      let skew_factor = (5_f32.sqrt() - 1.) / 4.;
+      let unskew_factor = (1. - 1. / 5_f32.sqrt()) / 4.;
+      let skew = (point.x() + point.y() + point.z() + point.w()) * val(skew_factor);
+      let cell = (point + skew.splat()).floor();
+      let unskew = (cell.x() + cell.y() + cell.z() + cell.w()) * val(unskew_factor);
+      let local = point - (cell - unskew.splat());
+
+      // the rank of the axis in the descending order, the axis with the smaller index goes first
+      // if equal, the corner k contains the axes that rank less than k
+      let rank: [Node<f32>; 4] = std::array::from_fn(|i| {
+        let local_i = component(local, i);
+        (0..4)
+          .filter(|&j| j != i)
+          .map(|j| {
+            let local_j = component(local, j);
+            if j < i {
+              select_one(local_j.greater_equal_than(local_i))
+            } else {
+              select_one(local_j.greater_than(local_i))
+            }
+          })
+          .reduce(|a, b| a + b)
+          .unwrap()
+      });
+
+      let mut value = val(0.);
+      for k in 0..=4 {
+        let corner: Node<Vec4<f32>> = (
+          select_one(rank[0].less_than(val(k as f32))),
+          select_one(rank[1].less_than(val(k as f32))),
+          select_one(rank[2].less_than(val(k as f32))),
+          select_one(rank[3].less_than(val(k as f32))),
+        )
+          .into();
+        let d = local - corner + val(Vec4::splat(k as f32 * unskew_factor));
+        let gradient = gradient_4d(cell + corner + seed_offset);
+        value += simplex_contribution(d, gradient);
+      }
+      cx.do_return(value * val(SIMPLEX_SCALE[2]))
+    })
+    .prepare_parameters()
+    .push(point)
+    .push(seed_offset)
+    .call()
+}
+
+/// The shader version of the [Tileable2D], the noise is the 4d noise.
+pub fn shader_tileable_2d(
+  point: Node<Vec2<f32>>,
+  period: Node<f32>,
+  noise: impl Fn(Node<Vec4<f32>>) -> Node<f32>,
+) -> Node<f32> {
+  let radius = period / val(2. * f32::PI());
+  let angle = point * (val(2. * f32::PI()) / period).splat::<Vec2<f32>>();
+  let point: Node<Vec4<f32>> = (
+    angle.x().cos(),
+    angle.x().sin(),
+    angle.y().cos(),
+    angle.y().sin(),
+  )
+    .into();
+  noise(point * radius.splat::<Vec4<f32>>())
+}
+
+/// The octaves are unrolled, see [FractalOption].
+pub fn shader_fractal<P>(
+  point: Node<P>,
+  option: &FractalOption,
+  octave: impl Fn(Node<P>) -> Node<f32>,
+) -> Node<f32>
+where
+  P: PrimitiveShaderNodeType + Mul<f32, Output = P>,
+{
+  let mut frequency = 1.;
+  let mut amplitude = 1.;
+  let mut octaves = Vec::new();
+  for _ in 0..option.octaves.max(1) {
+    octaves.push((frequency, amplitude));
+    frequency *= option.lacunarity;
+    amplitude *= option.gain;
+  }
+  let amplitude_sum: f32 = octaves.iter().map(|(_, amplitude)| amplitude).sum();
+
+  octaves
+    .into_iter()
+    .fold(val(0.), |sum, (frequency, amplitude)| {
+      sum + octave(point * val(frequency)) * val(amplitude / amplitude_sum)
+    })
+}
+
+/// The shader version of the [Fbm].
+pub fn shader_fbm<P>(
+  point: Node<P>,
+  option: &FractalOption,
+  noise: impl Fn(Node<P>) -> Node<f32>,
+) -> Node<f32>
+where
+  P: PrimitiveShaderNodeType + Mul<f32, Output = P>,
+{
+  shader_fractal(point, option, noise)
+}
+
+/// The shader version of the [Ridged].
+pub fn shader_ridged<P>(
+  point: Node<P>,
+  option: &FractalOption,
+  noise: impl Fn(Node<P>) -> Node<f32>,
+) -> Node<f32>
+where
+  P: PrimitiveShaderNodeType + Mul<f32, Output = P>,
+{
+  shader_fractal(point, option, |p| {
+    let ridge = val(1.) - noise(p).abs();
+    ridge * ridge
+  })
+}
+
+/// The shader version of the [Turbulence].
+pub fn shader_turbulence<P>(
+  point: Node<P>,
+  option: &FractalOption,
+  noise: impl Fn(Node<P>) -> Node<f32>,
+) -> Node<f32>
+where
+  P: PrimitiveShaderNodeType + Mul<f32, Output = P>,
+{
+  shader_fractal(point, option, |p| noise(p).abs())
+}
+
+/// The shader version of the [DomainWarp].
+pub fn shader_domain_warp<P>(
+  point: Node<P>,
+  strength: Node<f32>,
+  warp: impl Fn(Node<P>) -> Node<f32>,
+  noise: impl Fn(Node<P>) -> Node<f32>,
+) -> Node<f32>
+where
+  P: NoisePoint + PrimitiveShaderNodeType + Mul<f32, Output = P> + Add<P, Output = P>,
+{
+  let mut offset = val(P::from_fn(|_| 0.));
+  for axis in 0..P::DIMENSION {
+    let shift = P::from_fn(|i| (axis * P::DIMENSION + i) as f32 * 5.2 + 1.3);
+    let unit = P::from_fn(|i| if i == axis { 1. } else { 0. });
+    offset += val(unit) * warp(point + val(shift));
+  }
+  noise(point + offset * strength)
+}
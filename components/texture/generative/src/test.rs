@@ -0,0 +1,208 @@
+use rendiation_shader_api::*;
+use rendiation_shader_backend_interpreter::interpret_by;
+
+use crate::*;
+
+fn assert_near(a: f32, b: f32, tolerance: f32) {
+  assert!((a - b).abs() < tolerance, "{a} != {b}");
+}
+
+/// The scattered points that not aligned to the lattice.
+fn sample_points<P: NoisePoint>(count: usize) -> Vec<P> {
+  (0..count)
+    .map(|i| P::from_fn(|axis| (i * (axis + 3)) as f32 * 0.6180339 + axis as f32 * 7.31 - 40.))
+    .collect()
+}
+
+fn value_range<P: NoisePoint>(noise: &impl Noise<P>) -> (f32, f32) {
+  sample_points::<P>(2000)
+    .into_iter()
+    .map(|p| noise.sample(p))
+    .fold((f32::MAX, f32::MIN), |(min, max), v| {
+      (min.min(v), max.max(v))
+    })
+}
+
+fn assert_seed_determinism<P: NoisePoint>(create: impl Fn(usize) -> Box<dyn Noise<P>>) {
+  let points = sample_points::<P>(100);
+  let sample = |seed| {
+    let noise = create(seed);
+    points.iter().map(|&p| noise.sample(p)).collect::<Vec<_>>()
+  };
+  assert_eq!(sample(1), sample(1));
+  assert_ne!(sample(1), sample(2));
+}
+
+#[test]
+fn seed_determinism() {
+  assert_seed_determinism::<Vec2<f32>>(|seed| Box::new(PerlinNoise::new(seed)));
+  assert_seed_determinism::<Vec3<f32>>(|seed| Box::new(PerlinNoise::new(seed)));
+  assert_seed_determinism::<Vec4<f32>>(|seed| Box::new(PerlinNoise::new(seed)));
+  assert_seed_determinism::<Vec2<f32>>(|seed| Box::new(SimplexNoise::new(seed)));
+  assert_seed_determinism::<Vec3<f32>>(|seed| Box::new(SimplexNoise::new(seed)));
+  assert_seed_determinism::<Vec4<f32>>(|seed| Box::new(SimplexNoise::new(seed)));
+}
+
+#[test]
+fn tileable_wrapping() {
+  let period = 4;
+  let noise = PerlinNoise::new(7).tileable(period);
+  let shift = period as f32;
+  for p in sample_points::<Vec2<f32>>(100) {
+    assert_near(
+      noise.sample(p),
+      noise.sample(p + Vec2::new(shift, 0.)),
+      1e-4,
+    );
+    assert_near(
+      noise.sample(p),
+      noise.sample(p + Vec2::new(0., -shift)),
+      1e-4,
+    );
+  }
+  for p in sample_points::<Vec3<f32>>(100) {
+    assert_near(noise.sample(p), noise.sample(p + Vec3::splat(shift)), 1e-4);
+  }
+
+  let tileable = Tileable2D {
+    noise: SimplexNoise::new(3),
+    period: 5.,
+  };
+  for p in sample_points::<Vec2<f32>>(100) {
+    assert_near(
+      tileable.sample(p),
+      tileable.sample(p + Vec2::new(5., 0.)),
+      1e-3,
+    );
+    assert_near(
+      tileable.sample(p),
+      tileable.sample(p + Vec2::new(0., 5.)),
+      1e-3,
+    );
+  }
+}
+
+#[test]
+fn output_range() {
+  fn check<P: NoisePoint>(noise: impl Noise<P>, range: (f32, f32)) {
+    let (min, max) = value_range(&noise);
+    assert!(min >= range.0 && max <= range.1, "{min}..{max}");
+    // the noise is not flat
+    assert!(max - min > 0.3 * (range.1 - range.0), "{min}..{max}");
+  }
+  let option = FractalOption::default();
+
+  check::<Vec2<f32>>(PerlinNoise::new(1), (-1., 1.));
+  check::<Vec3<f32>>(PerlinNoise::new(1), (-1., 1.));
+  check::<Vec4<f32>>(PerlinNoise::new(1), (-1., 1.));
+  check::<Vec2<f32>>(SimplexNoise::new(1), (-1., 1.));
+  check::<Vec3<f32>>(SimplexNoise::new(1), (-1., 1.));
+  check::<Vec4<f32>>(SimplexNoise::new(1), (-1., 1.));
+
+  let noise = PerlinNoise::new(2);
+  check::<Vec3<f32>>(Fbm { noise, option }, (-1., 1.));
+  check::<Vec3<f32>>(Ridged { noise, option }, (0., 1.));
+  check::<Vec3<f32>>(Turbulence { noise, option }, (0., 1.));
+}
+
+#[test]
+fn shader_noise_matches_cpu() {
+  let perlin = PerlinNoise::new(5);
+  let simplex = SimplexNoise::new(5);
+  let (perlin_seed, simplex_seed) = (perlin.seed_offset(), simplex.seed_offset());
+  let xy = |v: Vec4<f32>| Vec2::new(v.x, v.y);
+  let xyz = |v: Vec4<f32>| Vec3::new(v.x, v.y, v.z);
+
+  for p in sample_points::<Vec4<f32>>(16) {
+    let (p2, p3) = (xy(p), xyz(p));
+
+    let r = interpret_by(|_| shader_perlin_noise_2d(val(p2), val(xy(perlin_seed))));
+    assert_near(r, perlin.sample(p2), 1e-4);
+    let r = interpret_by(|_| shader_perlin_noise_3d(val(p3), val(xyz(perlin_seed))));
+    assert_near(r, perlin.sample(p3), 1e-4);
+    let r = interpret_by(|_| shader_perlin_noise_4d(val(p), val(perlin_seed)));
+    assert_near(r, perlin.sample(p), 1e-4);
+
+    let r = interpret_by(|_| shader_simplex_noise_2d(val(p2), val(xy(simplex_seed))));
+    assert_near(r, simplex.sample(p2), 1e-4);
+    let r = interpret_by(|_| shader_simplex_noise_3d(val(p3), val(xyz(simplex_seed))));
+    assert_near(r, simplex.sample(p3), 1e-4);
+    let r = interpret_by(|_| shader_simplex_noise_4d(val(p), val(simplex_seed)));
+    assert_near(r, simplex.sample(p), 1e-4);
+  }
+}
+
+#[test]
+fn shader_tileable_and_fractal_matches_cpu() {
+  let perlin = PerlinNoise::new(9);
+  let tileable_perlin = perlin.tileable(3);
+  let simplex = SimplexNoise::new(9);
+  let seed = perlin.seed_offset();
+  let simplex_seed = simplex.seed_offset();
+  let option = FractalOption {
+    octaves: 3,
+    ..Default::default()
+  };
+
+  for p in sample_points::<Vec3<f32>>(16) {
+    let r = interpret_by(|_| shader_tileable_perlin_noise_3d(val(p), val(seed.xyz()), val(3.)));
+    assert_near(r, tileable_perlin.sample(p), 1e-4);
+
+    let r = interpret_by(|_| {
+      shader_fbm(val(p), &option, |p| {
+        shader_perlin_noise_3d(p, val(seed.xyz()))
+      })
+    });
+    assert_near(
+      r,
+      Fbm {
+        noise: perlin,
+        option,
+      }
+      .sample(p),
+      1e-4,
+    );
+
+    let r = interpret_by(|_| {
+      shader_ridged(val(p), &option, |p| {
+        shader_perlin_noise_3d(p, val(seed.xyz()))
+      })
+    });
+    assert_near(
+      r,
+      Ridged {
+        noise: perlin,
+        option,
+      }
+      .sample(p),
+      1e-4,
+    );
+
+    let r = interpret_by(|_| {
+      shader_turbulence(val(p), &option, |p| {
+        shader_perlin_noise_3d(p, val(seed.xyz()))
+      })
+    });
+    assert_near(
+      r,
+      Turbulence {
+        noise: perlin,
+        option,
+      }
+      .sample(p),
+      1e-4,
+    );
+
+    let p2 = Vec2::new(p.x, p.y);
+    let tileable = Tileable2D {
+      noise: simplex,
+      period: 5.,
+    };
+    let r = interpret_by(|_| {
+      shader_tileable_2d(val(p2), val(5.), |p| {
+        shader_simplex_noise_4d(p, val(simplex_seed))
+      })
+    });
+    assert_near(r, tileable.sample(p2), 1e-4);
+  }
+}
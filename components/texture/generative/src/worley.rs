@@ -109,7 +109,7 @@ const PRIME32_2: Wrapping<u32> = Wrapping(3266489917);
 const PRIME32_3: Wrapping<u32> = Wrapping(668265263);
 const PRIME32_4: Wrapping<u32> = Wrapping(374761393);
 
-struct Hasher {
+pub(crate) struct Hasher {
   seeds: Wrapping<u32>,
 }
 
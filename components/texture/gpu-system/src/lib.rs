@@ -3,33 +3,33 @@
 
 // we could not depend on rendiation_shader_api theoretically if we abstract over shader node
 // compose but that will too complicated
+use std::ops::Range;
+
 use rendiation_shader_api::*;
 use slab::Slab;
 pub type Texture2DHandle = u32;
 pub type SamplerHandle = u32;
 
-// todo, support runtime size by query client limitation
-pub const MAX_TEXTURE_BINDING_ARRAY_LENGTH: usize = 8192;
-pub const MAX_SAMPLER_BINDING_ARRAY_LENGTH: usize = 8192;
+/// The length of the binding array is decided at runtime, see [BindlessArrayLimits]
+pub type BindlessTexture2DArray = BindingArray<ShaderTexture2D, 0>;
+pub type BindlessSamplerArray = BindingArray<ShaderSampler, 0>;
 
 pub trait GPUTextureBackend {
   type GPUTexture2D: ShaderBindingProvider<Node = ShaderTexture2D> + Clone;
   type GPUSampler: ShaderBindingProvider<Node = ShaderSampler> + Clone;
-  type GPUTexture2DBindingArray<const N: usize>: ShaderBindingProvider<Node = BindingArray<ShaderTexture2D, N>>
-    + Default;
-  type GPUSamplerBindingArray<const N: usize>: ShaderBindingProvider<Node = BindingArray<ShaderSampler, N>>
-    + Default;
+  type GPUTexture2DBindingArray: ShaderBindingProvider<Node = BindlessTexture2DArray> + Default;
+  type GPUSamplerBindingArray: ShaderBindingProvider<Node = BindlessSamplerArray> + Default;
 
   type BindingCollector;
   fn bind_texture2d(collector: &mut Self::BindingCollector, texture: &Self::GPUTexture2D);
   fn bind_sampler(collector: &mut Self::BindingCollector, sampler: &Self::GPUSampler);
-  fn bind_texture2d_array<const N: usize>(
+  fn bind_texture2d_array(
     collector: &mut Self::BindingCollector,
-    textures: &Self::GPUTexture2DBindingArray<N>,
+    textures: &Self::GPUTexture2DBindingArray,
   );
-  fn bind_sampler_array<const N: usize>(
+  fn bind_sampler_array(
     collector: &mut Self::BindingCollector,
-    samplers: &Self::GPUSamplerBindingArray<N>,
+    samplers: &Self::GPUSamplerBindingArray,
   );
 
   fn register_shader_texture2d(
@@ -42,32 +42,31 @@ pub trait GPUTextureBackend {
   ) -> HandleNode<ShaderSampler>;
   fn register_shader_texture2d_array(
     builder: &mut ShaderRenderPipelineBuilder,
-    textures: &Self::GPUTexture2DBindingArray<MAX_TEXTURE_BINDING_ARRAY_LENGTH>,
-  ) -> BindingPreparer<
-    BindingArray<ShaderTexture2D, MAX_TEXTURE_BINDING_ARRAY_LENGTH>,
-    { AddressSpace::Handle },
-  >;
+    textures: &Self::GPUTexture2DBindingArray,
+  ) -> BindingPreparer<BindlessTexture2DArray, { AddressSpace::Handle }>;
   fn register_shader_sampler_array(
     builder: &mut ShaderRenderPipelineBuilder,
-    samplers: &Self::GPUSamplerBindingArray<MAX_SAMPLER_BINDING_ARRAY_LENGTH>,
-  ) -> BindingPreparer<
-    BindingArray<ShaderSampler, MAX_SAMPLER_BINDING_ARRAY_LENGTH>,
-    { AddressSpace::Handle },
-  >;
-
-  /// note, we should design some interface to partial update the array
-  /// but the wgpu not support partial update at all, so we not bother to do this now.
+    samplers: &Self::GPUSamplerBindingArray,
+  ) -> BindingPreparer<BindlessSamplerArray, { AddressSpace::Handle }>;
+
+  /// Resize the array to the length and update the changed items. The array never shrinks.
   ///
   /// the Option None case is to match the hole in linear allocated array, the implementation could
-  /// fill this by default value or use other proper ways to handle this case
-  fn update_texture2d_array<const N: usize>(
-    textures: &mut Self::GPUTexture2DBindingArray<N>,
-    source: Vec<Option<Self::GPUTexture2D>>,
+  /// fill this and the new grown items by default value or use other proper ways to handle this
+  /// case
+  ///
+  /// note, the wgpu not support partial update at all, the implementation could still rebind the
+  /// whole array but should keep the unchanged items.
+  fn update_texture2d_array(
+    textures: &mut Self::GPUTexture2DBindingArray,
+    length: usize,
+    changes: impl Iterator<Item = (usize, Option<Self::GPUTexture2D>)>,
   );
 
-  fn update_sampler_array<const N: usize>(
-    samplers: &mut Self::GPUSamplerBindingArray<N>,
-    source: Vec<Option<Self::GPUSampler>>,
+  fn update_sampler_array(
+    samplers: &mut Self::GPUSamplerBindingArray,
+    length: usize,
+    changes: impl Iterator<Item = (usize, Option<Self::GPUSampler>)>,
   );
 }
 
@@ -177,74 +176,157 @@ impl<B: GPUTextureBackend> AbstractTraditionalTextureSystem<B>
   }
 }
 
+/// The binding arrays start with the init length and grow by doubling until the max length,
+/// which is usually decided by the device limits.
+#[derive(Debug, Clone, Copy)]
+pub struct BindlessArrayLimits {
+  pub init_length: usize,
+  pub max_texture_length: usize,
+  pub max_sampler_length: usize,
+}
+
+impl Default for BindlessArrayLimits {
+  fn default() -> Self {
+    Self {
+      init_length: 256,
+      max_texture_length: 8192,
+      max_sampler_length: 8192,
+    }
+  }
+}
+
+#[derive(Default)]
+struct BindingArrayState {
+  length: usize,
+  /// one past the max handle ever allocated
+  required: usize,
+  dirty: Option<Range<usize>>,
+}
+
+impl BindingArrayState {
+  fn mark_dirty(&mut self, index: u32) {
+    let index = index as usize;
+    self.required = self.required.max(index + 1);
+    let dirty = self.dirty.get_or_insert(index..index + 1);
+    dirty.start = dirty.start.min(index);
+    dirty.end = dirty.end.max(index + 1);
+  }
+
+  /// return None if the required length exceeds the max length
+  fn fit_length(&self, init: usize, max: usize) -> Option<usize> {
+    if self.required > max {
+      return None;
+    }
+    let mut length = self.length.max(init).max(1);
+    while length < self.required {
+      length *= 2;
+    }
+    length.min(max).into()
+  }
+}
+
 pub struct BindlessTextureSystem<B: GPUTextureBackend> {
   inner: TraditionalPerDrawBindingSystem<B>,
-  texture_binding_array: B::GPUTexture2DBindingArray<MAX_TEXTURE_BINDING_ARRAY_LENGTH>,
-  sampler_binding_array: B::GPUSamplerBindingArray<MAX_SAMPLER_BINDING_ARRAY_LENGTH>,
-  any_changed: bool, // should we add change mark to per type?
-  enable_bindless: bool,
+  texture_binding_array: B::GPUTexture2DBindingArray,
+  sampler_binding_array: B::GPUSamplerBindingArray,
+  texture_state: BindingArrayState,
+  sampler_state: BindingArrayState,
+  /// None if the bindless is disabled
+  limits: Option<BindlessArrayLimits>,
 }
 impl<B: GPUTextureBackend> BindlessTextureSystem<B> {
-  pub fn new(enable_bindless: bool) -> Self {
+  /// Pass None to disable the bindless
+  pub fn new(limits: Option<BindlessArrayLimits>) -> Self {
     Self {
       inner: Default::default(),
       texture_binding_array: Default::default(),
       sampler_binding_array: Default::default(),
-      any_changed: true,
-      enable_bindless,
+      texture_state: Default::default(),
+      sampler_state: Default::default(),
+      limits,
     }
   }
+
+  /// The bindless could be disabled in [AbstractGPUTextureSystemBase::maintain] if the handles
+  /// exceed the max length, and the system falls back to the per draw binding.
+  pub fn bindless_enabled(&self) -> bool {
+    self.limits.is_some()
+  }
+
+  /// The current length of the texture and sampler binding arrays, the shader should be rebuilt
+  /// if they changed.
+  pub fn binding_array_lengths(&self) -> (usize, usize) {
+    (self.texture_state.length, self.sampler_state.length)
+  }
 }
 
 /// pass through inner implementation
 impl<B: GPUTextureBackend> AbstractGPUTextureSystemBase<B> for BindlessTextureSystem<B> {
   fn register_texture(&mut self, t: B::GPUTexture2D) -> Texture2DHandle {
-    self.any_changed = true;
-    self.inner.register_texture(t)
+    let handle = self.inner.register_texture(t);
+    self.texture_state.mark_dirty(handle);
+    handle
   }
   fn deregister_texture(&mut self, t: Texture2DHandle) {
-    self.any_changed = true;
+    self.texture_state.mark_dirty(t);
     self.inner.deregister_texture(t)
   }
   fn register_sampler(&mut self, t: B::GPUSampler) -> SamplerHandle {
-    self.any_changed = true;
-    self.inner.register_sampler(t)
+    let handle = self.inner.register_sampler(t);
+    self.sampler_state.mark_dirty(handle);
+    handle
   }
   fn deregister_sampler(&mut self, t: SamplerHandle) {
-    self.any_changed = true;
+    self.sampler_state.mark_dirty(t);
     self.inner.deregister_sampler(t)
   }
   fn maintain(&mut self) {
-    if !self.any_changed {
-      return;
-    }
-    self.any_changed = false;
     self.inner.maintain();
 
-    if !self.enable_bindless {
+    let Some(limits) = self.limits else {
       return;
+    };
+
+    let texture_length = self
+      .texture_state
+      .fit_length(limits.init_length, limits.max_texture_length);
+    let sampler_length = self
+      .sampler_state
+      .fit_length(limits.init_length, limits.max_sampler_length);
+
+    let (Some(texture_length), Some(sampler_length)) = (texture_length, sampler_length) else {
+      // the handles are shared by both path, so we could fall back at any time
+      self.limits = None;
+      return;
+    };
+
+    // the array could not be filled before any item is registered, the dirty range is kept for
+    // the later update
+    let texture_changed =
+      texture_length != self.texture_state.length || self.texture_state.dirty.is_some();
+    if texture_changed && !self.inner.textures.is_empty() {
+      let textures = &self.inner.textures;
+      let changes = self.texture_state.dirty.take().unwrap_or_default();
+      B::update_texture2d_array(
+        &mut self.texture_binding_array,
+        texture_length,
+        changes.map(|i| (i, textures.get(i).cloned())),
+      );
+      self.texture_state.length = texture_length;
     }
 
-    // this is not good, maybe we should impl slab by ourself?
-    fn slab_to_hole_vec<T: Clone>(s: &Slab<T>) -> Vec<Option<T>> {
-      let mut r = Vec::with_capacity(s.capacity());
-      s.iter().for_each(|(idx, v)| {
-        while idx >= r.len() {
-          r.push(None)
-        }
-        r[idx] = v.clone().into();
-      });
-      r
+    let sampler_changed =
+      sampler_length != self.sampler_state.length || self.sampler_state.dirty.is_some();
+    if sampler_changed && !self.inner.samplers.is_empty() {
+      let samplers = &self.inner.samplers;
+      let changes = self.sampler_state.dirty.take().unwrap_or_default();
+      B::update_sampler_array(
+        &mut self.sampler_binding_array,
+        sampler_length,
+        changes.map(|i| (i, samplers.get(i).cloned())),
+      );
+      self.sampler_state.length = sampler_length;
     }
-
-    B::update_sampler_array(
-      &mut self.sampler_binding_array,
-      slab_to_hole_vec(&self.inner.samplers),
-    );
-    B::update_texture2d_array(
-      &mut self.texture_binding_array,
-      slab_to_hole_vec(&self.inner.textures),
-    );
   }
 }
 
@@ -273,14 +355,8 @@ impl<B: GPUTextureBackend> AbstractTraditionalTextureSystem<B> for BindlessTextu
     self.inner.register_shader_sampler(builder, handle)
   }
 }
-both!(
-  BindlessTexturesInShader,
-  HandlePtr<BindingArray<ShaderTexture2D, MAX_TEXTURE_BINDING_ARRAY_LENGTH>>
-);
-both!(
-  BindlessSamplersInShader,
-  HandlePtr<BindingArray<ShaderSampler, MAX_SAMPLER_BINDING_ARRAY_LENGTH>>
-);
+both!(BindlessTexturesInShader, HandlePtr<BindlessTexture2DArray>);
+both!(BindlessSamplersInShader, HandlePtr<BindlessSamplerArray>);
 
 impl<B: GPUTextureBackend> AbstractIndirectGPUTextureSystem<B> for BindlessTextureSystem<B> {
   fn bind_system_self(&mut self, collector: &mut B::BindingCollector) {
@@ -323,3 +399,159 @@ impl<B: GPUTextureBackend> AbstractIndirectGPUTextureSystem<B> for BindlessTextu
     texture.sample(sampler, uv)
   }
 }
+
+#[cfg(test)]
+mod test {
+  use std::cell::RefCell;
+
+  use super::*;
+
+  #[test]
+  fn fit_length() {
+    let mut state = BindingArrayState::default();
+    assert_eq!(state.fit_length(4, 64), Some(4));
+
+    state.mark_dirty(4);
+    assert_eq!(state.fit_length(4, 64), Some(8));
+    state.mark_dirty(20);
+    assert_eq!(state.fit_length(4, 64), Some(32));
+    assert_eq!(state.dirty, Some(4..21));
+
+    // the length never shrinks, and is clamped by the max length
+    state.length = 32;
+    state.required = 1;
+    assert_eq!(state.fit_length(4, 64), Some(32));
+    state.required = 40;
+    assert_eq!(state.fit_length(4, 48), Some(48));
+    state.required = 49;
+    assert_eq!(state.fit_length(4, 48), None);
+  }
+
+  thread_local! {
+    static UPDATES: RefCell<Vec<(usize, Vec<usize>)>> = Default::default();
+  }
+
+  #[derive(Clone)]
+  struct MockTexture;
+  impl ShaderBindingProvider for MockTexture {
+    const SPACE: AddressSpace = AddressSpace::Handle;
+    type Node = ShaderTexture2D;
+  }
+
+  #[derive(Clone)]
+  struct MockSampler;
+  impl ShaderBindingProvider for MockSampler {
+    const SPACE: AddressSpace = AddressSpace::Handle;
+    type Node = ShaderSampler;
+  }
+
+  #[derive(Default)]
+  struct MockTextureArray;
+  impl ShaderBindingProvider for MockTextureArray {
+    const SPACE: AddressSpace = AddressSpace::Handle;
+    type Node = BindlessTexture2DArray;
+  }
+
+  #[derive(Default)]
+  struct MockSamplerArray;
+  impl ShaderBindingProvider for MockSamplerArray {
+    const SPACE: AddressSpace = AddressSpace::Handle;
+    type Node = BindlessSamplerArray;
+  }
+
+  struct MockBackend;
+  impl GPUTextureBackend for MockBackend {
+    type GPUTexture2D = MockTexture;
+    type GPUSampler = MockSampler;
+    type GPUTexture2DBindingArray = MockTextureArray;
+    type GPUSamplerBindingArray = MockSamplerArray;
+    type BindingCollector = ();
+
+    fn bind_texture2d(_: &mut (), _: &MockTexture) {}
+    fn bind_sampler(_: &mut (), _: &MockSampler) {}
+    fn bind_texture2d_array(_: &mut (), _: &MockTextureArray) {}
+    fn bind_sampler_array(_: &mut (), _: &MockSamplerArray) {}
+
+    fn register_shader_texture2d(
+      _: &mut ShaderBindGroupDirectBuilder,
+      _: &MockTexture,
+    ) -> HandleNode<ShaderTexture2D> {
+      unreachable!()
+    }
+    fn register_shader_sampler(
+      _: &mut ShaderBindGroupDirectBuilder,
+      _: &MockSampler,
+    ) -> HandleNode<ShaderSampler> {
+      unreachable!()
+    }
+    fn register_shader_texture2d_array(
+      _: &mut ShaderRenderPipelineBuilder,
+      _: &MockTextureArray,
+    ) -> BindingPreparer<BindlessTexture2DArray, { AddressSpace::Handle }> {
+      unreachable!()
+    }
+    fn register_shader_sampler_array(
+      _: &mut ShaderRenderPipelineBuilder,
+      _: &MockSamplerArray,
+    ) -> BindingPreparer<BindlessSamplerArray, { AddressSpace::Handle }> {
+      unreachable!()
+    }
+
+    fn update_texture2d_array(
+      _: &mut MockTextureArray,
+      length: usize,
+      changes: impl Iterator<Item = (usize, Option<MockTexture>)>,
+    ) {
+      let changes = changes.map(|(i, _)| i).collect();
+      UPDATES.with(|updates| updates.borrow_mut().push((length, changes)));
+    }
+    fn update_sampler_array(
+      _: &mut MockSamplerArray,
+      _: usize,
+      _: impl Iterator<Item = (usize, Option<MockSampler>)>,
+    ) {
+    }
+  }
+
+  fn take_updates() -> Vec<(usize, Vec<usize>)> {
+    UPDATES.with(|updates| updates.take())
+  }
+
+  #[test]
+  fn bindless_fallback() {
+    let mut system = BindlessTextureSystem::<MockBackend>::new(None);
+    assert!(!system.bindless_enabled());
+    system.register_texture(MockTexture);
+    system.register_sampler(MockSampler);
+    system.maintain();
+    assert!(take_updates().is_empty());
+    assert_eq!(system.binding_array_lengths(), (0, 0));
+
+    let limits = BindlessArrayLimits {
+      init_length: 2,
+      max_texture_length: 4,
+      max_sampler_length: 4,
+    };
+    let mut system = BindlessTextureSystem::<MockBackend>::new(Some(limits));
+    // nothing to fill the array yet
+    system.maintain();
+    assert!(take_updates().is_empty());
+
+    system.register_sampler(MockSampler);
+    for _ in 0..3 {
+      system.register_texture(MockTexture);
+    }
+    system.maintain();
+    assert!(system.bindless_enabled());
+    assert_eq!(take_updates(), vec![(4, vec![0, 1, 2])]);
+    assert_eq!(system.binding_array_lengths(), (4, 2));
+
+    // exceeds the max length, fall back to the per draw binding
+    for _ in 0..2 {
+      system.register_texture(MockTexture);
+    }
+    system.maintain();
+    assert!(!system.bindless_enabled());
+    assert!(take_updates().is_empty());
+  }
+}
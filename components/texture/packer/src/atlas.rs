@@ -0,0 +1,431 @@
+//! Build the texture atlas from the named images: pack them with the padding, composite the
+//! pixels into the pages, and remap the uv of the source images into the atlas.
+
+use std::hash::Hash;
+
+use fast_hash_collection::*;
+use rendiation_algebra::*;
+use rendiation_texture::{Size, Texture2D, Texture2DBuffer, Texture2dInitAble, TextureRange};
+
+use crate::skyline::SkylinePacker;
+use crate::*;
+
+#[derive(Debug, Clone, Copy)]
+pub struct AtlasBuilderConfig {
+  /// The pixels kept around every image, so the filtering will not read the neighbor images.
+  pub padding: usize,
+  /// How many padding pixels are filled by extending the image edges, the rest of the padding is
+  /// filled by the default pixel. Should not be larger than the padding.
+  pub bleed: usize,
+  pub allow_90_rotation: bool,
+  pub init_size: Size,
+  /// The page is grown by doubling the shorter side until this size.
+  pub max_size: Size,
+  pub grow: bool,
+  /// Spill the images that could not fit in the page into the new pages, or the build will fail.
+  pub multi_page: bool,
+}
+
+impl Default for AtlasBuilderConfig {
+  fn default() -> Self {
+    Self {
+      padding: 1,
+      bleed: 1,
+      allow_90_rotation: false,
+      init_size: Size::from_usize_pair_min_one((512, 512)),
+      max_size: Size::from_usize_pair_min_one((4096, 4096)),
+      grow: true,
+      multi_page: false,
+    }
+  }
+}
+
+pub struct AtlasBuilder<K, T> {
+  config: AtlasBuilderConfig,
+  images: Vec<(K, T)>,
+}
+
+impl<K, T> AtlasBuilder<K, T>
+where
+  K: Hash + Eq,
+  T: Texture2D,
+  T::Pixel: Default,
+{
+  pub fn new(config: AtlasBuilderConfig) -> Self {
+    Self {
+      config,
+      images: Default::default(),
+    }
+  }
+
+  pub fn add(&mut self, name: K, image: T) -> &mut Self {
+    self.images.push((name, image));
+    self
+  }
+
+  pub fn build(self) -> Result<TextureAtlas<K, T::Pixel>, PackError> {
+    self.build_with(&mut SkylinePacker::new(Default::default()))
+  }
+
+  /// The packer will be reconfigured for every page.
+  pub fn build_with(
+    self,
+    packer: &mut impl TexturePacker,
+  ) -> Result<TextureAtlas<K, T::Pixel>, PackError> {
+    let config = self.config;
+    let padded_sizes: Vec<_> = self
+      .images
+      .iter()
+      .map(|(_, image)| {
+        let (width, height) = image.size().into_usize();
+        Size::from_usize_pair_min_one((width + 2 * config.padding, height + 2 * config.padding))
+      })
+      .collect();
+
+    // the large images first yields the better packing
+    let mut remaining: Vec<usize> = (0..self.images.len()).collect();
+    remaining.sort_by_key(|i| {
+      let (width, height) = padded_sizes[*i].into_usize();
+      std::cmp::Reverse((height, width))
+    });
+
+    let mut pages = Vec::new();
+    let mut placements = Vec::with_capacity(self.images.len());
+    let (max_width, max_height) = config.max_size.into_usize();
+    let (init_width, init_height) = config.init_size.into_usize();
+    let init_size =
+      Size::from_usize_pair_min_one((init_width.min(max_width), init_height.min(max_height)));
+
+    while !remaining.is_empty() {
+      let mut page_size = init_size;
+      loop {
+        packer.config(PackerConfig {
+          allow_90_rotation: config.allow_90_rotation,
+          init_size: page_size,
+        });
+
+        let mut packed = Vec::new();
+        let mut failed = Vec::new();
+        for index in remaining.iter().copied() {
+          match packer.pack(padded_sizes[index]) {
+            Ok(result) => packed.push((index, result)),
+            Err(_) => failed.push(index),
+          }
+        }
+
+        if !failed.is_empty() {
+          if let Some(next) = config
+            .grow
+            .then(|| grow_size(page_size, config.max_size))
+            .flatten()
+          {
+            page_size = next;
+            continue;
+          }
+          if !config.multi_page || packed.is_empty() {
+            return Err(PackError::SpaceNotEnough);
+          }
+        }
+
+        let page = pages.len();
+        pages.push(page_size);
+        placements.extend(
+          packed
+            .into_iter()
+            .map(|(index, result)| (index, page, result)),
+        );
+        remaining = failed;
+        break;
+      }
+    }
+
+    let mut page_buffers: Vec<_> = pages
+      .iter()
+      .map(|size| Texture2DBuffer::init_with(*size, T::Pixel::default()))
+      .collect();
+
+    let mut entries: Vec<_> = placements
+      .into_iter()
+      .map(|(index, page, result)| {
+        let (width, height) = self.images[index].1.size().into_usize();
+        let size = if result.rotated {
+          Size::from_usize_pair_min_one((height, width))
+        } else {
+          Size::from_usize_pair_min_one((width, height))
+        };
+        let origin = result.range.origin;
+        let entry = AtlasEntry {
+          page,
+          range: TextureRange {
+            origin: (origin.x + config.padding, origin.y + config.padding).into(),
+            size,
+          },
+          rotated: result.rotated,
+          page_size: pages[page],
+        };
+        composite(
+          &mut page_buffers[page],
+          &self.images[index].1,
+          &entry,
+          config.bleed.min(config.padding),
+        );
+        (index, entry)
+      })
+      .collect();
+    entries.sort_by_key(|(index, _)| *index);
+
+    let entries = self
+      .images
+      .into_iter()
+      .zip(entries)
+      .map(|((name, _), (_, entry))| (name, entry))
+      .collect();
+
+    Ok(TextureAtlas {
+      pages: page_buffers,
+      entries,
+    })
+  }
+}
+
+fn grow_size(size: Size, max: Size) -> Option<Size> {
+  let (width, height) = size.into_usize();
+  let (max_width, max_height) = max.into_usize();
+  let next = if (width <= height && width < max_width) || height >= max_height {
+    ((width * 2).min(max_width), height)
+  } else {
+    (width, (height * 2).min(max_height))
+  };
+  (next != (width, height)).then(|| Size::from_usize_pair_min_one(next))
+}
+
+fn composite<T: Texture2D>(
+  page: &mut Texture2DBuffer<T::Pixel>,
+  image: &T,
+  entry: &AtlasEntry,
+  bleed: usize,
+) {
+  let (width, height) = entry.range.size.into_usize();
+  let origin = entry.range.origin;
+  let image_height = image.height();
+  let bleed = bleed as isize;
+
+  for y in -bleed..height as isize + bleed {
+    for x in -bleed..width as isize + bleed {
+      // the bleed pixels copy the nearest edge pixel
+      let local_x = x.clamp(0, width as isize - 1) as usize;
+      let local_y = y.clamp(0, height as isize - 1) as usize;
+      let source = if entry.rotated {
+        (local_y, image_height - 1 - local_x)
+      } else {
+        (local_x, local_y)
+      };
+      let target = (
+        (origin.x as isize + x) as usize,
+        (origin.y as isize + y) as usize,
+      );
+      page.write(target, image.read(source));
+    }
+  }
+}
+
+pub struct TextureAtlas<K, P> {
+  pub pages: Vec<Texture2DBuffer<P>>,
+  pub entries: FastHashMap<K, AtlasEntry>,
+}
+
+impl<K: Hash + Eq, P> TextureAtlas<K, P> {
+  pub fn get(&self, name: &K) -> Option<&AtlasEntry> {
+    self.entries.get(name)
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AtlasEntry {
+  pub page: usize,
+  /// The image pixels in the page, the padding is excluded. The width and height are swapped if
+  /// rotated.
+  pub range: TextureRange,
+  /// The image is rotated 90 degrees clockwise in the page.
+  pub rotated: bool,
+  pub page_size: Size,
+}
+
+impl AtlasEntry {
+  /// Map the uv of the source image to the uv of the page. The uv is expected in [0, 1], the
+  /// repeat wrapping could not be kept after merged into the atlas.
+  pub fn remap_uv(&self, uv: Vec2<f32>) -> Vec2<f32> {
+    let local = if self.rotated {
+      Vec2::new(1. - uv.y, uv.x)
+    } else {
+      uv
+    };
+    let (width, height) = self.range.size.into_usize();
+    let (page_width, page_height) = self.page_size.into_usize();
+    let origin = Vec2::new(self.range.origin.x as f32, self.range.origin.y as f32);
+    let size = Vec2::new(width as f32, height as f32);
+    let page_size = Vec2::new(page_width as f32, page_height as f32);
+    (origin + local * size) / page_size
+  }
+
+  /// Rewrite the uv of the mesh vertices, used when the materials of the meshes are merged into
+  /// the one using the atlas.
+  pub fn remap_mesh_uv<V>(&self, vertices: &mut [V], uv: impl Fn(&mut V) -> &mut Vec2<f32>) {
+    for vertex in vertices {
+      let uv = uv(vertex);
+      *uv = self.remap_uv(*uv);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn size(width: usize, height: usize) -> Size {
+    Size::from_usize_pair_min_one((width, height))
+  }
+
+  /// Every pixel has the unique value, the zero is left for the default pixel.
+  fn image(id: u32, width: usize, height: usize) -> Texture2DBuffer<u32> {
+    let data = (0..width * height)
+      .map(|i| id * 1000 + i as u32 + 1)
+      .collect();
+    Texture2DBuffer::from_raw(data, size(width, height))
+  }
+
+  #[test]
+  fn padding_and_bleed() {
+    let config = AtlasBuilderConfig {
+      padding: 2,
+      bleed: 1,
+      init_size: size(32, 32),
+      ..Default::default()
+    };
+    let mut builder = AtlasBuilder::new(config);
+    builder.add("a", image(1, 4, 3)).add("b", image(2, 5, 5));
+    let atlas = builder.build().unwrap();
+    assert_eq!(atlas.pages.len(), 1);
+    let page = &atlas.pages[0];
+
+    for (name, id, width, height) in [("a", 1, 4, 3), ("b", 2, 5, 5)] {
+      let source = image(id, width, height);
+      let entry = atlas.get(&name).unwrap();
+      assert!(!entry.rotated);
+      let origin = entry.range.origin;
+      let pixel = |x: isize, y: isize| {
+        page.read((
+          (origin.x as isize + x) as usize,
+          (origin.y as isize + y) as usize,
+        ))
+      };
+
+      for y in -2..height as isize + 2 {
+        for x in -2..width as isize + 2 {
+          let inside_bleed =
+            (-1..=width as isize).contains(&x) && (-1..=height as isize).contains(&y);
+          let expect = if inside_bleed {
+            let nearest = (
+              x.clamp(0, width as isize - 1) as usize,
+              y.clamp(0, height as isize - 1) as usize,
+            );
+            source.read(nearest)
+          } else {
+            0
+          };
+          assert_eq!(pixel(x, y), expect, "{name} at ({x}, {y})");
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn grow_or_spill() {
+    let images = || (0..3).map(|i| (i, image(i, 12, 12)));
+    let config = AtlasBuilderConfig {
+      padding: 1,
+      init_size: size(16, 16),
+      max_size: size(32, 32),
+      ..Default::default()
+    };
+
+    let mut builder = AtlasBuilder::new(config);
+    images().for_each(|(name, image)| {
+      builder.add(name, image);
+    });
+    let atlas = builder.build().unwrap();
+    assert_eq!(atlas.pages.len(), 1);
+    assert_eq!(atlas.pages[0].size(), size(32, 32));
+
+    let config = AtlasBuilderConfig {
+      grow: false,
+      multi_page: true,
+      ..config
+    };
+    let mut builder = AtlasBuilder::new(config);
+    images().for_each(|(name, image)| {
+      builder.add(name, image);
+    });
+    let atlas = builder.build().unwrap();
+    assert_eq!(atlas.pages.len(), 3);
+    let mut pages: Vec<_> = (0..3).map(|i| atlas.get(&i).unwrap().page).collect();
+    pages.sort_unstable();
+    assert_eq!(pages, vec![0, 1, 2]);
+    assert!(atlas.pages.iter().all(|page| page.size() == size(16, 16)));
+
+    let config = AtlasBuilderConfig {
+      multi_page: false,
+      ..config
+    };
+    let mut builder = AtlasBuilder::new(config);
+    images().for_each(|(name, image)| {
+      builder.add(name, image);
+    });
+    assert!(builder.build().is_err());
+  }
+
+  #[test]
+  fn remap_uv() {
+    let (width, height) = (4, 2);
+    let source = image(1, width, height);
+    let page_size = size(16, 8);
+
+    for rotated in [false, true] {
+      let entry = AtlasEntry {
+        page: 0,
+        range: TextureRange {
+          origin: (3, 2).into(),
+          size: if rotated {
+            size(height, width)
+          } else {
+            size(width, height)
+          },
+        },
+        rotated,
+        page_size,
+      };
+      let mut page = Texture2DBuffer::init_with(page_size, 0);
+      composite(&mut page, &source, &entry, 0);
+
+      // the pixel centers of the source should map to the same pixel in the page
+      for y in 0..height {
+        for x in 0..width {
+          let uv = Vec2::new(
+            (x as f32 + 0.5) / width as f32,
+            (y as f32 + 0.5) / height as f32,
+          );
+          let uv = entry.remap_uv(uv);
+          let page_pixel = (
+            (uv.x * page_size.width_usize() as f32) as usize,
+            (uv.y * page_size.height_usize() as f32) as usize,
+          );
+          assert_eq!(
+            page.read(page_pixel),
+            source.read((x, y)),
+            "rotated: {rotated}"
+          );
+        }
+      }
+    }
+  }
+}
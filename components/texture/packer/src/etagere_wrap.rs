@@ -34,13 +34,20 @@ impl BaseTexturePacker for EtagerePacker {
 
 impl RePackablePacker for EtagerePacker {
   fn pack_with_id(&mut self, input: Size) -> Result<PackResultWithId, PackError> {
-    let result = self
-      .inner
-      .allocate(size2(
-        input.width_usize() as i32,
-        input.height_usize() as i32,
-      ))
-      .ok_or(PackError::SpaceNotEnough)?;
+    let (width, height) = input.into_usize();
+    let mut rotated = false;
+    let mut result = self.inner.allocate(size2(width as i32, height as i32));
+    if result.is_none() && self.config.allow_90_rotation {
+      rotated = true;
+      result = self.inner.allocate(size2(height as i32, width as i32));
+    }
+    let result = result.ok_or(PackError::SpaceNotEnough)?;
+
+    let size = if rotated {
+      Size::from_usize_pair_min_one((height, width))
+    } else {
+      input
+    };
 
     Ok(PackResultWithId {
       result: PackResult {
@@ -50,9 +57,9 @@ impl RePackablePacker for EtagerePacker {
             result.rectangle.min.y as usize,
           )
             .into(),
-          size: input,
+          size,
         },
-        rotated: false,
+        rotated,
       },
       id: PackId(result.id.serialize()),
     })
@@ -2,6 +2,7 @@ use std::sync::atomic::AtomicU32;
 
 use rendiation_texture::{Size, TextureRange};
 
+pub mod atlas;
 pub mod etagere_wrap;
 pub mod shelf;
 pub mod skyline;
@@ -22,11 +23,17 @@ pub enum UnpackError {
   UnpackItemNotExist,
 }
 
-/// padding should handle in user side
+/// padding should handle in user side, or use the [atlas::AtlasBuilder]
 pub trait TexturePacker: BaseTexturePacker {
   fn pack(&mut self, input: Size) -> Result<PackResult, PackError>;
 }
 
+impl<T: RePackablePacker> TexturePacker for T {
+  fn pack(&mut self, input: Size) -> Result<PackResult, PackError> {
+    self.pack_with_id(input).map(|r| r.result)
+  }
+}
+
 pub trait PackableChecker: TexturePacker {
   /// this should have lower cost than pack, and not request mutable self
   fn can_pack(&self, input: Size) -> bool;
@@ -70,6 +70,9 @@ impl<T: SectionLike> RowAllocator<T> {
 }
 
 trait SectionLike {
+  /// If the allocated section could be allocated again, for example the shelf could hold many
+  /// items in a row.
+  const SHARED: bool;
   fn section(&self) -> &Section;
   fn is_empty(&self) -> bool;
 }
@@ -86,15 +89,14 @@ impl<T: SectionLike> RowAllocator<T> {
     let mut min: Option<(usize, usize, bool)> = None;
     for section_id in &self.free {
       let section_new = self.sections.get(section_id).unwrap();
-      if !section_packable(section_new) {
+      if section_new.section().extent < extent || !section_packable(section_new) {
         continue;
       }
 
       let is_new_should_split = section_new.is_empty();
       let extend_new = section_new.section().extent;
       if let Some((_, min_extend, should_split)) = min {
-        if (!is_new_should_split || should_split) && extend_new >= extent && min_extend > extend_new
-        {
+        if (!is_new_should_split || should_split) && min_extend > extend_new {
           min = (*section_id, extend_new, is_new_should_split).into();
         }
       } else {
@@ -102,33 +104,34 @@ impl<T: SectionLike> RowAllocator<T> {
       }
     }
 
-    if let Some((section_id, _, should_split)) = min {
-      if should_split {
-        self.free.remove(&section_id);
-        let section = self.sections.remove(&section_id).unwrap();
+    let (section_id, _, should_split) = min?;
+    if should_split {
+      self.free.remove(&section_id);
+      let section = self.sections.remove(&section_id).unwrap();
 
-        let (top, bottom) = section.section().split(extent);
+      let (top, bottom) = section.section().split(extent);
 
-        let top = section_creator(top);
-
-        if bottom.extent != 0 {
-          let bottom = section_creator(bottom);
-          self.id += 1;
-          self.sections.insert(self.id, bottom);
-          self.free.insert(self.id);
-        }
+      let top = section_creator(top);
 
+      if bottom.extent != 0 {
+        let bottom = section_creator(bottom);
         self.id += 1;
-        let section_id = self.id;
-        let section = self.sections.entry(section_id).or_insert(top);
+        self.sections.insert(self.id, bottom);
+        self.free.insert(self.id);
+      }
 
-        (section, section_id).into()
-      } else {
-        let section = self.sections.get_mut(&section_id).unwrap();
-        (section, section_id).into()
+      self.id += 1;
+      let section_id = self.id;
+      // the shared section could still accept the other allocations
+      if T::SHARED {
+        self.free.insert(section_id);
       }
+      let section = self.sections.entry(section_id).or_insert(top);
+
+      (section, section_id).into()
     } else {
-      None
+      let section = self.sections.get_mut(&section_id).unwrap();
+      (section, section_id).into()
     }
   }
 
@@ -145,37 +148,26 @@ impl<T: SectionLike> RowAllocator<T> {
     section_creator: impl FnOnce(Section) -> T + Copy,
   ) -> Result<bool, SectionNotExist> {
     let section = self.sections.remove(&section_id).ok_or(SectionNotExist)?;
+    self.free.remove(&section_id);
     assert!(section.is_empty()); // todo should we return error?
 
-    let section = *section.section();
-
-    if let Some((new_sec, old_to_remove)) = self.free.iter().find_map(|sec_id| {
-      let sec = self.sections.get(&section_id).unwrap();
-      section.try_merge(sec.section()).map(|r| (r, *sec_id))
+    let mut merged = *section.section();
+    while let Some((old_to_remove, new_sec)) = self.free.iter().find_map(|sec_id| {
+      let sec = self.sections.get(sec_id).unwrap();
+      if !sec.is_empty() {
+        return None;
+      }
+      merged.try_merge(sec.section()).map(|r| (*sec_id, r))
     }) {
       self.sections.remove(&old_to_remove);
       self.free.remove(&old_to_remove);
-      self.id += 1;
-      self.sections.insert(self.id, section_creator(new_sec));
-      self.free.insert(self.id);
-
-      let x_section = self.id;
-
-      if let Some((new_sec, old_to_remove)) = self.free.iter().find_map(|sec_id| {
-        let sec = self.sections.get(&section_id).unwrap();
-        section.try_merge(sec.section()).map(|r| (r, *sec_id))
-      }) {
-        self.sections.remove(&x_section);
-        self.free.remove(&x_section);
-
-        self.sections.remove(&old_to_remove);
-        self.free.remove(&old_to_remove);
-        self.id += 1;
-        self.sections.insert(self.id, section_creator(new_sec));
-        self.free.insert(self.id);
-      }
+      merged = new_sec;
     }
 
+    self.id += 1;
+    self.sections.insert(self.id, section_creator(merged));
+    self.free.insert(self.id);
+
     Ok(self.should_split())
   }
 
@@ -192,7 +184,7 @@ pub struct Section {
 
 impl Section {
   pub fn split(&self, extent: usize) -> (Section, Section) {
-    assert!(extent < self.extent);
+    assert!(extent <= self.extent);
     (
       Section {
         start: self.start,
@@ -227,6 +219,7 @@ impl Section {
 }
 
 impl SectionLike for Section {
+  const SHARED: bool = false;
   fn section(&self) -> &Section {
     self
   }
@@ -247,9 +240,18 @@ impl Shelf {
       allocator: RowAllocator::new(h_section),
     }
   }
+
+  fn can_fit(&self, width: usize) -> bool {
+    self
+      .allocator
+      .free
+      .iter()
+      .any(|id| self.allocator.sections[id].extent >= width)
+  }
 }
 
 impl SectionLike for Shelf {
+  const SHARED: bool = true;
   fn section(&self) -> &Section {
     &self.section
   }
@@ -269,41 +271,48 @@ impl BaseTexturePacker for ShelfPacker {
   }
 }
 
+impl ShelfPacker {
+  fn pack_impl(&mut self, width: usize, height: usize) -> Option<(TextureRange, usize, usize)> {
+    let (row, row_id) =
+      self
+        .allocator
+        .find_or_create_suitable(height, self.shelf_creator(), |shelf: &Shelf| {
+          shelf.can_fit(width)
+        })?;
+
+    let (section, section_id) =
+      row
+        .allocator
+        .find_or_create_suitable(width, Section::from, |_| true)?;
+
+    let range = TextureRange {
+      origin: (section.start, row.section.start).into(),
+      size: Size::from_usize_pair_min_one((width, height)),
+    };
+    (range, row_id, section_id).into()
+  }
+}
+
 impl RePackablePacker for ShelfPacker {
   fn pack_with_id(
     &mut self,
     input: rendiation_texture::Size,
   ) -> Result<PackResultWithId, PackError> {
-    let width = usize::from(input.width);
-    let height = usize::from(input.height);
-
-    let packable = |_shelf: &Shelf| {
-      todo!();
-    };
-
-    let (row, row_id) = self
-      .allocator
-      .find_or_create_suitable(height, self.shelf_creator(), packable)
-      .ok_or(PackError::SpaceNotEnough)?;
-
-    let (section, section_id) = row
-      .allocator
-      .find_or_create_suitable(width, Section::from, |_| true)
-      .ok_or(PackError::SpaceNotEnough)?;
+    let (width, height) = input.into_usize();
 
-    let range = TextureRange {
-      origin: (section.start, row.section.start).into(),
-      size: input,
-    };
+    let mut rotated = false;
+    let mut packed = self.pack_impl(width, height);
+    if packed.is_none() && self.config.allow_90_rotation {
+      rotated = true;
+      packed = self.pack_impl(height, width);
+    }
+    let (range, row_id, section_id) = packed.ok_or(PackError::SpaceNotEnough)?;
 
     let id = Default::default();
     self.packed.insert(id, (range, row_id, section_id));
 
     Ok(PackResultWithId {
-      result: PackResult {
-        range,
-        rotated: false,
-      },
+      result: PackResult { range, rotated },
       id,
     })
   }
@@ -334,3 +343,89 @@ impl RePackablePacker for ShelfPacker {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn size(width: usize, height: usize) -> Size {
+    Size::from_usize_pair_min_one((width, height))
+  }
+
+  fn overlap(a: &TextureRange, b: &TextureRange) -> bool {
+    let (a_width, a_height) = a.size.into_usize();
+    let (b_width, b_height) = b.size.into_usize();
+    a.origin.x < b.origin.x + b_width
+      && b.origin.x < a.origin.x + a_width
+      && a.origin.y < b.origin.y + b_height
+      && b.origin.y < a.origin.y + a_height
+  }
+
+  #[test]
+  fn allocate_deallocate_and_merge() {
+    let mut packer = ShelfPacker::new(PackerConfig {
+      allow_90_rotation: false,
+      init_size: size(64, 64),
+    });
+
+    let a = packer.pack_with_id(size(32, 16)).unwrap();
+    let b = packer.pack_with_id(size(32, 16)).unwrap();
+    // the same shelf is shared by the items of the same height
+    assert_eq!(a.result.range.origin.y, b.result.range.origin.y);
+    assert!(!overlap(&a.result.range, &b.result.range));
+    let c = packer.pack_with_id(size(64, 48)).unwrap();
+    assert!(packer.pack(size(1, 1)).is_err());
+
+    packer.unpack(a.id).unwrap();
+    assert!(packer.unpack(a.id).is_err());
+    // the freed space is reused
+    let d = packer.pack_with_id(size(32, 16)).unwrap();
+    assert_eq!(d.result.range.origin, a.result.range.origin);
+
+    // the empty sections and shelves are merged back into the whole page
+    for id in [b.id, c.id, d.id] {
+      packer.unpack(id).unwrap();
+    }
+    let full = packer.pack_with_id(size(64, 64)).unwrap();
+    assert_eq!(full.result.range.origin, (0, 0).into());
+  }
+
+  #[test]
+  fn no_overlap_after_cycles() {
+    let page = 128;
+    let mut packer = ShelfPacker::new(PackerConfig {
+      allow_90_rotation: true,
+      init_size: size(page, page),
+    });
+
+    // the linear congruential sequence to make the test deterministic
+    let mut state = 12345_u32;
+    let mut random = move |max: usize| {
+      state = state.wrapping_mul(1103515245).wrapping_add(12345);
+      (state >> 16) as usize % max
+    };
+
+    let mut live: Vec<(PackId, TextureRange)> = Vec::new();
+    for _ in 0..2000 {
+      if live.is_empty() || random(3) != 0 {
+        let input = size(random(24) + 1, random(24) + 1);
+        if let Ok(packed) = packer.pack_with_id(input) {
+          let range = packed.result.range;
+          let (width, height) = range.size.into_usize();
+          assert!(range.origin.x + width <= page && range.origin.y + height <= page);
+          assert!(live.iter().all(|(_, other)| !overlap(&range, other)));
+          live.push((packed.id, range));
+        }
+      } else {
+        let (id, _) = live.swap_remove(random(live.len()));
+        packer.unpack(id).unwrap();
+      }
+    }
+
+    assert!(!live.is_empty());
+    for (id, _) in live {
+      packer.unpack(id).unwrap();
+    }
+    assert!(packer.pack(size(page, page)).is_ok());
+  }
+}
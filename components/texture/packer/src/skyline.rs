@@ -163,8 +163,9 @@ impl SkylinePacker {
       w: rect.w,
     };
 
+    // the rect could touch the bottom border, so the skyline could be just outside the border
     assert!(skyline.right() <= self.border.right());
-    assert!(skyline.y <= self.border.bottom());
+    assert!(rect.bottom() <= self.border.bottom());
 
     self.skylines.insert(index, skyline);
 
@@ -235,15 +236,6 @@ impl BaseTexturePacker for SkylinePacker {
 
 impl PackableChecker for SkylinePacker {
   fn can_pack(&self, input: Size) -> bool {
-    if let Some((_, rect)) = self.find_skyline(input) {
-      let skyline = Skyline {
-        x: rect.left(),
-        y: rect.bottom() + 1,
-        w: rect.w,
-      };
-
-      return skyline.right() <= self.border.right() && skyline.y <= self.border.bottom();
-    }
-    false
+    self.find_skyline(input).is_some()
   }
 }
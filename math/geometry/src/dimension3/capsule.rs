@@ -0,0 +1,210 @@
+use crate::*;
+
+/// The solid capsule, which is the set of points that within the radius to the segment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Capsule<T: Scalar = f32> {
+  pub start: Vec3<T>,
+  pub end: Vec3<T>,
+  pub radius: T,
+}
+
+impl<T: Scalar> Capsule<T> {
+  pub fn new(start: Vec3<T>, end: Vec3<T>, radius: T) -> Self {
+    Self { start, end, radius }
+  }
+
+  pub fn segment(&self) -> LineSegment3D<T> {
+    LineSegment::line_segment(self.start, self.end)
+  }
+}
+
+impl<T: Scalar> SpaceEntity<T, 3> for Capsule<T> {
+  type Matrix = Mat4<T>;
+  /// The radius is scaled by the max scale of the matrix.
+  fn apply_matrix(&mut self, mat: Self::Matrix) -> &mut Self {
+    self.start = mat * self.start;
+    self.end = mat * self.end;
+    self.radius *= mat.get_scale().max_channel();
+    self
+  }
+}
+
+impl<T: Scalar> LebesgueMeasurable<T, 3> for Capsule<T> {
+  fn measure(&self) -> T {
+    let r2 = self.radius * self.radius;
+    let length = self.start.distance(self.end);
+    T::PI() * r2 * (length + self.radius * T::eval::<{ scalar_transmute(4.0 / 3.0) }>())
+  }
+}
+
+impl<T: Scalar> LebesgueMeasurable<T, 2> for Capsule<T> {
+  fn measure(&self) -> T {
+    let length = self.start.distance(self.end);
+    T::two() * T::PI() * self.radius * (length + T::two() * self.radius)
+  }
+}
+
+impl<T: Scalar> SolidEntity<T, 3> for Capsule<T> {
+  type Center = Vec3<T>;
+  fn centroid(&self) -> Vec3<T> {
+    (self.start + self.end) * T::half()
+  }
+}
+
+impl<T: Scalar> ContainAble<T, Vec3<T>, 3> for Capsule<T> {
+  fn contains(&self, point: &Vec3<T>) -> bool {
+    point.distance_sq_to(&self.segment()) <= self.radius * self.radius
+  }
+}
+
+impl<T: Scalar> SpaceBounding<T, Box3<T>, 3> for Capsule<T> {
+  fn to_bounding(&self) -> Box3<T> {
+    let extent = Vec3::splat(self.radius);
+    [
+      self.start - extent,
+      self.start + extent,
+      self.end - extent,
+      self.end + extent,
+    ]
+    .iter()
+    .collect()
+  }
+}
+
+/// the ray distances where the ray hit the sphere surface
+fn ray_sphere_hits(ray: &Ray3, center: Vec3<f32>, radius: f32) -> impl Iterator<Item = f32> {
+  let oc = center - ray.origin;
+  let tca = oc.dot(ray.direction);
+  let d2 = oc.length2() - tca * tca;
+  let radius2 = radius * radius;
+  let roots = (d2 <= radius2).then(|| {
+    let thc = (radius2 - d2).sqrt();
+    [tca - thc, tca + thc]
+  });
+  roots.into_iter().flatten()
+}
+
+intersect_reverse!(Capsule, OptionalNearest<HitPoint3D>, (), Ray3);
+impl IntersectAble<Capsule, OptionalNearest<HitPoint3D>> for Ray3 {
+  fn intersect(&self, capsule: &Capsule, p: &()) -> OptionalNearest<HitPoint3D> {
+    let axis = capsule.end - capsule.start;
+    let length = axis.length();
+    if length == 0. {
+      // the capsule degenerates into the sphere
+      let sphere = Sphere::new(capsule.start, capsule.radius);
+      return IntersectAble::<Sphere, OptionalNearest<HitPoint3D>>::intersect(self, &sphere, p);
+    }
+    let axis = axis / length;
+
+    // only the part of each surface that is on the capsule boundary is valid
+    let side = ray_cylinder_surface_hits(self, capsule.start, axis, capsule.radius)
+      .filter(|&(_, h)| h >= 0. && h <= length)
+      .map(|(t, _)| t);
+    let axis_param = |t: f32| (self.at(t) - capsule.start).dot(axis);
+    let start_cap =
+      ray_sphere_hits(self, capsule.start, capsule.radius).filter(|&t| axis_param(t) <= 0.);
+    let end_cap =
+      ray_sphere_hits(self, capsule.end, capsule.radius).filter(|&t| axis_param(t) >= length);
+
+    side
+      .chain(start_cap)
+      .chain(end_cap)
+      .filter(|&t| t >= 0.)
+      .min_by(|a, b| a.total_cmp(b))
+      .map(|t| self.at_into(t))
+      .into()
+  }
+}
+
+intersect_reverse!(Capsule, bool, (), Ray3);
+impl IntersectAble<Capsule, bool> for Ray3 {
+  fn intersect(&self, other: &Capsule, p: &()) -> bool {
+    IntersectAble::<Capsule, OptionalNearest<HitPoint3D>>::intersect(self, other, p).is_some()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::*;
+
+  fn assert_near(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-4, "{a} != {b}");
+  }
+
+  fn hit(ray: &Ray3, capsule: &Capsule) -> Option<f32> {
+    IntersectAble::<Capsule, OptionalNearest<HitPoint3D>>::intersect(ray, capsule, &())
+      .0
+      .map(|h| h.distance)
+  }
+
+  #[test]
+  fn measure_and_centroid() {
+    let capsule = Capsule::new(Vec3::new(0., 0., 0.), Vec3::new(0., 2., 0.), 1.);
+    let pi = std::f32::consts::PI;
+    assert_near(
+      LebesgueMeasurable::<f32, 3>::measure(&capsule),
+      pi * 2. + pi * 4. / 3.,
+    );
+    assert_near(
+      LebesgueMeasurable::<f32, 2>::measure(&capsule),
+      2. * pi * 2. + 4. * pi,
+    );
+    assert_eq!(capsule.centroid(), Vec3::new(0., 1., 0.));
+    assert!(capsule.contains(&Vec3::new(0., 2.9, 0.)));
+    assert!(!capsule.contains(&Vec3::new(0.8, 2.8, 0.)));
+  }
+
+  #[test]
+  fn ray_capsule() {
+    let capsule = Capsule::new(Vec3::new(0., -1., 0.), Vec3::new(0., 1., 0.), 0.5);
+
+    // the side
+    let ray = Ray3::new(
+      Vec3::new(5., 0., 0.),
+      Vec3::new(-1., 0., 0.).into_normalized(),
+    );
+    assert_near(hit(&ray, &capsule).unwrap(), 4.5);
+    // the end cap
+    let ray = Ray3::new(
+      Vec3::new(0., 5., 0.),
+      Vec3::new(0., -1., 0.).into_normalized(),
+    );
+    assert_near(hit(&ray, &capsule).unwrap(), 3.5);
+    // the start cap, the ray is tilted so the hit point is not on the axis
+    let ray = Ray3::new(
+      Vec3::new(0.3, -5., 0.),
+      Vec3::new(0., 1., 0.).into_normalized(),
+    );
+    assert_near(hit(&ray, &capsule).unwrap(), 4. - (0.25_f32 - 0.09).sqrt());
+    // miss
+    let ray = Ray3::new(
+      Vec3::new(5., 0., 0.6),
+      Vec3::new(-1., 0., 0.).into_normalized(),
+    );
+    assert_eq!(hit(&ray, &capsule), None);
+    let ray = Ray3::new(
+      Vec3::new(5., 0., 0.),
+      Vec3::new(1., 0., 0.).into_normalized(),
+    );
+    assert!(!IntersectAble::<Capsule, bool>::intersect(
+      &ray,
+      &capsule,
+      &()
+    ));
+  }
+
+  #[test]
+  fn ray_degenerated_capsule() {
+    let sphere_like = Capsule::new(Vec3::new(1., 0., 0.), Vec3::new(1., 0., 0.), 0.5);
+    let ray = Ray3::new(
+      Vec3::new(5., 0., 0.),
+      Vec3::new(-1., 0., 0.).into_normalized(),
+    );
+    assert_near(hit(&ray, &sphere_like).unwrap(), 3.5);
+    let ray = Ray3::new(
+      Vec3::new(5., 1., 0.),
+      Vec3::new(-1., 0., 0.).into_normalized(),
+    );
+    assert_eq!(hit(&ray, &sphere_like), None);
+  }
+}
@@ -0,0 +1,180 @@
+use super::*;
+
+const EPA_MAX_ITERATION: usize = 64;
+const EPA_TOLERANCE: f32 = 1e-5;
+
+/// The penetration of two overlapped convex shapes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Penetration {
+  /// the direction to move the second shape to separate them
+  pub normal: Vec3<f32>,
+  pub depth: f32,
+  /// the deepest point of the first shape inside the second shape
+  pub point_a: Vec3<f32>,
+  /// the deepest point of the second shape inside the first shape
+  pub point_b: Vec3<f32>,
+}
+
+struct PolytopeFace {
+  vertices: [usize; 3],
+  normal: Vec3<f32>,
+  distance: f32,
+}
+
+/// Return the penetration of two convex shapes by the GJK and EPA algorithm, return None if the
+/// shapes are separated.
+pub fn epa_penetration(a: &impl SupportFunction, b: &impl SupportFunction) -> Option<Penetration> {
+  let simplex = match gjk(a, b) {
+    GjkResult::Separated(_) => return None,
+    GjkResult::Overlapped(simplex) => simplex,
+  };
+
+  let mut vertices = expand_to_tetrahedron(a, b, simplex.points.clone());
+  if vertices.len() < 4 {
+    // the Minkowski difference is flat, the shapes are only touching
+    return Some(touching(simplex, Vec3::new(1., 0., 0.)));
+  }
+
+  let interior = vertices.iter().fold(Vec3::zero(), |sum, p| sum + p.w) * 0.25;
+  let make_face = |vertices: &[SupportPoint], face: [usize; 3]| {
+    let [a, b, c] = face.map(|i| vertices[i].w);
+    let mut normal = (b - a).cross(c - a).normalize();
+    let mut face = face;
+    if normal.dot(a - interior) < 0. {
+      normal = normal.reverse();
+      face.swap(1, 2);
+    }
+    PolytopeFace {
+      vertices: face,
+      normal,
+      distance: normal.dot(a),
+    }
+  };
+
+  let mut faces: Vec<_> = [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]]
+    .into_iter()
+    .map(|f| make_face(&vertices, f))
+    .collect();
+
+  // the GJK simplex is within the tolerance to the origin, so the tetrahedron expanded from it
+  // should contain the origin. If the numerical error breaks that, the expansion could not reach
+  // the real boundary, so fall back to the touching contact of the GJK simplex.
+  if let Some(face) = faces.iter().find(|f| f.distance < -EPA_TOLERANCE) {
+    return Some(touching(simplex, face.normal));
+  }
+
+  for _ in 0..EPA_MAX_ITERATION {
+    let (closest, _) = faces
+      .iter()
+      .enumerate()
+      .min_by(|x, y| x.1.distance.total_cmp(&y.1.distance))?;
+    let face = &faces[closest];
+    let support = SupportPoint::new(a, b, face.normal);
+    if support.w.dot(face.normal) - face.distance <= EPA_TOLERANCE {
+      break;
+    }
+
+    // remove the faces that visible from the new point, and connect the horizon to it
+    let new_index = vertices.len();
+    vertices.push(support);
+    let mut horizon = Vec::new();
+    faces.retain(|face| {
+      let visible = face.normal.dot(support.w - vertices[face.vertices[0]].w) > 0.;
+      if visible {
+        for i in 0..3 {
+          let edge = (face.vertices[i], face.vertices[(i + 1) % 3]);
+          if let Some(pair) = horizon.iter().position(|&e| e == (edge.1, edge.0)) {
+            horizon.swap_remove(pair);
+          } else {
+            horizon.push(edge);
+          }
+        }
+      }
+      !visible
+    });
+    for (from, to) in horizon {
+      faces.push(make_face(&vertices, [from, to, new_index]));
+    }
+  }
+
+  let face = faces
+    .iter()
+    .min_by(|x, y| x.distance.total_cmp(&y.distance))?;
+  let [va, vb, vc] = face.vertices.map(|i| vertices[i]);
+  let weights =
+    Triangle::new(va.w, vb.w, vc.w).closest_point_barycentric(face.normal * face.distance);
+  Some(Penetration {
+    normal: face.normal,
+    depth: face.distance.max(0.),
+    point_a: va.a * weights.x + vb.a * weights.y + vc.a * weights.z,
+    point_b: va.b * weights.x + vb.b * weights.y + vc.b * weights.z,
+  })
+}
+
+/// The zero depth penetration at the point of the simplex that closest to the origin.
+fn touching(simplex: Simplex, normal: Vec3<f32>) -> Penetration {
+  let mut reduced = simplex.clone();
+  let (point_a, point_b) = match reduced.reduce_to_closest() {
+    Some((_, weights)) => reduced.witness_points(&weights),
+    // the origin is inside the tetrahedron, any vertex is as good as the others
+    None => (simplex.points[0].a, simplex.points[0].b),
+  };
+  Penetration {
+    normal,
+    depth: 0.,
+    point_a,
+    point_b,
+  }
+}
+
+/// Add support points until the simplex is a tetrahedron with volume, return less than four
+/// points if the Minkowski difference is flat.
+fn expand_to_tetrahedron(
+  a: &impl SupportFunction,
+  b: &impl SupportFunction,
+  mut points: Vec<SupportPoint>,
+) -> Vec<SupportPoint> {
+  let axes = [
+    Vec3::new(1., 0., 0.),
+    Vec3::new(0., 1., 0.),
+    Vec3::new(0., 0., 1.),
+  ];
+  // the candidate direction must grow the dimension of the simplex
+  let grows = |points: &[SupportPoint], w: Vec3<f32>| match points.len() {
+    1 => (w - points[0].w).length2() > EPA_TOLERANCE,
+    2 => (points[1].w - points[0].w).cross(w - points[0].w).length2() > EPA_TOLERANCE,
+    _ => {
+      let normal = (points[1].w - points[0].w).cross(points[2].w - points[0].w);
+      normal.dot(w - points[0].w).abs() > EPA_TOLERANCE
+    }
+  };
+
+  while points.len() < 4 {
+    let directions: Vec<Vec3<f32>> = match points.len() {
+      1 => axes.iter().flat_map(|&d| [d, d.reverse()]).collect(),
+      2 => {
+        let line = points[1].w - points[0].w;
+        axes
+          .iter()
+          .map(|&axis| line.cross(axis))
+          .filter(|d| d.length2() > 0.)
+          .flat_map(|d| [d, d.reverse()])
+          .collect()
+      }
+      _ => {
+        let normal = (points[1].w - points[0].w).cross(points[2].w - points[0].w);
+        vec![normal, normal.reverse()]
+      }
+    };
+
+    let found = directions
+      .into_iter()
+      .map(|d| SupportPoint::new(a, b, d))
+      .find(|p| grows(&points, p.w));
+    match found {
+      Some(p) => points.push(p),
+      None => break,
+    }
+  }
+  points
+}
@@ -0,0 +1,168 @@
+use super::*;
+
+const GJK_MAX_ITERATION: usize = 64;
+const GJK_TOLERANCE: f32 = 1e-6;
+
+/// The simplex of the Minkowski difference, up to four vertices.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Simplex {
+  pub points: Vec<SupportPoint>,
+}
+
+impl Simplex {
+  /// Reduce the simplex to the sub simplex that contains the point closest to the origin, return
+  /// the closest point, or None if the origin is inside the tetrahedron.
+  pub(crate) fn reduce_to_closest(&mut self) -> Option<(Vec3<f32>, Vec<f32>)> {
+    let w = |i: usize| self.points[i].w;
+    let (vertices, weights) = match self.points.len() {
+      1 => (vec![0], vec![1.]),
+      2 => {
+        let segment = LineSegment::line_segment(w(0), w(1));
+        let (_, t) = segment.closest_point(Vec3::zero());
+        (vec![0, 1], vec![1. - t, t])
+      }
+      3 => {
+        let weights = Triangle::new(w(0), w(1), w(2)).closest_point_barycentric(Vec3::zero());
+        (vec![0, 1, 2], vec![weights.x, weights.y, weights.z])
+      }
+      _ => {
+        // check the faces that the origin is outside of, the closest point must be on them
+        let mut best: Option<(f32, Vec<usize>, Vec<f32>)> = None;
+        for (face, opposite) in [
+          ([0, 1, 2], 3),
+          ([0, 1, 3], 2),
+          ([0, 2, 3], 1),
+          ([1, 2, 3], 0),
+        ] {
+          let [a, b, c] = face.map(w);
+          let normal = (b - a).cross(c - a);
+          let origin_side = -normal.dot(a);
+          let opposite_side = normal.dot(w(opposite) - a);
+          if origin_side * opposite_side > 0. {
+            continue;
+          }
+          let weights = Triangle::new(a, b, c).closest_point_barycentric(Vec3::zero());
+          let point = a * weights.x + b * weights.y + c * weights.z;
+          let distance = point.length2();
+          if best.as_ref().map_or(true, |(d, _, _)| distance < *d) {
+            best = Some((
+              distance,
+              face.to_vec(),
+              vec![weights.x, weights.y, weights.z],
+            ));
+          }
+        }
+        let (_, vertices, weights) = best?;
+        (vertices, weights)
+      }
+    };
+
+    let mut reduced = Vec::with_capacity(vertices.len());
+    let mut reduced_weights = Vec::with_capacity(vertices.len());
+    for (&i, &weight) in vertices.iter().zip(weights.iter()) {
+      if weight > 0. {
+        reduced.push(self.points[i]);
+        reduced_weights.push(weight);
+      }
+    }
+    self.points = reduced;
+    let closest = self
+      .points
+      .iter()
+      .zip(reduced_weights.iter())
+      .fold(Vec3::zero(), |sum, (p, &weight)| sum + p.w * weight);
+    Some((closest, reduced_weights))
+  }
+
+  /// Return the points on the source shapes by the barycentric weights of the simplex points.
+  pub(crate) fn witness_points(&self, weights: &[f32]) -> (Vec3<f32>, Vec3<f32>) {
+    self
+      .points
+      .iter()
+      .zip(weights.iter())
+      .fold((Vec3::zero(), Vec3::zero()), |(pa, pb), (p, &weight)| {
+        (pa + p.a * weight, pb + p.b * weight)
+      })
+  }
+}
+
+pub(crate) enum GjkResult {
+  Separated(ClosestPointPair<f32, Vec3<f32>>),
+  /// the final simplex contains the origin
+  Overlapped(Simplex),
+}
+
+pub(crate) fn gjk(a: &impl SupportFunction, b: &impl SupportFunction) -> GjkResult {
+  let mut simplex = Simplex {
+    points: vec![SupportPoint::new(a, b, Vec3::new(1., 0., 0.))],
+  };
+  let mut closest = simplex.points[0].w;
+  let mut weights = vec![1.];
+
+  for _ in 0..GJK_MAX_ITERATION {
+    let distance_sq = closest.length2();
+    if distance_sq <= GJK_TOLERANCE * GJK_TOLERANCE {
+      return GjkResult::Overlapped(simplex);
+    }
+
+    let support = SupportPoint::new(a, b, closest.reverse());
+    // the support plane is the lower bound of the distance, if it could not separate the shapes
+    // by the tolerance, they are touching when no more progress could be made
+    let lower_bound = closest.dot(support.w);
+    let touching = lower_bound <= GJK_TOLERANCE * distance_sq.sqrt();
+
+    // no more progress could be made toward the origin
+    if distance_sq - lower_bound <= GJK_TOLERANCE * distance_sq.max(1.)
+      || simplex
+        .points
+        .iter()
+        .any(|p| (p.w - support.w).length2() <= f32::EPSILON)
+    {
+      if touching {
+        return GjkResult::Overlapped(simplex);
+      }
+      break;
+    }
+
+    let mut next = simplex.clone();
+    next.points.push(support);
+    match next.reduce_to_closest() {
+      Some((new_closest, new_weights)) => {
+        if new_closest.length2() >= distance_sq {
+          if touching {
+            return GjkResult::Overlapped(simplex);
+          }
+          break;
+        }
+        simplex = next;
+        closest = new_closest;
+        weights = new_weights;
+      }
+      None => return GjkResult::Overlapped(next),
+    }
+  }
+
+  let (self_point, target_point) = simplex.witness_points(&weights);
+  GjkResult::Separated(ClosestPointPair {
+    self_point,
+    target_point,
+    distance_sq: closest.length2(),
+  })
+}
+
+/// Return the closest points of two convex shapes by the GJK algorithm, return None if the
+/// shapes are overlapped.
+pub fn gjk_distance(
+  a: &impl SupportFunction,
+  b: &impl SupportFunction,
+) -> Option<ClosestPointPair<f32, Vec3<f32>>> {
+  match gjk(a, b) {
+    GjkResult::Separated(result) => Some(result),
+    GjkResult::Overlapped(_) => None,
+  }
+}
+
+/// Test if two convex shapes are overlapped by the GJK algorithm, touching counts as overlapped.
+pub fn gjk_intersect(a: &impl SupportFunction, b: &impl SupportFunction) -> bool {
+  matches!(gjk(a, b), GjkResult::Overlapped(_))
+}
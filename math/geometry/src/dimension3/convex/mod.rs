@@ -0,0 +1,104 @@
+//! Convex shape algorithms based on the support function: quickhull, GJK and EPA.
+
+use crate::*;
+
+mod epa;
+mod gjk;
+mod quickhull;
+
+pub use epa::*;
+pub use gjk::*;
+pub use quickhull::*;
+
+#[cfg(test)]
+mod test;
+
+/// The convex shape that described by the support mapping.
+pub trait SupportFunction {
+  /// Return the furthest point of the shape in the direction, the direction is not required to
+  /// be normalized.
+  fn support(&self, direction: Vec3<f32>) -> Vec3<f32>;
+}
+
+fn furthest_point<'a>(
+  points: impl IntoIterator<Item = &'a Vec3<f32>>,
+  direction: Vec3<f32>,
+) -> Vec3<f32> {
+  points
+    .into_iter()
+    .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+    .copied()
+    .unwrap_or_else(Vec3::zero)
+}
+
+impl SupportFunction for Box3 {
+  fn support(&self, direction: Vec3<f32>) -> Vec3<f32> {
+    self.max_corner(direction)
+  }
+}
+
+impl SupportFunction for OrientedBox3 {
+  fn support(&self, direction: Vec3<f32>) -> Vec3<f32> {
+    let sign = |axis: Vec3<f32>| if axis.dot(direction) > 0. { 1. } else { -1. };
+    self.center
+      + self.axes[0] * (self.half_size.x * sign(self.axes[0]))
+      + self.axes[1] * (self.half_size.y * sign(self.axes[1]))
+      + self.axes[2] * (self.half_size.z * sign(self.axes[2]))
+  }
+}
+
+/// Any point is the furthest point in the zero direction, the rounded shapes return the point
+/// of the core shape instead of the NaN.
+fn normalize_or_zero(direction: Vec3<f32>) -> Vec3<f32> {
+  let length = direction.length();
+  if length > 0. {
+    direction / length
+  } else {
+    Vec3::zero()
+  }
+}
+
+impl SupportFunction for Sphere {
+  fn support(&self, direction: Vec3<f32>) -> Vec3<f32> {
+    self.center + normalize_or_zero(direction) * self.radius
+  }
+}
+
+impl SupportFunction for Capsule {
+  fn support(&self, direction: Vec3<f32>) -> Vec3<f32> {
+    let end = if self.end.dot(direction) > self.start.dot(direction) {
+      self.end
+    } else {
+      self.start
+    };
+    end + normalize_or_zero(direction) * self.radius
+  }
+}
+
+impl SupportFunction for Triangle {
+  fn support(&self, direction: Vec3<f32>) -> Vec3<f32> {
+    furthest_point(self.iter_point(), direction)
+  }
+}
+
+impl SupportFunction for ConvexHull {
+  fn support(&self, direction: Vec3<f32>) -> Vec3<f32> {
+    furthest_point(&self.vertices, direction)
+  }
+}
+
+/// The point on the Minkowski difference A - B, with the source points on A and B.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SupportPoint {
+  pub w: Vec3<f32>,
+  pub a: Vec3<f32>,
+  pub b: Vec3<f32>,
+}
+
+impl SupportPoint {
+  pub fn new(a: &impl SupportFunction, b: &impl SupportFunction, direction: Vec3<f32>) -> Self {
+    let a = a.support(direction);
+    let b = b.support(direction.reverse());
+    Self { w: a - b, a, b }
+  }
+}
@@ -0,0 +1,196 @@
+use super::*;
+
+/// The convex hull of a point set, the faces are triangles in counter clockwise order when viewed
+/// from outside.
+#[derive(Debug, Clone)]
+pub struct ConvexHull {
+  pub vertices: Vec<Vec3<f32>>,
+  pub faces: Vec<[u32; 3]>,
+}
+
+struct HullFace {
+  vertices: [usize; 3],
+  normal: Vec3<f32>,
+  offset: f32,
+  /// the points that above this face, and not assigned to other faces
+  outside: Vec<usize>,
+  removed: bool,
+}
+
+impl HullFace {
+  fn new(points: &[Vec3<f32>], vertices: [usize; 3]) -> Self {
+    let [a, b, c] = vertices.map(|i| points[i]);
+    let normal = (b - a).cross(c - a).normalize();
+    Self {
+      vertices,
+      normal,
+      offset: normal.dot(a),
+      outside: Vec::new(),
+      removed: false,
+    }
+  }
+
+  fn distance(&self, p: Vec3<f32>) -> f32 {
+    self.normal.dot(p) - self.offset
+  }
+}
+
+impl ConvexHull {
+  /// Build the convex hull by the quickhull algorithm, return None if all points are coplanar.
+  ///
+  /// http://media.steampowered.com/apps/valve/2014/DirkGregorius_ImplementingQuickHull.pdf
+  pub fn from_points(points: &[Vec3<f32>]) -> Option<Self> {
+    if points.len() < 4 {
+      return None;
+    }
+    let bound: Box3 = points.iter().collect();
+    let extent = bound.max.x.abs().max(bound.min.x.abs())
+      + bound.max.y.abs().max(bound.min.y.abs())
+      + bound.max.z.abs().max(bound.min.z.abs());
+    let epsilon = 3. * f32::EPSILON * extent;
+
+    let mut faces = initial_tetrahedron(points, epsilon)?;
+    for i in 0..points.len() {
+      assign_to_outside(&mut faces, 0..4, points, i, epsilon);
+    }
+
+    while let Some(current) = faces
+      .iter()
+      .position(|f| !f.removed && !f.outside.is_empty())
+    {
+      // the furthest point of the face must be on the hull
+      let face = &faces[current];
+      let eye = *face
+        .outside
+        .iter()
+        .max_by(|&&a, &&b| {
+          face
+            .distance(points[a])
+            .total_cmp(&face.distance(points[b]))
+        })
+        .unwrap();
+
+      let mut horizon: Vec<(usize, usize)> = Vec::new();
+      let mut orphans = Vec::new();
+      for face in faces.iter_mut() {
+        if face.removed || face.distance(points[eye]) <= epsilon {
+          continue;
+        }
+        face.removed = true;
+        orphans.append(&mut face.outside);
+        for i in 0..3 {
+          let edge = (face.vertices[i], face.vertices[(i + 1) % 3]);
+          if let Some(pair) = horizon.iter().position(|&e| e == (edge.1, edge.0)) {
+            horizon.swap_remove(pair);
+          } else {
+            horizon.push(edge);
+          }
+        }
+      }
+
+      let new_faces = faces.len()..faces.len() + horizon.len();
+      for (from, to) in horizon {
+        faces.push(HullFace::new(points, [from, to, eye]));
+      }
+      for p in orphans {
+        if p != eye {
+          assign_to_outside(&mut faces, new_faces.clone(), points, p, epsilon);
+        }
+      }
+    }
+
+    // compact the vertices that used by the hull
+    let mut remapping = vec![None; points.len()];
+    let mut vertices = Vec::new();
+    let faces = faces
+      .iter()
+      .filter(|f| !f.removed)
+      .map(|f| {
+        f.vertices.map(|v| {
+          *remapping[v].get_or_insert_with(|| {
+            vertices.push(points[v]);
+            vertices.len() as u32 - 1
+          })
+        })
+      })
+      .collect();
+
+    Some(Self { vertices, faces })
+  }
+
+  pub fn iter_triangle(&self) -> impl Iterator<Item = Triangle> + '_ {
+    self.faces.iter().map(|f| {
+      let [a, b, c] = f.map(|v| self.vertices[v as usize]);
+      Triangle::new(a, b, c)
+    })
+  }
+}
+
+fn assign_to_outside(
+  faces: &mut [HullFace],
+  range: std::ops::Range<usize>,
+  points: &[Vec3<f32>],
+  point: usize,
+  epsilon: f32,
+) {
+  if let Some(face) = faces[range]
+    .iter_mut()
+    .find(|f| !f.removed && f.distance(points[point]) > epsilon)
+  {
+    face.outside.push(point);
+  }
+}
+
+fn initial_tetrahedron(points: &[Vec3<f32>], epsilon: f32) -> Option<Vec<HullFace>> {
+  // the two most distant extreme points on the axes
+  let mut extremes = [0; 6];
+  for (i, p) in points.iter().enumerate() {
+    for axis in 0..3 {
+      if p[axis] < points[extremes[axis * 2]][axis] {
+        extremes[axis * 2] = i;
+      }
+      if p[axis] > points[extremes[axis * 2 + 1]][axis] {
+        extremes[axis * 2 + 1] = i;
+      }
+    }
+  }
+  let (v0, v1) = (0..3)
+    .map(|axis| (extremes[axis * 2], extremes[axis * 2 + 1]))
+    .max_by(|a, b| {
+      let da = (points[a.0] - points[a.1]).length2();
+      let db = (points[b.0] - points[b.1]).length2();
+      da.total_cmp(&db)
+    })?;
+
+  let line = LineSegment::line_segment(points[v0], points[v1]);
+  let line_distance = |p: &Vec3<f32>| (line.closest_point(*p).0 - *p).length2();
+  let v2 = (0..points.len())
+    .max_by(|&a, &b| line_distance(&points[a]).total_cmp(&line_distance(&points[b])))?;
+  if line_distance(&points[v2]) <= epsilon * epsilon {
+    return None;
+  }
+
+  let base = HullFace::new(points, [v0, v1, v2]);
+  let v3 = (0..points.len()).max_by(|&a, &b| {
+    base
+      .distance(points[a])
+      .abs()
+      .total_cmp(&base.distance(points[b]).abs())
+  })?;
+  if base.distance(points[v3]).abs() <= epsilon {
+    return None;
+  }
+
+  // make the base face point away from the apex
+  let [v0, v1, v2] = if base.distance(points[v3]) > 0. {
+    [v0, v2, v1]
+  } else {
+    [v0, v1, v2]
+  };
+  Some(vec![
+    HullFace::new(points, [v0, v1, v2]),
+    HullFace::new(points, [v0, v3, v1]),
+    HullFace::new(points, [v1, v3, v2]),
+    HullFace::new(points, [v2, v3, v0]),
+  ])
+}
@@ -0,0 +1,207 @@
+use crate::*;
+
+fn assert_near(a: f32, b: f32) {
+  assert!((a - b).abs() < 1e-4, "{a} != {b}");
+}
+
+fn assert_point(a: Vec3<f32>, b: Vec3<f32>) {
+  assert!((a - b).length() < 1e-4, "{a:?} != {b:?}");
+}
+
+fn cube_corners(center: Vec3<f32>, half: f32) -> Vec<Vec3<f32>> {
+  (0..8)
+    .map(|i| {
+      let sign = |bit: usize| if i & bit != 0 { half } else { -half };
+      center + Vec3::new(sign(1), sign(2), sign(4))
+    })
+    .collect()
+}
+
+/// Every hull vertex is on or behind every face, so the faces are outward.
+fn assert_convex_and_outward(hull: &ConvexHull) {
+  for face in hull.iter_triangle() {
+    let normal = (face.b - face.a).cross(face.c - face.a).normalize();
+    for v in &hull.vertices {
+      assert!(normal.dot(*v - face.a) <= 1e-4);
+    }
+  }
+}
+
+#[test]
+fn gjk_overlap_and_separation() {
+  let unit = Box3::new3(Vec3::zero(), Vec3::one());
+
+  let overlapped = Box3::new3(Vec3::splat(0.5), Vec3::splat(1.5));
+  assert!(gjk_intersect(&unit, &overlapped));
+  let touching = Box3::new3(Vec3::new(1., 0., 0.), Vec3::new(2., 1., 1.));
+  assert!(gjk_intersect(&unit, &touching));
+  let separated = Box3::new3(Vec3::new(1.1, 0., 0.), Vec3::new(2., 1., 1.));
+  assert!(!gjk_intersect(&unit, &separated));
+
+  let sphere = Sphere::new(Vec3::new(0.5, 0.5, 1.5), 0.6);
+  assert!(gjk_intersect(&unit, &sphere));
+  let sphere = Sphere::new(Vec3::new(0.5, 0.5, 2.), 1.);
+  assert!(gjk_intersect(&unit, &sphere));
+  let sphere = Sphere::new(Vec3::new(0.5, 0.5, 1.5), 0.4);
+  assert!(!gjk_intersect(&unit, &sphere));
+
+  let triangle = Triangle::new(
+    Vec3::new(-1., 0.5, -1.),
+    Vec3::new(2., 0.5, -1.),
+    Vec3::new(0.5, 0.5, 2.),
+  );
+  assert!(gjk_intersect(&unit, &triangle));
+  let triangle = Triangle::new(
+    Vec3::new(-1., 1.5, -1.),
+    Vec3::new(2., 1.5, -1.),
+    Vec3::new(0.5, 1.5, 2.),
+  );
+  assert!(!gjk_intersect(&unit, &triangle));
+
+  let hull = ConvexHull::from_points(&[
+    Vec3::new(0., 0., 0.),
+    Vec3::new(1., 0., 0.),
+    Vec3::new(0., 1., 0.),
+    Vec3::new(0., 0., 1.),
+  ])
+  .unwrap();
+  let sphere = Sphere::new(Vec3::splat(0.2), 0.1);
+  assert!(gjk_intersect(&hull, &sphere));
+  let sphere = Sphere::new(Vec3::splat(1.), 0.1);
+  assert!(!gjk_intersect(&hull, &sphere));
+
+  let capsule = Capsule::new(Vec3::new(0.5, -1., 0.5), Vec3::new(0.5, 2., 0.5), 0.1);
+  assert!(gjk_intersect(&unit, &capsule));
+}
+
+#[test]
+fn gjk_distance_and_witness_points() {
+  let a = Sphere::new(Vec3::zero(), 1.);
+  let b = Sphere::new(Vec3::new(5., 0., 0.), 1.);
+  let result = gjk_distance(&a, &b).unwrap();
+  assert_near(result.distance_sq, 9.);
+  assert_point(result.self_point, Vec3::new(1., 0., 0.));
+  assert_point(result.target_point, Vec3::new(4., 0., 0.));
+
+  // the closest feature is the edge of the box against the face of the other box
+  let a = Box3::new3(Vec3::zero(), Vec3::one());
+  let b = Box3::new3(Vec3::new(3., 2., -5.), Vec3::new(4., 3., 5.));
+  let result = gjk_distance(&a, &b).unwrap();
+  assert_near(result.distance_sq, 4. + 1.);
+  assert_near(result.self_point.x, 1.);
+  assert_near(result.self_point.y, 1.);
+  assert_near(result.target_point.x, 3.);
+  assert_near(result.target_point.y, 2.);
+  assert_near(result.self_point.z, result.target_point.z);
+
+  let triangle = Triangle::new(
+    Vec3::new(0., 0., 0.),
+    Vec3::new(1., 0., 0.),
+    Vec3::new(0., 1., 0.),
+  );
+  let sphere = Sphere::new(Vec3::new(0.2, 0.2, 3.), 1.);
+  let result = gjk_distance(&triangle, &sphere).unwrap();
+  assert_near(result.distance_sq, 4.);
+  // the witness points against the curved shape converge slower than the distance
+  assert!((result.self_point - Vec3::new(0.2, 0.2, 0.)).length() < 1e-3);
+  assert!((result.target_point - Vec3::new(0.2, 0.2, 2.)).length() < 1e-3);
+
+  assert!(gjk_distance(&a, &Sphere::new(Vec3::zero(), 1.)).is_none());
+}
+
+#[test]
+fn epa_box_penetration() {
+  let a = Box3::new3(Vec3::zero(), Vec3::splat(2.));
+
+  let b = Box3::new3(Vec3::new(1.5, 0.5, 0.5), Vec3::new(3.5, 1.5, 1.5));
+  let penetration = epa_penetration(&a, &b).unwrap();
+  assert_near(penetration.depth, 0.5);
+  assert_point(penetration.normal, Vec3::new(1., 0., 0.));
+
+  let b = Box3::new3(Vec3::new(0.5, -1.8, 0.5), Vec3::new(1.5, 0.2, 1.5));
+  let penetration = epa_penetration(&a, &b).unwrap();
+  assert_near(penetration.depth, 0.2);
+  assert_point(penetration.normal, Vec3::new(0., -1., 0.));
+
+  // moving the second shape by the penetration separates them
+  let offset = penetration.normal * (penetration.depth + 1e-3);
+  let moved = Box3::new3(b.min + offset, b.max + offset);
+  assert!(!gjk_intersect(&a, &moved));
+
+  let touching = Box3::new3(Vec3::new(2., 0., 0.), Vec3::new(3., 1., 1.));
+  let penetration = epa_penetration(&a, &touching).unwrap();
+  assert_near(penetration.depth, 0.);
+
+  let separated = Box3::new3(Vec3::new(2.5, 0., 0.), Vec3::new(3., 1., 1.));
+  assert!(epa_penetration(&a, &separated).is_none());
+}
+
+#[test]
+fn support_in_zero_direction() {
+  let sphere = Sphere::new(Vec3::new(1., 2., 3.), 1.);
+  assert_eq!(sphere.support(Vec3::zero()), sphere.center);
+
+  let capsule = Capsule::new(Vec3::zero(), Vec3::new(0., 2., 0.), 0.5);
+  let support = capsule.support(Vec3::zero());
+  assert!(support.x.is_finite() && support.y.is_finite() && support.z.is_finite());
+
+  // the sphere against itself makes the GJK search in the zero direction
+  assert!(gjk_intersect(&sphere, &sphere));
+}
+
+#[test]
+fn quickhull_cube() {
+  let corners = cube_corners(Vec3::zero(), 1.);
+  let hull = ConvexHull::from_points(&corners).unwrap();
+  assert_eq!(hull.vertices.len(), 8);
+  assert_eq!(hull.faces.len(), 12);
+  assert_convex_and_outward(&hull);
+  for v in &hull.vertices {
+    assert!(corners.contains(v));
+  }
+
+  let area: f32 = hull
+    .iter_triangle()
+    .map(|t| (t.b - t.a).cross(t.c - t.a).length() * 0.5)
+    .sum();
+  assert_near(area, 24.);
+}
+
+#[test]
+fn quickhull_ignores_interior_points() {
+  let mut points = cube_corners(Vec3::new(1., 2., 3.), 2.);
+  // interior points, and the points on the faces and edges
+  points.push(Vec3::new(1., 2., 3.));
+  points.push(Vec3::new(0.5, 2.5, 3.5));
+  points.push(Vec3::new(2.9, 0.1, 4.9));
+  points.push(Vec3::new(3., 2., 3.));
+  points.push(Vec3::new(3., 4., 3.));
+  points.rotate_left(5);
+
+  let hull = ConvexHull::from_points(&points).unwrap();
+  assert_eq!(hull.vertices.len(), 8);
+  assert_eq!(hull.faces.len(), 12);
+  assert_convex_and_outward(&hull);
+}
+
+#[test]
+fn quickhull_degenerated_input() {
+  let coplanar = [
+    Vec3::new(0., 0., 0.),
+    Vec3::new(1., 0., 0.),
+    Vec3::new(0., 1., 0.),
+    Vec3::new(1., 1., 0.),
+    Vec3::new(0.5, 0.3, 0.),
+  ];
+  assert!(ConvexHull::from_points(&coplanar).is_none());
+
+  let collinear = [
+    Vec3::new(0., 0., 0.),
+    Vec3::new(1., 1., 1.),
+    Vec3::new(2., 2., 2.),
+    Vec3::new(-3., -3., -3.),
+  ];
+  assert!(ConvexHull::from_points(&collinear).is_none());
+
+  assert!(ConvexHull::from_points(&coplanar[..3]).is_none());
+}
@@ -0,0 +1,214 @@
+use crate::*;
+
+/// The solid cylinder with flat caps, the axis is from start to end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cylinder<T: Scalar = f32> {
+  pub start: Vec3<T>,
+  pub end: Vec3<T>,
+  pub radius: T,
+}
+
+impl<T: Scalar> Cylinder<T> {
+  pub fn new(start: Vec3<T>, end: Vec3<T>, radius: T) -> Self {
+    Self { start, end, radius }
+  }
+
+  pub fn height(&self) -> T {
+    self.start.distance(self.end)
+  }
+
+  /// The radius of the cylinder projected on the direction.
+  pub fn projected_radius(&self, direction: Vec3<T>) -> T {
+    let axis = (self.end - self.start).normalize();
+    let cos = axis.dot(direction);
+    let half_axis = (self.end - self.start).dot(direction).abs() * T::half();
+    half_axis + self.radius * (T::one() - cos * cos).max(T::zero()).sqrt()
+  }
+}
+
+impl<T: Scalar> SpaceEntity<T, 3> for Cylinder<T> {
+  type Matrix = Mat4<T>;
+  /// The radius is scaled by the max scale of the matrix.
+  fn apply_matrix(&mut self, mat: Self::Matrix) -> &mut Self {
+    self.start = mat * self.start;
+    self.end = mat * self.end;
+    self.radius *= mat.get_scale().max_channel();
+    self
+  }
+}
+
+impl<T: Scalar> LebesgueMeasurable<T, 3> for Cylinder<T> {
+  fn measure(&self) -> T {
+    T::PI() * self.radius * self.radius * self.height()
+  }
+}
+
+impl<T: Scalar> LebesgueMeasurable<T, 2> for Cylinder<T> {
+  fn measure(&self) -> T {
+    T::two() * T::PI() * self.radius * (self.height() + self.radius)
+  }
+}
+
+impl<T: Scalar> SolidEntity<T, 3> for Cylinder<T> {
+  type Center = Vec3<T>;
+  fn centroid(&self) -> Vec3<T> {
+    (self.start + self.end) * T::half()
+  }
+}
+
+impl<T: Scalar> SpaceBounding<T, Box3<T>, 3> for Cylinder<T> {
+  fn to_bounding(&self) -> Box3<T> {
+    let axis = (self.end - self.start).normalize();
+    let extent = axis.map(|v| self.radius * (T::one() - v * v).max(T::zero()).sqrt());
+    [
+      self.start - extent,
+      self.start + extent,
+      self.end - extent,
+      self.end + extent,
+    ]
+    .iter()
+    .collect()
+  }
+}
+
+/// Return the ray distances where the ray hit the infinite cylinder surface, and the axis
+/// parameters of the hit points, the axis parameter is the distance to start along the axis.
+pub(crate) fn ray_cylinder_surface_hits(
+  ray: &Ray3,
+  start: Vec3<f32>,
+  axis: Vec3<f32>,
+  radius: f32,
+) -> impl Iterator<Item = (f32, f32)> {
+  let m = ray.origin - start;
+  let d = *ray.direction;
+  let d_perp = d - axis * d.dot(axis);
+  let m_perp = m - axis * m.dot(axis);
+  let a = d_perp.length2();
+  let b = 2. * m_perp.dot(d_perp);
+  let c = m_perp.length2() - radius * radius;
+  let discriminant = b * b - 4. * a * c;
+
+  let roots = if a <= f32::EPSILON || discriminant < 0. {
+    None
+  } else {
+    let sqrt = discriminant.sqrt();
+    Some([(-b - sqrt) / (2. * a), (-b + sqrt) / (2. * a)])
+  };
+  roots
+    .into_iter()
+    .flatten()
+    .map(move |t| (t, (m + d * t).dot(axis)))
+}
+
+intersect_reverse!(Cylinder, OptionalNearest<HitPoint3D>, (), Ray3);
+impl IntersectAble<Cylinder, OptionalNearest<HitPoint3D>> for Ray3 {
+  fn intersect(&self, cylinder: &Cylinder, _: &()) -> OptionalNearest<HitPoint3D> {
+    let height = cylinder.height();
+    if height == 0. {
+      // the degenerated cylinder has no volume
+      return OptionalNearest::none();
+    }
+    let axis = (cylinder.end - cylinder.start) / height;
+
+    let side = ray_cylinder_surface_hits(self, cylinder.start, axis, cylinder.radius)
+      .filter(|&(_, h)| h >= 0. && h <= height)
+      .map(|(t, _)| t);
+
+    let m = self.origin - cylinder.start;
+    let d_dot_axis = self.direction.dot(axis);
+    let caps = [0., height].into_iter().filter_map(|cap| {
+      if d_dot_axis == 0. {
+        return None;
+      }
+      let t = (cap - m.dot(axis)) / d_dot_axis;
+      let radial = m + *self.direction * t - axis * cap;
+      (radial.length2() <= cylinder.radius * cylinder.radius).then_some(t)
+    });
+
+    side
+      .chain(caps)
+      .filter(|&t| t >= 0.)
+      .min_by(|a, b| a.total_cmp(b))
+      .map(|t| self.at_into(t))
+      .into()
+  }
+}
+
+intersect_reverse!(Cylinder, bool, (), Ray3);
+impl IntersectAble<Cylinder, bool> for Ray3 {
+  fn intersect(&self, other: &Cylinder, p: &()) -> bool {
+    IntersectAble::<Cylinder, OptionalNearest<HitPoint3D>>::intersect(self, other, p).is_some()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::*;
+
+  fn assert_near(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-4, "{a} != {b}");
+  }
+
+  fn hit(ray: &Ray3, cylinder: &Cylinder) -> Option<f32> {
+    IntersectAble::<Cylinder, OptionalNearest<HitPoint3D>>::intersect(ray, cylinder, &())
+      .0
+      .map(|h| h.distance)
+  }
+
+  #[test]
+  fn measure_and_centroid() {
+    let cylinder = Cylinder::new(Vec3::new(0., 0., 0.), Vec3::new(0., 0., 3.), 2.);
+    let pi = std::f32::consts::PI;
+    assert_near(LebesgueMeasurable::<f32, 3>::measure(&cylinder), pi * 12.);
+    assert_near(
+      LebesgueMeasurable::<f32, 2>::measure(&cylinder),
+      2. * pi * 2. * 5.,
+    );
+    assert_eq!(cylinder.centroid(), Vec3::new(0., 0., 1.5));
+    assert_near(cylinder.projected_radius(Vec3::new(0., 0., 1.)), 1.5);
+    assert_near(cylinder.projected_radius(Vec3::new(1., 0., 0.)), 2.);
+  }
+
+  #[test]
+  fn ray_cylinder() {
+    let cylinder = Cylinder::new(Vec3::new(0., -1., 0.), Vec3::new(0., 1., 0.), 0.5);
+
+    // the side
+    let ray = Ray3::new(
+      Vec3::new(5., 0., 0.),
+      Vec3::new(-1., 0., 0.).into_normalized(),
+    );
+    assert_near(hit(&ray, &cylinder).unwrap(), 4.5);
+    // the flat cap
+    let ray = Ray3::new(
+      Vec3::new(0.3, 5., 0.),
+      Vec3::new(0., -1., 0.).into_normalized(),
+    );
+    assert_near(hit(&ray, &cylinder).unwrap(), 4.);
+    // miss beyond the cap, which the capsule would hit
+    let ray = Ray3::new(
+      Vec3::new(5., 1.2, 0.),
+      Vec3::new(-1., 0., 0.).into_normalized(),
+    );
+    assert_eq!(hit(&ray, &cylinder), None);
+    let ray = Ray3::new(
+      Vec3::new(5., 0., 0.6),
+      Vec3::new(-1., 0., 0.).into_normalized(),
+    );
+    assert!(!IntersectAble::<Cylinder, bool>::intersect(
+      &ray,
+      &cylinder,
+      &()
+    ));
+  }
+
+  #[test]
+  fn ray_degenerated_cylinder() {
+    let flat = Cylinder::new(Vec3::new(1., 0., 0.), Vec3::new(1., 0., 0.), 0.5);
+    let ray = Ray3::new(
+      Vec3::new(5., 0., 0.),
+      Vec3::new(-1., 0., 0.).into_normalized(),
+    );
+    assert_eq!(hit(&ray, &flat), None);
+  }
+}
@@ -0,0 +1,305 @@
+use crate::*;
+
+impl<T: Scalar> LineSegment3D<T> {
+  /// Return the point on the segment that closest to the given point, and the parameter of it.
+  pub fn closest_point(&self, p: Vec3<T>) -> (Vec3<T>, T) {
+    let dir = self.end - self.start;
+    let length_sq = dir.length2();
+    if length_sq == T::zero() {
+      return (self.start, T::zero());
+    }
+    let t = ((p - self.start).dot(dir) / length_sq)
+      .max(T::zero())
+      .min(T::one());
+    (self.start + dir * t, t)
+  }
+
+  /// Return the intersection point with the triangle, the segment lies in the triangle plane is
+  /// not considered as intersected.
+  pub fn intersect_triangle(&self, triangle: &Triangle3D<T>) -> Option<Vec3<T>> {
+    let plane = Plane::from(*triangle);
+    let d0 = plane.distance_to(&self.start);
+    let d1 = plane.distance_to(&self.end);
+    if d0 * d1 > T::zero() || d0 == d1 {
+      return None;
+    }
+    let point = self.start.lerp(self.end, d0 / (d0 - d1));
+    let b = triangle.barycentric(point)?;
+    (b.x >= T::zero() && b.y >= T::zero() && b.z >= T::zero()).then_some(point)
+  }
+}
+
+impl<T: Scalar> ClosestPointTo<LineSegment3D<T>, T, Vec3<T>> for Vec3<T> {
+  fn closest_point_to(&self, segment: &LineSegment3D<T>) -> ClosestPointPair<T, Vec3<T>> {
+    let (target_point, _) = segment.closest_point(*self);
+    ClosestPointPair {
+      self_point: *self,
+      target_point,
+      distance_sq: (target_point - *self).length2(),
+    }
+  }
+}
+
+impl<T: Scalar> ClosestPointTo<Triangle3D<T>, T, Vec3<T>> for Vec3<T> {
+  fn closest_point_to(&self, triangle: &Triangle3D<T>) -> ClosestPointPair<T, Vec3<T>> {
+    let target_point = triangle.closest_point(*self);
+    ClosestPointPair {
+      self_point: *self,
+      target_point,
+      distance_sq: (target_point - *self).length2(),
+    }
+  }
+}
+
+impl<T: Scalar> ClosestPointTo<Box3<T>, T, Vec3<T>> for Vec3<T> {
+  /// The box is treated as solid, so the point inside the box has zero distance.
+  fn closest_point_to(&self, box3: &Box3<T>) -> ClosestPointPair<T, Vec3<T>> {
+    let target_point = Vec3::new(
+      self.x.max(box3.min.x).min(box3.max.x),
+      self.y.max(box3.min.y).min(box3.max.y),
+      self.z.max(box3.min.z).min(box3.max.z),
+    );
+    ClosestPointPair {
+      self_point: *self,
+      target_point,
+      distance_sq: (target_point - *self).length2(),
+    }
+  }
+}
+
+impl<T: Scalar> ClosestPointTo<LineSegment3D<T>, T, Vec3<T>> for LineSegment3D<T> {
+  /// Real-Time Collision Detection 5.1.9
+  fn closest_point_to(&self, other: &LineSegment3D<T>) -> ClosestPointPair<T, Vec3<T>> {
+    let d1 = self.end - self.start;
+    let d2 = other.end - other.start;
+    let r = self.start - other.start;
+    let a = d1.length2();
+    let e = d2.length2();
+    let f = d2.dot(r);
+    let clamp01 = |v: T| v.max(T::zero()).min(T::one());
+
+    let (s, t) = if a == T::zero() && e == T::zero() {
+      // both segments degenerate into points
+      (T::zero(), T::zero())
+    } else if a == T::zero() {
+      (T::zero(), clamp01(f / e))
+    } else {
+      let c = d1.dot(r);
+      if e == T::zero() {
+        (clamp01(-c / a), T::zero())
+      } else {
+        let b = d1.dot(d2);
+        let denom = a * e - b * b;
+        // pick arbitrary s when the segments are parallel
+        let s = if denom != T::zero() {
+          clamp01((b * f - c * e) / denom)
+        } else {
+          T::zero()
+        };
+        let t = (b * s + f) / e;
+        if t < T::zero() {
+          (clamp01(-c / a), T::zero())
+        } else if t > T::one() {
+          (clamp01((b - c) / a), T::one())
+        } else {
+          (s, t)
+        }
+      }
+    };
+
+    let self_point = self.start + d1 * s;
+    let target_point = other.start + d2 * t;
+    ClosestPointPair {
+      self_point,
+      target_point,
+      distance_sq: (target_point - self_point).length2(),
+    }
+  }
+}
+
+impl<T: Scalar> ClosestPointTo<Triangle3D<T>, T, Vec3<T>> for LineSegment3D<T> {
+  fn closest_point_to(&self, triangle: &Triangle3D<T>) -> ClosestPointPair<T, Vec3<T>> {
+    if let Some(point) = self.intersect_triangle(triangle) {
+      return ClosestPointPair {
+        self_point: point,
+        target_point: point,
+        distance_sq: T::zero(),
+      };
+    }
+
+    // not intersected, the closest points must be on the boundary of one of them
+    let mut result = self.start.closest_point_to(triangle);
+    let mut refresh = |candidate: ClosestPointPair<T, Vec3<T>>| {
+      if candidate.distance_sq < result.distance_sq {
+        result = candidate;
+      }
+    };
+    refresh(self.end.closest_point_to(triangle));
+    triangle.for_each_edge(|edge| refresh(self.closest_point_to(&edge)));
+    result
+  }
+}
+
+macro_rules! distance_by_closest_point {
+  ($self_item: ty, $target:ty) => {
+    impl<T: Scalar> DistanceSquareTo<$target, T> for $self_item {
+      fn distance_sq_to(&self, other: &$target) -> T {
+        self.closest_point_to(other).distance_sq
+      }
+    }
+  };
+}
+
+distance_by_closest_point!(Vec3<T>, LineSegment3D<T>);
+distance_by_closest_point!(Vec3<T>, Triangle3D<T>);
+distance_by_closest_point!(Vec3<T>, Box3<T>);
+distance_by_closest_point!(LineSegment3D<T>, LineSegment3D<T>);
+distance_by_closest_point!(LineSegment3D<T>, Triangle3D<T>);
+
+#[cfg(test)]
+mod test {
+  use crate::*;
+
+  fn assert_point(a: Vec3<f32>, b: Vec3<f32>) {
+    assert!((a - b).length() < 1e-5, "{a:?} != {b:?}");
+  }
+
+  fn segment(start: Vec3<f32>, end: Vec3<f32>) -> LineSegment3D {
+    LineSegment::line_segment(start, end)
+  }
+
+  fn triangle() -> Triangle {
+    Triangle::new(
+      Vec3::new(0., 0., 0.),
+      Vec3::new(2., 0., 0.),
+      Vec3::new(0., 2., 0.),
+    )
+  }
+
+  #[test]
+  fn point_segment() {
+    let s = segment(Vec3::new(0., 0., 0.), Vec3::new(2., 0., 0.));
+
+    let r = Vec3::new(1., 1., 0.).closest_point_to(&s);
+    assert_point(r.target_point, Vec3::new(1., 0., 0.));
+    assert_eq!(r.distance_sq, 1.);
+
+    let r = Vec3::new(-1., 0., 1.).closest_point_to(&s);
+    assert_point(r.target_point, Vec3::new(0., 0., 0.));
+    assert_eq!(r.distance_sq, 2.);
+
+    let r = Vec3::new(5., 0., 0.).closest_point_to(&s);
+    assert_point(r.target_point, Vec3::new(2., 0., 0.));
+    assert_eq!(Vec3::new(5., 0., 0.).distance_to(&s), 3.);
+  }
+
+  #[test]
+  fn point_triangle() {
+    let t = triangle();
+    // vertex regions
+    assert_point(
+      Vec3::new(-1., -1., 1.).closest_point_to(&t).target_point,
+      t.a,
+    );
+    assert_point(
+      Vec3::new(3., -1., 0.).closest_point_to(&t).target_point,
+      t.b,
+    );
+    assert_point(
+      Vec3::new(-0.5, 3., 0.).closest_point_to(&t).target_point,
+      t.c,
+    );
+    // edge regions
+    assert_point(
+      Vec3::new(1., -1., 0.).closest_point_to(&t).target_point,
+      Vec3::new(1., 0., 0.),
+    );
+    assert_point(
+      Vec3::new(-1., 1., 2.).closest_point_to(&t).target_point,
+      Vec3::new(0., 1., 0.),
+    );
+    assert_point(
+      Vec3::new(2., 2., 0.).closest_point_to(&t).target_point,
+      Vec3::new(1., 1., 0.),
+    );
+    // face region
+    let r = Vec3::new(0.5, 0.5, 3.).closest_point_to(&t);
+    assert_point(r.target_point, Vec3::new(0.5, 0.5, 0.));
+    assert_eq!(r.distance_sq, 9.);
+  }
+
+  #[test]
+  fn point_box() {
+    let box3 = Box3::new3(Vec3::splat(-1.), Vec3::splat(1.));
+
+    let r = Vec3::new(3., 0.5, -4.).closest_point_to(&box3);
+    assert_point(r.target_point, Vec3::new(1., 0.5, -1.));
+    assert_eq!(r.distance_sq, 4. + 9.);
+
+    let inside = Vec3::new(0.2, 0.3, 0.4);
+    let r = inside.closest_point_to(&box3);
+    assert_point(r.target_point, inside);
+    assert_eq!(r.distance_sq, 0.);
+  }
+
+  #[test]
+  fn segment_segment_skew() {
+    let a = segment(Vec3::new(-1., 0., 0.), Vec3::new(1., 0., 0.));
+    let b = segment(Vec3::new(0.5, -1., 2.), Vec3::new(0.5, 1., 2.));
+    let r = a.closest_point_to(&b);
+    assert_point(r.self_point, Vec3::new(0.5, 0., 0.));
+    assert_point(r.target_point, Vec3::new(0.5, 0., 2.));
+    assert_eq!(r.distance_sq, 4.);
+
+    // the closest points are clamped to the end points
+    let b = segment(Vec3::new(3., 1., 1.), Vec3::new(3., 5., 1.));
+    let r = a.closest_point_to(&b);
+    assert_point(r.self_point, Vec3::new(1., 0., 0.));
+    assert_point(r.target_point, Vec3::new(3., 1., 1.));
+
+    // crossing segments
+    let b = segment(Vec3::new(0., -1., 0.), Vec3::new(0., 1., 0.));
+    assert_eq!(a.distance_sq_to(&b), 0.);
+  }
+
+  #[test]
+  fn segment_segment_parallel() {
+    let a = segment(Vec3::new(0., 0., 0.), Vec3::new(2., 0., 0.));
+
+    // overlapped parallel segments, any pair of witness points has the same distance
+    let b = segment(Vec3::new(1., 1., 0.), Vec3::new(3., 1., 0.));
+    let r = a.closest_point_to(&b);
+    assert!((r.distance_sq - 1.).abs() < 1e-5);
+    assert!(((r.self_point - r.target_point).length() - 1.).abs() < 1e-5);
+
+    // disjoint parallel segments
+    let b = segment(Vec3::new(4., 1., 0.), Vec3::new(6., 1., 0.));
+    let r = a.closest_point_to(&b);
+    assert_point(r.self_point, Vec3::new(2., 0., 0.));
+    assert_point(r.target_point, Vec3::new(4., 1., 0.));
+    assert_eq!(r.distance_sq, 5.);
+  }
+
+  #[test]
+  fn segment_triangle() {
+    let t = triangle();
+
+    let crossing = segment(Vec3::new(0.5, 0.5, -1.), Vec3::new(0.5, 0.5, 1.));
+    let r = crossing.closest_point_to(&t);
+    assert_point(r.self_point, Vec3::new(0.5, 0.5, 0.));
+    assert_eq!(r.distance_sq, 0.);
+
+    // the end point is closest to the face
+    let above = segment(Vec3::new(0.5, 0.5, 1.), Vec3::new(0.5, 0.5, 3.));
+    let r = above.closest_point_to(&t);
+    assert_point(r.self_point, Vec3::new(0.5, 0.5, 1.));
+    assert_point(r.target_point, Vec3::new(0.5, 0.5, 0.));
+
+    // the segment passes over the hypotenuse, closest to the edge
+    let apart = segment(Vec3::new(2., 2., -1.), Vec3::new(2., 2., 1.));
+    let r = apart.closest_point_to(&t);
+    assert_point(r.self_point, Vec3::new(2., 2., 0.));
+    assert_point(r.target_point, Vec3::new(1., 1., 0.));
+    assert!((apart.distance_to(&t) - 2_f32.sqrt()).abs() < 1e-5);
+  }
+}
@@ -93,3 +93,215 @@ impl IntersectAble<Box3, bool> for Frustum {
     true
   }
 }
+
+intersect_reverse!(Triangle, bool, (), Frustum);
+impl IntersectAble<Triangle, bool> for Frustum {
+  /// Exact test by separating axis, the cheap plane rejection is tested first.
+  fn intersect(&self, triangle: &Triangle, _: &()) -> bool {
+    let points = [triangle.a, triangle.b, triangle.c];
+    for p in &self.planes {
+      if points.iter().all(|v| p.distance_to(v) < 0.) {
+        return false;
+      }
+    }
+
+    let corners = self.corners();
+    let normal = (triangle.b - triangle.a).cross(triangle.c - triangle.a);
+    if is_separating_axis(&points, &corners, normal) {
+      return false;
+    }
+
+    let triangle_edges = [
+      triangle.b - triangle.a,
+      triangle.c - triangle.b,
+      triangle.a - triangle.c,
+    ];
+    for (from, to) in FRUSTUM_EDGES {
+      let frustum_edge = corners[to] - corners[from];
+      if triangle_edges
+        .iter()
+        .any(|e| is_separating_axis(&points, &corners, e.cross(frustum_edge)))
+      {
+        return false;
+      }
+    }
+
+    true
+  }
+}
+
+/// the corner index pairs of the frustum edges, see [Frustum::corners]
+const FRUSTUM_EDGES: [(usize, usize); 12] = [
+  (0, 1),
+  (2, 3),
+  (4, 5),
+  (6, 7),
+  (0, 2),
+  (1, 3),
+  (4, 6),
+  (5, 7),
+  (0, 4),
+  (1, 5),
+  (2, 6),
+  (3, 7),
+];
+
+impl<T: Scalar> Frustum<T> {
+  /// Return the 8 corners by intersecting the side planes, the index bits are (x, y, z) where the
+  /// x bit selects right(0) or left(1), the y bit selects bottom(0) or top(1), the z bit selects
+  /// far(0) or near(1).
+  pub fn corners(&self) -> [Vec3<T>; 8] {
+    std::array::from_fn(|i| {
+      let p1 = self.planes[i & 1];
+      let p2 = self.planes[2 + ((i >> 1) & 1)];
+      let p3 = self.planes[4 + ((i >> 2) & 1)];
+      let (n1, n2, n3) = (*p1.normal, *p2.normal, *p3.normal);
+      let det = n1.dot(n2.cross(n3));
+      (n2.cross(n3) * p1.constant + n3.cross(n1) * p2.constant + n1.cross(n2) * p3.constant)
+        * (-T::one() / det)
+    })
+  }
+}
+
+intersect_reverse!(OrientedBox3, bool, (), Frustum);
+impl IntersectAble<OrientedBox3, bool> for Frustum {
+  fn intersect(&self, obb: &OrientedBox3, _: &()) -> bool {
+    for p in &self.planes {
+      if p.distance_to(&obb.center) < -obb.projected_radius(*p.normal) {
+        return false;
+      }
+    }
+
+    true
+  }
+}
+
+intersect_reverse!(Capsule, bool, (), Frustum);
+impl IntersectAble<Capsule, bool> for Frustum {
+  fn intersect(&self, capsule: &Capsule, _: &()) -> bool {
+    let neg_radius = -capsule.radius;
+
+    for p in &self.planes {
+      if p.distance_to(&capsule.start) < neg_radius && p.distance_to(&capsule.end) < neg_radius {
+        return false;
+      }
+    }
+
+    true
+  }
+}
+
+intersect_reverse!(Cylinder, bool, (), Frustum);
+impl IntersectAble<Cylinder, bool> for Frustum {
+  fn intersect(&self, cylinder: &Cylinder, _: &()) -> bool {
+    let center = cylinder.centroid();
+    for p in &self.planes {
+      if p.distance_to(&center) < -cylinder.projected_radius(*p.normal) {
+        return false;
+      }
+    }
+
+    true
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::*;
+
+  /// the frustum of the identity matrix is the [-1, 1] cube
+  fn cube_frustum() -> Frustum {
+    let mut frustum = Frustum::new();
+    frustum.set_from_matrix(Mat4::identity());
+    frustum
+  }
+
+  #[test]
+  fn frustum_corners() {
+    let corners = cube_frustum().corners();
+    for (i, corner) in corners.iter().enumerate() {
+      let expect = Vec3::new(
+        if i & 1 == 0 { 1. } else { -1. },
+        if i & 2 == 0 { -1. } else { 1. },
+        if i & 4 == 0 { 1. } else { -1. },
+      );
+      assert!((*corner - expect).length() < 1e-5, "{corner:?} {expect:?}");
+    }
+  }
+
+  #[test]
+  fn frustum_triangle_overlap() {
+    let frustum = cube_frustum();
+
+    let inside = Triangle::new(
+      Vec3::new(-0.5, 0., 0.),
+      Vec3::new(0.5, 0., 0.),
+      Vec3::new(0., 0.5, 0.),
+    );
+    assert!(frustum.intersect(&inside, &()));
+    assert!(inside.intersect(&frustum, &()));
+
+    let outside = Triangle::new(
+      Vec3::new(2., 0., 0.),
+      Vec3::new(3., 0., 0.),
+      Vec3::new(2., 1., 0.),
+    );
+    assert!(!frustum.intersect(&outside, &()));
+  }
+
+  #[test]
+  fn frustum_triangle_crossing_with_all_vertices_outside() {
+    let frustum = cube_frustum();
+    let crossing = Triangle::new(
+      Vec3::new(10., 0., 0.),
+      Vec3::new(-10., 10., 0.),
+      Vec3::new(-10., -10., 0.),
+    );
+    assert!(crossing.iter_point().all(|p| !frustum.contains(p)));
+    assert!(frustum.intersect(&crossing, &()));
+  }
+
+  #[test]
+  fn frustum_triangle_near_corner() {
+    // every vertex is outside, but no single plane rejects all of them, only the cross product
+    // of the triangle edge and the frustum edge separates.
+    let frustum = cube_frustum();
+    let near_corner = Triangle::new(
+      Vec3::new(3., 0., 0.),
+      Vec3::new(0., 3., 0.),
+      Vec3::new(3., 3., 0.),
+    );
+    assert!(!frustum.intersect(&near_corner, &()));
+
+    let cut_corner = Triangle::new(
+      Vec3::new(1.5, 0., 0.),
+      Vec3::new(0., 1.5, 0.),
+      Vec3::new(3., 3., 0.),
+    );
+    assert!(frustum.intersect(&cut_corner, &()));
+  }
+
+  #[test]
+  fn frustum_culling() {
+    let frustum = cube_frustum();
+
+    let obb = |center: Vec3<f32>| {
+      OrientedBox3::from_box3_and_matrix(
+        Box3::new_cube(Vec3::zero(), 0.5),
+        Mat4::translate(center) * Mat4::rotate_z(std::f32::consts::FRAC_PI_4),
+      )
+    };
+    assert!(frustum.intersect(&obb(Vec3::zero()), &()));
+    // the rotated box reaches x = 1.5 + 0.5 * sqrt(2)
+    assert!(frustum.intersect(&obb(Vec3::new(1.6, 0., 0.)), &()));
+    assert!(!frustum.intersect(&obb(Vec3::new(1.8, 0., 0.)), &()));
+
+    let capsule = |x: f32| Capsule::new(Vec3::new(x, -5., 0.), Vec3::new(x, 5., 0.), 0.5);
+    assert!(frustum.intersect(&capsule(1.4), &()));
+    assert!(!frustum.intersect(&capsule(1.6), &()));
+
+    let cylinder = |x: f32| Cylinder::new(Vec3::new(x, 0., -0.5), Vec3::new(x, 0., 0.5), 0.5);
+    assert!(frustum.intersect(&cylinder(1.4), &()));
+    assert!(!frustum.intersect(&cylinder(1.6), &()));
+  }
+}
@@ -246,3 +246,270 @@ impl IntersectAble<Plane, OptionalNearest<HitPoint3D>> for Ray3 {
       .into()
   }
 }
+
+intersect_reverse!(Box3, bool, (), Triangle);
+impl IntersectAble<Box3, bool> for Triangle {
+  /// Separating axis test, the box is treated as solid.
+  ///
+  /// https://fileadmin.cs.lth.se/cs/Personal/Tomas_Akenine-Moller/code/tribox_tam.pdf
+  #[inline]
+  fn intersect(&self, box3: &Box3, _: &()) -> bool {
+    if box3.is_empty() {
+      return false;
+    }
+    let center = box3.center();
+    let half = box3.half_size();
+    let points = [self.a - center, self.b - center, self.c - center];
+    let edges = [
+      points[1] - points[0],
+      points[2] - points[1],
+      points[0] - points[2],
+    ];
+
+    let is_separating = |axis: Vec3<f32>| {
+      let (min, max) = project_range(&points, axis);
+      let radius = half.x * axis.x.abs() + half.y * axis.y.abs() + half.z * axis.z.abs();
+      min > radius || max < -radius
+    };
+
+    // the box face normals, the 9 cross products of the edges, and the triangle normal
+    let box_axes = [
+      Vec3::new(1., 0., 0.),
+      Vec3::new(0., 1., 0.),
+      Vec3::new(0., 0., 1.),
+    ];
+    if box_axes.iter().any(|&axis| is_separating(axis)) {
+      return false;
+    }
+    for axis in box_axes {
+      if edges.iter().any(|&edge| is_separating(axis.cross(edge))) {
+        return false;
+      }
+    }
+    !is_separating(edges[0].cross(edges[1]))
+  }
+}
+
+impl IntersectAble<Triangle, bool> for Triangle {
+  /// Separating axis test, the coplanar triangles are also handled.
+  #[inline]
+  fn intersect(&self, other: &Triangle, _: &()) -> bool {
+    let a = [self.a, self.b, self.c];
+    let b = [other.a, other.b, other.c];
+    let a_edges = [a[1] - a[0], a[2] - a[1], a[0] - a[2]];
+    let b_edges = [b[1] - b[0], b[2] - b[1], b[0] - b[2]];
+    let a_normal = a_edges[0].cross(a_edges[1]);
+    let b_normal = b_edges[0].cross(b_edges[1]);
+
+    if is_separating_axis(&a, &b, a_normal) || is_separating_axis(&a, &b, b_normal) {
+      return false;
+    }
+    for a_edge in a_edges {
+      if b_edges
+        .iter()
+        .any(|&b_edge| is_separating_axis(&a, &b, a_edge.cross(b_edge)))
+      {
+        return false;
+      }
+    }
+
+    // the in plane edge normals, only useful when the triangles are coplanar
+    let mut in_plane_axes = a_edges
+      .iter()
+      .map(|e| a_normal.cross(*e))
+      .chain(b_edges.iter().map(|e| b_normal.cross(*e)));
+    !in_plane_axes.any(|axis| is_separating_axis(&a, &b, axis))
+  }
+}
+
+intersect_reverse!(Sphere, bool, (), Triangle);
+impl IntersectAble<Sphere, bool> for Triangle {
+  #[inline]
+  fn intersect(&self, sphere: &Sphere, _: &()) -> bool {
+    let closest = self.closest_point(sphere.center);
+    (closest - sphere.center).length2() <= sphere.radius * sphere.radius
+  }
+}
+
+impl IntersectAble<Box3, bool> for Box3 {
+  #[inline]
+  fn intersect(&self, other: &Box3, _: &()) -> bool {
+    self.min.x <= other.max.x
+      && self.min.y <= other.max.y
+      && self.min.z <= other.max.z
+      && self.max.x >= other.min.x
+      && self.max.y >= other.min.y
+      && self.max.z >= other.min.z
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::*;
+
+  fn unit_box() -> Box3 {
+    Box3::new3(Vec3::splat(-1.), Vec3::splat(1.))
+  }
+
+  #[test]
+  fn triangle_box_overlap() {
+    let box3 = unit_box();
+
+    let crossing = Triangle::new(
+      Vec3::new(-5., 0., 0.),
+      Vec3::new(5., 0.5, 0.),
+      Vec3::new(0., 0.2, 5.),
+    );
+    assert!(crossing.intersect(&box3, &()));
+    assert!(box3.intersect(&crossing, &()));
+
+    let inside = Triangle::new(
+      Vec3::new(-0.5, 0., 0.),
+      Vec3::new(0.5, 0., 0.),
+      Vec3::new(0., 0.5, 0.),
+    );
+    assert!(inside.intersect(&box3, &()));
+
+    let above = Triangle::new(
+      Vec3::new(-5., 2., -5.),
+      Vec3::new(5., 2., -5.),
+      Vec3::new(0., 2., 5.),
+    );
+    assert!(!above.intersect(&box3, &()));
+
+    let touching = Triangle::new(
+      Vec3::new(-5., 1., -5.),
+      Vec3::new(5., 1., -5.),
+      Vec3::new(0., 1., 5.),
+    );
+    assert!(touching.intersect(&box3, &()));
+
+    assert!(!inside.intersect(&Box3::empty(), &()));
+  }
+
+  #[test]
+  fn triangle_box_separated_by_edge_axis() {
+    // the box faces and the triangle face are not separating, only the cross product of the z
+    // axis and the ab edge, which is the (1, 1, 0) direction, could separate them.
+    let separated = Triangle::new(
+      Vec3::new(2.5, -0.4, 0.),
+      Vec3::new(-0.4, 2.5, 0.),
+      Vec3::new(3., 3., 5.),
+    );
+    assert!(!separated.intersect(&unit_box(), &()));
+
+    let overlapped = Triangle::new(
+      Vec3::new(2.3, -0.4, 0.),
+      Vec3::new(-0.4, 2.3, 0.),
+      Vec3::new(3., 3., 5.),
+    );
+    assert!(overlapped.intersect(&unit_box(), &()));
+  }
+
+  #[test]
+  fn triangle_triangle_overlap() {
+    let a = Triangle::new(
+      Vec3::new(0., 0., 0.),
+      Vec3::new(2., 0., 0.),
+      Vec3::new(0., 2., 0.),
+    );
+
+    let piercing = Triangle::new(
+      Vec3::new(0.5, 0.5, -1.),
+      Vec3::new(0.5, 0.5, 1.),
+      Vec3::new(-1., -1., 0.5),
+    );
+    assert!(a.intersect(&piercing, &()));
+    assert!(piercing.intersect(&a, &()));
+
+    let parallel = Triangle::new(
+      Vec3::new(0., 0., 1.),
+      Vec3::new(2., 0., 1.),
+      Vec3::new(0., 2., 1.),
+    );
+    assert!(!a.intersect(&parallel, &()));
+  }
+
+  #[test]
+  fn triangle_triangle_separated_by_edge_axis() {
+    // the face normals are (0, 0, 1) and (-1, 1, 0), neither separates. The cross product of the
+    // hypotenuse of a and the vertical edge of b is the (1, 1, 0) direction, which separates.
+    let a = Triangle::new(
+      Vec3::new(0., 0., 0.),
+      Vec3::new(2., 0., 0.),
+      Vec3::new(0., 2., 0.),
+    );
+    let b = Triangle::new(
+      Vec3::new(1.5, 1.5, -1.),
+      Vec3::new(1.5, 1.5, 1.),
+      Vec3::new(3., 3., 0.5),
+    );
+    assert!(!a.intersect(&b, &()));
+
+    let b = Triangle::new(
+      Vec3::new(0.9, 0.9, -1.),
+      Vec3::new(0.9, 0.9, 1.),
+      Vec3::new(3., 3., 0.5),
+    );
+    assert!(a.intersect(&b, &()));
+  }
+
+  #[test]
+  fn coplanar_triangle_overlap() {
+    let a = Triangle::new(
+      Vec3::new(0., 0., 0.),
+      Vec3::new(1., 0., 0.),
+      Vec3::new(0., 1., 0.),
+    );
+    let shifted = |offset: f32| {
+      Triangle::new(
+        Vec3::new(offset, offset, 0.),
+        Vec3::new(offset + 1., offset, 0.),
+        Vec3::new(offset, offset + 1., 0.),
+      )
+    };
+
+    assert!(a.intersect(&shifted(0.2), &()));
+    // touching at the point (0.5, 0.5)
+    assert!(a.intersect(&shifted(0.5), &()));
+    // only the in plane edge normal separates them
+    assert!(!a.intersect(&shifted(0.6), &()));
+
+    let contained = Triangle::new(
+      Vec3::new(0.1, 0.1, 0.),
+      Vec3::new(0.3, 0.1, 0.),
+      Vec3::new(0.1, 0.3, 0.),
+    );
+    assert!(a.intersect(&contained, &()));
+    assert!(contained.intersect(&a, &()));
+  }
+
+  #[test]
+  fn sphere_triangle_overlap() {
+    let triangle = Triangle::new(
+      Vec3::new(0., 0., 0.),
+      Vec3::new(2., 0., 0.),
+      Vec3::new(0., 2., 0.),
+    );
+    // face region
+    assert!(triangle.intersect(&Sphere::new(Vec3::new(0.5, 0.5, 0.9), 1.), &()));
+    assert!(!triangle.intersect(&Sphere::new(Vec3::new(0.5, 0.5, 1.1), 1.), &()));
+    // edge region, the closest point is (1, 1, 0)
+    assert!(Sphere::new(Vec3::new(1.5, 1.5, 0.), 0.8).intersect(&triangle, &()));
+    assert!(!Sphere::new(Vec3::new(1.5, 1.5, 0.), 0.7).intersect(&triangle, &()));
+    // vertex region
+    assert!(triangle.intersect(&Sphere::new(Vec3::new(-1., -1., 0.), 1.5), &()));
+    assert!(!triangle.intersect(&Sphere::new(Vec3::new(-1., -1., 0.), 1.4), &()));
+  }
+
+  #[test]
+  fn box_box_overlap() {
+    let a = unit_box();
+    assert!(a.intersect(&Box3::new_cube(Vec3::new(1.5, 0., 0.), 1.), &()));
+    assert!(a.intersect(&Box3::new_cube(Vec3::new(0., 0., 0.), 0.1), &()));
+    // touching face
+    assert!(a.intersect(&Box3::new_cube(Vec3::new(2., 0., 0.), 1.), &()));
+    assert!(!a.intersect(&Box3::new_cube(Vec3::new(2.1, 0., 0.), 1.), &()));
+    assert!(!a.intersect(&Box3::new_cube(Vec3::new(1.5, 1.5, 2.5), 1.), &()));
+  }
+}
@@ -1,8 +1,13 @@
 pub mod bounding_impl;
 pub mod box3;
+pub mod capsule;
+pub mod convex;
+pub mod cylinder;
+pub mod distance;
 pub mod frustum;
 pub mod intersection;
 pub mod line_segment;
+pub mod oriented_box3;
 pub mod plane;
 pub mod ray3;
 pub mod sphere;
@@ -10,9 +15,14 @@ pub mod spherical;
 pub mod triangle;
 
 pub use box3::*;
+pub use capsule::*;
+pub use convex::*;
+pub use cylinder::*;
+pub use distance::*;
 pub use frustum::*;
 pub use intersection::*;
 pub use line_segment::*;
+pub use oriented_box3::*;
 pub use plane::*;
 pub use ray3::*;
 pub use sphere::*;
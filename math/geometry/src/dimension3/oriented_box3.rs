@@ -0,0 +1,366 @@
+use crate::*;
+
+/// The oriented bounding box, the axes are orthonormal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrientedBox3<T: Scalar = f32> {
+  pub center: Vec3<T>,
+  pub axes: [Vec3<T>; 3],
+  pub half_size: Vec3<T>,
+}
+
+impl<T: Scalar> OrientedBox3<T> {
+  pub fn new(center: Vec3<T>, axes: [Vec3<T>; 3], half_size: Vec3<T>) -> Self {
+    Self {
+      center,
+      axes,
+      half_size,
+    }
+  }
+
+  /// The matrix should not contain shear, or the result is not a valid oriented box.
+  pub fn from_box3_and_matrix(box3: Box3<T>, mat: Mat4<T>) -> Self {
+    let mut result = Self::from(box3);
+    result.apply_matrix(mat);
+    result
+  }
+
+  /// Fit the box by principal component analysis, the axes are the eigenvectors of the
+  /// covariance matrix of the points.
+  pub fn from_points_pca<'a>(points: impl IntoIterator<Item = &'a Vec3<T>> + Clone) -> Self {
+    let mut count = T::zero();
+    let mut mean = Vec3::zero();
+    for &p in points.clone() {
+      mean += p;
+      count += T::one();
+    }
+    if count == T::zero() {
+      return Box3::empty().into();
+    }
+    mean /= count;
+
+    let mut covariance = [[T::zero(); 3]; 3];
+    for &p in points.clone() {
+      let d: [T; 3] = (p - mean).into();
+      for i in 0..3 {
+        for j in 0..3 {
+          covariance[i][j] += d[i] * d[j];
+        }
+      }
+    }
+
+    let [x, y, _] = symmetric_eigen_vectors(covariance);
+    // keep the basis orthonormal and right handed regardless of the numerical error
+    let x = x.normalize();
+    let z = x.cross(y).normalize();
+    let y = z.cross(x);
+    let axes = [x, y, z];
+
+    let mut local = Box3::empty();
+    for &p in points {
+      let d = p - mean;
+      local.expand_by_point(Vec3::new(d.dot(x), d.dot(y), d.dot(z)));
+    }
+    let local_center = local.center();
+    Self {
+      center: mean + x * local_center.x + y * local_center.y + z * local_center.z,
+      axes,
+      half_size: local.half_size(),
+    }
+  }
+
+  /// Transform the world space point to the box local space, the box center is the origin.
+  pub fn to_local(&self, point: Vec3<T>) -> Vec3<T> {
+    let d = point - self.center;
+    Vec3::new(
+      d.dot(self.axes[0]),
+      d.dot(self.axes[1]),
+      d.dot(self.axes[2]),
+    )
+  }
+
+  pub fn corners(&self) -> [Vec3<T>; 8] {
+    std::array::from_fn(|i| {
+      let sign = |bit: usize| if i & bit != 0 { T::one() } else { -T::one() };
+      self.center
+        + self.axes[0] * (self.half_size.x * sign(1))
+        + self.axes[1] * (self.half_size.y * sign(2))
+        + self.axes[2] * (self.half_size.z * sign(4))
+    })
+  }
+
+  /// The radius of the box projected on the direction.
+  pub fn projected_radius(&self, direction: Vec3<T>) -> T {
+    self.half_size.x * self.axes[0].dot(direction).abs()
+      + self.half_size.y * self.axes[1].dot(direction).abs()
+      + self.half_size.z * self.axes[2].dot(direction).abs()
+  }
+}
+
+impl<T: Scalar> From<Box3<T>> for OrientedBox3<T> {
+  fn from(box3: Box3<T>) -> Self {
+    Self {
+      center: box3.center(),
+      axes: [
+        Vec3::new(T::one(), T::zero(), T::zero()),
+        Vec3::new(T::zero(), T::one(), T::zero()),
+        Vec3::new(T::zero(), T::zero(), T::one()),
+      ],
+      half_size: box3.half_size(),
+    }
+  }
+}
+
+impl<T: Scalar> SpaceEntity<T, 3> for OrientedBox3<T> {
+  type Matrix = Mat4<T>;
+  fn apply_matrix(&mut self, mat: Self::Matrix) -> &mut Self {
+    let mat3 = mat.to_mat3();
+    self.center = mat * self.center;
+    let half_size: [T; 3] = self.half_size.into();
+    let mut new_half_size = [T::zero(); 3];
+    for i in 0..3 {
+      let axis = mat3 * self.axes[i];
+      let scale = axis.length();
+      new_half_size[i] = half_size[i] * scale;
+      if scale > T::zero() {
+        self.axes[i] = axis / scale;
+      }
+    }
+    self.half_size = new_half_size.into();
+    self
+  }
+}
+
+impl<T: Scalar> LebesgueMeasurable<T, 3> for OrientedBox3<T> {
+  fn measure(&self) -> T {
+    let size = self.half_size * T::two();
+    size.x * size.y * size.z
+  }
+}
+
+impl<T: Scalar> LebesgueMeasurable<T, 2> for OrientedBox3<T> {
+  fn measure(&self) -> T {
+    let size = self.half_size * T::two();
+    T::two() * (size.x * size.y + size.x * size.z + size.y * size.z)
+  }
+}
+
+impl<T: Scalar> SolidEntity<T, 3> for OrientedBox3<T> {
+  type Center = Vec3<T>;
+  fn centroid(&self) -> Vec3<T> {
+    self.center
+  }
+}
+
+impl<T: Scalar> ContainAble<T, Vec3<T>, 3> for OrientedBox3<T> {
+  fn contains(&self, point: &Vec3<T>) -> bool {
+    let local = self.to_local(*point);
+    local.x.abs() <= self.half_size.x
+      && local.y.abs() <= self.half_size.y
+      && local.z.abs() <= self.half_size.z
+  }
+}
+
+impl<T: Scalar> SpaceBounding<T, Box3<T>, 3> for OrientedBox3<T> {
+  fn to_bounding(&self) -> Box3<T> {
+    let extent = Vec3::new(
+      self.projected_radius(Vec3::new(T::one(), T::zero(), T::zero())),
+      self.projected_radius(Vec3::new(T::zero(), T::one(), T::zero())),
+      self.projected_radius(Vec3::new(T::zero(), T::zero(), T::one())),
+    );
+    Box3::new_from_center(self.center, extent)
+  }
+}
+
+intersect_reverse!(OrientedBox3, OptionalNearest<HitPoint3D>, (), Ray3);
+impl IntersectAble<OrientedBox3, OptionalNearest<HitPoint3D>> for Ray3 {
+  fn intersect(&self, obb: &OrientedBox3, p: &()) -> OptionalNearest<HitPoint3D> {
+    // the box space is a rigid transform of the world space, so the hit distance is kept
+    let origin = obb.to_local(self.origin);
+    let direction = Vec3::new(
+      self.direction.dot(obb.axes[0]),
+      self.direction.dot(obb.axes[1]),
+      self.direction.dot(obb.axes[2]),
+    );
+    let local_ray = Ray3::new(origin, direction.into_normalized());
+    let local_box = Box3::new_from_center(Vec3::zero(), obb.half_size);
+    IntersectAble::<Box3, OptionalNearest<HitPoint3D>>::intersect(&local_ray, &local_box, p)
+      .map(|hit| self.at_into(hit.distance))
+  }
+}
+
+intersect_reverse!(OrientedBox3, bool, (), Ray3);
+impl IntersectAble<OrientedBox3, bool> for Ray3 {
+  fn intersect(&self, other: &OrientedBox3, p: &()) -> bool {
+    IntersectAble::<OrientedBox3, OptionalNearest<HitPoint3D>>::intersect(self, other, p).is_some()
+  }
+}
+
+/// Jacobi eigenvalue algorithm for the real symmetric matrix, the eigenvectors are sorted by the
+/// eigenvalue in descending order.
+fn symmetric_eigen_vectors<T: Scalar>(mut m: [[T; 3]; 3]) -> [Vec3<T>; 3] {
+  let mut v = [[T::zero(); 3]; 3];
+  for (i, row) in v.iter_mut().enumerate() {
+    row[i] = T::one();
+  }
+
+  for _ in 0..32 {
+    // pick the largest off diagonal element
+    let (p, q) = [(0, 1), (0, 2), (1, 2)]
+      .into_iter()
+      .fold((0, 1), |max, (i, j)| {
+        if m[i][j].abs() > m[max.0][max.1].abs() {
+          (i, j)
+        } else {
+          max
+        }
+      });
+    if m[p][q].abs() <= T::epsilon() * (m[p][p].abs() + m[q][q].abs()) {
+      break;
+    }
+
+    let theta = (m[q][q] - m[p][p]) / (T::two() * m[p][q]);
+    let t = theta.signum() / (theta.abs() + (theta * theta + T::one()).sqrt());
+    let c = T::one() / (t * t + T::one()).sqrt();
+    let s = t * c;
+
+    for row in m.iter_mut() {
+      let (mkp, mkq) = (row[p], row[q]);
+      row[p] = c * mkp - s * mkq;
+      row[q] = s * mkp + c * mkq;
+    }
+    for k in 0..3 {
+      let (mpk, mqk) = (m[p][k], m[q][k]);
+      m[p][k] = c * mpk - s * mqk;
+      m[q][k] = s * mpk + c * mqk;
+    }
+    for row in v.iter_mut() {
+      let (vkp, vkq) = (row[p], row[q]);
+      row[p] = c * vkp - s * vkq;
+      row[q] = s * vkp + c * vkq;
+    }
+  }
+
+  let mut order = [0, 1, 2];
+  order.sort_by(|&a, &b| {
+    m[b][b]
+      .partial_cmp(&m[a][a])
+      .unwrap_or(std::cmp::Ordering::Equal)
+  });
+  order.map(|i| Vec3::new(v[0][i], v[1][i], v[2][i]))
+}
+
+#[cfg(test)]
+mod test {
+  use crate::*;
+
+  fn assert_near(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-4, "{a} != {b}");
+  }
+
+  fn assert_point(a: Vec3<f32>, b: Vec3<f32>) {
+    assert!((a - b).length() < 1e-4, "{a:?} != {b:?}");
+  }
+
+  #[test]
+  fn measure_and_centroid() {
+    let obb = OrientedBox3::from(Box3::new3(Vec3::new(0., 0., 0.), Vec3::new(2., 3., 4.)));
+    assert_eq!(LebesgueMeasurable::<f32, 3>::measure(&obb), 24.);
+    assert_eq!(
+      LebesgueMeasurable::<f32, 2>::measure(&obb),
+      2. * (6. + 8. + 12.)
+    );
+    assert_eq!(obb.centroid(), Vec3::new(1., 1.5, 2.));
+    assert!(obb.contains(&Vec3::new(1.9, 0.1, 3.9)));
+    assert!(!obb.contains(&Vec3::new(2.1, 0.1, 3.9)));
+  }
+
+  #[test]
+  fn from_box3_and_matrix() {
+    let box3 = Box3::new3(Vec3::new(-1., -2., -3.), Vec3::new(1., 2., 3.));
+    let mat = Mat4::translate((5., 0., -2.)) * Mat4::rotate(Vec3::new(1., 1., 0.).normalize(), 0.7);
+    let obb = OrientedBox3::from_box3_and_matrix(box3, mat);
+
+    assert_point(obb.center, mat * Vec3::zero());
+    assert_point(obb.half_size, Vec3::new(1., 2., 3.));
+    let expect: Vec<_> = OrientedBox3::from(box3)
+      .corners()
+      .iter()
+      .map(|&c| mat * c)
+      .collect();
+    for corner in obb.corners() {
+      assert!(expect.iter().any(|e| (*e - corner).length() < 1e-4));
+    }
+
+    // the scale is moved into the half size
+    let obb = OrientedBox3::from_box3_and_matrix(box3, Mat4::scale((2., 1., 0.5)));
+    assert_point(obb.half_size, Vec3::new(2., 2., 1.5));
+  }
+
+  #[test]
+  fn pca_fit_rotated_box() {
+    let half_size = Vec3::new(3., 1., 0.5);
+    let rotation = Mat4::rotate(Vec3::new(1., 2., 3.).normalize(), 0.9);
+    let translation = Vec3::new(10., -4., 2.);
+
+    let mut points = Vec::new();
+    for i in 0..=10 {
+      for j in 0..=10 {
+        for k in 0..=10 {
+          let local = Vec3::new(i as f32, j as f32, k as f32) / 5. - Vec3::one();
+          let local = Vec3::new(
+            local.x * half_size.x,
+            local.y * half_size.y,
+            local.z * half_size.z,
+          );
+          points.push(rotation * local + translation);
+        }
+      }
+    }
+
+    let obb = OrientedBox3::from_points_pca(points.iter());
+    assert_point(obb.center, translation);
+    assert_point(obb.half_size, half_size);
+    let expect_axes = [
+      rotation * Vec3::new(1., 0., 0.),
+      rotation * Vec3::new(0., 1., 0.),
+      rotation * Vec3::new(0., 0., 1.),
+    ];
+    for (axis, expect) in obb.axes.iter().zip(expect_axes) {
+      assert_near(axis.dot(expect).abs(), 1.);
+    }
+    // every point is inside the fitted box, up to the numerical error
+    let tolerance = 1e-4;
+    assert!(points.iter().all(|p| {
+      let local = obb.to_local(*p).map(|v| v.abs());
+      local.x <= obb.half_size.x + tolerance
+        && local.y <= obb.half_size.y + tolerance
+        && local.z <= obb.half_size.z + tolerance
+    }));
+  }
+
+  #[test]
+  fn ray_obb() {
+    let obb = OrientedBox3::from_box3_and_matrix(
+      Box3::new3(Vec3::splat(-1.), Vec3::splat(1.)),
+      Mat4::rotate_z(std::f32::consts::FRAC_PI_4),
+    );
+    // the corner of the rotated box is at (sqrt(2), 0, 0)
+    let ray = Ray3::new(
+      Vec3::new(5., 0., 0.),
+      Vec3::new(-1., 0., 0.).into_normalized(),
+    );
+    let hit =
+      IntersectAble::<OrientedBox3, OptionalNearest<HitPoint3D>>::intersect(&ray, &obb, &());
+    assert_near(hit.0.unwrap().distance, 5. - 2_f32.sqrt());
+
+    let miss = Ray3::new(
+      Vec3::new(5., 1.5, 0.),
+      Vec3::new(-1., 0., 0.).into_normalized(),
+    );
+    assert!(!IntersectAble::<OrientedBox3, bool>::intersect(
+      &miss,
+      &obb,
+      &()
+    ));
+  }
+}
@@ -44,3 +44,147 @@ impl<T: Scalar> Triangle3D<T> {
     Vec3::new(u, v, w).into()
   }
 }
+
+impl<T: Scalar> Triangle3D<T> {
+  /// Return the point on the triangle that closest to the given point.
+  pub fn closest_point(&self, p: Vec3<T>) -> Vec3<T> {
+    let w = self.closest_point_barycentric(p);
+    self.a * w.x + self.b * w.y + self.c * w.z
+  }
+
+  /// Return the barycentric coordinate of the point on the triangle that closest to the given
+  /// point. Unlike [Triangle3D::barycentric], this also works for the degenerated triangle, in
+  /// that case the closest point is searched on the edges.
+  ///
+  /// Real-Time Collision Detection 5.1.5
+  pub fn closest_point_barycentric(&self, p: Vec3<T>) -> Vec3<T> {
+    let (zero, one) = (T::zero(), T::one());
+    let Triangle { a, b, c } = *self;
+    let ab = b - a;
+    let ac = c - a;
+
+    // the degenerated triangle has no face region, and the region tests below divide by zero
+    if ab.cross(ac).length2() == zero {
+      return self.closest_point_on_edges_barycentric(p);
+    }
+
+    // vertex region outside a
+    let ap = p - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= zero && d2 <= zero {
+      return Vec3::new(one, zero, zero);
+    }
+
+    // vertex region outside b
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= zero && d4 <= d3 {
+      return Vec3::new(zero, one, zero);
+    }
+
+    // edge region of ab
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= zero && d1 >= zero && d3 <= zero {
+      let v = d1 / (d1 - d3);
+      return Vec3::new(one - v, v, zero);
+    }
+
+    // vertex region outside c
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= zero && d5 <= d6 {
+      return Vec3::new(zero, zero, one);
+    }
+
+    // edge region of ac
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= zero && d2 >= zero && d6 <= zero {
+      let w = d2 / (d2 - d6);
+      return Vec3::new(one - w, zero, w);
+    }
+
+    // edge region of bc
+    let va = d3 * d6 - d5 * d4;
+    if va <= zero && (d4 - d3) >= zero && (d5 - d6) >= zero {
+      let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+      return Vec3::new(zero, one - w, w);
+    }
+
+    // inside face region
+    let denom = one / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    Vec3::new(one - v - w, v, w)
+  }
+
+  fn closest_point_on_edges_barycentric(&self, p: Vec3<T>) -> Vec3<T> {
+    let (zero, one) = (T::zero(), T::one());
+    let edge = |from: Vec3<T>, to: Vec3<T>| {
+      let (point, t) = LineSegment::line_segment(from, to).closest_point(p);
+      ((point - p).length2(), t)
+    };
+    let (ab_distance, ab) = edge(self.a, self.b);
+    let (bc_distance, bc) = edge(self.b, self.c);
+    let (ca_distance, ca) = edge(self.c, self.a);
+
+    if ab_distance <= bc_distance && ab_distance <= ca_distance {
+      Vec3::new(one - ab, ab, zero)
+    } else if bc_distance <= ca_distance {
+      Vec3::new(zero, one - bc, bc)
+    } else {
+      Vec3::new(ca, zero, one - ca)
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::*;
+
+  fn assert_point(a: Vec3<f32>, b: Vec3<f32>) {
+    assert!((a - b).length() < 1e-5, "{a:?} != {b:?}");
+  }
+
+  #[test]
+  fn closest_point_on_degenerated_triangle() {
+    let p = Vec3::new(1., 2., 3.);
+    let point = Triangle::new(p, p, p);
+    assert_eq!(
+      point.closest_point_barycentric(Vec3::zero()),
+      Vec3::new(1., 0., 0.)
+    );
+    assert_point(point.closest_point(Vec3::zero()), p);
+
+    // collinear, with the vertex c in the middle
+    let line = Triangle::new(
+      Vec3::new(0., 0., 0.),
+      Vec3::new(2., 0., 0.),
+      Vec3::new(1., 0., 0.),
+    );
+    let weights = line.closest_point_barycentric(Vec3::new(1.5, 1., 0.));
+    assert!(weights.x.is_finite() && weights.y.is_finite() && weights.z.is_finite());
+    assert_point(
+      line.closest_point(Vec3::new(1.5, 1., 0.)),
+      Vec3::new(1.5, 0., 0.),
+    );
+    assert_point(line.closest_point(Vec3::new(-1., 0., 0.)), Vec3::zero());
+    assert_point(
+      line.closest_point(Vec3::new(3., -1., 0.)),
+      Vec3::new(2., 0., 0.),
+    );
+
+    // two vertices are the same
+    let segment = Triangle::new(
+      Vec3::new(0., 0., 0.),
+      Vec3::new(0., 0., 0.),
+      Vec3::new(0., 0., 4.),
+    );
+    assert_point(
+      segment.closest_point(Vec3::new(1., 0., 1.)),
+      Vec3::new(0., 0., 1.),
+    );
+  }
+}
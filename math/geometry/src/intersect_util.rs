@@ -116,3 +116,21 @@ impl<T: Scalar, V> HitList<T, V> {
     }
   }
 }
+
+/// Project the points onto the axis, return the (min, max) of the projection.
+pub(crate) fn project_range(points: &[Vec3<f32>], axis: Vec3<f32>) -> (f32, f32) {
+  points
+    .iter()
+    .map(|p| p.dot(axis))
+    .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), d| {
+      (min.min(d), max.max(d))
+    })
+}
+
+/// Separating axis test of two convex point sets, return true if the projections of two sets on
+/// the axis are not overlapped. The degenerated axis never separates.
+pub(crate) fn is_separating_axis(a: &[Vec3<f32>], b: &[Vec3<f32>], axis: Vec3<f32>) -> bool {
+  let (a_min, a_max) = project_range(a, axis);
+  let (b_min, b_max) = project_range(b, axis);
+  a_min > b_max || b_min > a_max
+}
@@ -61,6 +61,35 @@ pub trait DistanceSquareTo<Target, T: Scalar, Parameter = ()> {
   }
 }
 
+/// The closest points between two entities, and the squared distance between them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClosestPointPair<T, V> {
+  /// the witness point on the queried entity
+  pub self_point: V,
+  /// the witness point on the target entity
+  pub target_point: V,
+  pub distance_sq: T,
+}
+
+impl<T: Scalar, V> ClosestPointPair<T, V> {
+  pub fn distance(&self) -> T {
+    self.distance_sq.sqrt()
+  }
+
+  #[must_use]
+  pub fn swap(self) -> Self {
+    Self {
+      self_point: self.target_point,
+      target_point: self.self_point,
+      distance_sq: self.distance_sq,
+    }
+  }
+}
+
+pub trait ClosestPointTo<Target, T: Scalar, V> {
+  fn closest_point_to(&self, other: &Target) -> ClosestPointPair<T, V>;
+}
+
 /// https://en.wikipedia.org/wiki/Lebesgue_measure
 pub trait LebesgueMeasurable<T: Scalar, const D: usize> {
   fn measure(&self) -> T;
@@ -136,7 +136,7 @@ impl BindGroupBuilder<CacheAbleBindingBuildSource> {
   {
     self.bind_raw(
       item.get_binding_build_source(),
-      map_shader_value_ty_to_binding_layout_type(T::binding_desc(), self.items.len()),
+      map_shader_value_ty_to_binding_layout_type(item.instance_binding_desc(), self.items.len()),
     )
   }
   fn hash_binding_ids(&self, hasher: &mut impl Hasher) {
@@ -179,6 +179,24 @@ impl BindingBuilder {
     pass: &mut GPURenderPass,
     device: &GPUDevice,
     pipeline: &GPURenderPipeline,
+  ) {
+    self.setup_bindgroups(pass, device, &pipeline.bg_layouts)
+  }
+
+  pub fn setup_compute_pass(
+    &mut self,
+    pass: &mut GPUComputePass,
+    device: &GPUDevice,
+    pipeline: &GPUComputePipeline,
+  ) {
+    self.setup_bindgroups(pass, device, &pipeline.bg_layouts)
+  }
+
+  fn setup_bindgroups(
+    &mut self,
+    pass: &mut impl BindGroupSetter,
+    device: &GPUDevice,
+    layouts: &[GPUBindGroupLayout],
   ) {
     let mut is_visiting_empty_tail = true;
     for (group_index, group) in self.groups.iter_mut().enumerate().rev() {
@@ -191,7 +209,7 @@ impl BindingBuilder {
       }
       is_visiting_empty_tail = false;
 
-      let layout = &pipeline.bg_layouts[group_index];
+      let layout = &layouts[group_index];
 
       // hash
       let mut hasher = FastHasher::default();
@@ -215,7 +233,31 @@ impl BindingBuilder {
         Arc::new(bindgroup)
       });
 
-      pass.set_bind_group_owned(group_index as u32, bindgroup, &[]);
+      pass.set_bind_group_owned(group_index as u32, bindgroup);
     }
   }
 }
+
+/// The render pass and compute pass share the same bindgroup setup logic
+trait BindGroupSetter {
+  fn set_bind_group_placeholder(&mut self, index: u32);
+  fn set_bind_group_owned(&mut self, index: u32, bind_group: &Arc<gpu::BindGroup>);
+}
+
+impl<'a> BindGroupSetter for GPURenderPass<'a> {
+  fn set_bind_group_placeholder(&mut self, index: u32) {
+    GPURenderPass::set_bind_group_placeholder(self, index)
+  }
+  fn set_bind_group_owned(&mut self, index: u32, bind_group: &Arc<gpu::BindGroup>) {
+    GPURenderPass::set_bind_group_owned(self, index, bind_group, &[])
+  }
+}
+
+impl<'a> BindGroupSetter for GPUComputePass<'a> {
+  fn set_bind_group_placeholder(&mut self, index: u32) {
+    GPUComputePass::set_bind_group_placeholder(self, index)
+  }
+  fn set_bind_group_owned(&mut self, index: u32, bind_group: &Arc<gpu::BindGroup>) {
+    GPUComputePass::set_bind_group_owned(self, index, bind_group, &[])
+  }
+}
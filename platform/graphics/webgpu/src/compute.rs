@@ -0,0 +1,148 @@
+use rendiation_shader_api::*;
+use rendiation_shader_backend_naga::ShaderAPINagaImpl;
+
+use crate::*;
+
+pub struct GPUComputePass<'a> {
+  pub(crate) pass: gpu::ComputePass<'a>,
+  pub(crate) holder: &'a GPUComputePassDataHolder,
+  pub(crate) placeholder_bg: Arc<gpu::BindGroup>,
+}
+
+impl<'a> Deref for GPUComputePass<'a> {
+  type Target = gpu::ComputePass<'a>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.pass
+  }
+}
+
+impl<'a> DerefMut for GPUComputePass<'a> {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.pass
+  }
+}
+
+#[derive(Default)]
+pub struct GPUComputePassDataHolder {
+  buffers: Arena<Arc<gpu::Buffer>>,
+  bindgroups: Arena<Arc<gpu::BindGroup>>,
+  pipelines: Arena<GPUComputePipeline>,
+}
+
+impl<'a> GPUComputePass<'a> {
+  pub fn set_pipeline_owned(&mut self, pipeline: &GPUComputePipeline) {
+    let pipeline = self.holder.pipelines.alloc(pipeline.clone());
+    self.pass.set_pipeline(&pipeline.inner.as_ref().pipeline)
+  }
+
+  pub fn set_bind_group_placeholder(&mut self, index: u32) {
+    self.set_bind_group_owned(index, &self.placeholder_bg.clone(), &[]);
+  }
+
+  pub fn set_bind_group_owned(
+    &mut self,
+    index: u32,
+    bind_group: &Arc<gpu::BindGroup>,
+    offsets: &[gpu::DynamicOffset],
+  ) {
+    let bind_group = self.holder.bindgroups.alloc(bind_group.clone());
+    self.set_bind_group(index, bind_group, offsets)
+  }
+
+  /// The dispatch args is read from the view's start, laid out as [util::DispatchIndirect]. The
+  /// buffer should be created with the INDIRECT usage.
+  pub fn dispatch_workgroups_indirect_owned(&mut self, view: &GPUBufferResourceView) {
+    let buffer = self
+      .holder
+      .buffers
+      .alloc(view.resource.resource.gpu.clone());
+    self
+      .pass
+      .dispatch_workgroups_indirect(buffer, view.desc.offset)
+  }
+}
+
+pub trait ShaderComputePassBuilder {
+  fn setup_pass(&self, _ctx: &mut GPUComputePassCtx) {}
+}
+
+impl ShaderComputePassBuilder for () {}
+
+/// Stored extra binding states info for up level usage
+pub struct GPUComputePassCtx<'encoder, 'gpu> {
+  pub pass: GPUComputePass<'encoder>,
+  pub gpu: &'gpu GPU,
+  pub binding: BindingBuilder,
+}
+
+impl<'encoder, 'gpu> GPUComputePassCtx<'encoder, 'gpu> {
+  pub fn new(pass: GPUComputePass<'encoder>, gpu: &'gpu GPU) -> Self {
+    Self {
+      pass,
+      gpu,
+      binding: Default::default(),
+    }
+  }
+}
+
+/// The pipeline is cached by the type and the [ShaderHashProvider] impl of the component, and the
+/// bindgroups are cached by the bound resources, so dispatch the component repeatedly is cheap.
+pub trait ComputeComponent:
+  ShaderHashProviderAny + ComputeShaderProvider + ShaderComputePassBuilder
+{
+  fn prepare_dispatch(&self, ctx: &mut GPUComputePassCtx) -> GPUComputePipeline {
+    let mut hasher = PipelineHasher::default();
+    self.hash_pipeline_and_with_type_id(&mut hasher);
+
+    let pipeline = ctx
+      .gpu
+      .device
+      .get_or_cache_create_compute_pipeline(hasher, |device| {
+        device
+          .build_compute_pipeline_by_shader_api(
+            self
+              .build_self(Box::new(ShaderAPINagaImpl::new(
+                rendiation_shader_api::ShaderStages::Compute,
+              )))
+              .unwrap(),
+          )
+          .unwrap()
+      });
+
+    ctx.binding.reset();
+    self.setup_pass(ctx);
+
+    ctx.pass.set_pipeline_owned(&pipeline);
+
+    ctx
+      .binding
+      .setup_compute_pass(&mut ctx.pass, &ctx.gpu.device, &pipeline);
+
+    pipeline
+  }
+
+  fn dispatch(&self, ctx: &mut GPUComputePassCtx, workgroup_count: (u32, u32, u32)) {
+    self.prepare_dispatch(ctx);
+    let (x, y, z) = workgroup_count;
+    ctx.pass.dispatch_workgroups(x, y, z);
+  }
+
+  /// Dispatch enough workgroups to cover the given invocation count by the pipeline's workgroup
+  /// size, the shader should skip the exceeded invocations itself.
+  fn dispatch_invocations(&self, ctx: &mut GPUComputePassCtx, invocation_count: (u32, u32, u32)) {
+    let pipeline = self.prepare_dispatch(ctx);
+    let (x, y, z) = pipeline.compute_workgroup_count(invocation_count);
+    ctx.pass.dispatch_workgroups(x, y, z);
+  }
+
+  fn dispatch_indirect(&self, ctx: &mut GPUComputePassCtx, indirect: &GPUBufferResourceView) {
+    self.prepare_dispatch(ctx);
+    ctx.pass.dispatch_workgroups_indirect_owned(indirect);
+  }
+}
+
+impl<T> ComputeComponent for T where
+  T: ShaderHashProviderAny + ComputeShaderProvider + ShaderComputePassBuilder
+{
+}
@@ -27,6 +27,7 @@ impl GPUDevice {
       bindgroup_cache: BindGroupCache::new(),
       bindgroup_layout_cache: Default::default(),
       pipeline_cache: Default::default(),
+      compute_pipeline_cache: Default::default(),
       placeholder_bg: Arc::new(placeholder_bg),
     };
 
@@ -41,6 +42,7 @@ impl GPUDevice {
       bindgroup_layout_count: self.inner.bindgroup_layout_cache.cache.read().unwrap().len(),
       sampler_count:self.inner.sampler_cache.cache.read().unwrap().len(),
       pipeline_count: self.inner.pipeline_cache.cache.read().unwrap().len(),
+      compute_pipeline_count: self.inner.compute_pipeline_cache.cache.read().unwrap().len(),
     }
   }
 
@@ -49,6 +51,7 @@ impl GPUDevice {
     self.inner.bindgroup_layout_cache.clear();
     self.inner.sampler_cache.clear();
     self.inner.pipeline_cache.clear();
+    self.inner.compute_pipeline_cache.clear();
   }
 
   pub fn create_encoder(&self) -> GPUCommandEncoder {
@@ -71,6 +74,17 @@ impl GPUDevice {
       .get_or_insert_with(hasher, || creator(self))
   }
 
+  pub fn get_or_cache_create_compute_pipeline(
+    &self,
+    hasher: PipelineHasher,
+    creator: impl FnOnce(&Self) -> GPUComputePipeline,
+  ) -> GPUComputePipeline {
+    self
+      .inner
+      .compute_pipeline_cache
+      .get_or_insert_with(hasher, || creator(self))
+  }
+
   pub fn create_and_cache_bindgroup_layout(
     &self,
     layouts: &[gpu::BindGroupLayoutEntry],
@@ -110,6 +124,7 @@ pub struct GPUResourceCacheSizeReport {
   pub bindgroup_layout_count: usize,
   pub sampler_count: usize,
   pub pipeline_count: usize,
+  pub compute_pipeline_count: usize,
 }
 
 pub(crate) struct GPUDeviceInner {
@@ -118,6 +133,7 @@ pub(crate) struct GPUDeviceInner {
   bindgroup_cache: BindGroupCache,
   bindgroup_layout_cache: BindGroupLayoutCache,
   pipeline_cache: RenderPipelineCache,
+  compute_pipeline_cache: ComputePipelineCache,
   pub(crate) placeholder_bg: Arc<gpu::BindGroup>,
 }
 
@@ -159,11 +175,21 @@ impl SamplerCache {
   }
 }
 
-#[derive(Default)]
-pub struct RenderPipelineCache {
-  pub cache: RwLock<FastHashMap<u64, GPURenderPipeline>>,
+pub struct PipelineCache<T> {
+  pub cache: RwLock<FastHashMap<u64, T>>,
 }
 
+impl<T> Default for PipelineCache<T> {
+  fn default() -> Self {
+    Self {
+      cache: Default::default(),
+    }
+  }
+}
+
+pub type RenderPipelineCache = PipelineCache<GPURenderPipeline>;
+pub type ComputePipelineCache = PipelineCache<GPUComputePipeline>;
+
 pub trait ShaderHashProvider {
   fn hash_pipeline(&self, _hasher: &mut PipelineHasher) {}
 }
@@ -202,12 +228,8 @@ impl std::hash::Hasher for PipelineHasher {
   }
 }
 
-impl RenderPipelineCache {
-  pub fn get_or_insert_with(
-    &self,
-    hasher: PipelineHasher,
-    creator: impl FnOnce() -> GPURenderPipeline,
-  ) -> GPURenderPipeline {
+impl<T: Clone> PipelineCache<T> {
+  pub fn get_or_insert_with(&self, hasher: PipelineHasher, creator: impl FnOnce() -> T) -> T {
     let key = hasher.finish();
     self
       .cache
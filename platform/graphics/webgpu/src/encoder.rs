@@ -5,6 +5,7 @@ use crate::*;
 pub struct GPUCommandEncoder {
   pub(crate) encoder: gpu::CommandEncoder,
   holder: GPURenderPassDataHolder,
+  compute_holder: GPUComputePassDataHolder,
   active_pass_target_holder: Option<RenderPassDescriptorOwned>,
   placeholder_bg: Arc<gpu::BindGroup>,
   pub(crate) on_submit: EventSource<()>,
@@ -29,6 +30,7 @@ impl GPUCommandEncoder {
     Self {
       encoder,
       holder: Default::default(),
+      compute_holder: Default::default(),
       placeholder_bg: device.inner.placeholder_bg.clone(),
       active_pass_target_holder: Default::default(),
       on_submit: Default::default(),
@@ -111,6 +113,17 @@ impl GPUCommandEncoder {
     }
   }
 
+  pub fn begin_compute_pass(&mut self) -> GPUComputePass {
+    let pass = self
+      .encoder
+      .begin_compute_pass(&gpu::ComputePassDescriptor { label: None });
+    GPUComputePass {
+      pass,
+      holder: &self.compute_holder,
+      placeholder_bg: self.placeholder_bg.clone(),
+    }
+  }
+
   pub fn copy_source_to_texture_2d(
     &mut self,
     device: &GPUDevice,
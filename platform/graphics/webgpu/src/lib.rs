@@ -1,10 +1,12 @@
 #![feature(specialization)]
 #![feature(hash_raw_entry)]
 #![feature(type_alias_impl_trait)]
+#![feature(int_roundings)]
 #![allow(incomplete_features)]
 #![allow(clippy::field_reassign_with_default)]
 
 mod binding;
+mod compute;
 mod device;
 mod encoder;
 mod frame;
@@ -31,6 +33,7 @@ use __core::fmt::Debug;
 use __core::num::NonZeroUsize;
 pub use binding::*;
 use bytemuck::*;
+pub use compute::*;
 pub use device::*;
 use dyn_downcast::*;
 pub use encoder::*;
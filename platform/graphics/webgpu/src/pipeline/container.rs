@@ -7,18 +7,14 @@ impl<T: ShaderSizedValueNodeType + Std140> ShaderBindingProvider for UniformBuff
   type Node = T;
 }
 
-impl<T: ShaderUnsizedValueNodeType + Std430> ShaderBindingProvider
-  for StorageBufferReadOnlyDataView<T>
-{
+impl<T: ShaderNodeType + ?Sized> ShaderBindingProvider for StorageBufferReadOnlyDataView<T> {
   const SPACE: AddressSpace = AddressSpace::Storage { writeable: false };
   type Node = T;
+}
 
-  fn binding_desc() -> ShaderBindingDescriptor {
-    ShaderBindingDescriptor {
-      should_as_storage_buffer_if_is_buffer_like: true,
-      ty: Self::Node::TYPE,
-    }
-  }
+impl<T: ShaderNodeType + ?Sized> ShaderBindingProvider for StorageBufferDataView<T> {
+  const SPACE: AddressSpace = AddressSpace::Storage { writeable: true };
+  type Node = T;
 }
 
 macro_rules! map_shader_ty {
@@ -46,3 +42,8 @@ map_shader_ty!(GPUCubeArrayDepthTextureView, ShaderDepthTextureCubeArray);
 
 map_shader_ty!(GPUSamplerView, ShaderSampler);
 map_shader_ty!(GPUComparisonSamplerView, ShaderCompareSampler);
+
+impl<V, N: ShaderNodeType> ShaderBindingProvider for StorageTextureView<V, N> {
+  const SPACE: AddressSpace = AddressSpace::Handle;
+  type Node = N;
+}
@@ -39,17 +39,77 @@ impl Deref for GPURenderPipeline {
   }
 }
 
+#[derive(Clone)]
+pub struct GPUComputePipeline {
+  pub inner: Arc<GPUComputePipelineInner>,
+}
+
+impl GPUComputePipeline {
+  fn new(
+    pipeline: gpu::ComputePipeline,
+    bg_layouts: Vec<GPUBindGroupLayout>,
+    workgroup_size: (u32, u32, u32),
+  ) -> Self {
+    let inner = GPUComputePipelineInner {
+      pipeline,
+      bg_layouts,
+      workgroup_size,
+    };
+    Self {
+      inner: Arc::new(inner),
+    }
+  }
+
+  pub fn get_layout(&self, index: usize) -> &GPUBindGroupLayout {
+    self.bg_layouts.get(index).unwrap()
+  }
+
+  /// The workgroup count required to cover the given invocation count in each dimension.
+  pub fn compute_workgroup_count(&self, invocation_count: (u32, u32, u32)) -> (u32, u32, u32) {
+    compute_workgroup_count(invocation_count, self.workgroup_size)
+  }
+}
+
+fn compute_workgroup_count(
+  invocation_count: (u32, u32, u32),
+  workgroup_size: (u32, u32, u32),
+) -> (u32, u32, u32) {
+  let (x, y, z) = workgroup_size;
+  (
+    invocation_count.0.div_ceil(x),
+    invocation_count.1.div_ceil(y),
+    invocation_count.2.div_ceil(z),
+  )
+}
+
+pub struct GPUComputePipelineInner {
+  pub pipeline: gpu::ComputePipeline,
+  pub bg_layouts: Vec<GPUBindGroupLayout>,
+  pub workgroup_size: (u32, u32, u32),
+}
+
+impl Deref for GPUComputePipeline {
+  type Target = GPUComputePipelineInner;
+
+  fn deref(&self) -> &Self::Target {
+    &self.inner
+  }
+}
+
 pub fn map_shader_value_ty_to_binding_layout_type(
   v: ShaderBindingDescriptor,
   id: usize,
 ) -> gpu::BindGroupLayoutEntry {
   use ShaderValueSingleType::*;
+  let storage_buffer = gpu::BufferBindingType::Storage {
+    read_only: !v.writeable_if_storage,
+  };
   let ty = v
     .ty
     .visit_single(|ty| match *ty {
       Sized(_) => gpu::BindingType::Buffer {
         ty: if v.should_as_storage_buffer_if_is_buffer_like {
-          gpu::BufferBindingType::Storage { read_only: true }
+          storage_buffer
         } else {
           gpu::BufferBindingType::Uniform
         },
@@ -58,7 +118,7 @@ pub fn map_shader_value_ty_to_binding_layout_type(
         min_binding_size: None,
       },
       Unsized(_) => gpu::BindingType::Buffer {
-        ty: gpu::BufferBindingType::Storage { read_only: true },
+        ty: storage_buffer,
         has_dynamic_offset: false,
         // min_binding_size: gpu::BufferSize::new(std::mem::size_of::<T>() as u64), // todo
         min_binding_size: None,
@@ -72,17 +132,45 @@ pub fn map_shader_value_ty_to_binding_layout_type(
         sample_type,
         view_dimension: dimension,
       },
+      StorageTexture {
+        dimension,
+        format,
+        access,
+      } => gpu::BindingType::StorageTexture {
+        access,
+        format,
+        view_dimension: dimension,
+      },
     })
     .unwrap();
 
   let count = match v.ty {
-    ShaderValueType::BindingArray { count, .. } => Some(NonZeroU32::new(count as u32).unwrap()),
+    ShaderValueType::BindingArray { count, .. } => Some(
+      NonZeroU32::new(count as u32).expect("the binding array length should be decided by now"),
+    ),
     _ => None,
   };
 
+  // the writeable storage resource is not allowed in vertex stage
+  let writeable = match ty {
+    gpu::BindingType::Buffer {
+      ty: gpu::BufferBindingType::Storage { read_only },
+      ..
+    } => !read_only,
+    gpu::BindingType::StorageTexture { access, .. } => {
+      !matches!(access, gpu::StorageTextureAccess::ReadOnly)
+    }
+    _ => false,
+  };
+  let visibility = if writeable {
+    gpu::ShaderStages::FRAGMENT | gpu::ShaderStages::COMPUTE
+  } else {
+    gpu::ShaderStages::VERTEX_FRAGMENT | gpu::ShaderStages::COMPUTE
+  };
+
   gpu::BindGroupLayoutEntry {
     binding: id as u32,
-    visibility: gpu::ShaderStages::VERTEX_FRAGMENT,
+    visibility,
     ty,
     count,
   }
@@ -101,6 +189,25 @@ pub fn create_bindgroup_layout_by_node_ty<'a>(
 }
 
 impl GPUDevice {
+  fn create_bindgroup_layouts_by_shader_bindings(
+    &self,
+    bindings: &ShaderBindGroupBuilder,
+  ) -> Vec<GPUBindGroupLayout> {
+    let binding = &bindings.bindings;
+    let last_empty_count = binding
+      .iter()
+      .rev()
+      .take_while(|l| l.bindings.is_empty())
+      .count();
+
+    binding
+      .get(0..binding.len() - last_empty_count)
+      .unwrap()
+      .iter()
+      .map(|b| create_bindgroup_layout_by_node_ty(self, b.bindings.iter().map(|e| &e.desc)))
+      .collect()
+  }
+
   pub fn build_pipeline_by_shader_api(
     &self,
     builder: ShaderRenderPipelineBuilder,
@@ -119,16 +226,6 @@ impl GPUDevice {
       multisample,
     } = compile_result;
 
-    fn convert_module_by_wgsl(module: &naga::Module, v: naga::valid::ValidationFlags) -> String {
-      use naga::back::wgsl;
-
-      let info = naga::valid::Validator::new(v, naga::valid::Capabilities::all())
-        .validate(module)
-        .unwrap();
-
-      wgsl::write_string(module, &info, wgsl::WriterFlags::empty()).unwrap()
-    }
-
     let naga_vertex = *vertex_shader.downcast::<naga::Module>().unwrap();
     let naga_fragment = *frag_shader.downcast::<naga::Module>().unwrap();
 
@@ -165,20 +262,7 @@ impl GPUDevice {
     //   source: gpu::ShaderSource::Wgsl(Cow::Owned(convert_module_by_wgsl(&naga_fragment))),
     // });
 
-    let binding = &bindings.bindings;
-    let last_empty_count = binding
-      .iter()
-      .rev()
-      .take_while(|l| l.bindings.is_empty())
-      .count();
-
-    let layouts: Vec<_> = binding
-      .get(0..binding.len() - last_empty_count)
-      .unwrap()
-      .iter()
-      .map(|b| create_bindgroup_layout_by_node_ty(self, b.bindings.iter().map(|e| &e.desc)))
-      .collect();
-
+    let layouts = self.create_bindgroup_layouts_by_shader_bindings(&bindings);
     let layouts_ref: Vec<_> = layouts.iter().map(|l| l.inner.as_ref()).collect();
 
     let pipeline_layout = self.create_pipeline_layout(&gpu::PipelineLayoutDescriptor {
@@ -214,6 +298,64 @@ impl GPUDevice {
 
     Ok(GPURenderPipeline::new(pipeline, layouts))
   }
+
+  pub fn build_compute_pipeline_by_shader_api(
+    &self,
+    builder: ShaderComputePipelineBuilder,
+  ) -> Result<GPUComputePipeline, ShaderBuildError> {
+    let log_result = builder.log_result;
+    let ComputeShaderCompileResult {
+      shader: (entry, shader),
+      bindings,
+      workgroup_size,
+    } = builder.build()?;
+
+    let naga_compute = *shader.downcast::<naga::Module>().unwrap();
+
+    if log_result {
+      println!();
+      println!("=== rendiation_shader_api build result ===");
+
+      println!("compute shader: ");
+      let compute = convert_module_by_wgsl(&naga_compute, naga::valid::ValidationFlags::empty());
+      println!("{compute}");
+
+      println!("=== result output finished ===");
+    }
+
+    let module = self.create_shader_module(gpu::ShaderModuleDescriptor {
+      label: None,
+      source: gpu::ShaderSource::Naga(Cow::Owned(naga_compute)),
+    });
+
+    let layouts = self.create_bindgroup_layouts_by_shader_bindings(&bindings);
+    let layouts_ref: Vec<_> = layouts.iter().map(|l| l.inner.as_ref()).collect();
+
+    let pipeline_layout = self.create_pipeline_layout(&gpu::PipelineLayoutDescriptor {
+      label: None,
+      bind_group_layouts: layouts_ref.as_slice(),
+      push_constant_ranges: &[],
+    });
+
+    let pipeline = self.create_compute_pipeline(&gpu::ComputePipelineDescriptor {
+      label: None,
+      layout: Some(&pipeline_layout),
+      module: &module,
+      entry_point: &entry,
+    });
+
+    Ok(GPUComputePipeline::new(pipeline, layouts, workgroup_size))
+  }
+}
+
+fn convert_module_by_wgsl(module: &naga::Module, v: naga::valid::ValidationFlags) -> String {
+  use naga::back::wgsl;
+
+  let info = naga::valid::Validator::new(v, naga::valid::Capabilities::all())
+    .validate(module)
+    .unwrap();
+
+  wgsl::write_string(module, &info, wgsl::WriterFlags::empty()).unwrap()
 }
 
 pub fn convert_vertex_layout(layout: &ShaderVertexBufferLayout) -> gpu::VertexBufferLayout {
@@ -223,3 +365,24 @@ pub fn convert_vertex_layout(layout: &ShaderVertexBufferLayout) -> gpu::VertexBu
     attributes: layout.attributes.as_slice(),
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn workgroup_count() {
+    let size = (64, 8, 1);
+    // the exact multiples
+    assert_eq!(compute_workgroup_count((128, 16, 3), size), (2, 2, 3));
+    // the remainders are covered by one more workgroup
+    assert_eq!(compute_workgroup_count((129, 17, 3), size), (3, 3, 3));
+    assert_eq!(compute_workgroup_count((0, 0, 0), size), (0, 0, 0));
+    assert_eq!(compute_workgroup_count((1, 1, 1), size), (1, 1, 1));
+    // the count near the max does not overflow
+    assert_eq!(
+      compute_workgroup_count((u32::MAX, u32::MAX, u32::MAX), size),
+      (u32::MAX / 64 + 1, u32::MAX / 8 + 1, u32::MAX)
+    );
+  }
+}
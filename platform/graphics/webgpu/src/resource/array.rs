@@ -1,7 +1,11 @@
-use rendiation_shader_api::{BindingArray, ShaderBindingProvider, ShaderNodeSingleType};
+use rendiation_shader_api::{
+  BindingArray, ShaderBindingDescriptor, ShaderBindingProvider, ShaderNodeSingleType,
+  ShaderValueType,
+};
 
 use crate::*;
 
+/// If the N is 0, the length is decided at runtime by the bindings.
 pub struct BindingResourceArray<T, const N: usize> {
   bindings: Arc<Vec<T>>,
   resource_id: usize,
@@ -20,6 +24,24 @@ impl<T, const N: usize> BindingResourceArray<T, N> {
       resource_id: get_new_resource_guid(),
     }
   }
+
+  pub fn len(&self) -> usize {
+    self.bindings.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.bindings.is_empty()
+  }
+
+  /// The binding array could not be partially updated in wgpu, so the array is still rebound
+  /// after the update, but the unchanged items are kept without rebuilding the whole array.
+  pub fn update(&mut self, updater: impl FnOnce(&mut Vec<T>))
+  where
+    T: Clone,
+  {
+    updater(Arc::make_mut(&mut self.bindings));
+    self.resource_id = get_new_resource_guid();
+  }
 }
 
 impl<const N: usize> CacheAbleBindingSource for BindingResourceArray<GPUTextureView, N> {
@@ -54,8 +76,18 @@ impl<const N: usize> CacheAbleBindingSource for BindingResourceArray<GPUSamplerV
 impl<T, const N: usize> ShaderBindingProvider for BindingResourceArray<T, N>
 where
   T: ShaderBindingProvider,
-  T::Node: ShaderNodeSingleType,
+  T::Node: ShaderNodeSingleType + Sized,
 {
   const SPACE: rendiation_shader_api::AddressSpace = T::SPACE;
   type Node = BindingArray<T::Node, N>;
+
+  fn instance_binding_desc(&self) -> ShaderBindingDescriptor {
+    let mut desc = Self::binding_desc();
+    if let ShaderValueType::BindingArray { count, .. } = &mut desc.ty {
+      if *count == 0 {
+        *count = self.bindings.len();
+      }
+    }
+    desc
+  }
 }
@@ -3,32 +3,42 @@ use rendiation_shader_api::Std430;
 
 use crate::*;
 
-#[derive(Clone)]
-pub struct StorageBufferReadOnlyDataView<T: Std430 + ?Sized> {
+pub struct StorageBufferReadOnlyDataView<T: ?Sized> {
   gpu: GPUBufferResourceView,
   phantom: PhantomData<T>,
 }
 
-impl<T: Std430 + ?Sized> BindableResourceProvider for StorageBufferReadOnlyDataView<T> {
+impl<T: ?Sized> Clone for StorageBufferReadOnlyDataView<T> {
+  fn clone(&self) -> Self {
+    Self {
+      gpu: self.gpu.clone(),
+      phantom: PhantomData,
+    }
+  }
+}
+
+impl<T: ?Sized> BindableResourceProvider for StorageBufferReadOnlyDataView<T> {
   fn get_bindable(&self) -> BindingResourceOwned {
     self.gpu.get_bindable()
   }
 }
-impl<T: Std430 + ?Sized> CacheAbleBindingSource for StorageBufferReadOnlyDataView<T> {
+impl<T: ?Sized> CacheAbleBindingSource for StorageBufferReadOnlyDataView<T> {
   fn get_binding_build_source(&self) -> CacheAbleBindingBuildSource {
     self.gpu.get_binding_build_source()
   }
 }
-impl<T: Std430 + ?Sized> BindableResourceView for StorageBufferReadOnlyDataView<T> {
+impl<T: ?Sized> BindableResourceView for StorageBufferReadOnlyDataView<T> {
   fn as_bindable(&self) -> gpu::BindingResource {
     self.gpu.as_bindable()
   }
 }
 
-impl<T: Std430 + ?Sized> StorageBufferReadOnlyDataView<T> {
-  pub fn create(device: &GPUDevice, data: T) -> Self {
+impl<T: ?Sized> StorageBufferReadOnlyDataView<T> {
+  /// The bytes should be laid out in the std430 layout of T, this is useful for the unsized
+  /// struct which can not be created in rust directly.
+  pub fn create_by_bytes(device: &GPUDevice, bytes: &[u8]) -> Self {
     let usage = gpu::BufferUsages::STORAGE | gpu::BufferUsages::COPY_DST;
-    let gpu = GPUBuffer::create(device, bytemuck::cast_slice(&[data]), usage);
+    let gpu = GPUBuffer::create(device, bytes, usage);
     let gpu = GPUBufferResource::create_with_raw(gpu, usage).create_default_view();
 
     Self {
@@ -36,12 +46,101 @@ impl<T: Std430 + ?Sized> StorageBufferReadOnlyDataView<T> {
       phantom: PhantomData,
     }
   }
+
+  pub fn gpu(&self) -> &GPUBufferResourceView {
+    &self.gpu
+  }
+}
+
+impl<T: Std430> StorageBufferReadOnlyDataView<T> {
+  pub fn create(device: &GPUDevice, data: T) -> Self {
+    Self::create_by_bytes(device, bytemuck::cast_slice(&[data]))
+  }
+}
+
+impl<T: Std430> StorageBufferReadOnlyDataView<[T]> {
+  /// The runtime sized array, the length is decided by the slice length
+  pub fn create_by_slice(device: &GPUDevice, data: &[T]) -> Self {
+    Self::create_by_bytes(device, bytemuck::cast_slice(data))
+  }
 }
 
 /// just short convenient method
-pub fn create_storage<T: Std430 + ?Sized>(
+pub fn create_storage<T: Std430>(
   data: T,
   device: impl AsRef<GPUDevice>,
 ) -> StorageBufferReadOnlyDataView<T> {
   StorageBufferReadOnlyDataView::create(device.as_ref(), data)
 }
+
+/// The storage buffer could be written in the shader, it's not visible in the vertex stage.
+pub struct StorageBufferDataView<T: ?Sized> {
+  gpu: GPUBufferResourceView,
+  phantom: PhantomData<T>,
+}
+
+impl<T: ?Sized> Clone for StorageBufferDataView<T> {
+  fn clone(&self) -> Self {
+    Self {
+      gpu: self.gpu.clone(),
+      phantom: PhantomData,
+    }
+  }
+}
+
+impl<T: ?Sized> BindableResourceProvider for StorageBufferDataView<T> {
+  fn get_bindable(&self) -> BindingResourceOwned {
+    self.gpu.get_bindable()
+  }
+}
+impl<T: ?Sized> CacheAbleBindingSource for StorageBufferDataView<T> {
+  fn get_binding_build_source(&self) -> CacheAbleBindingBuildSource {
+    self.gpu.get_binding_build_source()
+  }
+}
+impl<T: ?Sized> BindableResourceView for StorageBufferDataView<T> {
+  fn as_bindable(&self) -> gpu::BindingResource {
+    self.gpu.as_bindable()
+  }
+}
+
+impl<T: ?Sized> StorageBufferDataView<T> {
+  /// The bytes should be laid out in the std430 layout of T, the buffer could also be copied out
+  /// for reading back the shader results
+  pub fn create_by_bytes(device: &GPUDevice, bytes: &[u8]) -> Self {
+    let usage =
+      gpu::BufferUsages::STORAGE | gpu::BufferUsages::COPY_DST | gpu::BufferUsages::COPY_SRC;
+    let gpu = GPUBuffer::create(device, bytes, usage);
+    let gpu = GPUBufferResource::create_with_raw(gpu, usage).create_default_view();
+
+    Self {
+      gpu,
+      phantom: PhantomData,
+    }
+  }
+
+  pub fn gpu(&self) -> &GPUBufferResourceView {
+    &self.gpu
+  }
+}
+
+impl<T: Std430> StorageBufferDataView<T> {
+  pub fn create(device: &GPUDevice, data: T) -> Self {
+    Self::create_by_bytes(device, bytemuck::cast_slice(&[data]))
+  }
+}
+
+impl<T: Std430> StorageBufferDataView<[T]> {
+  /// The runtime sized array, the length is decided by the slice length
+  pub fn create_by_slice(device: &GPUDevice, data: &[T]) -> Self {
+    Self::create_by_bytes(device, bytemuck::cast_slice(data))
+  }
+}
+
+/// just short convenient method
+pub fn create_storage_rw<T: Std430>(
+  data: T,
+  device: impl AsRef<GPUDevice>,
+) -> StorageBufferDataView<T> {
+  StorageBufferDataView::create(device.as_ref(), data)
+}
@@ -2,6 +2,8 @@ pub mod d2;
 pub use d2::*;
 pub mod cube;
 pub use cube::*;
+pub mod storage;
+pub use storage::*;
 
 use crate::*;
 
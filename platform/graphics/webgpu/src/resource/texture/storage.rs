@@ -0,0 +1,54 @@
+use __core::marker::PhantomData;
+use rendiation_shader_api::*;
+
+use crate::*;
+
+/// The texture view bound as the storage texture of the shader type N. The texture should be
+/// created with the STORAGE_BINDING usage.
+pub struct StorageTextureView<V, N> {
+  pub view: V,
+  phantom: PhantomData<N>,
+}
+
+impl<V: Clone, N> Clone for StorageTextureView<V, N> {
+  fn clone(&self) -> Self {
+    Self {
+      view: self.view.clone(),
+      phantom: PhantomData,
+    }
+  }
+}
+
+impl<V, N> StorageTextureView<V, N>
+where
+  V: Deref<Target = GPUTextureView>,
+  N: ShaderNodeSingleType,
+{
+  pub fn new(view: V) -> Self {
+    if let ShaderValueSingleType::StorageTexture { format, .. } = N::SINGLE_TYPE {
+      assert_eq!(
+        format, view.resource.desc.format,
+        "storage texture format mismatch"
+      );
+    }
+    Self {
+      view,
+      phantom: PhantomData,
+    }
+  }
+}
+
+impl<V: CacheAbleBindingSource, N> CacheAbleBindingSource for StorageTextureView<V, N> {
+  fn get_binding_build_source(&self) -> CacheAbleBindingBuildSource {
+    self.view.get_binding_build_source()
+  }
+}
+
+pub type StorageTextureView1D<F, A = StorageWriteOnly> =
+  StorageTextureView<GPU1DTextureView, ShaderStorageTexture1D<F, A>>;
+pub type StorageTextureView2D<F, A = StorageWriteOnly> =
+  StorageTextureView<GPU2DTextureView, ShaderStorageTexture2D<F, A>>;
+pub type StorageTextureView2DArray<F, A = StorageWriteOnly> =
+  StorageTextureView<GPU2DArrayTextureView, ShaderStorageTexture2DArray<F, A>>;
+pub type StorageTextureView3D<F, A = StorageWriteOnly> =
+  StorageTextureView<GPU3DTextureView, ShaderStorageTexture3D<F, A>>;
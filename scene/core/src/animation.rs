@@ -17,6 +17,8 @@ impl SceneAnimation {
 /// An animation channel combines an animation sampler with a target property being animated.
 pub struct SceneAnimationChannel {
   pub target_node: SceneNode,
+  /// the morph weights driven by the morph target weights channel, the other channels ignore it
+  pub target_morph_weights: Option<MorphWeights>,
   pub sampler: AnimationSampler,
 }
 
@@ -41,7 +43,10 @@ impl SceneAnimationChannel {
         let local_mat = Mat4::compose(t, quat, s);
         self.target_node.set_local_matrix(local_mat);
       }
-      InterpolationItem::MorphTargetWeights(_) => todo!(),
+      InterpolationItem::MorphTargetWeights(weights) => {
+        let target = self.target_morph_weights.as_ref()?;
+        target.mutate(|mut m| m.modify(MorphWeightsImpl { weights }));
+      }
     };
     Some(())
   }
@@ -61,7 +66,7 @@ impl KeyframeTrack for AnimationSampler {
   type Value = InterpolationItem;
   fn sample_animation(&mut self, time: f32) -> Option<Self::Value> {
     let (mut spline, (start_time, end_time)) = InterpolateInstance::try_from_sampler(self, time)?;
-    let normalized_time = (end_time - time) / (end_time - start_time);
+    let normalized_time = (time - start_time) / (end_time - start_time);
     spline.sample_animation(normalized_time)
   }
 }
@@ -87,7 +92,7 @@ where
     loop {
       // do we have get_or_insert_with_option?
       if let Some((spline, (start_time, end_time))) = &mut self.spline {
-        let normalized_time = (*end_time - time) / (*end_time - *start_time);
+        let normalized_time = (time - *start_time) / (*end_time - *start_time);
         if 0. < normalized_time && normalized_time <= 1.0 {
           break spline.sample_animation(normalized_time);
         } else {
@@ -108,12 +113,13 @@ pub enum SceneAnimationField {
   MorphTargetWeights,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub enum InterpolationItem {
   Position(Vec3<f32>),
   Scale(Vec3<f32>),
   Quaternion(Quat<f32>),
-  MorphTargetWeights(f32),
+  /// the weights of all morph targets in one keyframe
+  MorphTargetWeights(Vec<f32>),
 }
 
 impl InterpolateAble for InterpolationItem {
@@ -123,19 +129,24 @@ impl InterpolateAble for InterpolationItem {
       (Position(a), Position(b)) => Position(a.lerp(b, t)),
       (Scale(a), Scale(b)) => Scale(a.lerp(b, t)),
       (Quaternion(a), Quaternion(b)) => Quaternion(a.slerp(b, t)),
-      (MorphTargetWeights(a), MorphTargetWeights(b)) => MorphTargetWeights(a.lerp(b, t)),
+      (MorphTargetWeights(a), MorphTargetWeights(b)) => {
+        if a.len() != b.len() {
+          return None;
+        }
+        MorphTargetWeights(a.iter().zip(b).map(|(a, b)| a.lerp(b, t)).collect())
+      }
       _ => return None,
     }
     .into()
   }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 enum InterpolationCubicItem {
   Position(CubicVertex<Vec3<f32>>),
   Scale(CubicVertex<Vec3<f32>>),
   Quaternion(CubicVertex<Quat<f32>>),
-  MorphTargetWeights(CubicVertex<f32>),
+  MorphTargetWeights(CubicVertex<Vec<f32>>),
 }
 
 impl InterpolationCubicItem {
@@ -197,19 +208,19 @@ impl KeyframeTrack for InterpolateInstance<InterpolationItem> {
 
   fn sample_animation(&mut self, t: f32) -> Option<Self::Value> {
     match self {
-      InterpolateInstance::Step { start, end } => if t == 1. { *end } else { *start }.into(),
-      InterpolateInstance::Linear { start, end } => start.interpolate(*end, t),
+      InterpolateInstance::Step { start, end } => if t == 1. { end } else { start }.clone().into(),
+      InterpolateInstance::Linear { start, end } => start.clone().interpolate(end.clone(), t),
       InterpolateInstance::Cubic {
         start,
         ctrl1,
         ctrl2,
         end,
       } => {
-        let t1 = start.interpolate(*ctrl1, t)?;
-        let t2 = ctrl1.interpolate(*ctrl2, t)?;
-        let t3 = ctrl2.interpolate(*end, t)?;
+        let t1 = start.clone().interpolate(ctrl1.clone(), t)?;
+        let t2 = ctrl1.clone().interpolate(ctrl2.clone(), t)?;
+        let t3 = ctrl2.clone().interpolate(end.clone(), t)?;
 
-        let t4 = t1.interpolate(t2, t)?;
+        let t4 = t1.interpolate(t2.clone(), t)?;
         let t5 = t2.interpolate(t3, t)?;
 
         t4.interpolate(t5, t)
@@ -239,15 +250,34 @@ impl TryFromAnimationSampler for InterpolateInstance<InterpolationItem> {
     let (end_time, end_index) = (sampler_input.get::<f32>(end_index)?, end_index);
     let field_ty = sampler.field;
 
+    // the morph target weights output has the weights of all targets in each keyframe
+    let weights_count =
+      |vertex_per_keyframe: usize| (sampler.output.count / (len * vertex_per_keyframe)).max(1);
+
+    fn get_weights(
+      output: &AttributeAccessorReadView,
+      start: usize,
+      count: usize,
+    ) -> Option<Vec<f32>> {
+      (start..start + count)
+        .map(|i| output.get::<f32>(i))
+        .collect()
+    }
+
     fn get_output_single(
       output: &AttributeAccessor,
       index: usize,
       field_ty: SceneAnimationField,
+      weights_count: usize,
     ) -> Option<InterpolationItem> {
       use SceneAnimationField::*;
       let output = output.read();
       match field_ty {
-        MorphTargetWeights => InterpolationItem::MorphTargetWeights(output.get::<f32>(index)?),
+        MorphTargetWeights => InterpolationItem::MorphTargetWeights(get_weights(
+          &output,
+          index * weights_count,
+          weights_count,
+        )?),
         Position => InterpolationItem::Position(output.get::<Vec3<f32>>(index)?),
         Rotation => InterpolationItem::Quaternion(output.get::<Quat<f32>>(index)?),
         Scale => InterpolationItem::Scale(output.get::<Vec3<f32>>(index)?),
@@ -259,12 +289,20 @@ impl TryFromAnimationSampler for InterpolateInstance<InterpolationItem> {
       output: &AttributeAccessor,
       index: usize,
       field_ty: SceneAnimationField,
+      weights_count: usize,
     ) -> Option<InterpolationCubicItem> {
       use InterpolationCubicItem::*;
       use SceneAnimationField as SF;
       let output = output.read();
       match field_ty {
-        SF::MorphTargetWeights => MorphTargetWeights(output.get::<CubicVertex<f32>>(index)?),
+        SF::MorphTargetWeights => {
+          let start = index * 3 * weights_count;
+          MorphTargetWeights(CubicVertex {
+            enter: get_weights(&output, start, weights_count)?,
+            center: get_weights(&output, start + weights_count, weights_count)?,
+            exit: get_weights(&output, start + 2 * weights_count, weights_count)?,
+          })
+        }
         SF::Position => Position(output.get::<CubicVertex<Vec3<f32>>>(index)?),
         SF::Rotation => Quaternion(output.get::<CubicVertex<Quat<f32>>>(index)?),
         SF::Scale => Scale(output.get::<CubicVertex<Vec3<f32>>>(index)?),
@@ -273,17 +311,26 @@ impl TryFromAnimationSampler for InterpolateInstance<InterpolationItem> {
     }
 
     let curve = match sampler.interpolation {
-      InterpolationStyle::Linear => InterpolateInstance::Linear {
-        start: get_output_single(&sampler.output, start_index, field_ty)?,
-        end: get_output_single(&sampler.output, end_index, field_ty)?,
-      },
-      InterpolationStyle::Step => InterpolateInstance::Step {
-        start: get_output_single(&sampler.output, start_index, field_ty)?,
-        end: get_output_single(&sampler.output, end_index, field_ty)?,
-      },
+      InterpolationStyle::Linear => {
+        let count = weights_count(1);
+        InterpolateInstance::Linear {
+          start: get_output_single(&sampler.output, start_index, field_ty, count)?,
+          end: get_output_single(&sampler.output, end_index, field_ty, count)?,
+        }
+      }
+      InterpolationStyle::Step => {
+        let count = weights_count(1);
+        InterpolateInstance::Step {
+          start: get_output_single(&sampler.output, start_index, field_ty, count)?,
+          end: get_output_single(&sampler.output, end_index, field_ty, count)?,
+        }
+      }
       InterpolationStyle::Cubic => {
-        let cubic_vertex_a = get_output_cubic(&sampler.output, start_index, field_ty)?.transpose();
-        let cubic_vertex_b = get_output_cubic(&sampler.output, end_index, field_ty)?.transpose();
+        let count = weights_count(3);
+        let cubic_vertex_a =
+          get_output_cubic(&sampler.output, start_index, field_ty, count)?.transpose();
+        let cubic_vertex_b =
+          get_output_cubic(&sampler.output, end_index, field_ty, count)?.transpose();
         InterpolateInstance::Cubic {
           start: cubic_vertex_a.center,
           ctrl1: cubic_vertex_a.exit,
@@ -296,3 +343,83 @@ impl TryFromAnimationSampler for InterpolateInstance<InterpolationItem> {
     (curve, (start_time, end_time)).into()
   }
 }
+
+#[test]
+fn sample_morph_target_weights() {
+  let mut sampler = AnimationSampler {
+    interpolation: InterpolationStyle::Linear,
+    field: SceneAnimationField::MorphTargetWeights,
+    input: AttributeAccessor::create_owned(vec![0_f32, 1., 2.], 4),
+    output: AttributeAccessor::create_owned(vec![0_f32, 1., 1., 0., 1., 1.], 4),
+  };
+
+  let weights = |item| match item {
+    Some(InterpolationItem::MorphTargetWeights(weights)) => weights,
+    _ => panic!("expect morph target weights"),
+  };
+
+  assert_eq!(weights(sampler.sample_animation(0.25)), vec![0.25, 0.75]);
+  assert_eq!(weights(sampler.sample_animation(1.5)), vec![1., 0.5]);
+  assert!(sampler.sample_animation(3.).is_none());
+
+  sampler.interpolation = InterpolationStyle::Step;
+  assert_eq!(weights(sampler.sample_animation(0.5)), vec![0., 1.]);
+}
+
+#[test]
+fn sample_towards_later_keyframe() {
+  // the normalized time in the keyframe interval goes from the earlier key to the later key
+  let mut translation = AnimationSamplerExecutor::<InterpolateInstance<InterpolationItem>> {
+    spline: None,
+    sampler: AnimationSampler {
+      interpolation: InterpolationStyle::Linear,
+      field: SceneAnimationField::Position,
+      input: AttributeAccessor::create_owned(vec![0_f32, 1., 2.], 4),
+      output: AttributeAccessor::create_owned(
+        vec![
+          Vec3::new(0_f32, 0., 0.),
+          Vec3::new(10., 0., 0.),
+          Vec3::new(10., 10., 0.),
+        ],
+        4 * 3,
+      ),
+    },
+  };
+  let position = |item| match item {
+    Some(InterpolationItem::Position(position)) => position,
+    _ => panic!("expect position"),
+  };
+  assert_eq!(
+    position(translation.sample_animation(0.5)),
+    Vec3::new(5., 0., 0.)
+  );
+  let samples = [1.25, 1.5, 1.75].map(|t| position(translation.sample_animation(t)));
+  assert_eq!(samples[1], Vec3::new(10., 5., 0.));
+  let to_later = samples.map(|p| (p - Vec3::new(10., 10., 0.)).length());
+  assert!(to_later[0] > to_later[1] && to_later[1] > to_later[2]);
+
+  let later = Quat::rotation_z(std::f32::consts::FRAC_PI_2);
+  let mut rotation = AnimationSampler {
+    interpolation: InterpolationStyle::Linear,
+    field: SceneAnimationField::Rotation,
+    input: AttributeAccessor::create_owned(vec![0_f32, 1.], 4),
+    output: AttributeAccessor::create_owned(vec![Quat::rotation_z(0_f32), later], 4 * 4),
+  };
+  let quat = |item| match item {
+    Some(InterpolationItem::Quaternion(quat)) => quat,
+    _ => panic!("expect rotation"),
+  };
+  let half = quat(rotation.sample_animation(0.5));
+  let expect = Quat::rotation_z(std::f32::consts::FRAC_PI_4);
+  assert!((half - expect).length() < 1e-5);
+  let closeness = [0.25, 0.5, 0.75].map(|t| quat(rotation.sample_animation(t)).dot(later));
+  assert!(closeness[0] < closeness[1] && closeness[1] < closeness[2]);
+
+  // the step interpolation keeps the earlier key until the next key
+  translation.sampler.interpolation = InterpolationStyle::Step;
+  translation.spline = None;
+  assert_eq!(
+    position(translation.sample_animation(0.9)),
+    Vec3::new(0., 0., 0.)
+  );
+}
@@ -21,6 +21,8 @@ mod mesh_picking;
 pub use mesh_picking::*;
 mod mesh_merge;
 pub use mesh_merge::*;
+mod mesh_csg;
+pub use mesh_csg::*;
 
 mod material;
 pub use material::*;
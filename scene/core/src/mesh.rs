@@ -257,6 +257,20 @@ pub struct AttributesMesh {
   pub indices: Option<(AttributeIndexFormat, AttributeAccessor)>,
   pub mode: PrimitiveTopology,
   pub groups: MeshGroupsInfo,
+  pub morph_targets: Vec<MorphTarget>,
+}
+
+/// The attribute displacements that added to the mesh attributes, scaled by the weight of the
+/// target in [MorphWeightsImpl]. The semantics could be positions or normals.
+#[derive(Clone)]
+pub struct MorphTarget {
+  pub attributes: Vec<(AttributeSemantic, AttributeAccessor)>,
+}
+
+impl MorphTarget {
+  pub fn get_attribute(&self, s: AttributeSemantic) -> Option<&AttributeAccessor> {
+    self.attributes.iter().find(|(k, _)| *k == s).map(|r| &r.1)
+  }
 }
 
 pub struct AttributeMeshReadView<'a> {
@@ -0,0 +1,335 @@
+use rendiation_renderable_mesh::{
+  vertex::Vertex, IndexedMesh, MeshGroupsInfo, PrimitiveTopology, TriangleList,
+};
+use space_algorithm::bsp::csg::{CSGMesh, CSGVertex};
+
+use crate::*;
+
+#[derive(Debug)]
+pub enum CSGConvertError {
+  UnsupportedTopology,
+  AttributeDataAccessFailed,
+}
+
+impl AttributesMesh {
+  /// Only the triangle list is supported. The missing normals are filled by the face normal, the
+  /// missing uvs (TexCoords(0)) are filled by zero. The face that has no area is skipped.
+  pub fn build_csg_mesh(&self) -> Result<CSGMesh, CSGConvertError> {
+    if self.mode != PrimitiveTopology::TriangleList {
+      return Err(CSGConvertError::UnsupportedTopology);
+    }
+
+    let mesh = self.read();
+    let positions = mesh
+      .get_position()
+      .visit_slice::<Vec3<f32>>()
+      .ok_or(CSGConvertError::AttributeDataAccessFailed)?;
+    let normals = mesh
+      .get_attribute(AttributeSemantic::Normals)
+      .map(|n| n.visit_slice::<Vec3<f32>>())
+      .map(|n| n.ok_or(CSGConvertError::AttributeDataAccessFailed))
+      .transpose()?;
+    let uvs = mesh
+      .get_attribute(AttributeSemantic::TexCoords(0))
+      .map(|uv| uv.visit_slice::<Vec2<f32>>())
+      .map(|uv| uv.ok_or(CSGConvertError::AttributeDataAccessFailed))
+      .transpose()?;
+
+    let indices: Vec<usize> = match &mesh.indices {
+      Some((AttributeIndexFormat::Uint16, index)) => index
+        .visit_slice::<u16>()
+        .ok_or(CSGConvertError::AttributeDataAccessFailed)?
+        .iter()
+        .map(|&i| i as usize)
+        .collect(),
+      Some((AttributeIndexFormat::Uint32, index)) => index
+        .visit_slice::<u32>()
+        .ok_or(CSGConvertError::AttributeDataAccessFailed)?
+        .iter()
+        .map(|&i| i as usize)
+        .collect(),
+      None => (0..positions.len()).collect(),
+    };
+
+    let mut triangles = Vec::with_capacity(indices.len() / 3);
+    for face in indices.chunks_exact(3) {
+      let position = |index: usize| {
+        positions
+          .get(index)
+          .copied()
+          .ok_or(CSGConvertError::AttributeDataAccessFailed)
+      };
+      let (a, b, c) = (position(face[0])?, position(face[1])?, position(face[2])?);
+
+      // the face that has no area could not be classified by the bsp plane, skip it
+      let face_normal = (b - a).cross(c - a);
+      if face_normal.length2() <= f32::EPSILON * f32::EPSILON {
+        continue;
+      }
+      let face_normal = face_normal.normalize();
+
+      let vertex = |index: usize, position| {
+        let normal = normals
+          .and_then(|n| n.get(index).copied())
+          .unwrap_or(face_normal);
+        let uv = uvs
+          .and_then(|uv| uv.get(index).copied())
+          .unwrap_or_else(|| Vec2::new(0., 0.));
+        CSGVertex::new(position, normal, uv)
+      };
+      triangles.push([vertex(face[0], a), vertex(face[1], b), vertex(face[2], c)]);
+    }
+
+    Ok(CSGMesh::from_triangles(triangles))
+  }
+}
+
+impl From<&CSGMesh> for AttributesMesh {
+  /// Create the indexed triangle list with positions, normals and TexCoords(0).
+  fn from(mesh: &CSGMesh) -> Self {
+    let mesh: IndexedMesh<TriangleList, Vec<Vertex>, Vec<u32>> = mesh.into();
+    let positions: Vec<_> = mesh.vertex.iter().map(|v| v.position).collect();
+    let normals: Vec<_> = mesh.vertex.iter().map(|v| v.normal).collect();
+    let uvs: Vec<_> = mesh.vertex.iter().map(|v| v.uv).collect();
+
+    let attributes = vec![
+      (
+        AttributeSemantic::Positions,
+        AttributeAccessor::create_owned(positions, 4 * 3),
+      ),
+      (
+        AttributeSemantic::Normals,
+        AttributeAccessor::create_owned(normals, 4 * 3),
+      ),
+      (
+        AttributeSemantic::TexCoords(0),
+        AttributeAccessor::create_owned(uvs, 4 * 2),
+      ),
+    ];
+
+    AttributesMesh {
+      attributes,
+      indices: Some((
+        AttributeIndexFormat::Uint32,
+        AttributeAccessor::create_owned(mesh.index, 4),
+      )),
+      mode: PrimitiveTopology::TriangleList,
+      groups: MeshGroupsInfo::new(),
+      morph_targets: Default::default(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  // axis, plane value selector(min or max), the two tangent axes in counter clockwise order
+  const BOX_FACES: [(usize, bool, usize, usize); 6] = [
+    (0, true, 1, 2),
+    (0, false, 2, 1),
+    (1, true, 2, 0),
+    (1, false, 0, 2),
+    (2, true, 0, 1),
+    (2, false, 1, 0),
+  ];
+
+  /// the uv is the planar mapping of the face in the box
+  fn box_uv(min: Vec3<f32>, max: Vec3<f32>, u: usize, v: usize, position: Vec3<f32>) -> Vec2<f32> {
+    Vec2::new(
+      (position[u] - min[u]) / (max[u] - min[u]),
+      (position[v] - min[v]) / (max[v] - min[v]),
+    )
+  }
+
+  fn box_mesh(
+    min: Vec3<f32>,
+    max: Vec3<f32>,
+    format: AttributeIndexFormat,
+    with_normal: bool,
+  ) -> AttributesMesh {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+    for (axis, is_max, u, v) in BOX_FACES {
+      let start = positions.len();
+      indices.extend([0, 1, 2, 0, 2, 3].map(|i| start + i));
+      for (s, t) in [(0., 0.), (1., 0.), (1., 1.), (0., 1.)] {
+        let mut position = Vec3::zero();
+        position[axis] = if is_max { max[axis] } else { min[axis] };
+        position[u] = min[u] + (max[u] - min[u]) * s;
+        position[v] = min[v] + (max[v] - min[v]) * t;
+        let mut normal = Vec3::<f32>::zero();
+        normal[axis] = if is_max { 1. } else { -1. };
+        positions.push(position);
+        normals.push(normal);
+        uvs.push(box_uv(min, max, u, v, position));
+      }
+    }
+    // the degenerated face
+    indices.extend([0, 0, 1]);
+
+    let mut attributes = vec![
+      (
+        AttributeSemantic::Positions,
+        AttributeAccessor::create_owned(positions, 4 * 3),
+      ),
+      (
+        AttributeSemantic::TexCoords(0),
+        AttributeAccessor::create_owned(uvs, 4 * 2),
+      ),
+    ];
+    if with_normal {
+      attributes.push((
+        AttributeSemantic::Normals,
+        AttributeAccessor::create_owned(normals, 4 * 3),
+      ));
+    }
+
+    let indices = match format {
+      AttributeIndexFormat::Uint16 => {
+        let indices: Vec<_> = indices.into_iter().map(|i| i as u16).collect();
+        AttributeAccessor::create_owned(indices, 2)
+      }
+      AttributeIndexFormat::Uint32 => {
+        let indices: Vec<_> = indices.into_iter().map(|i| i as u32).collect();
+        AttributeAccessor::create_owned(indices, 4)
+      }
+    };
+
+    AttributesMesh {
+      attributes,
+      indices: Some((format, indices)),
+      mode: PrimitiveTopology::TriangleList,
+      groups: MeshGroupsInfo::new(),
+      morph_targets: Default::default(),
+    }
+  }
+
+  fn read_attribute<T: bytemuck::Pod>(
+    mesh: &AttributesMesh,
+    semantic: AttributeSemantic,
+  ) -> Vec<T> {
+    let attribute = mesh.get_attribute(semantic).unwrap().read();
+    attribute.visit_slice::<T>().unwrap().to_vec()
+  }
+
+  fn read_triangles(mesh: &AttributesMesh) -> Vec<[(Vec3<f32>, Vec3<f32>, Vec2<f32>); 3]> {
+    let positions = read_attribute::<Vec3<f32>>(mesh, AttributeSemantic::Positions);
+    let normals = read_attribute::<Vec3<f32>>(mesh, AttributeSemantic::Normals);
+    let uvs = read_attribute::<Vec2<f32>>(mesh, AttributeSemantic::TexCoords(0));
+    let (format, indices) = mesh.indices.as_ref().unwrap();
+    assert_eq!(*format, AttributeIndexFormat::Uint32);
+    let indices = indices.read();
+    let indices = indices.visit_slice::<u32>().unwrap();
+    indices
+      .chunks_exact(3)
+      .map(|face| {
+        [0, 1, 2].map(|i| {
+          let index = face[i] as usize;
+          (positions[index], normals[index], uvs[index])
+        })
+      })
+      .collect()
+  }
+
+  fn volume(triangles: &[[(Vec3<f32>, Vec3<f32>, Vec2<f32>); 3]]) -> f32 {
+    triangles
+      .iter()
+      .map(|[a, b, c]| a.0.dot(b.0.cross(c.0)) / 6.)
+      .sum()
+  }
+
+  #[test]
+  fn csg_mesh_conversion() {
+    for format in [AttributeIndexFormat::Uint16, AttributeIndexFormat::Uint32] {
+      let cube = box_mesh(Vec3::splat(-1.), Vec3::splat(1.), format, true);
+      let csg = cube.build_csg_mesh().unwrap();
+      // the degenerated face is skipped
+      assert_eq!(csg.polygon_count(), 12);
+
+      let result: AttributesMesh = (&csg).into();
+      let triangles = read_triangles(&result);
+      assert_eq!(triangles.len(), 12);
+      // the shared vertices are merged
+      assert_eq!(result.get_position().count, 24);
+      assert!((volume(&triangles) - 8.).abs() < 1e-4);
+
+      // the normals and uvs are kept
+      let positions = read_attribute::<Vec3<f32>>(&cube, AttributeSemantic::Positions);
+      let normals = read_attribute::<Vec3<f32>>(&cube, AttributeSemantic::Normals);
+      let uvs = read_attribute::<Vec2<f32>>(&cube, AttributeSemantic::TexCoords(0));
+      for &(position, normal, uv) in triangles.iter().flatten() {
+        let source = positions
+          .iter()
+          .zip(&normals)
+          .zip(&uvs)
+          .find(|((&p, &n), _)| p == position && n == normal);
+        let ((_, _), &source_uv) = source.unwrap();
+        assert_eq!(uv, source_uv);
+      }
+    }
+  }
+
+  #[test]
+  fn csg_mesh_conversion_missing_normal() {
+    let cube = box_mesh(
+      Vec3::splat(-1.),
+      Vec3::splat(1.),
+      AttributeIndexFormat::Uint16,
+      false,
+    );
+    let csg = cube.build_csg_mesh().unwrap();
+    for [a, b, c] in csg.triangles() {
+      let face_normal = (b.position - a.position)
+        .cross(c.position - a.position)
+        .normalize();
+      for v in [a, b, c] {
+        assert!((v.normal - face_normal).length() < 1e-5);
+      }
+    }
+  }
+
+  #[test]
+  fn csg_mesh_subtract_round_trip() {
+    let boxes = [
+      (Vec3::splat(-1.), Vec3::splat(1.)),
+      (Vec3::splat(0.), Vec3::splat(2.)),
+    ];
+
+    for format in [AttributeIndexFormat::Uint16, AttributeIndexFormat::Uint32] {
+      let [a, b] = boxes.map(|(min, max)| box_mesh(min, max, format, true));
+      let result = a
+        .build_csg_mesh()
+        .unwrap()
+        .subtract(b.build_csg_mesh().unwrap());
+      let result: AttributesMesh = (&result).into();
+      let triangles = read_triangles(&result);
+      assert!((volume(&triangles) - 7.).abs() < 1e-4);
+
+      for triangle in &triangles {
+        let [(pa, ..), (pb, ..), (pc, ..)] = triangle;
+        let face_normal = (*pb - *pa).cross(*pc - *pa).normalize();
+        for &(position, normal, uv) in triangle {
+          // the normal keeps the face direction, the inner faces from the subtracted box are
+          // flipped
+          assert!((normal - face_normal).length() < 1e-4);
+
+          // the uv is interpolated from the face of the source box that the vertex lies on
+          let axis = (0..3).find(|&axis| normal[axis] != 0.).unwrap();
+          let expect = boxes
+            .iter()
+            .flat_map(|&(min, max)| BOX_FACES.map(|face| (min, max, face)))
+            .find_map(|(min, max, (face_axis, is_max, u, v))| {
+              let value = if is_max { max[axis] } else { min[axis] };
+              (face_axis == axis && (position[axis] - value).abs() < 1e-4)
+                .then(|| box_uv(min, max, u, v, position))
+            })
+            .unwrap();
+          assert!((uv - expect).length() < 1e-4, "{uv:?} != {expect:?}");
+        }
+      }
+    }
+  }
+}
@@ -107,6 +107,10 @@ pub fn compute_merge_key(att: &&AttributesMesh) -> AttributeMeshMergeKey {
 }
 
 pub fn could_merge_together(inputs: &[&AttributesMesh]) -> bool {
+  // the morph target weights are decided by each model, so the morphed mesh can not be merged
+  if inputs.iter().any(|m| !m.morph_targets.is_empty()) {
+    return false;
+  }
   if let Some(first) = inputs.first() {
     let first_key = compute_merge_key(first);
     inputs
@@ -213,6 +217,7 @@ fn merge_assume_all_suitable_and_fit(
     indices: merged_indices,
     mode: first.mode,
     groups: merged_groups,
+    morph_targets: Default::default(),
   })
 }
 
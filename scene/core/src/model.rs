@@ -37,6 +37,7 @@ pub struct StandardModel {
   pub mesh: SceneMeshType,
   pub group: MeshDrawGroup,
   pub skeleton: Option<Skeleton>,
+  pub morph_weights: Option<MorphWeights>,
 }
 
 impl StandardModel {
@@ -46,6 +47,7 @@ impl StandardModel {
       mesh: mesh.into(),
       group: Default::default(),
       skeleton: Default::default(),
+      morph_weights: Default::default(),
     }
   }
 }
@@ -97,3 +99,12 @@ impl Joint {
     d_sys.get_world_matrix(&self.node) * self.bind_inverse
   }
 }
+
+/// The weights of the morph targets of the model's mesh, could be shared between the models
+/// that split from one morphed mesh, and animated by the morph target weights channel.
+pub type MorphWeights = SceneItemRef<MorphWeightsImpl>;
+#[derive(Clone, Default, PartialEq)]
+pub struct MorphWeightsImpl {
+  pub weights: Vec<f32>,
+}
+clone_self_diffable_incremental!(MorphWeightsImpl);
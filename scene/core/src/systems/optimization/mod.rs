@@ -154,6 +154,10 @@ fn compute_instance_key_inner(model: &SceneItemRef<StandardModel>) -> Option<Ins
     return None;
   }
 
+  if model.morph_weights.is_some() {
+    return None;
+  }
+
   InstanceContentKey {
     material_id: model.material.guid()?,
     mesh_id: model.mesh.guid()?,
@@ -441,6 +445,7 @@ fn create_instance(
       mesh: SceneMeshType::TransformInstanced(instance_mesh),
       group: model.group,
       skeleton: None,
+      morph_weights: None,
     }
     .into_ref();
 
@@ -14,7 +14,10 @@ pub fn map_draw_mode(mode: PrimitiveTopology) -> gltf_json::mesh::Mode {
 
 pub fn map_wrapping(mode: AddressMode) -> gltf_json::texture::WrappingMode {
   match mode {
-    AddressMode::ClampToEdge => gltf_json::texture::WrappingMode::ClampToEdge,
+    // gltf has no border, the edge is the closest
+    AddressMode::ClampToEdge | AddressMode::ClampToBorder => {
+      gltf_json::texture::WrappingMode::ClampToEdge
+    }
     AddressMode::MirrorRepeat => gltf_json::texture::WrappingMode::MirroredRepeat,
     AddressMode::Repeat => gltf_json::texture::WrappingMode::Repeat,
   }
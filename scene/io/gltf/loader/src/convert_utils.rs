@@ -21,6 +21,7 @@ pub fn map_sampler(sampler: gltf::texture::Sampler) -> rendiation_texture::Textu
       .min_filter()
       .map(map_min_filter_mipmap)
       .unwrap_or(rendiation_texture::FilterMode::Nearest),
+    border_color: Default::default(),
   }
 }
 
@@ -8,12 +8,13 @@ use fast_hash_collection::*;
 use gltf::{Node, Result as GltfResult};
 use rendiation_algebra::*;
 use rendiation_scene_core::{
-  AnimationSampler, AttributeAccessor, AttributeIndexFormat, AttributesMesh, BufferViewRange,
-  GeometryBuffer, GeometryBufferInner, IntoSceneItemRef, Joint, ModelType, NormalMapping,
-  PhysicalMetallicRoughnessMaterial, Scene, SceneAnimation, SceneAnimationChannel,
-  SceneMaterialType, SceneMeshType, SceneModel, SceneModelHandle, SceneModelImpl, SceneNode,
-  SceneTexture2D, SceneTexture2DType, Skeleton, SkeletonImpl, StandardModel,
-  Texture2DWithSamplingData, TextureWithSamplingData, UnTypedBufferView,
+  AnimationSampler, AttributeAccessor, AttributeIndexFormat, AttributeSemantic, AttributesMesh,
+  BufferViewRange, GeometryBuffer, GeometryBufferInner, IntoSceneItemRef, Joint, ModelType,
+  MorphTarget, MorphWeights, MorphWeightsImpl, NormalMapping, PhysicalMetallicRoughnessMaterial,
+  Scene, SceneAnimation, SceneAnimationChannel, SceneMaterialType, SceneMeshType, SceneModel,
+  SceneModelHandle, SceneModelImpl, SceneNode, SceneTexture2D, SceneTexture2DType, Skeleton,
+  SkeletonImpl, StandardModel, Texture2DWithSamplingData, TextureWithSamplingData,
+  UnTypedBufferView,
 };
 
 mod convert_utils;
@@ -72,6 +73,8 @@ pub struct GltfLoadResult {
   pub node_map: FastHashMap<usize, SceneNode>,
   pub view_map: FastHashMap<usize, UnTypedBufferView>,
   pub skin_map: FastHashMap<usize, Skeleton>,
+  /// map node index => the morph weights shared by the node's mesh primitives
+  pub morph_weights_map: FastHashMap<usize, MorphWeights>,
   pub animations: Vec<SceneAnimation>,
 }
 
@@ -88,6 +91,13 @@ fn create_node_recursive(parent_to_attach: SceneNode, gltf_node: &Node, ctx: &mu
 
   node.set_local_matrix(map_transform(gltf_node.transform()));
 
+  if let Some(weights) = build_morph_weights(gltf_node) {
+    ctx
+      .result
+      .morph_weights_map
+      .insert(gltf_node.index(), weights);
+  }
+
   for gltf_node in gltf_node.children() {
     create_node_recursive(node.clone(), &gltf_node, ctx)
   }
@@ -138,11 +148,27 @@ fn build_model(
 
   let mode = map_draw_mode(primitive.mode()).unwrap();
 
+  let morph_targets = primitive
+    .morph_targets()
+    .map(|target| {
+      // the tangent displacements are not blended by the renderer, so they are not imported
+      let attributes = [
+        (AttributeSemantic::Positions, target.positions()),
+        (AttributeSemantic::Normals, target.normals()),
+      ]
+      .into_iter()
+      .filter_map(|(semantic, accessor)| Some((semantic, build_accessor(accessor?, ctx))))
+      .collect();
+      MorphTarget { attributes }
+    })
+    .collect();
+
   let mesh = AttributesMesh {
     attributes,
     indices,
     mode,
     groups: Default::default(),
+    morph_targets,
   };
   let mesh = SceneMeshType::AttributesMesh(mesh.into());
 
@@ -156,6 +182,12 @@ fn build_model(
     model.skeleton = Some(sk.clone())
   }
 
+  model.morph_weights = ctx
+    .result
+    .morph_weights_map
+    .get(&gltf_node.index())
+    .cloned();
+
   let model = ModelType::Standard(model.into());
   let model = SceneModelImpl { model, node };
   SceneModel::new(model)
@@ -182,8 +214,15 @@ fn build_animation(animation: gltf::Animation, ctx: &mut Context) {
         output: build_accessor(gltf_sampler.output(), ctx),
       };
 
+      let target_morph_weights = ctx
+        .result
+        .morph_weights_map
+        .get(&target.node().index())
+        .cloned();
+
       SceneAnimationChannel {
         target_node: node,
+        target_morph_weights,
         sampler,
       }
     })
@@ -192,6 +231,26 @@ fn build_animation(animation: gltf::Animation, ctx: &mut Context) {
   ctx.result.animations.push(SceneAnimation { channels })
 }
 
+/// the node's weights override the mesh's default weights, the weights are zero if both absent
+fn build_morph_weights(gltf_node: &Node) -> Option<MorphWeights> {
+  let mesh = gltf_node.mesh()?;
+  let target_count = mesh
+    .primitives()
+    .map(|primitive| primitive.morph_targets().len())
+    .max()?;
+  if target_count == 0 {
+    return None;
+  }
+
+  let weights = gltf_node
+    .weights()
+    .or_else(|| mesh.weights())
+    .map(|weights| weights.to_vec())
+    .unwrap_or_else(|| vec![0.; target_count]);
+
+  MorphWeightsImpl { weights }.into_ref().into()
+}
+
 fn build_skin(skin: gltf::Skin, ctx: &mut Context) {
   let mut joints: Vec<_> = skin
     .joints()
@@ -82,6 +82,7 @@ pub fn load_obj_content(
         indices: (AttributeIndexFormat::Uint32, indices).into(),
         mode: rendiation_renderable_mesh::PrimitiveTopology::TriangleList,
         groups: Default::default(),
+        morph_targets: Default::default(),
       };
       let mesh = SceneMeshType::AttributesMesh(attribute_mesh.into_ref());
 
@@ -100,6 +101,7 @@ pub fn load_obj_content(
         mesh,
         group: Default::default(),
         skeleton: None,
+        morph_weights: None,
       }
     })
     .collect();
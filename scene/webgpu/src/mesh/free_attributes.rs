@@ -3,6 +3,7 @@ use crate::*;
 pub struct AttributesMeshGPU {
   attributes: Vec<(AttributeSemantic, GPUBufferResourceView)>,
   indices: Option<(GPUBufferResourceView, webgpu::IndexFormat)>,
+  morph_targets: Option<StorageBufferReadOnlyDataView<MorphTargets>>,
   mode: webgpu::PrimitiveTopology,
   draw: DrawCommand,
 }
@@ -30,6 +31,9 @@ impl ShaderPassBuilder for AttributesMeshGPU {
     if let Some((buffer, index_format)) = &self.indices {
       ctx.pass.set_index_buffer_owned(buffer, *index_format)
     }
+    if let Some(morph_targets) = &self.morph_targets {
+      ctx.binding.bind(morph_targets);
+    }
   }
 }
 
@@ -38,6 +42,7 @@ impl ShaderHashProvider for AttributesMeshGPU {
     for (s, _) in &self.attributes {
       s.hash(hasher)
     }
+    self.morph_targets.is_some().hash(hasher);
     self.mode.hash(hasher);
     if let Some((_, f)) = &self.indices {
       if webgpu::PrimitiveTopology::LineStrip == self.mode
@@ -51,7 +56,7 @@ impl ShaderHashProvider for AttributesMeshGPU {
 impl GraphicsShaderProvider for AttributesMeshGPU {
   fn build(&self, builder: &mut ShaderRenderPipelineBuilder) -> Result<(), ShaderBuildError> {
     let mode = VertexStepMode::Vertex;
-    builder.vertex(|builder, _| {
+    builder.vertex(|builder, binding| {
       for (s, _) in &self.attributes {
         match s {
           AttributeSemantic::Positions => {
@@ -85,6 +90,10 @@ impl GraphicsShaderProvider for AttributesMeshGPU {
           },
         }
       }
+      if let Some(morph_targets) = &self.morph_targets {
+        let morph_targets = binding.bind_by(morph_targets);
+        builder.register::<MorphTargetsInShader>(morph_targets);
+      }
       builder.primitive_state.topology = self.mode;
       Ok(())
     })
@@ -184,9 +193,12 @@ impl WebGPUMesh for AttributesMesh {
         (buffer_view, map_index(*format))
       });
 
+      let morph_targets = create_morph_targets_buffer(&mesh, &ctx.gpu.device);
+
       AttributesMeshGPU {
         attributes,
         indices,
+        morph_targets,
         mode: map_topology(mesh.mode),
         draw: draw_command(&mesh),
       }
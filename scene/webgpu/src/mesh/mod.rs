@@ -4,6 +4,8 @@ pub mod transform_instance;
 pub use transform_instance::*;
 pub mod free_attributes;
 pub use free_attributes::*;
+pub mod morph;
+pub use morph::*;
 
 use crate::*;
 
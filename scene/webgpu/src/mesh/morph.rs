@@ -0,0 +1,396 @@
+use crate::*;
+
+#[repr(C)]
+#[std430_layout]
+#[derive(Clone, Copy, ShaderStruct)]
+pub struct MorphTargetDelta {
+  pub position: Vec3<f32>,
+  pub normal: Vec3<f32>,
+}
+
+/// All the morph target displacements of the mesh, the deltas are laid out by vertex, each
+/// vertex has `target_count` deltas in the target order.
+#[repr(C)]
+#[derive(ShaderStruct)]
+pub struct MorphTargets {
+  pub target_count: u32,
+  pub deltas: [MorphTargetDelta],
+}
+
+const READONLY_STORAGE: AddressSpace = AddressSpace::Storage { writeable: false };
+
+only_vertex!(MorphTargetsInShader, ShaderPtr<MorphTargets, READONLY_STORAGE>);
+
+/// Pack the position and normal displacements of the mesh's morph targets laid out as the
+/// deltas of [MorphTargets], the missing displacements are filled by zero. Return the target
+/// count with the deltas.
+pub fn pack_morph_targets(mesh: &AttributesMesh) -> Option<(u32, Vec<MorphTargetDelta>)> {
+  if mesh.morph_targets.is_empty() {
+    return None;
+  }
+
+  let vertex_count = mesh.get_attribute(AttributeSemantic::Positions)?.count;
+  let target_count = mesh.morph_targets.len();
+  let mut data = vec![MorphTargetDelta::zeroed(); vertex_count * target_count];
+
+  for (target_index, target) in mesh.morph_targets.iter().enumerate() {
+    if let Some(deltas) = target.get_attribute(AttributeSemantic::Positions) {
+      let deltas = deltas.read();
+      let deltas = deltas.visit_slice::<Vec3<f32>>()?;
+      for (vertex, delta) in deltas.iter().take(vertex_count).enumerate() {
+        data[vertex * target_count + target_index].position = *delta;
+      }
+    }
+    if let Some(deltas) = target.get_attribute(AttributeSemantic::Normals) {
+      let deltas = deltas.read();
+      let deltas = deltas.visit_slice::<Vec3<f32>>()?;
+      for (vertex, delta) in deltas.iter().take(vertex_count).enumerate() {
+        data[vertex * target_count + target_index].normal = *delta;
+      }
+    }
+  }
+  Some((target_count as u32, data))
+}
+
+/// The std430 bytes of [MorphTargets], the deltas array starts at the delta's std430 alignment
+fn morph_targets_bytes(target_count: u32, deltas: &[MorphTargetDelta]) -> Vec<u8> {
+  let header_size = <MorphTargetDelta as Std430>::ALIGNMENT.max(std::mem::size_of::<u32>());
+  let mut bytes = vec![0; header_size];
+  bytes[0..4].copy_from_slice(bytemuck::bytes_of(&target_count));
+  bytes.extend_from_slice(bytemuck::cast_slice(deltas));
+  bytes
+}
+
+pub fn create_morph_targets_buffer(
+  mesh: &AttributesMesh,
+  device: &GPUDevice,
+) -> Option<StorageBufferReadOnlyDataView<MorphTargets>> {
+  let (target_count, deltas) = pack_morph_targets(mesh)?;
+  let bytes = morph_targets_bytes(target_count, &deltas);
+  StorageBufferReadOnlyDataView::create_by_bytes(device, &bytes).into()
+}
+
+/// The weights uploaded as a runtime sized array, the empty weights are padded by a zero weight
+/// because the storage binding can not be empty.
+fn create_morph_weights_data(weights: &MorphWeightsImpl) -> Vec<f32> {
+  if weights.weights.is_empty() {
+    vec![0.]
+  } else {
+    weights.weights.clone()
+  }
+}
+
+/// Add the displacements of the vertex weighted by the weights, the targets blended are bounded
+/// by both the target count and the weights count.
+pub fn blend_morph_targets(
+  targets: Node<ShaderPtr<MorphTargets, READONLY_STORAGE>>,
+  weights: Node<ShaderPtr<[f32], READONLY_STORAGE>>,
+  vertex_index: Node<u32>,
+  position: Node<Vec3<f32>>,
+  normal: Option<Node<Vec3<f32>>>,
+) -> (Node<Vec3<f32>>, Option<Node<Vec3<f32>>>) {
+  let target_count = MorphTargets::target_count(targets).load();
+  let deltas = MorphTargets::deltas(targets);
+
+  let position = position.make_local_var();
+  let normal = normal.map(|normal| normal.make_local_var());
+
+  let count = target_count.min(weights.array_length());
+  for_by(count, |_, target, _| {
+    let weight = weights.index(target).load();
+    let delta = deltas
+      .index(vertex_index * target_count + target)
+      .load()
+      .expand();
+    position.store(position.load() + delta.position * weight);
+    if let Some(normal) = normal {
+      normal.store(normal.load() + delta.normal * weight);
+    }
+  });
+
+  (position.load(), normal.map(|normal| normal.load()))
+}
+
+/// Blend the morph target displacements provided by the mesh into the geometry position and
+/// normal. The mesh without morph targets is not affected. The targets blended are bounded by
+/// both the mesh's target count and the weights count.
+pub struct MorphWeightsGPU {
+  weights: StorageBufferReadOnlyDataView<[f32]>,
+  weights_count: usize,
+}
+
+impl Stream for MorphWeightsGPU {
+  type Item = RenderComponentDeltaFlag;
+
+  fn poll_next(self: Pin<&mut Self>, _: &mut Context) -> Poll<Option<Self::Item>> {
+    Poll::Pending
+  }
+}
+
+impl ShaderHashProvider for MorphWeightsGPU {}
+
+impl GraphicsShaderProvider for MorphWeightsGPU {
+  fn build(&self, builder: &mut ShaderRenderPipelineBuilder) -> Result<(), ShaderBuildError> {
+    builder.vertex(|builder, binding| {
+      let weights = binding.bind_by(&self.weights);
+
+      let Ok(targets) = builder.query::<MorphTargetsInShader>() else {
+        return Ok(());
+      };
+
+      let vertex_index = builder.query::<VertexIndex>()?;
+      let position = builder.query::<GeometryPosition>()?;
+      let normal = builder.query::<GeometryNormal>().ok();
+
+      let (position, normal) =
+        blend_morph_targets(targets, weights, vertex_index, position, normal);
+
+      builder.register::<GeometryPosition>(position);
+      if let Some(normal) = normal {
+        builder.register::<GeometryNormal>(normal.normalize());
+      }
+      Ok(())
+    })
+  }
+}
+
+impl ShaderPassBuilder for MorphWeightsGPU {
+  fn setup_pass(&self, ctx: &mut GPURenderPassCtx) {
+    ctx.binding.bind(&self.weights);
+  }
+}
+
+pub type ReactiveMorphWeightsGPU = impl AsRef<RenderComponentCell<MorphWeightsGPU>>
+  + Stream<Item = RenderComponentDeltaFlag>
+  + Unpin;
+
+/// The weights data changes, the stream ends when the weights are dropped, so the gpu instance
+/// kept in the stream map is removed with it.
+fn morph_weights_data_changes(source: &MorphWeights) -> impl Stream<Item = Vec<f32>> + Unpin {
+  source
+    .single_listen_by::<()>(any_change_no_init)
+    .filter_map_sync(source.defer_weak())
+    .map(|weights| create_morph_weights_data(&weights.read()))
+}
+
+fn create_morph_weights_gpu(data: &[f32], device: &GPUDevice) -> MorphWeightsGPU {
+  MorphWeightsGPU {
+    weights: StorageBufferReadOnlyDataView::create_by_slice(device, data),
+    weights_count: data.len(),
+  }
+}
+
+pub fn build_morph_weights_gpu(
+  source: &MorphWeights,
+  cx: &ResourceGPUCtx,
+) -> ReactiveMorphWeightsGPU {
+  let data = create_morph_weights_data(&source.read());
+  let state = RenderComponentCell::new(create_morph_weights_gpu(&data, &cx.device));
+
+  let cx = cx.clone();
+
+  morph_weights_data_changes(source).fold_signal(state, move |data, state| {
+    // the weights count may change, the buffer should be recreated to fit the new length
+    if data.len() == state.inner.weights_count {
+      let gpu = state.inner.weights.gpu();
+      gpu
+        .resource
+        .resource
+        .update(&cx.queue, bytemuck::cast_slice(&data));
+      RenderComponentDeltaFlag::Content.into()
+    } else {
+      state.inner = create_morph_weights_gpu(&data, &cx.device);
+      RenderComponentDeltaFlag::ContentRef.into()
+    }
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use rendiation_shader_backend_interpreter::*;
+
+  use super::*;
+
+  fn accessor(data: Vec<Vec3<f32>>) -> AttributeAccessor {
+    AttributeAccessor::create_owned(data, 3 * 4)
+  }
+
+  fn morph_target(positions: Vec<Vec3<f32>>, normals: Option<Vec<Vec3<f32>>>) -> MorphTarget {
+    let mut attributes = vec![(AttributeSemantic::Positions, accessor(positions))];
+    if let Some(normals) = normals {
+      attributes.push((AttributeSemantic::Normals, accessor(normals)));
+    }
+    MorphTarget { attributes }
+  }
+
+  /// the mesh has two vertices and six targets, the second target has no normal displacement
+  fn morphed_mesh() -> AttributesMesh {
+    let positions = vec![Vec3::zero(); 2];
+    let target = |i: f32| {
+      morph_target(
+        vec![Vec3::splat(i), Vec3::splat(i + 0.5)],
+        (i != 1.).then(|| vec![Vec3::splat(-i), Vec3::splat(-i - 0.5)]),
+      )
+    };
+    AttributesMesh {
+      attributes: vec![(AttributeSemantic::Positions, accessor(positions))],
+      indices: None,
+      mode: PrimitiveTopology::TriangleList,
+      groups: Default::default(),
+      morph_targets: (0..6).map(|i| target(i as f32)).collect(),
+    }
+  }
+
+  #[test]
+  fn morph_targets_packing() {
+    let mut mesh = morphed_mesh();
+    let (target_count, data) = pack_morph_targets(&mesh).unwrap();
+
+    // all the targets are packed, laid out by vertex
+    assert_eq!(target_count, 6);
+    assert_eq!(data.len(), 2 * 6);
+    for vertex in 0..2 {
+      for target in 0..6 {
+        let delta = target as f32 + vertex as f32 * 0.5;
+        let packed = data[vertex * 6 + target];
+        assert_eq!(packed.position, Vec3::splat(delta));
+        // the missing normal displacement is zero
+        let normal_delta = if target == 1 { 0. } else { -delta };
+        assert_eq!(packed.normal, Vec3::splat(normal_delta));
+      }
+    }
+
+    let bytes = morph_targets_bytes(target_count, &data);
+    assert_eq!(std::mem::size_of::<MorphTargetDelta>(), 32);
+    assert_eq!(bytes.len(), 16 + data.len() * 32);
+    assert_eq!(bytes[0..4], 6_u32.to_ne_bytes());
+    assert_eq!(bytes[16..], *bytemuck::cast_slice::<_, u8>(&data));
+
+    mesh.morph_targets.clear();
+    assert!(pack_morph_targets(&mesh).is_none());
+  }
+
+  struct TestMorphTargets;
+
+  impl ShaderBindingProvider for TestMorphTargets {
+    const SPACE: AddressSpace = READONLY_STORAGE;
+    type Node = MorphTargets;
+  }
+
+  struct TestMorphWeights;
+
+  impl ShaderBindingProvider for TestMorphWeights {
+    const SPACE: AddressSpace = READONLY_STORAGE;
+    type Node = [f32];
+  }
+
+  fn morph_targets_value(target_count: u32, deltas: &[MorphTargetDelta]) -> InterpreterValue {
+    let deltas = deltas
+      .iter()
+      .map(|delta| {
+        InterpreterValue::Composite(vec![
+          InterpreterValue::primitive(&delta.position),
+          InterpreterValue::primitive(&delta.normal),
+        ])
+      })
+      .collect();
+    InterpreterValue::Composite(vec![
+      InterpreterValue::primitive(&target_count),
+      InterpreterValue::Composite(deltas),
+    ])
+  }
+
+  fn interpret_blend(
+    mesh: &AttributesMesh,
+    weights: &[f32],
+    vertex: u32,
+  ) -> (Vec3<f32>, Vec3<f32>) {
+    let api = ShaderAPIInterpreterImpl::new(ShaderStages::Compute);
+    let mut builder = ShaderComputePipelineBuilder::new(Box::new(api));
+    let targets = builder.binding::<TestMorphTargets>();
+    let weights_node = builder.binding::<TestMorphWeights>();
+
+    let position = val(Vec3::zero());
+    let normal = val(Vec3::new(0., 0., 1.));
+    let (position, normal) =
+      blend_morph_targets(targets, weights_node, val(vertex), position, Some(normal));
+    let normal = normal.unwrap();
+
+    let program = ShaderInterpreterProgram::from_built(builder.build().unwrap().shader);
+
+    let (target_count, deltas) = pack_morph_targets(mesh).unwrap();
+    let mut bindings = InterpreterBindings::default();
+    bindings
+      .bind(0, 0, morph_targets_value(target_count, &deltas))
+      .bind(0, 1, InterpreterValue::from_slice(weights));
+
+    let result = program.invoke(&bindings, &Default::default());
+    (result.read(position), result.read(normal))
+  }
+
+  #[test]
+  fn morph_targets_blending() {
+    let mesh = morphed_mesh();
+    let up = Vec3::new(0., 0., 1.);
+
+    // the targets after the fourth one are blended
+    let (position, normal) = interpret_blend(&mesh, &[0., 0., 0., 0., 0.5, 1.], 1);
+    assert_eq!(position, Vec3::splat(0.5 * 4.5 + 5.5));
+    assert_eq!(normal, up - Vec3::splat(0.5 * 4.5 + 5.5));
+
+    // the missing weights are treated as zero
+    let (position, normal) = interpret_blend(&mesh, &[0., 1.], 0);
+    assert_eq!(position, Vec3::splat(1.));
+    assert_eq!(normal, up);
+
+    // the exceeded weights are ignored
+    let (position, _) = interpret_blend(&mesh, &[0., 0., 0., 0., 0., 1., 1., 1.], 0);
+    assert_eq!(position, Vec3::splat(5.));
+  }
+
+  #[test]
+  fn morph_weights_data_packing() {
+    let weights = vec![0., 0., 0., 0., 0.25, 1.];
+    let data = create_morph_weights_data(&MorphWeightsImpl {
+      weights: weights.clone(),
+    });
+    assert_eq!(data, weights);
+
+    // the storage binding can not be empty
+    let data = create_morph_weights_data(&MorphWeightsImpl {
+      weights: Vec::new(),
+    });
+    assert_eq!(data, vec![0.]);
+  }
+
+  #[test]
+  fn morph_weights_removed_from_stream_map_after_drop() {
+    let weights: MorphWeights = MorphWeightsImpl { weights: vec![0.] }.into_ref();
+    let mut map = StreamMap::default();
+    map.insert(weights.guid(), morph_weights_data_changes(&weights));
+
+    let waker = futures::task::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    do_updates_by(&mut map, &mut cx, |_| {});
+
+    let mut updated = Vec::new();
+    weights.mutate(|mut m| {
+      m.modify(MorphWeightsImpl {
+        weights: vec![0.25, 0., 0., 0., 0., 1.],
+      })
+    });
+    do_updates_by(&mut map, &mut cx, |deltas| {
+      for delta in deltas {
+        if let StreamMapDelta::Delta(_, data) = delta {
+          updated.push(data);
+        }
+      }
+    });
+    assert_eq!(updated, vec![vec![0.25, 0., 0., 0., 0., 1.]]);
+    assert_eq!(map.len(), 1);
+
+    drop(weights);
+    do_updates_by(&mut map, &mut cx, |_| {});
+    assert!(map.is_empty());
+  }
+}
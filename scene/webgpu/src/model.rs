@@ -69,13 +69,41 @@ pub fn setup_pass_core(
       let draw_command = mesh_gpu.draw_command(model.group);
       let mesh_gpu: &dyn RenderComponentAny = mesh_gpu;
 
-      let components: [&dyn RenderComponentAny; 5] = [
-        &pass_gpu.assign_binding_index(0),
-        &mesh_gpu.assign_binding_index(2),
-        &node_gpu.assign_binding_index(2),
-        &camera_gpu.assign_binding_index(1),
-        &material_gpu.assign_binding_index(2),
-      ];
+      // only the morphed model touches the weights, and the write lock is only required when the
+      // weights are first seen
+      let morph_weights = model.morph_weights.as_ref().map(|weights| {
+        let all_weights = &resources.resources.model_ctx.morph_weights;
+        let guid = weights.guid();
+        if all_weights.read().unwrap().get(&guid).is_none() {
+          let gpu = &resources.resources.bindable_ctx.gpu;
+          all_weights
+            .write()
+            .unwrap()
+            .get_or_insert_with(guid, || build_morph_weights_gpu(weights, gpu));
+        }
+        (guid, all_weights.read().unwrap())
+      });
+      let morph_weights_gpu = morph_weights.as_ref().map(|(guid, all_weights)| {
+        let weights_gpu: &ReactiveMorphWeightsGPU = all_weights.get(guid).unwrap();
+        &weights_gpu.as_ref().inner
+      });
+
+      let pass_gpu = pass_gpu.assign_binding_index(0);
+      let mesh_gpu = mesh_gpu.assign_binding_index(2);
+      let morph_weights_gpu = morph_weights_gpu.map(|w| w.assign_binding_index(2));
+      let node_gpu = node_gpu.assign_binding_index(2);
+      let camera_gpu = camera_gpu.assign_binding_index(1);
+      let material_gpu = material_gpu.assign_binding_index(2);
+
+      let mut components: Vec<&dyn RenderComponentAny> = vec![&pass_gpu, &mesh_gpu];
+      if let Some(morph_weights_gpu) = &morph_weights_gpu {
+        components.push(morph_weights_gpu);
+      }
+      components.extend([
+        &node_gpu as &dyn RenderComponentAny,
+        &camera_gpu,
+        &material_gpu,
+      ]);
 
       RenderEmitter::new(components.as_slice()).render(&mut pass.ctx, draw_command);
     }
@@ -162,6 +190,7 @@ pub fn build_standard_model_gpu(
           RenderComponentDeltaFlag::Draw
         }
         StandardModelDelta::skeleton(_) => RenderComponentDeltaFlag::all(),
+        StandardModelDelta::morph_weights(_) => RenderComponentDeltaFlag::ContentRef,
       }
       .into()
     })
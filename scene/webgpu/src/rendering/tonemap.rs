@@ -167,3 +167,51 @@ impl<'a, T> GraphicsShaderProvider for ToneMapTask<'a, T> {
     })
   }
 }
+
+#[cfg(test)]
+mod test {
+  use rendiation_shader_backend_interpreter::interpret_by;
+
+  use super::*;
+
+  fn aces_reference(color: Vec3<f32>, exposure: f32) -> Vec3<f32> {
+    #[rustfmt::skip]
+    let input = Mat3::new(
+      0.59719, 0.07600, 0.02840,
+      0.35458, 0.90834, 0.13383,
+      0.04823, 0.01566, 0.83777,
+    );
+    #[rustfmt::skip]
+    let output = Mat3::new(
+      1.60475, -0.10208, -0.00327,
+      -0.53108, 1.10813, -0.07276,
+      -0.07367, -0.00605, 1.07602,
+    );
+    let fit =
+      |v: f32| (v * (v + 0.0245786) - 0.000090537) / (v * (0.983729 * v + 0.432951) + 0.238081);
+
+    let color = input * (color * (exposure / 0.6));
+    let color = output * Vec3::new(fit(color.x), fit(color.y), fit(color.z));
+    color.map(|c| c.clamp(0., 1.))
+  }
+
+  #[test]
+  fn tone_mapping_matches_reference() {
+    let colors = [
+      Vec3::new(0.05, 0.2, 0.8),
+      Vec3::new(1.5, 4., 0.),
+      Vec3::new(20., 0.7, 3.),
+    ];
+
+    for color in colors {
+      for exposure in [0.5, 1., 2.] {
+        let reinhard = interpret_by(|_| reinhard_tone_mapping_fn(val(color), val(exposure)));
+        let expect = (color * exposure).map(|c| (c / (1. + c)).clamp(0., 1.));
+        assert!((reinhard - expect).length() < 1e-5);
+
+        let aces = interpret_by(|_| aces_filmic_tone_mapping(val(color), val(exposure)));
+        assert!((aces - aces_reference(color, exposure)).length() < 1e-5);
+      }
+    }
+  }
+}
@@ -26,6 +26,7 @@ impl ContentGPUSystem {
       shared: bindable_ctx.clone(),
       materials: Default::default(),
       meshes: Default::default(),
+      morph_weights: Default::default(),
     };
 
     Self {
@@ -74,6 +75,7 @@ pub struct GPUModelResourceCtx {
   pub shared: ShareBindableResourceCtx,
   pub materials: Arc<RwLock<StreamMap<usize, MaterialGPUInstance>>>,
   pub meshes: Arc<RwLock<StreamMap<usize, MeshGPUInstance>>>,
+  pub morph_weights: Arc<RwLock<StreamMap<usize, ReactiveMorphWeightsGPU>>>,
 }
 
 impl Stream for GPUModelResourceCtx {
@@ -92,6 +94,10 @@ impl Stream for GPUModelResourceCtx {
     let meshes: &mut StreamMap<usize, MeshGPUInstance> = &mut meshes;
     do_updates_by(meshes, cx, |_| {});
 
+    let mut morph_weights = this.morph_weights.write().unwrap();
+    let morph_weights: &mut StreamMap<usize, ReactiveMorphWeightsGPU> = &mut morph_weights;
+    do_updates_by(morph_weights, cx, |_| {});
+
     Poll::Pending
   }
 }
@@ -137,6 +143,11 @@ impl Stream for ShareBindableResourceCtx {
 
 impl ShareBindableResourceCtx {
   pub fn new(gpu: &GPU) -> Self {
+    Self::new_with_bindless_preference(gpu, true)
+  }
+
+  /// The bindless is still disabled if it's not supported by the device.
+  pub fn new_with_bindless_preference(gpu: &GPU, prefer_enable_bindless: bool) -> Self {
     // create a 1x1 white pixel as the default texture;
     let default_texture_2d = GPUBufferImage {
       data: vec![255, 255, 255, 255],
@@ -145,7 +156,7 @@ impl ShareBindableResourceCtx {
     };
     let default_texture_2d = SceneTexture2DType::GPUBufferImage(default_texture_2d).into_ref();
     let sys = Self {
-      binding_sys: WebGPUTextureBindingSystem::new(gpu, true),
+      binding_sys: WebGPUTextureBindingSystem::new(gpu, prefer_enable_bindless),
       default_texture_2d,
       default_sampler: Default::default(),
       custom_storage: Arc::new(RwLock::new(AnyMap::new())),
@@ -37,8 +37,8 @@ struct WebGPUTextureBackend;
 impl GPUTextureBackend for WebGPUTextureBackend {
   type GPUTexture2D = GPU2DTextureView;
   type GPUSampler = GPUSamplerView;
-  type GPUTexture2DBindingArray<const N: usize> = BindingResourceArray<GPU2DTextureView, N>;
-  type GPUSamplerBindingArray<const N: usize> = BindingResourceArray<GPUSamplerView, N>;
+  type GPUTexture2DBindingArray = BindingResourceArray<GPU2DTextureView, 0>;
+  type GPUSamplerBindingArray = BindingResourceArray<GPUSamplerView, 0>;
   type BindingCollector = BindingBuilder;
 
   fn bind_texture2d(collector: &mut Self::BindingCollector, texture: &Self::GPUTexture2D) {
@@ -49,42 +49,34 @@ impl GPUTextureBackend for WebGPUTextureBackend {
     collector.bind(sampler);
   }
 
-  fn bind_texture2d_array<const N: usize>(
+  fn bind_texture2d_array(
     collector: &mut Self::BindingCollector,
-    textures: &Self::GPUTexture2DBindingArray<N>,
+    textures: &Self::GPUTexture2DBindingArray,
   ) {
     collector.bind(textures);
   }
 
-  fn bind_sampler_array<const N: usize>(
+  fn bind_sampler_array(
     collector: &mut Self::BindingCollector,
-    samplers: &Self::GPUSamplerBindingArray<N>,
+    samplers: &Self::GPUSamplerBindingArray,
   ) {
     collector.bind(samplers);
   }
 
-  fn update_texture2d_array<const N: usize>(
-    textures: &mut Self::GPUTexture2DBindingArray<N>,
-    source: Vec<Option<Self::GPUTexture2D>>,
+  fn update_texture2d_array(
+    textures: &mut Self::GPUTexture2DBindingArray,
+    length: usize,
+    changes: impl Iterator<Item = (usize, Option<Self::GPUTexture2D>)>,
   ) {
-    let first = source[0].clone().unwrap(); // we make sure the first is the default
-    let source: Vec<_> = source
-      .into_iter()
-      .map(|v| v.unwrap_or(first.clone()))
-      .collect();
-    *textures = BindingResourceArray::<GPU2DTextureView, N>::new(Arc::new(source));
+    update_binding_array(textures, length, changes)
   }
 
-  fn update_sampler_array<const N: usize>(
-    samplers: &mut Self::GPUSamplerBindingArray<N>,
-    source: Vec<Option<Self::GPUSampler>>,
+  fn update_sampler_array(
+    samplers: &mut Self::GPUSamplerBindingArray,
+    length: usize,
+    changes: impl Iterator<Item = (usize, Option<Self::GPUSampler>)>,
   ) {
-    let first = source[0].clone().unwrap(); // we make sure the first is the default
-    let source: Vec<_> = source
-      .into_iter()
-      .map(|v| v.unwrap_or(first.clone()))
-      .collect();
-    *samplers = BindingResourceArray::<GPUSamplerView, N>::new(Arc::new(source));
+    update_binding_array(samplers, length, changes)
   }
 
   fn register_shader_texture2d(
@@ -101,34 +93,55 @@ impl GPUTextureBackend for WebGPUTextureBackend {
   }
   fn register_shader_texture2d_array(
     builder: &mut ShaderRenderPipelineBuilder,
-    textures: &Self::GPUTexture2DBindingArray<MAX_TEXTURE_BINDING_ARRAY_LENGTH>,
-  ) -> BindingPreparer<
-    BindingArray<ShaderTexture2D, MAX_TEXTURE_BINDING_ARRAY_LENGTH>,
-    { AddressSpace::Handle },
-  > {
+    textures: &Self::GPUTexture2DBindingArray,
+  ) -> BindingPreparer<BindlessTexture2DArray, { AddressSpace::Handle }> {
     builder.bind_by(textures)
   }
   fn register_shader_sampler_array(
     builder: &mut ShaderRenderPipelineBuilder,
-    samplers: &Self::GPUSamplerBindingArray<MAX_SAMPLER_BINDING_ARRAY_LENGTH>,
-  ) -> BindingPreparer<
-    BindingArray<ShaderSampler, MAX_SAMPLER_BINDING_ARRAY_LENGTH>,
-    { AddressSpace::Handle },
-  > {
+    samplers: &Self::GPUSamplerBindingArray,
+  ) -> BindingPreparer<BindlessSamplerArray, { AddressSpace::Handle }> {
     builder.bind_by(samplers)
   }
 }
 
+fn update_binding_array<T: Clone>(
+  array: &mut BindingResourceArray<T, 0>,
+  length: usize,
+  changes: impl Iterator<Item = (usize, Option<T>)>,
+) {
+  let changes: Vec<_> = changes.collect();
+  array.update(|bindings| {
+    // we prefer the first as the default, but any registered item could fill the holes. If no
+    // item is registered yet, the array is kept and will be filled by the later update.
+    let default = changes
+      .iter()
+      .find(|(index, _)| *index == 0)
+      .and_then(|(_, item)| item.clone())
+      .or_else(|| bindings.first().cloned())
+      .or_else(|| changes.iter().find_map(|(_, item)| item.clone()));
+    let Some(default) = default else {
+      return;
+    };
+
+    bindings.resize(length, default.clone());
+    for (index, item) in changes {
+      bindings[index] = item.unwrap_or_else(|| default.clone());
+    }
+  })
+}
+
 #[derive(Clone)]
 pub struct WebGPUTextureBindingSystem {
-  bindless_enabled: bool,
   inner: Arc<RwLock<BindlessTextureSystem<WebGPUTextureBackend>>>,
 }
 
 impl WebGPUTextureBindingSystem {
+  /// The bindless is used if preferred and effectively supported by the device, and it will fall
+  /// back to the per draw binding at runtime if the textures exceed the device limits.
   pub fn new(gpu: &GPU, prefer_enable_bindless: bool) -> Self {
     let info = gpu.info();
-    let mut bindless_effectively_supported = info
+    let bindless_effectively_supported = info
       .supported_features
       .contains(Features::TEXTURE_BINDING_ARRAY)
       && info
@@ -140,21 +153,30 @@ impl WebGPUTextureBindingSystem {
 
     // we estimate that the texture used except under the binding system will not exceed 128 per
     // shader stage
-    if info.supported_limits.max_sampled_textures_per_shader_stage
-      < MAX_TEXTURE_BINDING_ARRAY_LENGTH as u32 + 128
-      || info.supported_limits.max_samplers_per_shader_stage
-        < MAX_SAMPLER_BINDING_ARRAY_LENGTH as u32 + 128
-    {
-      bindless_effectively_supported = false;
-    }
-
-    let bindless_enabled = prefer_enable_bindless && bindless_effectively_supported;
+    let limits = &info.supported_limits;
+    let limits = BindlessArrayLimits {
+      max_texture_length: limits
+        .max_sampled_textures_per_shader_stage
+        .saturating_sub(128) as usize,
+      max_sampler_length: limits.max_samplers_per_shader_stage.saturating_sub(128) as usize,
+      ..Default::default()
+    };
+    let limits_effectively_supported = limits.max_texture_length >= limits.init_length
+      && limits.max_sampler_length >= limits.init_length;
+
+    let bindless_enabled =
+      prefer_enable_bindless && bindless_effectively_supported && limits_effectively_supported;
 
     Self {
-      bindless_enabled,
-      inner: Arc::new(RwLock::new(BindlessTextureSystem::new(bindless_enabled))),
+      inner: Arc::new(RwLock::new(BindlessTextureSystem::new(
+        bindless_enabled.then_some(limits),
+      ))),
     }
   }
+
+  pub fn bindless_enabled(&self) -> bool {
+    self.inner.read().unwrap().bindless_enabled()
+  }
 }
 
 impl Stream for WebGPUTextureBindingSystem {
@@ -176,7 +198,11 @@ impl ShaderPassBuilder for WebGPUTextureBindingSystem {
 }
 impl ShaderHashProvider for WebGPUTextureBindingSystem {
   fn hash_pipeline(&self, hasher: &mut PipelineHasher) {
-    self.bindless_enabled.hash(hasher)
+    let inner = self.inner.read().unwrap();
+    inner.bindless_enabled().hash(hasher);
+    if inner.bindless_enabled() {
+      inner.binding_array_lengths().hash(hasher);
+    }
   }
 }
 impl GraphicsShaderProvider for WebGPUTextureBindingSystem {
@@ -205,7 +231,7 @@ impl WebGPUTextureBindingSystem {
   }
 
   pub fn bind_texture(&self, binding: &mut BindingBuilder, handle: Texture2DHandle) {
-    if self.bindless_enabled {
+    if self.bindless_enabled() {
       return;
     }
     // indeed, we lost performance in none bindless path by this lock access. This definitely has
@@ -215,7 +241,7 @@ impl WebGPUTextureBindingSystem {
   }
 
   pub fn bind_sampler(&self, binding: &mut BindingBuilder, handle: SamplerHandle) {
-    if self.bindless_enabled {
+    if self.bindless_enabled() {
       return;
     }
     // ditto
@@ -224,7 +250,7 @@ impl WebGPUTextureBindingSystem {
   }
 
   pub fn bind_system(&self, binding: &mut BindingBuilder) {
-    if !self.bindless_enabled {
+    if !self.bindless_enabled() {
       return;
     }
     let mut inner = self.inner.write().unwrap();
@@ -250,7 +276,7 @@ impl WebGPUTextureBindingSystem {
   }
 
   pub fn shader_system(&self, builder: &mut ShaderRenderPipelineBuilder) {
-    if !self.bindless_enabled {
+    if !self.bindless_enabled() {
       return;
     }
     let inner = self.inner.read().unwrap();
@@ -277,7 +303,7 @@ impl WebGPUTextureBindingSystem {
     shader_sampler_handle: Node<SamplerHandle>,
     uv: Node<Vec2<f32>>,
   ) -> Node<Vec4<f32>> {
-    if self.bindless_enabled {
+    if self.bindless_enabled() {
       let textures = reg
         .query_typed_both_stage::<BindlessTexturesInShader>()
         .unwrap();
@@ -297,3 +323,23 @@ impl WebGPUTextureBindingSystem {
     }
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn update_binding_array_default() {
+    let mut array = BindingResourceArray::<u32, 0>::default();
+    // nothing is registered yet
+    update_binding_array(&mut array, 4, [(2, None)].into_iter());
+    assert!(array.is_empty());
+
+    // the first item is removed, any other registered item fills the holes
+    update_binding_array(&mut array, 4, [(0, None), (2, Some(7))].into_iter());
+    assert_eq!(array.len(), 4);
+
+    update_binding_array(&mut array, 8, [(5, Some(3))].into_iter());
+    assert_eq!(array.len(), 8);
+  }
+}
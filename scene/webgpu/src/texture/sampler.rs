@@ -31,7 +31,10 @@ impl ShareBindableResourceCtx {
       let source = sampler.read();
       let source: TextureSampler = **source;
 
-      let gpu_sampler = GPUSampler::create(source.into_gpu(), &self.gpu.device);
+      let gpu_sampler = GPUSampler::create(
+        source.into_gpu_by_features(self.gpu.device.features()),
+        &self.gpu.device,
+      );
       let gpu_sampler = gpu_sampler.create_default_view();
       let handle = self.binding_sys.register_sampler(gpu_sampler.clone());
 
@@ -52,7 +55,10 @@ impl ShareBindableResourceCtx {
           let source: TextureSampler = **source;
           // creation will cached in device side now
           // todo, apply this reuse in handle level
-          let gpu_sampler = GPUSampler::create(source.into_gpu(), &gpu_clone.device);
+          let gpu_sampler = GPUSampler::create(
+            source.into_gpu_by_features(gpu_clone.device.features()),
+            &gpu_clone.device,
+          );
           let recreated = gpu_sampler.create_default_view();
 
           gpu_tex.gpu = recreated.clone();
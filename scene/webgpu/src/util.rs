@@ -57,6 +57,19 @@ fn convert_wrap(mode: rendiation_texture::AddressMode) -> webgpu::AddressMode {
     rendiation_texture::AddressMode::ClampToEdge => webgpu::AddressMode::ClampToEdge,
     rendiation_texture::AddressMode::Repeat => webgpu::AddressMode::Repeat,
     rendiation_texture::AddressMode::MirrorRepeat => webgpu::AddressMode::MirrorRepeat,
+    rendiation_texture::AddressMode::ClampToBorder => webgpu::AddressMode::ClampToBorder,
+  }
+}
+
+fn convert_border_color(
+  color: rendiation_texture::SamplerBorderColor,
+) -> webgpu::SamplerBorderColor {
+  match color {
+    rendiation_texture::SamplerBorderColor::TransparentBlack => {
+      webgpu::SamplerBorderColor::TransparentBlack
+    }
+    rendiation_texture::SamplerBorderColor::OpaqueBlack => webgpu::SamplerBorderColor::OpaqueBlack,
+    rendiation_texture::SamplerBorderColor::OpaqueWhite => webgpu::SamplerBorderColor::OpaqueWhite,
   }
 }
 
@@ -68,7 +81,12 @@ fn convert_filter(mode: rendiation_texture::FilterMode) -> webgpu::FilterMode {
 }
 
 pub trait SamplerConvertExt<'a> {
+  /// The clamp to border address mode requires the device feature, use
+  /// [SamplerConvertExt::into_gpu_by_features] if the sampler may use it.
   fn into_gpu(self) -> webgpu::SamplerDescriptor<'a>;
+  /// The clamp to border address mode falls back to the clamp to edge if the features not
+  /// support it.
+  fn into_gpu_by_features(self, features: webgpu::Features) -> webgpu::SamplerDescriptor<'a>;
 }
 
 impl<'a> SamplerConvertExt<'a> for rendiation_texture::TextureSampler {
@@ -81,7 +99,48 @@ impl<'a> SamplerConvertExt<'a> for rendiation_texture::TextureSampler {
       mag_filter: convert_filter(self.mag_filter),
       min_filter: convert_filter(self.min_filter),
       mipmap_filter: convert_filter(self.mipmap_filter),
+      border_color: self
+        .use_border()
+        .then(|| convert_border_color(self.border_color)),
       ..Default::default()
     }
   }
+
+  fn into_gpu_by_features(mut self, features: webgpu::Features) -> webgpu::SamplerDescriptor<'a> {
+    if !features.contains(webgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER) {
+      for mode in [
+        &mut self.address_mode_u,
+        &mut self.address_mode_v,
+        &mut self.address_mode_w,
+      ] {
+        if *mode == rendiation_texture::AddressMode::ClampToBorder {
+          *mode = rendiation_texture::AddressMode::ClampToEdge;
+        }
+      }
+    }
+    self.into_gpu()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::*;
+
+  #[test]
+  fn sampler_border_fallback() {
+    let sampler = rendiation_texture::TextureSampler {
+      address_mode_u: rendiation_texture::AddressMode::ClampToBorder,
+      address_mode_v: rendiation_texture::AddressMode::Repeat,
+      ..Default::default()
+    };
+
+    let desc = sampler.into_gpu_by_features(webgpu::Features::empty());
+    assert_eq!(desc.address_mode_u, webgpu::AddressMode::ClampToEdge);
+    assert_eq!(desc.address_mode_v, webgpu::AddressMode::Repeat);
+    assert!(desc.border_color.is_none());
+
+    let desc = sampler.into_gpu_by_features(webgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER);
+    assert_eq!(desc.address_mode_u, webgpu::AddressMode::ClampToBorder);
+    assert!(desc.border_color.is_some());
+  }
 }
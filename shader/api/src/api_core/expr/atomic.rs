@@ -0,0 +1,146 @@
+use crate::*;
+
+/// The value could only be accessed by the atomic operations, the atomic could only be placed in
+/// the writeable storage buffer or the workgroup memory.
+///
+/// https://www.w3.org/TR/WGSL/#atomic-types
+#[repr(transparent)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct ShaderAtomic<T>(pub T);
+
+unsafe impl<T: Zeroable> Zeroable for ShaderAtomic<T> {}
+unsafe impl<T: Pod> Pod for ShaderAtomic<T> {}
+
+unsafe impl<T: AtomicValueType + Std430> Std430 for ShaderAtomic<T> {
+  const ALIGNMENT: usize = 4;
+}
+
+pub trait AtomicValueType: PrimitiveShaderNodeType {
+  const ATOMIC_TYPE: ShaderAtomicValueType;
+}
+impl AtomicValueType for u32 {
+  const ATOMIC_TYPE: ShaderAtomicValueType = ShaderAtomicValueType::U32;
+}
+impl AtomicValueType for i32 {
+  const ATOMIC_TYPE: ShaderAtomicValueType = ShaderAtomicValueType::I32;
+}
+
+impl<T: AtomicValueType> ShaderNodeSingleType for ShaderAtomic<T> {
+  const SINGLE_TYPE: ShaderValueSingleType =
+    ShaderValueSingleType::Sized(ShaderSizedValueType::Atomic(T::ATOMIC_TYPE));
+}
+impl<T: AtomicValueType> ShaderNodeType for ShaderAtomic<T> {
+  const TYPE: ShaderValueType = ShaderValueType::Single(Self::SINGLE_TYPE);
+}
+impl<T: AtomicValueType> ShaderSizedValueNodeType for ShaderAtomic<T> {
+  const MEMBER_TYPE: ShaderSizedValueType = ShaderSizedValueType::Atomic(T::ATOMIC_TYPE);
+}
+
+#[derive(Clone, Copy)]
+pub enum AtomicFunction {
+  Add,
+  Subtract,
+  And,
+  InclusiveOr,
+  ExclusiveOr,
+  Min,
+  Max,
+  Exchange {
+    compare: Option<ShaderNodeRawHandle>,
+  },
+}
+
+impl<T: AtomicValueType, const S: AddressSpace> Node<ShaderPtr<ShaderAtomic<T>, S>>
+where
+  TruthCheckBool<{ S.atomic_available() }>: TruthCheckPass,
+{
+  pub fn atomic_load(&self) -> Node<T> {
+    call_shader_api(|g| unsafe { g.load(self.handle()).into_node() })
+  }
+
+  pub fn atomic_store(&self, v: impl Into<Node<T>>) {
+    let v = v.into();
+    call_shader_api(|g| g.store(v.handle(), self.handle()))
+  }
+
+  fn atomic_call(&self, function: AtomicFunction, v: Node<T>) -> ShaderNodeExpr {
+    ShaderNodeExpr::AtomicCall {
+      ty: T::ATOMIC_TYPE,
+      pointer: self.handle(),
+      function,
+      value: v.handle(),
+    }
+  }
+
+  /// return the value before the operation
+  pub fn atomic_add(&self, v: impl Into<Node<T>>) -> Node<T> {
+    self.atomic_call(AtomicFunction::Add, v.into()).insert_api()
+  }
+
+  /// return the value before the operation
+  pub fn atomic_sub(&self, v: impl Into<Node<T>>) -> Node<T> {
+    self
+      .atomic_call(AtomicFunction::Subtract, v.into())
+      .insert_api()
+  }
+
+  /// return the value before the operation
+  pub fn atomic_min(&self, v: impl Into<Node<T>>) -> Node<T> {
+    self.atomic_call(AtomicFunction::Min, v.into()).insert_api()
+  }
+
+  /// return the value before the operation
+  pub fn atomic_max(&self, v: impl Into<Node<T>>) -> Node<T> {
+    self.atomic_call(AtomicFunction::Max, v.into()).insert_api()
+  }
+
+  /// return the value before the operation
+  pub fn atomic_and(&self, v: impl Into<Node<T>>) -> Node<T> {
+    self.atomic_call(AtomicFunction::And, v.into()).insert_api()
+  }
+
+  /// return the value before the operation
+  pub fn atomic_or(&self, v: impl Into<Node<T>>) -> Node<T> {
+    self
+      .atomic_call(AtomicFunction::InclusiveOr, v.into())
+      .insert_api()
+  }
+
+  /// return the value before the operation
+  pub fn atomic_xor(&self, v: impl Into<Node<T>>) -> Node<T> {
+    self
+      .atomic_call(AtomicFunction::ExclusiveOr, v.into())
+      .insert_api()
+  }
+
+  /// return the value before the operation
+  pub fn atomic_exchange(&self, v: impl Into<Node<T>>) -> Node<T> {
+    self
+      .atomic_call(AtomicFunction::Exchange { compare: None }, v.into())
+      .insert_api()
+  }
+
+  /// Store the value if the current value equals to the compare, return the value before the
+  /// operation and if the value is exchanged. Note the exchange may spuriously fail.
+  pub fn atomic_compare_exchange_weak(
+    &self,
+    compare: impl Into<Node<T>>,
+    v: impl Into<Node<T>>,
+  ) -> (Node<T>, Node<bool>) {
+    let compare = compare.into().handle();
+    let result: Node<AnyType> = self
+      .atomic_call(
+        AtomicFunction::Exchange {
+          compare: Some(compare),
+        },
+        v.into(),
+      )
+      .insert_api();
+    unsafe {
+      (
+        expand_single(result.handle(), 0),
+        expand_single(result.handle(), 1),
+      )
+    }
+  }
+}
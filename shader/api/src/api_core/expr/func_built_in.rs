@@ -279,7 +279,7 @@ impl<T: ShaderNodeType> Node<T> {
 // todo restrict
 impl<T: ShaderNodeType> Node<T> {
   pub fn sqrt(self) -> Node<T> {
-    make_builtin_call(ShaderBuiltInFunction::Length, [self.handle()])
+    make_builtin_call(ShaderBuiltInFunction::Sqrt, [self.handle()])
   }
   pub fn inverse_sqrt(self) -> Node<T> {
     make_builtin_call(ShaderBuiltInFunction::InverseSqrt, [self.handle()])
@@ -298,6 +298,9 @@ impl<T: ShaderNodeType> Node<T> {
   pub fn fract(self) -> Node<T> {
     make_builtin_call(ShaderBuiltInFunction::Fract, [self.handle()])
   }
+  pub fn floor(self) -> Node<T> {
+    make_builtin_call(ShaderBuiltInFunction::Floor, [self.handle()])
+  }
 }
 
 // todo expand to more type
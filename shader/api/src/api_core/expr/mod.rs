@@ -18,6 +18,12 @@ pub use func::*;
 mod func_built_in;
 pub use func_built_in::*;
 
+mod atomic;
+pub use atomic::*;
+
+mod storage_texture;
+pub use storage_texture::*;
+
 pub enum ValueKind {
   Uint,
   Int,
@@ -77,6 +83,23 @@ pub enum ShaderNodeExpr {
     reference: Option<ShaderNodeRawHandle>,
     offset: Option<Vec2<i32>>,
   },
+  /// https://www.w3.org/TR/WGSL/#textureload
+  TextureLoad {
+    texture: ShaderNodeRawHandle,
+    position: ShaderNodeRawHandle,
+    index: Option<ShaderNodeRawHandle>,
+    level: Option<ShaderNodeRawHandle>,
+  },
+  /// https://www.w3.org/TR/WGSL/#arrayLength-builtin
+  ArrayLength {
+    array: ShaderNodeRawHandle,
+  },
+  AtomicCall {
+    ty: ShaderAtomicValueType,
+    pointer: ShaderNodeRawHandle,
+    function: AtomicFunction,
+    value: ShaderNodeRawHandle,
+  },
   Swizzle {
     ty: &'static str,
     source: ShaderNodeRawHandle,
@@ -120,6 +143,14 @@ pub enum DerivativeAxis {
   Width,
 }
 
+/// https://www.w3.org/TR/WGSL/#texturestore
+pub struct ShaderTextureStore {
+  pub texture: ShaderNodeRawHandle,
+  pub position: ShaderNodeRawHandle,
+  pub index: Option<ShaderNodeRawHandle>,
+  pub value: ShaderNodeRawHandle,
+}
+
 #[must_use]
 pub fn val<T>(v: T) -> Node<T>
 where
@@ -129,7 +160,7 @@ where
 }
 
 impl ShaderNodeExpr {
-  pub fn insert_api<T: ShaderNodeType>(self) -> Node<T> {
+  pub fn insert_api<T: ShaderNodeType + ?Sized>(self) -> Node<T> {
     call_shader_api(|api| unsafe { api.make_expression(self).into_node() })
   }
 }
@@ -43,7 +43,7 @@ pub enum OperatorNode {
 }
 
 impl OperatorNode {
-  pub fn insert_api<T: ShaderNodeType>(self) -> Node<T> {
+  pub fn insert_api<T: ShaderNodeType + ?Sized>(self) -> Node<T> {
     ShaderNodeExpr::Operator(self).insert_api()
   }
 }
@@ -353,6 +353,27 @@ where
   }
 }
 
+impl<T, const S: AddressSpace> Node<ShaderPtr<[T], S>>
+where
+  T: ShaderNodeType,
+{
+  pub fn index(&self, node: Node<impl ShaderNodeType>) -> Node<ShaderPtr<T, S>> {
+    OperatorNode::Index {
+      array: self.handle(),
+      entry: node.handle(),
+    }
+    .insert_api()
+  }
+
+  /// The element count of the runtime sized array, decided by the bound buffer size.
+  pub fn array_length(&self) -> Node<u32> {
+    ShaderNodeExpr::ArrayLength {
+      array: self.handle(),
+    }
+    .insert_api()
+  }
+}
+
 impl<T, const U: usize, const S: AddressSpace> Node<ShaderPtr<BindingArray<T, U>, S>>
 where
   T: ShaderNodeType,
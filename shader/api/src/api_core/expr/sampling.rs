@@ -280,3 +280,79 @@ impl HandleNode<ShaderDepthTexture2DArray> {
     .insert_api()
   }
 }
+
+/// Read the texel without sampler, the position and level are in texel unit.
+///
+/// https://www.w3.org/TR/WGSL/#textureload
+pub trait TexelLoadTarget {
+  type Position: ShaderNodeType;
+  type Output: PrimitiveShaderNodeType;
+}
+
+impl TexelLoadTarget for ShaderTexture1D {
+  type Position = u32;
+  type Output = Vec4<f32>;
+}
+
+impl TexelLoadTarget for ShaderTexture2D {
+  type Position = Vec2<u32>;
+  type Output = Vec4<f32>;
+}
+
+impl TexelLoadTarget for ShaderTexture3D {
+  type Position = Vec3<u32>;
+  type Output = Vec4<f32>;
+}
+
+impl TexelLoadTarget for ShaderDepthTexture2D {
+  type Position = Vec2<u32>;
+  type Output = f32;
+}
+
+pub trait ArrayTexelLoadTarget {
+  type Position: ShaderNodeType;
+  type Output: PrimitiveShaderNodeType;
+}
+
+impl ArrayTexelLoadTarget for ShaderTexture2DArray {
+  type Position = Vec2<u32>;
+  type Output = Vec4<f32>;
+}
+
+impl ArrayTexelLoadTarget for ShaderDepthTexture2DArray {
+  type Position = Vec2<u32>;
+  type Output = f32;
+}
+
+impl<T: TexelLoadTarget> HandleNode<T> {
+  pub fn load_texel(
+    &self,
+    position: impl Into<Node<T::Position>>,
+    level: impl Into<Node<u32>>,
+  ) -> Node<T::Output> {
+    ShaderNodeExpr::TextureLoad {
+      texture: self.handle(),
+      position: position.into().handle(),
+      index: None,
+      level: level.into().handle().into(),
+    }
+    .insert_api()
+  }
+}
+
+impl<T: ArrayTexelLoadTarget> HandleNode<T> {
+  pub fn load_texel_index(
+    &self,
+    position: impl Into<Node<T::Position>>,
+    index: Node<impl ShaderArrayTextureSampleIndexType>,
+    level: impl Into<Node<u32>>,
+  ) -> Node<T::Output> {
+    ShaderNodeExpr::TextureLoad {
+      texture: self.handle(),
+      position: position.into().handle(),
+      index: index.handle().into(),
+      level: level.into().handle().into(),
+    }
+    .insert_api()
+  }
+}
@@ -0,0 +1,209 @@
+use crate::*;
+
+/// The texel format of the storage texture, the marker types are in [storage_formats].
+pub trait ShaderStorageTextureFormat: 'static + Copy {
+  const FORMAT: TextureFormat;
+  /// the value type read from or written to the shader
+  type Texel: PrimitiveShaderNodeType;
+}
+
+pub mod storage_formats {
+  use crate::*;
+
+  macro_rules! storage_format {
+    ($name: tt, $texel: ty) => {
+      #[derive(Clone, Copy)]
+      pub struct $name;
+      impl ShaderStorageTextureFormat for $name {
+        const FORMAT: TextureFormat = TextureFormat::$name;
+        type Texel = $texel;
+      }
+    };
+  }
+
+  storage_format!(Rgba8Unorm, Vec4<f32>);
+  storage_format!(Rgba8Snorm, Vec4<f32>);
+  storage_format!(Rgba8Uint, Vec4<u32>);
+  storage_format!(Rgba8Sint, Vec4<i32>);
+  storage_format!(Rgba16Uint, Vec4<u32>);
+  storage_format!(Rgba16Sint, Vec4<i32>);
+  storage_format!(Rgba16Float, Vec4<f32>);
+  storage_format!(R32Uint, Vec4<u32>);
+  storage_format!(R32Sint, Vec4<i32>);
+  storage_format!(R32Float, Vec4<f32>);
+  storage_format!(Rg32Uint, Vec4<u32>);
+  storage_format!(Rg32Sint, Vec4<i32>);
+  storage_format!(Rg32Float, Vec4<f32>);
+  storage_format!(Rgba32Uint, Vec4<u32>);
+  storage_format!(Rgba32Sint, Vec4<i32>);
+  storage_format!(Rgba32Float, Vec4<f32>);
+}
+
+pub trait ShaderStorageTextureAccess: 'static + Copy {
+  const ACCESS: StorageTextureAccess;
+}
+pub trait ShaderStorageTextureReadable: ShaderStorageTextureAccess {}
+pub trait ShaderStorageTextureWritable: ShaderStorageTextureAccess {}
+
+#[derive(Clone, Copy)]
+pub struct StorageWriteOnly;
+impl ShaderStorageTextureAccess for StorageWriteOnly {
+  const ACCESS: StorageTextureAccess = StorageTextureAccess::WriteOnly;
+}
+impl ShaderStorageTextureWritable for StorageWriteOnly {}
+
+/// require the native only texture format feature
+#[derive(Clone, Copy)]
+pub struct StorageReadOnly;
+impl ShaderStorageTextureAccess for StorageReadOnly {
+  const ACCESS: StorageTextureAccess = StorageTextureAccess::ReadOnly;
+}
+impl ShaderStorageTextureReadable for StorageReadOnly {}
+
+/// require the native only texture format feature
+#[derive(Clone, Copy)]
+pub struct StorageReadWrite;
+impl ShaderStorageTextureAccess for StorageReadWrite {
+  const ACCESS: StorageTextureAccess = StorageTextureAccess::ReadWrite;
+}
+impl ShaderStorageTextureReadable for StorageReadWrite {}
+impl ShaderStorageTextureWritable for StorageReadWrite {}
+
+/// The texture accessed by texel position without sampler.
+///
+/// https://www.w3.org/TR/WGSL/#texture-storage
+pub trait StorageTextureTarget {
+  type Position: ShaderNodeType;
+  type Format: ShaderStorageTextureFormat;
+  type Access: ShaderStorageTextureAccess;
+}
+
+pub trait ArrayStorageTextureTarget {
+  type Position: ShaderNodeType;
+  type Format: ShaderStorageTextureFormat;
+  type Access: ShaderStorageTextureAccess;
+}
+
+macro_rules! storage_texture {
+  ($name: tt, $target: tt, $dimension: expr, $position: ty) => {
+    #[derive(Clone, Copy)]
+    pub struct $name<F, A = StorageWriteOnly>(PhantomData<(F, A)>);
+
+    impl<F: ShaderStorageTextureFormat, A: ShaderStorageTextureAccess> ShaderNodeSingleType
+      for $name<F, A>
+    {
+      const SINGLE_TYPE: ShaderValueSingleType = ShaderValueSingleType::StorageTexture {
+        dimension: $dimension,
+        format: F::FORMAT,
+        access: A::ACCESS,
+      };
+    }
+    impl<F: ShaderStorageTextureFormat, A: ShaderStorageTextureAccess> ShaderNodeType
+      for $name<F, A>
+    {
+      const TYPE: ShaderValueType = ShaderValueType::Single(Self::SINGLE_TYPE);
+    }
+
+    impl<F: ShaderStorageTextureFormat, A: ShaderStorageTextureAccess> $target for $name<F, A> {
+      type Position = $position;
+      type Format = F;
+      type Access = A;
+    }
+  };
+}
+
+storage_texture!(
+  ShaderStorageTexture1D,
+  StorageTextureTarget,
+  TextureViewDimension::D1,
+  u32
+);
+storage_texture!(
+  ShaderStorageTexture2D,
+  StorageTextureTarget,
+  TextureViewDimension::D2,
+  Vec2<u32>
+);
+storage_texture!(
+  ShaderStorageTexture3D,
+  StorageTextureTarget,
+  TextureViewDimension::D3,
+  Vec3<u32>
+);
+storage_texture!(
+  ShaderStorageTexture2DArray,
+  ArrayStorageTextureTarget,
+  TextureViewDimension::D2Array,
+  Vec2<u32>
+);
+
+type StorageTexel<T> = <<T as StorageTextureTarget>::Format as ShaderStorageTextureFormat>::Texel;
+type ArrayStorageTexel<T> =
+  <<T as ArrayStorageTextureTarget>::Format as ShaderStorageTextureFormat>::Texel;
+
+impl<T: StorageTextureTarget> HandleNode<T> {
+  pub fn read_texel(&self, position: impl Into<Node<T::Position>>) -> Node<StorageTexel<T>>
+  where
+    T::Access: ShaderStorageTextureReadable,
+  {
+    ShaderNodeExpr::TextureLoad {
+      texture: self.handle(),
+      position: position.into().handle(),
+      index: None,
+      level: None,
+    }
+    .insert_api()
+  }
+
+  pub fn write_texel(
+    &self,
+    position: impl Into<Node<T::Position>>,
+    value: impl Into<Node<StorageTexel<T>>>,
+  ) where
+    T::Access: ShaderStorageTextureWritable,
+  {
+    let store = ShaderTextureStore {
+      texture: self.handle(),
+      position: position.into().handle(),
+      index: None,
+      value: value.into().handle(),
+    };
+    call_shader_api(|api| api.texture_store(store))
+  }
+}
+
+impl<T: ArrayStorageTextureTarget> HandleNode<T> {
+  pub fn read_texel_index(
+    &self,
+    position: impl Into<Node<T::Position>>,
+    index: Node<impl ShaderArrayTextureSampleIndexType>,
+  ) -> Node<ArrayStorageTexel<T>>
+  where
+    T::Access: ShaderStorageTextureReadable,
+  {
+    ShaderNodeExpr::TextureLoad {
+      texture: self.handle(),
+      position: position.into().handle(),
+      index: index.handle().into(),
+      level: None,
+    }
+    .insert_api()
+  }
+
+  pub fn write_texel_index(
+    &self,
+    position: impl Into<Node<T::Position>>,
+    index: Node<impl ShaderArrayTextureSampleIndexType>,
+    value: impl Into<Node<ArrayStorageTexel<T>>>,
+  ) where
+    T::Access: ShaderStorageTextureWritable,
+  {
+    let store = ShaderTextureStore {
+      texture: self.handle(),
+      position: position.into().handle(),
+      index: index.handle().into(),
+      value: value.into().handle(),
+    };
+    call_shader_api(|api| api.texture_store(store))
+  }
+}
@@ -9,15 +9,22 @@ where
   }
 }
 
-// todo unsized struct
 pub fn extract_struct_define(
   ty: &ShaderValueType,
   visitor: &mut impl FnMut(&'static ShaderStructMetaInfo),
 ) {
-  ty.visit_single(|ty| {
-    if let ShaderValueSingleType::Sized(v) = ty {
+  ty.visit_single(|ty| match ty {
+    ShaderValueSingleType::Sized(v) => extract_struct_define_inner(v, visitor),
+    ShaderValueSingleType::Unsized(ShaderUnSizedValueType::UnsizedArray(v)) => {
       extract_struct_define_inner(v, visitor)
     }
+    ShaderValueSingleType::Unsized(ShaderUnSizedValueType::UnsizedStruct(s)) => {
+      s.sized_fields
+        .iter()
+        .for_each(|f| extract_struct_define_inner(&f.ty, visitor));
+      extract_struct_define_inner(s.last_dynamic_array_field.1, visitor)
+    }
+    _ => {}
   });
 }
 
@@ -27,6 +34,7 @@ pub fn extract_struct_define_inner(
 ) {
   match ty {
     ShaderSizedValueType::Primitive(_) => {}
+    ShaderSizedValueType::Atomic(_) => {}
     ShaderSizedValueType::Struct(s) => visitor(s),
     ShaderSizedValueType::FixedSizeArray((ty, _)) => extract_struct_define_inner(ty, visitor),
   }
@@ -48,6 +56,31 @@ where
   .insert_api()
 }
 
+/// The field access through the struct pointer. The unsized struct can not be loaded as a value,
+/// so its fields could only be accessed in this way.
+pub trait ShaderStructPtrNode: Copy {
+  type Pointee: ?Sized;
+  type FieldPtr<F: ShaderNodeType + ?Sized>;
+
+  /// # Safety
+  ///
+  /// the field index should be bounded and with correct type
+  unsafe fn field_ptr<F: ShaderNodeType + ?Sized>(self, field_index: usize) -> Self::FieldPtr<F>;
+}
+
+impl<T: ?Sized, const S: AddressSpace> ShaderStructPtrNode for Node<ShaderPtr<T, S>> {
+  type Pointee = T;
+  type FieldPtr<F: ShaderNodeType + ?Sized> = Node<ShaderPtr<F, S>>;
+
+  unsafe fn field_ptr<F: ShaderNodeType + ?Sized>(self, field_index: usize) -> Self::FieldPtr<F> {
+    ShaderNodeExpr::FieldGet {
+      field_index,
+      struct_node: self.handle(),
+    }
+    .insert_api()
+  }
+}
+
 /// use for compile time ubo field reflection by procedure macro;
 #[derive(Debug)]
 pub struct ShaderStructMetaInfo {
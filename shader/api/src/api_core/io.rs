@@ -12,10 +12,14 @@ pub enum ShaderInputNode {
     bindgroup_index: usize,
     entry_index: usize,
   },
+  /// the variable shared by all invocations in the compute workgroup
+  WorkGroupShared {
+    ty: ShaderSizedValueType,
+  },
 }
 
 impl ShaderInputNode {
-  pub fn insert_api<T: ShaderNodeType>(self) -> Node<T> {
+  pub fn insert_api<T: ShaderNodeType + ?Sized>(self) -> Node<T> {
     call_shader_api(|g| unsafe { g.define_module_input(self).into_node() })
   }
 }
@@ -31,6 +35,11 @@ pub enum ShaderBuiltInDecorator {
   FragDepth,
   FragSampleIndex,
   FragSampleMask,
+  CompGlobalInvocationId,
+  CompLocalInvocationId,
+  CompLocalInvocationIndex,
+  CompWorkgroupId,
+  CompNumWorkgroups,
 }
 
 #[derive(Default, Clone)]
@@ -38,28 +47,54 @@ pub struct ShaderBindGroup {
   pub bindings: Vec<ShaderBindEntry>,
 }
 
+/// The binding node is defined in every stage of the building pipeline, the node of the stage not
+/// being built is None.
 #[derive(Clone, Copy)]
 pub struct ShaderBindEntry {
   pub desc: ShaderBindingDescriptor,
-  pub vertex_node: ShaderNodeRawHandle,
-  pub fragment_node: ShaderNodeRawHandle,
+  pub vertex_node: Option<ShaderNodeRawHandle>,
+  pub fragment_node: Option<ShaderNodeRawHandle>,
+  pub compute_node: Option<ShaderNodeRawHandle>,
+}
+
+impl ShaderBindEntry {
+  pub fn stage_node(&self, stage: ShaderStages) -> ShaderNodeRawHandle {
+    match stage {
+      ShaderStages::Vertex => self.vertex_node,
+      ShaderStages::Fragment => self.fragment_node,
+      ShaderStages::Compute => self.compute_node,
+    }
+    .expect("the binding is not defined in the stage")
+  }
 }
 
 /// should impl by user's container ty
 pub trait ShaderBindingProvider {
   const SPACE: AddressSpace;
-  type Node: ShaderNodeType;
+  type Node: ShaderNodeType + ?Sized;
   fn binding_desc() -> ShaderBindingDescriptor {
     ShaderBindingDescriptor {
-      should_as_storage_buffer_if_is_buffer_like: false,
+      should_as_storage_buffer_if_is_buffer_like: matches!(
+        Self::SPACE,
+        AddressSpace::Storage { .. }
+      ),
+      writeable_if_storage: matches!(Self::SPACE, AddressSpace::Storage { writeable: true }),
       ty: Self::Node::TYPE,
     }
   }
+  /// The descriptor of the binding instance, override this if the descriptor is decided at
+  /// runtime, for example the length of the binding array.
+  fn instance_binding_desc(&self) -> ShaderBindingDescriptor {
+    Self::binding_desc()
+  }
 }
 
 #[derive(Clone, Copy)]
 pub struct ShaderBindingDescriptor {
   pub should_as_storage_buffer_if_is_buffer_like: bool,
+  /// only meaningful for the storage buffer, the read-write storage buffer is not visible in the
+  /// vertex stage
+  pub writeable_if_storage: bool,
   pub ty: ShaderValueType,
 }
 
@@ -78,6 +113,7 @@ impl ShaderBindingDescriptor {
       },
       ShaderValueType::BindingArray { ty, .. } => ShaderBindingDescriptor {
         should_as_storage_buffer_if_is_buffer_like: self.should_as_storage_buffer_if_is_buffer_like,
+        writeable_if_storage: self.writeable_if_storage,
         ty: ShaderValueType::Single(ty),
       }
       .get_buffer_layout(),
@@ -93,6 +129,9 @@ impl<'a, T: ShaderBindingProvider> ShaderBindingProvider for &'a T {
   fn binding_desc() -> ShaderBindingDescriptor {
     T::binding_desc()
   }
+  fn instance_binding_desc(&self) -> ShaderBindingDescriptor {
+    (*self).instance_binding_desc()
+  }
 }
 
 /// https://www.w3.org/TR/webgpu/#texture-format-caps
@@ -103,21 +142,26 @@ impl<T: ShaderBindingProvider> ShaderBindingProvider for DisableFiltering<T> {
   const SPACE: AddressSpace = T::SPACE;
   type Node = T::Node;
   fn binding_desc() -> ShaderBindingDescriptor {
-    let mut ty = T::binding_desc();
-    ty.ty.mutate_single(|ty| {
-      if let ShaderValueSingleType::Texture {
-        sample_type: TextureSampleType::Float { filterable },
-        ..
-      } = ty
-      {
-        *filterable = false;
-      }
+    disable_filtering(T::binding_desc())
+  }
+  fn instance_binding_desc(&self) -> ShaderBindingDescriptor {
+    disable_filtering(self.0.instance_binding_desc())
+  }
+}
 
-      if let ShaderValueSingleType::Sampler(ty) = ty {
-        *ty = SamplerBindingType::NonFiltering
-      }
-    });
+fn disable_filtering(mut ty: ShaderBindingDescriptor) -> ShaderBindingDescriptor {
+  ty.ty.mutate_single(|ty| {
+    if let ShaderValueSingleType::Texture {
+      sample_type: TextureSampleType::Float { filterable },
+      ..
+    } = ty
+    {
+      *filterable = false;
+    }
 
-    ty
-  }
+    if let ShaderValueSingleType::Sampler(ty) = ty {
+      *ty = SamplerBindingType::NonFiltering
+    }
+  });
+  ty
 }
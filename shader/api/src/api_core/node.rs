@@ -1,13 +1,19 @@
 use crate::*;
 
 #[repr(transparent)]
-#[derive(Clone, Copy)]
-pub struct Node<T> {
+pub struct Node<T: ?Sized> {
   phantom: PhantomData<T>,
   handle: ShaderNodeRawHandle,
 }
 
-impl<T> Node<T> {
+impl<T: ?Sized> Clone for Node<T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+impl<T: ?Sized> Copy for Node<T> {}
+
+impl<T: ?Sized> Node<T> {
   pub fn handle(&self) -> ShaderNodeRawHandle {
     self.handle
   }
@@ -88,7 +94,7 @@ impl ShaderNodeRawHandle {
   /// # Safety
   ///
   /// force type casting
-  pub unsafe fn into_node<X>(&self) -> Node<X> {
+  pub unsafe fn into_node<X: ?Sized>(&self) -> Node<X> {
     Node {
       handle: *self,
       phantom: PhantomData,
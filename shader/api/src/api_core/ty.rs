@@ -4,6 +4,7 @@ use crate::*;
 pub enum ShaderStages {
   Vertex,
   Fragment,
+  Compute,
 }
 
 /// https://www.w3.org/TR/WGSL/#address-space
@@ -37,23 +38,30 @@ impl AddressSpace {
   pub const fn loadable(self) -> bool {
     !matches!(self, AddressSpace::Handle)
   }
+  /// Atomic values could only be placed in the writeable storage buffer or the workgroup memory.
+  pub const fn atomic_available(self) -> bool {
+    matches!(
+      self,
+      AddressSpace::WorkGroup | AddressSpace::Storage { writeable: true }
+    )
+  }
 }
 
 impl core::marker::ConstParamTy for AddressSpace {}
 
-pub struct ShaderPtr<T, const S: AddressSpace>(PhantomData<T>);
+pub struct ShaderPtr<T: ?Sized, const S: AddressSpace>(PhantomData<T>);
 
-impl<T: ShaderNodeType, const S: AddressSpace> ShaderNodeType for ShaderPtr<T, S> {
+impl<T: ShaderNodeType + ?Sized, const S: AddressSpace> ShaderNodeType for ShaderPtr<T, S> {
   const TYPE: ShaderValueType = T::TYPE;
 }
 
 // we do not have alias rule like rust in shader, so clone copy at will
-impl<T, const S: AddressSpace> Clone for ShaderPtr<T, S> {
+impl<T: ?Sized, const S: AddressSpace> Clone for ShaderPtr<T, S> {
   fn clone(&self) -> Self {
-    Self(self.0)
+    *self
   }
 }
-impl<T, const S: AddressSpace> Copy for ShaderPtr<T, S> {}
+impl<T: ?Sized, const S: AddressSpace> Copy for ShaderPtr<T, S> {}
 
 pub type GlobalVariable<T> = Node<ShaderPtr<T, { AddressSpace::Private }>>;
 pub type LocalVarNode<T> = Node<ShaderPtr<T, { AddressSpace::Function }>>;
@@ -69,6 +77,8 @@ pub type ReadOnlyStorageNode<T> = Node<ReadOnlyStoragePtr<T>>;
 pub type StoragePtr<T> = ShaderPtr<T, { AddressSpace::Storage { writeable: true } }>;
 pub type StorageNode<T> = Node<StoragePtr<T>>;
 
+/// The length N could be 0 if the length is decided at runtime by the binding instance, see
+/// [ShaderBindingProvider::instance_binding_desc]
 #[derive(Clone, Copy)]
 pub struct BindingArray<T, const N: usize>(PhantomData<T>);
 
@@ -110,6 +120,11 @@ pub enum ShaderValueSingleType {
     dimension: TextureViewDimension,
     sample_type: TextureSampleType,
   },
+  StorageTexture {
+    dimension: TextureViewDimension,
+    format: TextureFormat,
+    access: StorageTextureAccess,
+  },
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
@@ -117,6 +132,13 @@ pub enum ShaderSizedValueType {
   Primitive(PrimitiveShaderValueType),
   Struct(&'static ShaderStructMetaInfo),
   FixedSizeArray((&'static ShaderSizedValueType, usize)),
+  Atomic(ShaderAtomicValueType),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum ShaderAtomicValueType {
+  I32,
+  U32,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
@@ -125,11 +147,11 @@ pub enum ShaderUnSizedValueType {
   UnsizedStruct(&'static ShaderUnSizedStructMetaInfo),
 }
 
-pub trait ShaderNodeType: 'static + Copy {
+pub trait ShaderNodeType: 'static {
   const TYPE: ShaderValueType;
 }
 
-pub trait ShaderNodeSingleType: 'static + Copy {
+pub trait ShaderNodeSingleType: 'static {
   const SINGLE_TYPE: ShaderValueSingleType;
 }
 
@@ -178,6 +200,20 @@ impl<T: ShaderSizedValueNodeType, const N: usize> ShaderNodeType for [T; N] {
   const TYPE: ShaderValueType = ShaderValueType::Single(Self::SINGLE_TYPE);
 }
 
+/// The runtime sized array, the length is decided by the bound storage buffer, see
+/// [Node::array_length]
+impl<T: ShaderSizedValueNodeType> ShaderNodeSingleType for [T] {
+  const SINGLE_TYPE: ShaderValueSingleType =
+    ShaderValueSingleType::Unsized(ShaderUnSizedValueType::UnsizedArray(&T::MEMBER_TYPE));
+}
+impl<T: ShaderSizedValueNodeType> ShaderNodeType for [T] {
+  const TYPE: ShaderValueType = ShaderValueType::Single(Self::SINGLE_TYPE);
+}
+impl<T: ShaderSizedValueNodeType> ShaderUnsizedValueNodeType for [T] {
+  const UNSIZED_TYPE: ShaderUnSizedValueType =
+    ShaderUnSizedValueType::UnsizedArray(&T::MEMBER_TYPE);
+}
+
 impl<T: ShaderSizedValueNodeType, const N: usize> ShaderNodeSingleType for Shader140Array<T, N> {
   const SINGLE_TYPE: ShaderValueSingleType =
     ShaderValueSingleType::Sized(ShaderSizedValueType::FixedSizeArray((&T::MEMBER_TYPE, N)));
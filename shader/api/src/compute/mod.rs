@@ -0,0 +1,148 @@
+use crate::*;
+
+/// The memory accesses synchronized by the barrier
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BarrierScope {
+  /// the storage buffer accesses
+  Storage,
+  /// the workgroup shared variable accesses
+  WorkGroup,
+}
+
+/// Synchronize the workgroup shared variable accesses of all invocations in the workgroup, must be
+/// called in uniform control flow.
+pub fn workgroup_barrier() {
+  call_shader_api(|api| api.barrier(BarrierScope::WorkGroup))
+}
+
+/// Synchronize the storage buffer accesses of all invocations in the workgroup, must be called in
+/// uniform control flow.
+pub fn storage_barrier() {
+  call_shader_api(|api| api.barrier(BarrierScope::Storage))
+}
+
+pub struct ShaderComputePipelineBuilder {
+  pub bindgroups: ShaderBindGroupBuilder,
+  workgroup_size: (u32, u32, u32),
+  global_invocation_id: Node<Vec3<u32>>,
+  local_invocation_id: Node<Vec3<u32>>,
+  local_invocation_index: Node<u32>,
+  workgroup_id: Node<Vec3<u32>>,
+  num_workgroups: Node<Vec3<u32>>,
+
+  /// Log the shader build result when building shader, for debug purpose.
+  pub log_result: bool,
+}
+
+impl std::ops::Deref for ShaderComputePipelineBuilder {
+  type Target = ShaderBindGroupBuilder;
+
+  fn deref(&self) -> &Self::Target {
+    &self.bindgroups
+  }
+}
+
+impl std::ops::DerefMut for ShaderComputePipelineBuilder {
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.bindgroups
+  }
+}
+
+impl ShaderComputePipelineBuilder {
+  /// The compute stage is kept as the current building stage until the builder is built, so the
+  /// shader logic could be written directly after the creation.
+  pub fn new(api: DynamicShaderAPI) -> Self {
+    set_compute_build_api(api);
+    set_current_building(ShaderStages::Compute.into());
+
+    use ShaderBuiltInDecorator::*;
+    Self {
+      bindgroups: Default::default(),
+      workgroup_size: (256, 1, 1),
+      global_invocation_id: ShaderInputNode::BuiltIn(CompGlobalInvocationId).insert_api(),
+      local_invocation_id: ShaderInputNode::BuiltIn(CompLocalInvocationId).insert_api(),
+      local_invocation_index: ShaderInputNode::BuiltIn(CompLocalInvocationIndex).insert_api(),
+      workgroup_id: ShaderInputNode::BuiltIn(CompWorkgroupId).insert_api(),
+      num_workgroups: ShaderInputNode::BuiltIn(CompNumWorkgroups).insert_api(),
+      log_result: false,
+    }
+  }
+
+  /// The default workgroup size is (256, 1, 1)
+  pub fn config_work_group_size(&mut self, size: (u32, u32, u32)) -> &mut Self {
+    self.workgroup_size = size;
+    self
+  }
+
+  pub fn workgroup_size(&self) -> (u32, u32, u32) {
+    self.workgroup_size
+  }
+
+  pub fn global_invocation_id(&self) -> Node<Vec3<u32>> {
+    self.global_invocation_id
+  }
+
+  pub fn local_invocation_id(&self) -> Node<Vec3<u32>> {
+    self.local_invocation_id
+  }
+
+  pub fn local_invocation_index(&self) -> Node<u32> {
+    self.local_invocation_index
+  }
+
+  pub fn workgroup_id(&self) -> Node<Vec3<u32>> {
+    self.workgroup_id
+  }
+
+  pub fn num_workgroups(&self) -> Node<Vec3<u32>> {
+    self.num_workgroups
+  }
+
+  pub fn define_workgroup_shared_var<T: ShaderSizedValueNodeType>(&self) -> WorkGroupSharedNode<T> {
+    ShaderInputNode::WorkGroupShared { ty: T::MEMBER_TYPE }.insert_api()
+  }
+
+  pub fn binding<T: ShaderBindingProvider>(&mut self) -> Node<ShaderPtr<T::Node, { T::SPACE }>> {
+    self.bindgroups.binding::<T>().using()
+  }
+
+  pub fn bind_by<T: ShaderBindingProvider>(
+    &mut self,
+    instance: &T,
+  ) -> Node<ShaderPtr<T::Node, { T::SPACE }>> {
+    self.bindgroups.bind_by(instance).using()
+  }
+
+  pub fn build(self) -> Result<ComputeShaderCompileResult, ShaderBuildError> {
+    let workgroup_size = self.workgroup_size;
+    call_shader_api(|api| api.set_workgroup_size(workgroup_size));
+    set_current_building(None);
+
+    let PipelineShaderAPIs { compute, .. } = take_build_api();
+
+    Ok(ComputeShaderCompileResult {
+      shader: compute.unwrap().build(),
+      bindings: self.bindgroups,
+      workgroup_size,
+    })
+  }
+}
+
+pub trait ComputeShaderProvider {
+  fn build(&self, builder: &mut ShaderComputePipelineBuilder) -> Result<(), ShaderBuildError>;
+
+  fn build_self(
+    &self,
+    api: DynamicShaderAPI,
+  ) -> Result<ShaderComputePipelineBuilder, ShaderBuildError> {
+    let mut builder = ShaderComputePipelineBuilder::new(api);
+    self.build(&mut builder)?;
+    Ok(builder)
+  }
+}
+
+pub struct ComputeShaderCompileResult {
+  pub shader: (String, Box<dyn Any>),
+  pub bindings: ShaderBindGroupBuilder,
+  pub workgroup_size: (u32, u32, u32),
+}
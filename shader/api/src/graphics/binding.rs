@@ -16,18 +16,14 @@ impl Default for ShaderBindGroupBuilder {
 
 // todo, constrain valid T and S
 #[derive(Clone)]
-pub struct BindingPreparer<T, const S: AddressSpace> {
+pub struct BindingPreparer<T: ?Sized, const S: AddressSpace> {
   phantom: PhantomData<T>,
   entry: ShaderBindEntry,
 }
 
-impl<T: ShaderNodeType, const S: AddressSpace> BindingPreparer<T, S> {
+impl<T: ShaderNodeType + ?Sized, const S: AddressSpace> BindingPreparer<T, S> {
   pub fn using(&self) -> Node<ShaderPtr<T, S>> {
-    let node = match get_current_stage().unwrap() {
-      ShaderStages::Vertex => self.entry.vertex_node,
-      ShaderStages::Fragment => self.entry.fragment_node,
-    };
-
+    let node = self.entry.stage_node(get_current_stage().unwrap());
     unsafe { node.into_node() }
   }
 
@@ -40,12 +36,12 @@ impl<T: ShaderNodeType, const S: AddressSpace> BindingPreparer<T, S> {
       set_current_building(ShaderStages::Vertex.into());
       register(
         &mut builder.vertex.registry,
-        self.entry.vertex_node.into_node(),
+        self.entry.stage_node(ShaderStages::Vertex).into_node(),
       );
       set_current_building(ShaderStages::Fragment.into());
       register(
         &mut builder.fragment.registry,
-        self.entry.fragment_node.into_node(),
+        self.entry.stage_node(ShaderStages::Fragment).into_node(),
       );
       set_current_building(None);
     }
@@ -60,12 +56,12 @@ impl ShaderBindGroupBuilder {
 
   pub(crate) fn binding_ty_inner<T: ShaderBindingProvider, const S: AddressSpace>(
     &mut self,
+    desc: ShaderBindingDescriptor,
   ) -> BindingPreparer<T::Node, S> {
     let bindgroup_index = self.current_index;
     let bindgroup = &mut self.bindings[bindgroup_index];
 
     let entry_index = bindgroup.bindings.len();
-    let desc = T::binding_desc();
 
     let node = ShaderInputNode::Binding {
       desc,
@@ -75,20 +71,22 @@ impl ShaderBindGroupBuilder {
 
     let current_stage = get_current_stage();
 
-    set_current_building(ShaderStages::Vertex.into());
-    let vertex_node = node.clone().insert_api::<T::Node>().handle();
-
-    set_current_building(ShaderStages::Fragment.into());
-    let fragment_node = node.insert_api::<T::Node>().handle();
-
-    set_current_building(current_stage);
+    let define_in = |stage: ShaderStages| {
+      is_building_stage(stage).then(|| {
+        set_current_building(stage.into());
+        node.clone().insert_api::<T::Node>().handle()
+      })
+    };
 
     let entry = ShaderBindEntry {
       desc,
-      vertex_node,
-      fragment_node,
+      vertex_node: define_in(ShaderStages::Vertex),
+      fragment_node: define_in(ShaderStages::Fragment),
+      compute_node: define_in(ShaderStages::Compute),
     };
 
+    set_current_building(current_stage);
+
     bindgroup.bindings.push(entry);
 
     BindingPreparer {
@@ -98,14 +96,14 @@ impl ShaderBindGroupBuilder {
   }
 
   pub fn binding<T: ShaderBindingProvider>(&mut self) -> BindingPreparer<T::Node, { T::SPACE }> {
-    self.binding_ty_inner::<T, { T::SPACE }>()
+    self.binding_ty_inner::<T, { T::SPACE }>(T::binding_desc())
   }
 
   pub fn bind_by<T: ShaderBindingProvider>(
     &mut self,
-    _instance: &T,
+    instance: &T,
   ) -> BindingPreparer<T::Node, { T::SPACE }> {
-    self.binding::<T>()
+    self.binding_ty_inner::<T, { T::SPACE }>(instance.instance_binding_desc())
   }
 
   pub(crate) fn wrap(&mut self) -> ShaderBindGroupDirectBuilder {
@@ -119,25 +117,31 @@ pub struct ShaderBindGroupDirectBuilder<'a> {
 
 impl<'a> ShaderBindGroupDirectBuilder<'a> {
   pub fn binding<T: ShaderBindingProvider>(&mut self) -> Node<ShaderPtr<T::Node, { T::SPACE }>> {
-    self.builder.binding_ty_inner::<T, { T::SPACE }>().using()
+    self.builder.binding::<T>().using()
   }
 
   pub fn bind_by<T: ShaderBindingProvider>(
     &mut self,
-    _instance: &T,
+    instance: &T,
   ) -> Node<ShaderPtr<T::Node, { T::SPACE }>> {
-    self.binding::<T>()
+    self.builder.bind_by(instance).using()
   }
 
   pub fn binding_unchecked<T: ShaderBindingProvider, const S: AddressSpace>(
     &mut self,
   ) -> Node<ShaderPtr<T::Node, S>> {
-    self.builder.binding_ty_inner::<T, S>().using()
+    self
+      .builder
+      .binding_ty_inner::<T, S>(T::binding_desc())
+      .using()
   }
   pub fn bind_by_unchecked<T: ShaderBindingProvider, const S: AddressSpace>(
     &mut self,
-    _instance: &T,
+    instance: &T,
   ) -> Node<ShaderPtr<T::Node, S>> {
-    self.binding_unchecked::<T, S>()
+    self
+      .builder
+      .binding_ty_inner::<T, S>(instance.instance_binding_desc())
+      .using()
   }
 }
@@ -106,15 +106,13 @@ impl ShaderRenderPipelineBuilder {
     self.fragment.finalize_depth_write();
     set_current_building(None);
 
-    let PipelineShaderAPIPair {
-      mut vertex,
-      mut fragment,
-      ..
+    let PipelineShaderAPIs {
+      vertex, fragment, ..
     } = take_build_api();
 
     Ok(ShaderCompileResult {
-      vertex_shader: vertex.build(),
-      frag_shader: fragment.build(),
+      vertex_shader: vertex.unwrap().build(),
+      frag_shader: fragment.unwrap().build(),
       bindings: self.bindgroups,
       vertex_layouts: self.vertex.vertex_layouts,
       primitive_state: self.vertex.primitive_state,
@@ -169,14 +167,17 @@ pub struct ShaderCompileResult {
   pub multisample: MultisampleState,
 }
 
-pub(crate) struct PipelineShaderAPIPair {
-  vertex: DynamicShaderAPI,
-  fragment: DynamicShaderAPI,
+/// The shader apis of the building pipeline, the render pipeline has the vertex and fragment
+/// stage, the compute pipeline has only the compute stage.
+pub(crate) struct PipelineShaderAPIs {
+  pub vertex: Option<DynamicShaderAPI>,
+  pub fragment: Option<DynamicShaderAPI>,
+  pub compute: Option<DynamicShaderAPI>,
   current: Option<ShaderStages>,
 }
 
 thread_local! {
-  static IN_BUILDING_SHADER_API: RefCell<Option<PipelineShaderAPIPair>> = RefCell::new(None);
+  static IN_BUILDING_SHADER_API: RefCell<Option<PipelineShaderAPIs>> = RefCell::new(None);
 }
 
 pub(crate) fn call_shader_api<T>(
@@ -187,13 +188,27 @@ pub(crate) fn call_shader_api<T>(
     let api = match api.current.unwrap() {
       ShaderStages::Vertex => &mut api.vertex,
       ShaderStages::Fragment => &mut api.fragment,
+      ShaderStages::Compute => &mut api.compute,
     }
+    .as_mut()
+    .expect("the stage is not being built")
     .as_mut();
 
     modifier(api)
   })
 }
 
+pub(crate) fn is_building_stage(stage: ShaderStages) -> bool {
+  IN_BUILDING_SHADER_API.with_borrow_mut(|api| {
+    let api = api.as_mut().unwrap();
+    match stage {
+      ShaderStages::Vertex => api.vertex.is_some(),
+      ShaderStages::Fragment => api.fragment.is_some(),
+      ShaderStages::Compute => api.compute.is_some(),
+    }
+  })
+}
+
 pub(crate) fn set_current_building(current: Option<ShaderStages>) {
   IN_BUILDING_SHADER_API.with_borrow_mut(|api| {
     let api = api.as_mut().unwrap();
@@ -207,14 +222,26 @@ pub(crate) fn get_current_stage() -> Option<ShaderStages> {
 
 pub(crate) fn set_build_api(vertex: DynamicShaderAPI, fragment: DynamicShaderAPI) {
   IN_BUILDING_SHADER_API.with_borrow_mut(|api| {
-    api.replace(PipelineShaderAPIPair {
-      vertex,
-      fragment,
+    api.replace(PipelineShaderAPIs {
+      vertex: vertex.into(),
+      fragment: fragment.into(),
+      compute: None,
+      current: None,
+    });
+  })
+}
+
+pub(crate) fn set_compute_build_api(compute: DynamicShaderAPI) {
+  IN_BUILDING_SHADER_API.with_borrow_mut(|api| {
+    api.replace(PipelineShaderAPIs {
+      vertex: None,
+      fragment: None,
+      compute: compute.into(),
       current: None,
     });
   })
 }
 
-pub(crate) fn take_build_api() -> PipelineShaderAPIPair {
+pub(crate) fn take_build_api() -> PipelineShaderAPIs {
   IN_BUILDING_SHADER_API.with_borrow_mut(|api| api.take().unwrap())
 }
@@ -67,6 +67,7 @@ impl ShaderSizedValueType {
           StructLayoutTarget::Std430 => align,
         }
       }
+      ShaderSizedValueType::Atomic(_) => 4,
     }
   }
 
@@ -77,6 +78,7 @@ impl ShaderSizedValueType {
       ShaderSizedValueType::FixedSizeArray((ty, size)) => {
         size * round_up(self.align_of_self(target), ty.size_of_self(target))
       }
+      ShaderSizedValueType::Atomic(_) => 4,
     }
   }
 }
@@ -57,6 +57,10 @@ pub trait ShaderAPI {
   fn end_switch(&mut self);
 
   fn discard(&mut self);
+  fn texture_store(&mut self, store: ShaderTextureStore);
+
+  fn barrier(&mut self, scope: BarrierScope);
+  fn set_workgroup_size(&mut self, size: (u32, u32, u32));
 
   fn get_fn(&mut self, name: String) -> Option<ShaderUserDefinedFunction>;
   fn begin_define_fn(&mut self, name: String, return_ty: Option<ShaderValueType>);
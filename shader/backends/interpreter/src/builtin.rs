@@ -0,0 +1,670 @@
+use std::cmp::Ordering;
+
+use ScalarValue as S;
+
+use crate::*;
+
+/// Apply the component-wise function, the scalar operand is broadcast to the other's shape.
+fn zip_map(
+  a: &InterpreterPrimitive,
+  b: &InterpreterPrimitive,
+  f: impl Fn(ScalarValue, ScalarValue) -> ScalarValue,
+) -> InterpreterPrimitive {
+  let shape = if a.shape == PrimitiveShape::Scalar {
+    b.shape
+  } else {
+    a.shape
+  };
+  let get = |v: &InterpreterPrimitive, i: usize| {
+    if v.shape == PrimitiveShape::Scalar {
+      v.scalars[0]
+    } else {
+      v.scalars[i]
+    }
+  };
+  let scalars = (0..shape.component_count())
+    .map(|i| f(get(a, i), get(b, i)))
+    .collect();
+  InterpreterPrimitive::new(shape, scalars)
+}
+
+fn zip_map3(
+  a: &InterpreterPrimitive,
+  b: &InterpreterPrimitive,
+  c: &InterpreterPrimitive,
+  f: impl Fn(ScalarValue, ScalarValue, ScalarValue) -> ScalarValue,
+) -> InterpreterPrimitive {
+  let shape = [a.shape, b.shape, c.shape]
+    .into_iter()
+    .find(|s| *s != PrimitiveShape::Scalar)
+    .unwrap_or(PrimitiveShape::Scalar);
+  let get = |v: &InterpreterPrimitive, i: usize| {
+    if v.shape == PrimitiveShape::Scalar {
+      v.scalars[0]
+    } else {
+      v.scalars[i]
+    }
+  };
+  let scalars = (0..shape.component_count())
+    .map(|i| f(get(a, i), get(b, i), get(c, i)))
+    .collect();
+  InterpreterPrimitive::new(shape, scalars)
+}
+
+fn arith(
+  a: ScalarValue,
+  b: ScalarValue,
+  float: impl Fn(f32, f32) -> f32,
+  uint: impl Fn(u32, u32) -> u32,
+  int: impl Fn(i32, i32) -> i32,
+) -> ScalarValue {
+  match (a, b) {
+    (S::Float(a), S::Float(b)) => S::Float(float(a, b)),
+    (S::Uint(a), S::Uint(b)) => S::Uint(uint(a, b)),
+    (S::Int(a), S::Int(b)) => S::Int(int(a, b)),
+    _ => panic!("invalid arithmetic operands {a:?} {b:?}"),
+  }
+}
+
+fn compare(a: ScalarValue, b: ScalarValue) -> Option<Ordering> {
+  match (a, b) {
+    (S::Float(a), S::Float(b)) => a.partial_cmp(&b),
+    (S::Uint(a), S::Uint(b)) => a.partial_cmp(&b),
+    (S::Int(a), S::Int(b)) => a.partial_cmp(&b),
+    (S::Bool(a), S::Bool(b)) => a.partial_cmp(&b),
+    _ => panic!("invalid comparison operands {a:?} {b:?}"),
+  }
+}
+
+fn bits(
+  a: ScalarValue,
+  b: ScalarValue,
+  boolean: impl Fn(bool, bool) -> bool,
+  uint: impl Fn(u32, u32) -> u32,
+) -> ScalarValue {
+  match (a, b) {
+    (S::Bool(a), S::Bool(b)) => S::Bool(boolean(a, b)),
+    (S::Uint(a), S::Uint(b)) => S::Uint(uint(a, b)),
+    (S::Int(a), S::Int(b)) => S::Int(uint(a as u32, b as u32) as i32),
+    _ => panic!("invalid bit operands {a:?} {b:?}"),
+  }
+}
+
+fn min(a: ScalarValue, b: ScalarValue) -> ScalarValue {
+  match (a, b) {
+    (S::Float(a), S::Float(b)) => S::Float(a.min(b)),
+    _ if compare(b, a) == Some(Ordering::Less) => b,
+    _ => a,
+  }
+}
+
+fn max(a: ScalarValue, b: ScalarValue) -> ScalarValue {
+  match (a, b) {
+    (S::Float(a), S::Float(b)) => S::Float(a.max(b)),
+    _ if compare(b, a) == Some(Ordering::Greater) => b,
+    _ => a,
+  }
+}
+
+/// The integer division by zero and the overflow returns the dividend, the remainder of them
+/// is zero.
+///
+/// https://www.w3.org/TR/WGSL/#arithmetic-expr
+fn binary_scalar(operator: &BinaryOperator, a: ScalarValue, b: ScalarValue) -> ScalarValue {
+  let is = |ordering: &[Ordering]| S::Bool(compare(a, b).map_or(false, |o| ordering.contains(&o)));
+  match operator {
+    BinaryOperator::Add => arith(a, b, |a, b| a + b, u32::wrapping_add, i32::wrapping_add),
+    BinaryOperator::Sub => arith(a, b, |a, b| a - b, u32::wrapping_sub, i32::wrapping_sub),
+    BinaryOperator::Mul => arith(a, b, |a, b| a * b, u32::wrapping_mul, i32::wrapping_mul),
+    BinaryOperator::Div => arith(
+      a,
+      b,
+      |a, b| a / b,
+      |a, b| a.checked_div(b).unwrap_or(a),
+      |a, b| a.checked_div(b).unwrap_or(a),
+    ),
+    BinaryOperator::Rem => arith(
+      a,
+      b,
+      |a, b| a % b,
+      |a, b| a.checked_rem(b).unwrap_or(0),
+      |a, b| a.checked_rem(b).unwrap_or(0),
+    ),
+    BinaryOperator::Eq => S::Bool(a == b),
+    BinaryOperator::NotEq => S::Bool(a != b),
+    BinaryOperator::GreaterThan => is(&[Ordering::Greater]),
+    BinaryOperator::LessThan => is(&[Ordering::Less]),
+    BinaryOperator::GreaterEqualThan => is(&[Ordering::Greater, Ordering::Equal]),
+    BinaryOperator::LessEqualThan => is(&[Ordering::Less, Ordering::Equal]),
+    BinaryOperator::LogicalOr => S::Bool(a.as_bool() || b.as_bool()),
+    BinaryOperator::LogicalAnd => S::Bool(a.as_bool() && b.as_bool()),
+    BinaryOperator::BitAnd => bits(a, b, |a, b| a & b, |a, b| a & b),
+    BinaryOperator::BitOr => bits(a, b, |a, b| a | b, |a, b| a | b),
+  }
+}
+
+fn dot_scalars(a: &[ScalarValue], b: &[ScalarValue]) -> ScalarValue {
+  a.iter()
+    .zip(b)
+    .map(|(a, b)| binary_scalar(&BinaryOperator::Mul, *a, *b))
+    .reduce(|a, b| binary_scalar(&BinaryOperator::Add, a, b))
+    .unwrap()
+}
+
+fn column(m: &InterpreterPrimitive, n: usize, c: usize) -> &[ScalarValue] {
+  &m.scalars[c * n..(c + 1) * n]
+}
+
+fn row(m: &InterpreterPrimitive, n: usize, r: usize) -> Vec<ScalarValue> {
+  (0..n).map(|c| m.scalars[c * n + r]).collect()
+}
+
+pub(crate) fn binary(
+  operator: &BinaryOperator,
+  a: &InterpreterPrimitive,
+  b: &InterpreterPrimitive,
+) -> InterpreterPrimitive {
+  use PrimitiveShape::*;
+  if let BinaryOperator::Mul = operator {
+    match (a.shape, b.shape) {
+      (Matrix(n), Matrix(_)) => {
+        let scalars = (0..n)
+          .flat_map(|c| (0..n).map(move |r| (c, r)))
+          .map(|(c, r)| dot_scalars(&row(a, n, r), column(b, n, c)))
+          .collect();
+        return InterpreterPrimitive::new(Matrix(n), scalars);
+      }
+      (Matrix(n), Vector(_)) => {
+        let scalars = (0..n)
+          .map(|r| dot_scalars(&row(a, n, r), &b.scalars))
+          .collect();
+        return InterpreterPrimitive::new(Vector(n), scalars);
+      }
+      (Vector(_), Matrix(n)) => {
+        let scalars = (0..n)
+          .map(|c| dot_scalars(&a.scalars, column(b, n, c)))
+          .collect();
+        return InterpreterPrimitive::new(Vector(n), scalars);
+      }
+      _ => {}
+    }
+  }
+  zip_map(a, b, |a, b| binary_scalar(operator, a, b))
+}
+
+pub(crate) fn unary(operator: &UnaryOperator, v: &InterpreterPrimitive) -> InterpreterPrimitive {
+  v.map(|v| match (operator, v) {
+    (UnaryOperator::LogicalNot, S::Bool(v)) => S::Bool(!v),
+    (UnaryOperator::Neg, S::Float(v)) => S::Float(-v),
+    (UnaryOperator::Neg, S::Int(v)) => S::Int(v.wrapping_neg()),
+    _ => panic!("invalid unary operand {v:?}"),
+  })
+}
+
+/// The numeric conversion if the convert has the byte width, otherwise the bitcast.
+pub(crate) fn convert(
+  v: &InterpreterPrimitive,
+  convert_to: &ValueKind,
+  convert: Option<u8>,
+) -> InterpreterPrimitive {
+  v.map(|v| match convert {
+    Some(_) => match convert_to {
+      ValueKind::Float => S::Float(match v {
+        S::Bool(v) => v as u32 as f32,
+        S::Uint(v) => v as f32,
+        S::Int(v) => v as f32,
+        S::Float(v) => v,
+      }),
+      // the float to integer conversion is saturated both in rust and in the shader
+      ValueKind::Uint => S::Uint(match v {
+        S::Bool(v) => v as u32,
+        S::Uint(v) => v,
+        S::Int(v) => v as u32,
+        S::Float(v) => v as u32,
+      }),
+      ValueKind::Int => S::Int(match v {
+        S::Bool(v) => v as i32,
+        S::Uint(v) => v as i32,
+        S::Int(v) => v,
+        S::Float(v) => v as i32,
+      }),
+      ValueKind::Bool => S::Bool(match v {
+        S::Bool(v) => v,
+        S::Uint(v) => v != 0,
+        S::Int(v) => v != 0,
+        S::Float(v) => v != 0.,
+      }),
+    },
+    None => {
+      let bits = match v {
+        S::Uint(v) => v,
+        S::Int(v) => v as u32,
+        S::Float(v) => v.to_bits(),
+        S::Bool(_) => panic!("bool could not be bitcast"),
+      };
+      match convert_to {
+        ValueKind::Float => S::Float(f32::from_bits(bits)),
+        ValueKind::Uint => S::Uint(bits),
+        ValueKind::Int => S::Int(bits as i32),
+        ValueKind::Bool => panic!("bool could not be bitcast"),
+      }
+    }
+  })
+}
+
+pub(crate) fn atomic(
+  function: &AtomicFunction,
+  current: ScalarValue,
+  value: ScalarValue,
+  compare: Option<ScalarValue>,
+) -> ScalarValue {
+  match function {
+    AtomicFunction::Add => binary_scalar(&BinaryOperator::Add, current, value),
+    AtomicFunction::Subtract => binary_scalar(&BinaryOperator::Sub, current, value),
+    AtomicFunction::And => binary_scalar(&BinaryOperator::BitAnd, current, value),
+    AtomicFunction::InclusiveOr => binary_scalar(&BinaryOperator::BitOr, current, value),
+    AtomicFunction::ExclusiveOr => bits(current, value, |a, b| a ^ b, |a, b| a ^ b),
+    AtomicFunction::Min => min(current, value),
+    AtomicFunction::Max => max(current, value),
+    AtomicFunction::Exchange { .. } => match compare {
+      Some(compare) if compare != current => current,
+      _ => value,
+    },
+  }
+}
+
+/// The round half to even, as the shader round does
+fn round(v: f32) -> f32 {
+  if v.fract().abs() == 0.5 {
+    2. * (v / 2.).round()
+  } else {
+    v.round()
+  }
+}
+
+fn length(v: &InterpreterPrimitive) -> f32 {
+  v.scalars
+    .iter()
+    .map(|v| v.as_f32() * v.as_f32())
+    .sum::<f32>()
+    .sqrt()
+}
+
+fn float_vector(v: impl IntoIterator<Item = f32>) -> InterpreterPrimitive {
+  let scalars: Vec<_> = v.into_iter().map(S::Float).collect();
+  let shape = match scalars.len() {
+    1 => PrimitiveShape::Scalar,
+    n => PrimitiveShape::Vector(n),
+  };
+  InterpreterPrimitive::new(shape, scalars)
+}
+
+fn floats(v: &InterpreterPrimitive) -> Vec<f32> {
+  v.scalars.iter().map(|v| v.as_f32()).collect()
+}
+
+fn to_matrix<R>(
+  m: &InterpreterPrimitive,
+  f2: impl FnOnce(Mat2<f32>) -> R,
+  f3: impl FnOnce(Mat3<f32>) -> R,
+  f4: impl FnOnce(Mat4<f32>) -> R,
+) -> R {
+  match m.to_shader_value() {
+    Some(PrimitiveShaderValue::Mat2Float32(m)) => f2(m),
+    Some(PrimitiveShaderValue::Mat3Float32(m)) => f3(m),
+    Some(PrimitiveShaderValue::Mat4Float32(m)) => f4(m),
+    _ => panic!("expect matrix"),
+  }
+}
+
+fn unpack(v: u32, count: u32, f: impl Fn(u32) -> f32) -> InterpreterPrimitive {
+  let width = 32 / count;
+  let mask = (1u64 << width) as u32 - 1;
+  float_vector((0..count).map(|i| f((v >> (i * width)) & mask)))
+}
+
+fn pack(v: &InterpreterPrimitive, f: impl Fn(f32) -> u32) -> ScalarValue {
+  let width = 32 / v.scalars.len() as u32;
+  let mask = (1u64 << width) as u32 - 1;
+  let packed = v.scalars.iter().enumerate().fold(0, |packed, (i, c)| {
+    packed | ((f(c.as_f32()) & mask) << (i as u32 * width))
+  });
+  S::Uint(packed)
+}
+
+/// The f32 to f16 bits conversion, the subnormal f16 is flushed to zero.
+fn f32_to_f16(v: f32) -> u32 {
+  let bits = v.to_bits();
+  let sign = (bits >> 16) & 0x8000;
+  let exponent = ((bits >> 23) & 0xff) as i32;
+  let mantissa = bits & 0x7f_ffff;
+  if exponent == 0xff {
+    let nan = if mantissa != 0 { 0x200 } else { 0 };
+    return sign | 0x7c00 | nan;
+  }
+  let exponent = exponent - 127 + 15;
+  if exponent >= 0x1f {
+    sign | 0x7c00
+  } else if exponent <= 0 {
+    sign
+  } else {
+    // round to nearest
+    let half = (exponent as u32) << 10 | mantissa >> 13;
+    sign | (half + ((mantissa >> 12) & 1))
+  }
+}
+
+fn f16_to_f32(v: u32) -> f32 {
+  let sign = (v & 0x8000) << 16;
+  let exponent = (v >> 10) & 0x1f;
+  let mantissa = v & 0x3ff;
+  let bits = match exponent {
+    0 if mantissa == 0 => sign,
+    0 => return (mantissa as f32 / 1024.) * 2f32.powi(-14) * if sign != 0 { -1. } else { 1. },
+    0x1f => sign | 0x7f80_0000 | mantissa << 13,
+    _ => sign | (exponent + 127 - 15) << 23 | mantissa << 13,
+  };
+  f32::from_bits(bits)
+}
+
+fn extract_bits(e: ScalarValue, offset: u32, count: u32) -> ScalarValue {
+  let offset = offset.min(32);
+  let count = count.min(32 - offset);
+  if count == 0 {
+    return match e {
+      S::Int(_) => S::Int(0),
+      _ => S::Uint(0),
+    };
+  }
+  match e {
+    S::Uint(v) => S::Uint(((v as u64 >> offset) & ((1u64 << count) - 1)) as u32),
+    // sign extended
+    S::Int(v) => S::Int((v << (32 - offset - count)) >> (32 - count)),
+    _ => panic!("expect integer"),
+  }
+}
+
+fn insert_bits(e: ScalarValue, new: ScalarValue, offset: u32, count: u32) -> ScalarValue {
+  let offset = offset.min(32);
+  let count = count.min(32 - offset);
+  let mask = (((1u64 << count) - 1) << offset) as u32;
+  let insert = |e: u32, new: u32| (e & !mask) | ((new << offset) & mask);
+  match (e, new) {
+    (S::Uint(e), S::Uint(new)) => S::Uint(insert(e, new)),
+    (S::Int(e), S::Int(new)) => S::Int(insert(e as u32, new as u32) as i32),
+    _ => panic!("expect integer"),
+  }
+}
+
+fn find_msb(v: ScalarValue) -> ScalarValue {
+  match v {
+    S::Uint(v) => S::Uint(if v == 0 {
+      u32::MAX
+    } else {
+      31 - v.leading_zeros()
+    }),
+    S::Int(v) => {
+      // for the negative value, find the most significant zero bit
+      let v = if v < 0 { !v } else { v } as u32;
+      S::Int(if v == 0 {
+        -1
+      } else {
+        31 - v.leading_zeros() as i32
+      })
+    }
+    _ => panic!("expect integer"),
+  }
+}
+
+fn map_integer(v: &InterpreterPrimitive, f: impl Fn(u32) -> u32) -> InterpreterPrimitive {
+  v.map(|v| match v {
+    S::Uint(v) => S::Uint(f(v)),
+    S::Int(v) => S::Int(f(v as u32) as i32),
+    _ => panic!("expect integer"),
+  })
+}
+
+/// https://www.w3.org/TR/WGSL/#builtin-functions
+pub(crate) fn call_builtin(
+  function: ShaderBuiltInFunction,
+  params: &[InterpreterPrimitive],
+) -> InterpreterValue {
+  use ShaderBuiltInFunction as F;
+  let p = |i: usize| &params[i];
+  let result = match function {
+    F::Select => {
+      let (reject, accept, condition) = (p(0), p(1), p(2));
+      if condition.shape == PrimitiveShape::Scalar {
+        if condition.scalar().as_bool() {
+          accept.clone()
+        } else {
+          reject.clone()
+        }
+      } else {
+        zip_map3(
+          reject,
+          accept,
+          condition,
+          |r, a, c| {
+            if c.as_bool() {
+              a
+            } else {
+              r
+            }
+          },
+        )
+      }
+    }
+    F::All => S::Bool(p(0).scalars.iter().all(|v| v.as_bool())).into(),
+    F::Any => S::Bool(p(0).scalars.iter().any(|v| v.as_bool())).into(),
+    F::IsNan => p(0).map(|v| S::Bool(v.as_f32().is_nan())),
+    F::IsInf => p(0).map(|v| S::Bool(v.as_f32().is_infinite())),
+    F::IsFinite => p(0).map(|v| S::Bool(v.as_f32().is_finite())),
+    F::IsNormal => p(0).map(|v| S::Bool(v.as_f32().is_normal())),
+    F::Abs => p(0).map(|v| match v {
+      S::Float(v) => S::Float(v.abs()),
+      S::Int(v) => S::Int(v.wrapping_abs()),
+      v => v,
+    }),
+    F::Min => zip_map(p(0), p(1), min),
+    F::Max => zip_map(p(0), p(1), max),
+    F::Clamp => zip_map3(p(0), p(1), p(2), |v, low, high| min(max(v, low), high)),
+    F::Saturate => p(0).map_f32(|v| v.clamp(0., 1.)),
+    F::Cos => p(0).map_f32(f32::cos),
+    F::Cosh => p(0).map_f32(f32::cosh),
+    F::Sin => p(0).map_f32(f32::sin),
+    F::Sinh => p(0).map_f32(f32::sinh),
+    F::Tan => p(0).map_f32(f32::tan),
+    F::Tanh => p(0).map_f32(f32::tanh),
+    F::Acos => p(0).map_f32(f32::acos),
+    F::Asin => p(0).map_f32(f32::asin),
+    F::Atan => p(0).map_f32(f32::atan),
+    F::Atan2 => zip_map(p(0), p(1), |y, x| S::Float(y.as_f32().atan2(x.as_f32()))),
+    F::Asinh => p(0).map_f32(f32::asinh),
+    F::Acosh => p(0).map_f32(f32::acosh),
+    F::Atanh => p(0).map_f32(f32::atanh),
+    F::Radians => p(0).map_f32(f32::to_radians),
+    F::Degrees => p(0).map_f32(f32::to_degrees),
+    F::Ceil => p(0).map_f32(f32::ceil),
+    F::Floor => p(0).map_f32(f32::floor),
+    F::Round => p(0).map_f32(round),
+    F::Fract => p(0).map_f32(|v| v - v.floor()),
+    F::Trunc => p(0).map_f32(f32::trunc),
+    F::Modf => {
+      let fract = p(0).map_f32(f32::fract);
+      let whole = p(0).map_f32(f32::trunc);
+      return InterpreterValue::Composite(vec![fract.into(), whole.into()]);
+    }
+    F::Frexp => {
+      let exponent = |v: f32| {
+        if v == 0. || !v.is_finite() {
+          0
+        } else {
+          v.abs().log2().floor() as i32 + 1
+        }
+      };
+      let fract = p(0).map_f32(|v| v / 2f32.powi(exponent(v)));
+      let exp = p(0).map(|v| S::Int(exponent(v.as_f32())));
+      return InterpreterValue::Composite(vec![fract.into(), exp.into()]);
+    }
+    F::Ldexp => zip_map(p(0), p(1), |v, e| {
+      S::Float(v.as_f32() * 2f32.powi(e.as_i32()))
+    }),
+    F::Exp => p(0).map_f32(f32::exp),
+    F::Exp2 => p(0).map_f32(f32::exp2),
+    F::Log => p(0).map_f32(f32::ln),
+    F::Log2 => p(0).map_f32(f32::log2),
+    F::Pow => zip_map(p(0), p(1), |v, e| S::Float(v.as_f32().powf(e.as_f32()))),
+    F::Dot => dot_scalars(&p(0).scalars, &p(1).scalars).into(),
+    F::Outer => {
+      let (a, b) = (p(0), p(1));
+      let n = a.scalars.len();
+      assert_eq!(
+        n,
+        b.scalars.len(),
+        "only the square outer product is supported"
+      );
+      let scalars = b
+        .scalars
+        .iter()
+        .flat_map(|b| {
+          a.scalars
+            .iter()
+            .map(|a| binary_scalar(&BinaryOperator::Mul, *a, *b))
+        })
+        .collect();
+      InterpreterPrimitive::new(PrimitiveShape::Matrix(n), scalars)
+    }
+    F::Cross => {
+      let (a, b) = (floats(p(0)), floats(p(1)));
+      float_vector([
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+      ])
+    }
+    F::Distance => {
+      let diff = binary(&BinaryOperator::Sub, p(0), p(1));
+      S::Float(length(&diff)).into()
+    }
+    F::Length => S::Float(length(p(0))).into(),
+    F::Normalize => {
+      let length = length(p(0));
+      p(0).map_f32(|v| v / length)
+    }
+    F::FaceForward => {
+      let (n, i, reference) = (p(0), p(1), p(2));
+      if dot_scalars(&reference.scalars, &i.scalars).as_f32() < 0. {
+        n.clone()
+      } else {
+        n.map_f32(|v| -v)
+      }
+    }
+    F::Reflect => {
+      let (e1, e2) = (p(0), p(1));
+      let d = dot_scalars(&e2.scalars, &e1.scalars).as_f32();
+      zip_map(e1, e2, |e1, e2| {
+        S::Float(e1.as_f32() - 2. * d * e2.as_f32())
+      })
+    }
+    F::Refract => {
+      let (e1, e2, eta) = (p(0), p(1), p(2).scalar().as_f32());
+      let d = dot_scalars(&e2.scalars, &e1.scalars).as_f32();
+      let k = 1. - eta * eta * (1. - d * d);
+      if k < 0. {
+        e1.map_f32(|_| 0.)
+      } else {
+        zip_map(e1, e2, |e1, e2| {
+          S::Float(eta * e1.as_f32() - (eta * d + k.sqrt()) * e2.as_f32())
+        })
+      }
+    }
+    F::Sign => p(0).map(|v| match v {
+      S::Float(v) if v == 0. => S::Float(0.),
+      S::Float(v) => S::Float(v.signum()),
+      S::Int(v) => S::Int(v.signum()),
+      v => v,
+    }),
+    F::Fma => zip_map3(p(0), p(1), p(2), |a, b, c| {
+      S::Float(a.as_f32().mul_add(b.as_f32(), c.as_f32()))
+    }),
+    F::Mix => zip_map3(p(0), p(1), p(2), |a, b, t| {
+      let (a, b, t) = (a.as_f32(), b.as_f32(), t.as_f32());
+      S::Float(a * (1. - t) + b * t)
+    }),
+    F::Step => zip_map(p(0), p(1), |edge, x| {
+      S::Float(if x.as_f32() >= edge.as_f32() { 1. } else { 0. })
+    }),
+    F::SmoothStep => zip_map3(p(0), p(1), p(2), |low, high, x| {
+      let (low, high, x) = (low.as_f32(), high.as_f32(), x.as_f32());
+      let t = ((x - low) / (high - low)).clamp(0., 1.);
+      S::Float(t * t * (3. - 2. * t))
+    }),
+    F::Sqrt => p(0).map_f32(f32::sqrt),
+    F::InverseSqrt => p(0).map_f32(|v| 1. / v.sqrt()),
+    F::Inverse => {
+      let inverse = to_matrix(
+        p(0),
+        |m| m.inverse().map(PrimitiveShaderValue::Mat2Float32),
+        |m| m.inverse().map(PrimitiveShaderValue::Mat3Float32),
+        |m| m.inverse().map(PrimitiveShaderValue::Mat4Float32),
+      );
+      match inverse {
+        Some(inverse) => inverse.into(),
+        // the singular matrix has no inverse, the result is undefined
+        None => p(0).map_f32(|_| f32::NAN),
+      }
+    }
+    F::Transpose => {
+      let v = p(0);
+      let n = match v.shape {
+        PrimitiveShape::Matrix(n) => n,
+        _ => panic!("expect matrix"),
+      };
+      let scalars = (0..n).flat_map(|c| row(v, n, c)).collect();
+      InterpreterPrimitive::new(v.shape, scalars)
+    }
+    F::Determinant => S::Float(to_matrix(p(0), |m| m.det(), |m| m.det(), |m| m.det())).into(),
+    F::CountTrailingZeros => map_integer(p(0), u32::trailing_zeros),
+    F::CountLeadingZeros => map_integer(p(0), u32::leading_zeros),
+    F::CountOneBits => map_integer(p(0), u32::count_ones),
+    F::ReverseBits => map_integer(p(0), u32::reverse_bits),
+    F::ExtractBits => {
+      let (offset, count) = (p(1).scalar().as_u32(), p(2).scalar().as_u32());
+      p(0).map(|e| extract_bits(e, offset, count))
+    }
+    F::InsertBits => {
+      let (offset, count) = (p(2).scalar().as_u32(), p(3).scalar().as_u32());
+      zip_map(p(0), p(1), |e, new| insert_bits(e, new, offset, count))
+    }
+    F::FindLsb => map_integer(p(0), |v| if v == 0 { u32::MAX } else { v.trailing_zeros() }),
+    F::FindMsb => p(0).map(find_msb),
+    F::Pack4x8snorm | F::Pack2x16snorm => {
+      let max = if function == F::Pack4x8snorm {
+        127.
+      } else {
+        32767.
+      };
+      pack(p(0), |v| (v.clamp(-1., 1.) * max).round() as i32 as u32).into()
+    }
+    F::Pack4x8unorm | F::Pack2x16unorm => {
+      let max = if function == F::Pack4x8unorm {
+        255.
+      } else {
+        65535.
+      };
+      pack(p(0), |v| (v.clamp(0., 1.) * max).round() as u32).into()
+    }
+    F::Pack2x16float => pack(p(0), f32_to_f16).into(),
+    F::Unpack4x8snorm => unpack(p(0).scalar().as_u32(), 4, |v| {
+      (v as u8 as i8 as f32 / 127.).max(-1.)
+    }),
+    F::Unpack2x16snorm => unpack(p(0).scalar().as_u32(), 2, |v| {
+      (v as u16 as i16 as f32 / 32767.).max(-1.)
+    }),
+    F::Unpack4x8unorm => unpack(p(0).scalar().as_u32(), 4, |v| v as f32 / 255.),
+    F::Unpack2x16unorm => unpack(p(0).scalar().as_u32(), 2, |v| v as f32 / 65535.),
+    F::Unpack2x16float => unpack(p(0).scalar().as_u32(), 2, f16_to_f32),
+  };
+  result.into()
+}
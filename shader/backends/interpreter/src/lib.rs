@@ -0,0 +1,350 @@
+#![cfg_attr(test, feature(generic_const_exprs))]
+#![cfg_attr(test, allow(incomplete_features))]
+
+//! The shader api implementation that records the shader graph and interprets it on the cpu, so
+//! the shader logic could be tested without the gpu. Only the base mip level of the texture is
+//! sampled, and the derivative is always zero.
+
+use std::{
+  any::Any,
+  sync::{Arc, Mutex, RwLock},
+};
+
+use fast_hash_collection::*;
+use rendiation_shader_api::*;
+use rendiation_texture::{
+  cube_direction_to_face_uv, CompareFunction, CubeTexture, Size, Texture2D, Texture2DBuffer,
+  Texture2dInitAble, Texture3D, Texture3DBuffer, TextureSampler, CUBE_FACES,
+};
+
+mod builtin;
+mod program;
+mod resource;
+mod value;
+
+pub use program::*;
+pub use resource::*;
+pub use value::*;
+
+#[cfg(test)]
+mod test;
+
+pub struct ShaderAPIInterpreterImpl {
+  stage: ShaderStages,
+  handle_id: usize,
+  block: Vec<(Vec<Statement>, BlockBuildingState)>,
+  control_structure: Vec<Statement>,
+  building_fn: Vec<(String, Vec<ShaderNodeRawHandle>)>,
+  functions: FastHashMap<String, InterpretedFunction>,
+  entry: Vec<Statement>,
+  inputs: Vec<(ShaderNodeRawHandle, ShaderInputNode)>,
+  outputs: Vec<(ShaderFieldDecorator, ShaderNodeRawHandle)>,
+  workgroup_size: (u32, u32, u32),
+  has_barrier: bool,
+}
+
+enum BlockBuildingState {
+  Common,
+  SwitchCase(SwitchCaseCondition),
+  Loop,
+  IfAccept,
+  Else,
+  Function,
+}
+
+const ENTRY_POINT_NAME: &str = "main";
+
+impl ShaderAPIInterpreterImpl {
+  pub fn new(stage: ShaderStages) -> Self {
+    Self {
+      stage,
+      handle_id: 0,
+      block: vec![(Default::default(), BlockBuildingState::Function)],
+      control_structure: Default::default(),
+      building_fn: Default::default(),
+      functions: Default::default(),
+      entry: Default::default(),
+      inputs: Default::default(),
+      outputs: Default::default(),
+      workgroup_size: (0, 0, 0),
+      has_barrier: false,
+    }
+  }
+
+  fn push_top_statement(&mut self, st: Statement) {
+    self.block.last_mut().unwrap().0.push(st);
+  }
+
+  fn make_new_handle(&mut self) -> ShaderNodeRawHandle {
+    self.handle_id += 1;
+    let handle = self.handle_id;
+    ShaderNodeRawHandle { handle }
+  }
+
+  fn define_out(
+    &mut self,
+    ty: PrimitiveShaderValueType,
+    deco: ShaderFieldDecorator,
+  ) -> ShaderNodeRawHandle {
+    assert!(self.block.len() == 1); // we should define output in root scope
+    let ty = ShaderValueType::Single(ShaderValueSingleType::Sized(
+      ShaderSizedValueType::Primitive(ty),
+    ));
+    let r = self.make_local_var(ty);
+    self.outputs.push((deco, r));
+    r
+  }
+}
+
+impl ShaderAPI for ShaderAPIInterpreterImpl {
+  type Output = Box<dyn Any>;
+
+  fn define_module_input(&mut self, input: ShaderInputNode) -> ShaderNodeRawHandle {
+    let handle = self.make_new_handle();
+    self.inputs.push((handle, input));
+    handle
+  }
+
+  fn define_next_frag_out(&mut self) -> ShaderNodeRawHandle {
+    let location = ShaderFieldDecorator::Location(self.outputs.len());
+    self.define_out(PrimitiveShaderValueType::Vec4Float32, location)
+  }
+
+  fn define_next_vertex_output(&mut self, ty: PrimitiveShaderValueType) -> ShaderNodeRawHandle {
+    let location = ShaderFieldDecorator::Location(self.outputs.len());
+    self.define_out(ty, location)
+  }
+
+  fn define_vertex_position_output(&mut self) -> ShaderNodeRawHandle {
+    self.define_out(
+      PrimitiveShaderValueType::Vec4Float32,
+      ShaderFieldDecorator::BuiltIn(ShaderBuiltInDecorator::VertexPositionOut),
+    )
+  }
+
+  fn define_frag_depth_output(&mut self) -> ShaderNodeRawHandle {
+    self.define_out(
+      PrimitiveShaderValueType::Float32,
+      ShaderFieldDecorator::BuiltIn(ShaderBuiltInDecorator::FragDepth),
+    )
+  }
+
+  fn make_expression(&mut self, expr: ShaderNodeExpr) -> ShaderNodeRawHandle {
+    let handle = self.make_new_handle();
+    self.push_top_statement(Statement::Expression { handle, expr });
+    handle
+  }
+
+  fn make_local_var(&mut self, ty: ShaderValueType) -> ShaderNodeRawHandle {
+    let handle = self.make_new_handle();
+    self.push_top_statement(Statement::LocalVar { handle, ty });
+    handle
+  }
+
+  fn store(&mut self, source: ShaderNodeRawHandle, target: ShaderNodeRawHandle) {
+    self.push_top_statement(Statement::Store { source, target });
+  }
+
+  fn load(&mut self, source: ShaderNodeRawHandle) -> ShaderNodeRawHandle {
+    let handle = self.make_new_handle();
+    self.push_top_statement(Statement::Load { handle, source });
+    handle
+  }
+
+  fn push_scope(&mut self) {
+    self
+      .block
+      .push((Default::default(), BlockBuildingState::Common))
+  }
+
+  fn pop_scope(&mut self) {
+    let (b, ty) = self.block.pop().unwrap();
+    match ty {
+      BlockBuildingState::Common => self.push_top_statement(Statement::Block(b)),
+      BlockBuildingState::SwitchCase(case) => {
+        if let Some(Statement::Switch { cases, .. }) = self.control_structure.last_mut() {
+          cases.push((case, b))
+        } else {
+          panic!("expect switch")
+        }
+      }
+      BlockBuildingState::Loop => {
+        let loop_s = self.control_structure.pop().unwrap();
+        assert!(matches!(loop_s, Statement::Loop(_)), "expect loop");
+        self.push_top_statement(Statement::Loop(b));
+      }
+      BlockBuildingState::IfAccept => {
+        let mut if_s = self.control_structure.pop().unwrap();
+        if let Statement::If { accept, .. } = &mut if_s {
+          *accept = b;
+        } else {
+          panic!("expect if")
+        }
+        self.push_top_statement(if_s);
+      }
+      BlockBuildingState::Else => {
+        let mut if_s = self.control_structure.pop().unwrap();
+        if let Statement::If { reject, .. } = &mut if_s {
+          *reject = b;
+        } else {
+          panic!("expect if")
+        }
+        self.push_top_statement(if_s);
+      }
+      BlockBuildingState::Function => match self.building_fn.pop() {
+        Some((name, parameters)) => {
+          let body = b;
+          self
+            .functions
+            .insert(name, InterpretedFunction { parameters, body });
+        }
+        // is entry
+        None => self.entry = b,
+      },
+    }
+  }
+
+  fn push_if_scope(&mut self, condition: ShaderNodeRawHandle) {
+    self
+      .block
+      .push((Default::default(), BlockBuildingState::IfAccept));
+    let if_s = Statement::If {
+      condition,
+      accept: Default::default(),
+      reject: Default::default(),
+    };
+    self.control_structure.push(if_s);
+  }
+
+  fn push_else_scope(&mut self) {
+    let if_s = self.block.last_mut().unwrap().0.pop().unwrap();
+    assert!(matches!(if_s, Statement::If { .. }));
+    self.control_structure.push(if_s);
+    self
+      .block
+      .push((Default::default(), BlockBuildingState::Else));
+  }
+
+  fn push_loop_scope(&mut self) {
+    self
+      .block
+      .push((Default::default(), BlockBuildingState::Loop));
+    self
+      .control_structure
+      .push(Statement::Loop(Default::default()));
+  }
+
+  fn do_continue(&mut self) {
+    self.push_top_statement(Statement::Continue);
+  }
+  fn do_break(&mut self) {
+    self.push_top_statement(Statement::Break);
+  }
+
+  fn begin_switch(&mut self, selector: ShaderNodeRawHandle) {
+    let switch = Statement::Switch {
+      selector,
+      cases: Default::default(),
+    };
+    self.control_structure.push(switch);
+  }
+
+  fn push_switch_case_scope(&mut self, case: SwitchCaseCondition) {
+    self
+      .block
+      .push((Default::default(), BlockBuildingState::SwitchCase(case)));
+  }
+
+  fn end_switch(&mut self) {
+    let switch = self.control_structure.pop().unwrap();
+    assert!(matches!(switch, Statement::Switch { .. }));
+    self.push_top_statement(switch);
+  }
+
+  fn discard(&mut self) {
+    self.push_top_statement(Statement::Discard);
+  }
+
+  fn texture_store(&mut self, store: ShaderTextureStore) {
+    self.push_top_statement(Statement::TextureStore(store));
+  }
+
+  fn barrier(&mut self, _scope: BarrierScope) {
+    // the memory is always coherent in the interpreter, so all the barriers are the same
+    self.has_barrier = true;
+    self.push_top_statement(Statement::Barrier);
+  }
+
+  fn set_workgroup_size(&mut self, size: (u32, u32, u32)) {
+    self.workgroup_size = size;
+  }
+
+  fn get_fn(&mut self, name: String) -> Option<ShaderUserDefinedFunction> {
+    self
+      .functions
+      .contains_key(&name)
+      .then_some(ShaderUserDefinedFunction { name })
+  }
+
+  fn begin_define_fn(&mut self, name: String, _return_ty: Option<ShaderValueType>) {
+    if self.building_fn.iter().any(|(f, _)| f.eq(&name)) {
+      panic!("recursive fn definition is not allowed")
+    }
+
+    assert!(self.functions.get(&name).is_none(), "function redefinition");
+
+    self.building_fn.push((name, Vec::new()));
+    self
+      .block
+      .push((Default::default(), BlockBuildingState::Function));
+  }
+
+  fn push_fn_parameter(&mut self, _ty: ShaderValueType) -> ShaderNodeRawHandle {
+    let handle = self.make_new_handle();
+    self.building_fn.last_mut().unwrap().1.push(handle);
+    handle
+  }
+
+  fn do_return(&mut self, v: Option<ShaderNodeRawHandle>) {
+    self.push_top_statement(Statement::Return(v));
+  }
+
+  fn end_fn_define(&mut self) -> ShaderUserDefinedFunction {
+    let (_, s) = self.block.last().unwrap();
+    let f_name = self.building_fn.last().unwrap().0.clone();
+    assert!(matches!(s, BlockBuildingState::Function));
+    self.pop_scope();
+    ShaderUserDefinedFunction { name: f_name }
+  }
+
+  fn build(&mut self) -> (String, Self::Output) {
+    self.pop_scope();
+
+    let program = ShaderInterpreterProgram {
+      stage: self.stage,
+      entry: std::mem::take(&mut self.entry),
+      functions: std::mem::take(&mut self.functions),
+      inputs: std::mem::take(&mut self.inputs),
+      outputs: std::mem::take(&mut self.outputs),
+      workgroup_size: self.workgroup_size,
+      has_barrier: self.has_barrier,
+    };
+
+    (ENTRY_POINT_NAME.to_owned(), Box::new(program))
+  }
+}
+
+/// Build the shader logic in the compute stage and interpret it by a single invocation, the
+/// returned node is read back as the rust value. This is the convenient way to test the shader
+/// function against the rust reference implementation.
+pub fn interpret_by<T: ReadInterpreterValue>(
+  logic: impl FnOnce(&mut ShaderComputePipelineBuilder) -> Node<T>,
+) -> T {
+  let api = ShaderAPIInterpreterImpl::new(ShaderStages::Compute);
+  let mut builder = ShaderComputePipelineBuilder::new(Box::new(api));
+  let node = logic(&mut builder);
+  let program = ShaderInterpreterProgram::from_built(builder.build().unwrap().shader);
+  program
+    .invoke(&Default::default(), &Default::default())
+    .read(node)
+}
@@ -0,0 +1,636 @@
+use std::sync::Condvar;
+
+use crate::*;
+
+/// The recorded shader statement, the nodes are evaluated in the recording order.
+pub(crate) enum Statement {
+  Expression {
+    handle: ShaderNodeRawHandle,
+    expr: ShaderNodeExpr,
+  },
+  LocalVar {
+    handle: ShaderNodeRawHandle,
+    ty: ShaderValueType,
+  },
+  Load {
+    handle: ShaderNodeRawHandle,
+    source: ShaderNodeRawHandle,
+  },
+  Store {
+    source: ShaderNodeRawHandle,
+    target: ShaderNodeRawHandle,
+  },
+  Block(Vec<Statement>),
+  If {
+    condition: ShaderNodeRawHandle,
+    accept: Vec<Statement>,
+    reject: Vec<Statement>,
+  },
+  Loop(Vec<Statement>),
+  Switch {
+    selector: ShaderNodeRawHandle,
+    cases: Vec<(SwitchCaseCondition, Vec<Statement>)>,
+  },
+  Continue,
+  Break,
+  Return(Option<ShaderNodeRawHandle>),
+  Discard,
+  TextureStore(ShaderTextureStore),
+  Barrier,
+}
+
+pub(crate) struct InterpretedFunction {
+  pub parameters: Vec<ShaderNodeRawHandle>,
+  pub body: Vec<Statement>,
+}
+
+/// The built result of the [ShaderAPIInterpreterImpl], which could be executed on the cpu.
+pub struct ShaderInterpreterProgram {
+  pub(crate) stage: ShaderStages,
+  pub(crate) entry: Vec<Statement>,
+  pub(crate) functions: FastHashMap<String, InterpretedFunction>,
+  pub(crate) inputs: Vec<(ShaderNodeRawHandle, ShaderInputNode)>,
+  pub(crate) outputs: Vec<(ShaderFieldDecorator, ShaderNodeRawHandle)>,
+  pub(crate) workgroup_size: (u32, u32, u32),
+  pub(crate) has_barrier: bool,
+}
+
+/// The stage inputs of one invocation, the not provided input is zero.
+#[derive(Default, Clone)]
+pub struct ShaderInvocationInput {
+  pub builtins: Vec<(ShaderBuiltInDecorator, PrimitiveShaderValue)>,
+  /// the user defined inputs by location
+  pub locations: Vec<PrimitiveShaderValue>,
+}
+
+impl ShaderInvocationInput {
+  fn builtin(&self, ty: ShaderBuiltInDecorator) -> InterpreterPrimitive {
+    let ty = std::mem::discriminant(&ty);
+    self
+      .builtins
+      .iter()
+      .find(|(b, _)| std::mem::discriminant(b) == ty)
+      .map(|(_, v)| (*v).into())
+      .unwrap_or_else(|| InterpreterPrimitive::zeroed(builtin_type(ty)))
+  }
+}
+
+fn builtin_type(ty: std::mem::Discriminant<ShaderBuiltInDecorator>) -> PrimitiveShaderValueType {
+  use ShaderBuiltInDecorator::*;
+  let is = |b: ShaderBuiltInDecorator| std::mem::discriminant(&b) == ty;
+  if is(FrontFacing) {
+    PrimitiveShaderValueType::Bool
+  } else if is(FragDepth) {
+    PrimitiveShaderValueType::Float32
+  } else if is(FragmentPositionIn) || is(VertexPositionOut) {
+    PrimitiveShaderValueType::Vec4Float32
+  } else if is(CompGlobalInvocationId)
+    || is(CompLocalInvocationId)
+    || is(CompWorkgroupId)
+    || is(CompNumWorkgroups)
+  {
+    PrimitiveShaderValueType::Vec3Uint32
+  } else {
+    PrimitiveShaderValueType::Uint32
+  }
+}
+
+pub struct ShaderInvocationResult {
+  /// The stage outputs in the defining order
+  pub outputs: Vec<(ShaderFieldDecorator, InterpreterValue)>,
+  /// If the fragment invocation is discarded
+  pub discarded: bool,
+  values: FastHashMap<ShaderNodeRawHandle, InterpreterValue>,
+}
+
+impl ShaderInvocationResult {
+  /// The value of the node evaluated in the entry scope, None if the node is not evaluated, for
+  /// example defined in the not executed branch or defined in the function.
+  pub fn value<T: ?Sized>(&self, node: Node<T>) -> Option<&InterpreterValue> {
+    self.values.get(&node.handle())
+  }
+
+  pub fn read<T: ReadInterpreterValue>(&self, node: Node<T>) -> T {
+    self.value(node).expect("the node is not evaluated").read()
+  }
+}
+
+enum Flow {
+  Next,
+  Continue,
+  Break,
+  Return(Option<InterpreterValue>),
+  Discard,
+}
+
+type Frame = FastHashMap<ShaderNodeRawHandle, InterpreterValue>;
+
+struct Invocation<'a> {
+  program: &'a ShaderInterpreterProgram,
+  globals: Frame,
+  barrier: Option<&'a WorkgroupBarrier>,
+  discarded: bool,
+}
+
+impl ShaderInterpreterProgram {
+  /// Extract the program from the shader built by the [ShaderAPIInterpreterImpl]
+  pub fn from_built(shader: (String, Box<dyn Any>)) -> Self {
+    *shader
+      .1
+      .downcast()
+      .expect("the shader is not built by the interpreter")
+  }
+
+  pub fn workgroup_size(&self) -> (u32, u32, u32) {
+    self.workgroup_size
+  }
+
+  fn create_workgroup_shared(&self) -> Frame {
+    self
+      .inputs
+      .iter()
+      .filter_map(|(handle, input)| match input {
+        ShaderInputNode::WorkGroupShared { ty } => {
+          let value = InterpreterPointer::new(InterpreterValue::zeroed(ty));
+          Some((*handle, value.into()))
+        }
+        _ => None,
+      })
+      .collect()
+  }
+
+  fn run(
+    &self,
+    bindings: &InterpreterBindings,
+    input: &ShaderInvocationInput,
+    workgroup_shared: &Frame,
+    barrier: Option<&WorkgroupBarrier>,
+  ) -> ShaderInvocationResult {
+    let globals = self
+      .inputs
+      .iter()
+      .map(|(handle, node)| {
+        let value = match node {
+          ShaderInputNode::BuiltIn(ty) => input.builtin(*ty).into(),
+          ShaderInputNode::UserDefinedIn { ty, location } => input
+            .locations
+            .get(*location)
+            .map(|v| InterpreterPrimitive::from(*v))
+            .unwrap_or_else(|| InterpreterPrimitive::zeroed(*ty))
+            .into(),
+          ShaderInputNode::Binding {
+            desc,
+            bindgroup_index,
+            entry_index,
+          } => bindings.resolve(desc, *bindgroup_index, *entry_index),
+          ShaderInputNode::WorkGroupShared { .. } => workgroup_shared[handle].clone(),
+        };
+        (*handle, value)
+      })
+      .collect();
+
+    let mut invocation = Invocation {
+      program: self,
+      globals,
+      barrier,
+      discarded: false,
+    };
+    let mut frame = Frame::default();
+    invocation.execute(&mut frame, &self.entry);
+
+    let outputs = self
+      .outputs
+      .iter()
+      .filter_map(|(deco, handle)| Some((*deco, frame.get(handle)?.as_pointer().load())))
+      .collect();
+
+    ShaderInvocationResult {
+      outputs,
+      discarded: invocation.discarded,
+      values: frame,
+    }
+  }
+
+  /// Execute one invocation. For the compute stage, the workgroup shared variables are only
+  /// visible to this invocation and the barrier is a no-op.
+  pub fn invoke(
+    &self,
+    bindings: &InterpreterBindings,
+    input: &ShaderInvocationInput,
+  ) -> ShaderInvocationResult {
+    self.run(bindings, input, &self.create_workgroup_shared(), None)
+  }
+
+  /// Execute all the invocations of the compute dispatch. The invocations in the workgroup are
+  /// executed in parallel threads if the barrier is used, otherwise sequentially.
+  pub fn dispatch(&self, bindings: &InterpreterBindings, workgroup_count: (u32, u32, u32)) {
+    assert!(
+      self.stage == ShaderStages::Compute,
+      "only the compute shader could be dispatched"
+    );
+
+    let (size_x, size_y, size_z) = self.workgroup_size;
+    let (count_x, count_y, count_z) = workgroup_count;
+    let num_workgroups = Vec3::new(count_x, count_y, count_z);
+    let size = Vec3::new(size_x, size_y, size_z);
+    let invocation_count = (size_x * size_y * size_z) as usize;
+
+    let grid = |(x, y, z): (u32, u32, u32)| {
+      (0..z).flat_map(move |k| (0..y).flat_map(move |j| (0..x).map(move |i| Vec3::new(i, j, k))))
+    };
+
+    for workgroup_id in grid(workgroup_count) {
+      let shared = self.create_workgroup_shared();
+      let input = |local_id: Vec3<u32>| {
+        use PrimitiveShaderValue::*;
+        use ShaderBuiltInDecorator::*;
+        let local_index = (local_id.z * size_y + local_id.y) * size_x + local_id.x;
+        let global_id = Vec3::new(
+          workgroup_id.x * size.x + local_id.x,
+          workgroup_id.y * size.y + local_id.y,
+          workgroup_id.z * size.z + local_id.z,
+        );
+        ShaderInvocationInput {
+          builtins: vec![
+            (CompGlobalInvocationId, Vec3Uint32(global_id)),
+            (CompLocalInvocationId, Vec3Uint32(local_id)),
+            (CompLocalInvocationIndex, Uint32(local_index)),
+            (CompWorkgroupId, Vec3Uint32(workgroup_id)),
+            (CompNumWorkgroups, Vec3Uint32(num_workgroups)),
+          ],
+          locations: Vec::new(),
+        }
+      };
+
+      if self.has_barrier {
+        let barrier = WorkgroupBarrier::new(invocation_count);
+        std::thread::scope(|s| {
+          for local_id in grid(self.workgroup_size) {
+            let input = input(local_id);
+            let (shared, barrier) = (&shared, &barrier);
+            s.spawn(move || {
+              let _leave = barrier.leave_on_drop();
+              self.run(bindings, &input, shared, Some(barrier));
+            });
+          }
+        });
+      } else {
+        for local_id in grid(self.workgroup_size) {
+          self.run(bindings, &input(local_id), &shared, None);
+        }
+      }
+    }
+  }
+}
+
+impl<'a> Invocation<'a> {
+  fn get(&self, frame: &Frame, handle: ShaderNodeRawHandle) -> InterpreterValue {
+    frame
+      .get(&handle)
+      .or_else(|| self.globals.get(&handle))
+      .expect("the node is used before evaluated")
+      .clone()
+  }
+
+  fn primitive(&self, frame: &Frame, handle: ShaderNodeRawHandle) -> InterpreterPrimitive {
+    match self.get(frame, handle) {
+      InterpreterValue::Primitive(v) => v,
+      _ => panic!("expect primitive value"),
+    }
+  }
+
+  fn index(&self, frame: &Frame, handle: Option<ShaderNodeRawHandle>) -> Option<usize> {
+    handle.map(|h| self.primitive(frame, h).scalar().as_index())
+  }
+
+  fn execute(&mut self, frame: &mut Frame, block: &[Statement]) -> Flow {
+    for statement in block {
+      let flow = match statement {
+        Statement::Expression { handle, expr } => {
+          let value = self.evaluate(frame, expr);
+          frame.insert(*handle, value);
+          Flow::Next
+        }
+        Statement::LocalVar { handle, ty } => {
+          let value = match ty {
+            ShaderValueType::Single(ShaderValueSingleType::Sized(ty)) => {
+              InterpreterValue::zeroed(ty)
+            }
+            _ => panic!("the local variable should be sized"),
+          };
+          frame.insert(*handle, InterpreterPointer::new(value).into());
+          Flow::Next
+        }
+        Statement::Load { handle, source } => {
+          let value = match self.get(frame, *source) {
+            InterpreterValue::Pointer(ptr) => ptr.load(),
+            value => value,
+          };
+          frame.insert(*handle, value);
+          Flow::Next
+        }
+        Statement::Store { source, target } => {
+          let value = self.get(frame, *source);
+          self.get(frame, *target).as_pointer().store(value);
+          Flow::Next
+        }
+        Statement::Block(block) => self.execute(frame, block),
+        Statement::If {
+          condition,
+          accept,
+          reject,
+        } => {
+          if self.primitive(frame, *condition).scalar().as_bool() {
+            self.execute(frame, accept)
+          } else {
+            self.execute(frame, reject)
+          }
+        }
+        Statement::Loop(body) => loop {
+          match self.execute(frame, body) {
+            Flow::Next | Flow::Continue => {}
+            Flow::Break => break Flow::Next,
+            flow => break flow,
+          }
+        },
+        Statement::Switch { selector, cases } => {
+          let selector = self.primitive(frame, *selector).scalar();
+          let matched = |case: &SwitchCaseCondition| match (case, selector) {
+            (SwitchCaseCondition::U32(v), ScalarValue::Uint(s)) => *v == s,
+            (SwitchCaseCondition::I32(v), ScalarValue::Int(s)) => *v == s,
+            _ => false,
+          };
+          let case = cases.iter().find(|(case, _)| matched(case)).or_else(|| {
+            cases
+              .iter()
+              .find(|(case, _)| matches!(case, SwitchCaseCondition::Default))
+          });
+          match case.map(|(_, body)| self.execute(frame, body)) {
+            // the break in the switch case exits the switch
+            Some(Flow::Break) | None => Flow::Next,
+            Some(flow) => flow,
+          }
+        }
+        Statement::Continue => Flow::Continue,
+        Statement::Break => Flow::Break,
+        Statement::Return(value) => Flow::Return(value.map(|v| self.get(frame, v))),
+        Statement::Discard => {
+          self.discarded = true;
+          Flow::Discard
+        }
+        Statement::TextureStore(store) => {
+          let texture = self.get(frame, store.texture);
+          let position = self.primitive(frame, store.position);
+          let index = self.index(frame, store.index);
+          let value = self.primitive(frame, store.value);
+          texture.as_texture().store(&position, index, &value);
+          Flow::Next
+        }
+        Statement::Barrier => {
+          if let Some(barrier) = self.barrier {
+            barrier.wait();
+          }
+          Flow::Next
+        }
+      };
+
+      // the discard in the function call terminates the invocation
+      if self.discarded {
+        return Flow::Discard;
+      }
+      if !matches!(flow, Flow::Next) {
+        return flow;
+      }
+    }
+    Flow::Next
+  }
+
+  fn call(&mut self, name: &str, parameters: Vec<InterpreterValue>) -> InterpreterValue {
+    let function = self
+      .program
+      .functions
+      .get(name)
+      .unwrap_or_else(|| panic!("function {name} is not defined"));
+    let mut frame: Frame = function
+      .parameters
+      .iter()
+      .copied()
+      .zip(parameters)
+      .collect();
+    match self.execute(&mut frame, &function.body) {
+      Flow::Return(Some(value)) => value,
+      _ => InterpreterValue::void(),
+    }
+  }
+
+  fn evaluate(&mut self, frame: &mut Frame, expr: &ShaderNodeExpr) -> InterpreterValue {
+    match expr {
+      ShaderNodeExpr::Convert {
+        source,
+        convert_to,
+        convert,
+      } => builtin::convert(&self.primitive(frame, *source), convert_to, *convert).into(),
+      ShaderNodeExpr::FunctionCall { meta, parameters } => match meta {
+        ShaderFunctionType::Custom(f) => {
+          let parameters = parameters.iter().map(|p| self.get(frame, *p)).collect();
+          self.call(&f.name, parameters)
+        }
+        ShaderFunctionType::BuiltIn(f) => {
+          let parameters: Vec<_> = parameters
+            .iter()
+            .map(|p| self.primitive(frame, *p))
+            .collect();
+          builtin::call_builtin(*f, &parameters)
+        }
+      },
+      ShaderNodeExpr::TextureSampling {
+        texture,
+        sampler,
+        position,
+        index,
+        reference,
+        offset,
+        ..
+      } => {
+        let texture = self.get(frame, *texture);
+        let sampler = self.get(frame, *sampler);
+        let position = self.primitive(frame, *position);
+        let index = self.index(frame, *index);
+        let reference = reference.map(|r| self.primitive(frame, r).scalar().as_f32());
+        texture
+          .as_texture()
+          .sample(sampler.as_sampler(), &position, index, reference, *offset)
+          .into()
+      }
+      ShaderNodeExpr::TextureLoad {
+        texture,
+        position,
+        index,
+        ..
+      } => {
+        let texture = self.get(frame, *texture);
+        let position = self.primitive(frame, *position);
+        let index = self.index(frame, *index);
+        texture.as_texture().load(&position, index).into()
+      }
+      ShaderNodeExpr::ArrayLength { array } => {
+        let length = match self.get(frame, *array).as_pointer().load() {
+          InterpreterValue::Composite(elements) => elements.len(),
+          _ => panic!("expect array"),
+        };
+        InterpreterPrimitive::from(ScalarValue::Uint(length as u32)).into()
+      }
+      ShaderNodeExpr::AtomicCall {
+        pointer,
+        function,
+        value,
+        ..
+      } => {
+        let value = self.primitive(frame, *value).scalar();
+        let compare = match function {
+          AtomicFunction::Exchange { compare } => {
+            compare.map(|c| self.primitive(frame, c).scalar())
+          }
+          _ => None,
+        };
+        let old = self.get(frame, *pointer).as_pointer().modify(|current| {
+          let old = current.as_primitive().scalar();
+          let new = builtin::atomic(function, old, value, compare);
+          *current = InterpreterPrimitive::from(new).into();
+          old
+        });
+        let old_value = InterpreterPrimitive::from(old).into();
+        match compare {
+          // the compare exchange returns the old value and if the value is exchanged
+          Some(compare) => {
+            let exchanged = InterpreterPrimitive::from(ScalarValue::Bool(compare == old));
+            InterpreterValue::Composite(vec![old_value, exchanged.into()])
+          }
+          None => old_value,
+        }
+      }
+      ShaderNodeExpr::Swizzle { ty, source } => {
+        let source = self.primitive(frame, *source);
+        let indices: Vec<usize> = ty
+          .chars()
+          .map(|c| match c {
+            'x' | 'r' => 0,
+            'y' | 'g' => 1,
+            'z' | 'b' => 2,
+            'w' | 'a' => 3,
+            _ => panic!("invalid swizzle {ty}"),
+          })
+          .collect();
+        if let [index] = indices.as_slice() {
+          source.component(*index).into()
+        } else {
+          let scalars = indices.iter().map(|i| source.scalars[*i]).collect();
+          InterpreterPrimitive::new(PrimitiveShape::Vector(indices.len()), scalars).into()
+        }
+      }
+      // every invocation is executed alone, so there is no neighbor to compute the derivative
+      ShaderNodeExpr::Derivative { source, .. } => {
+        self.primitive(frame, *source).map_f32(|_| 0.).into()
+      }
+      ShaderNodeExpr::Compose { target, parameters } => {
+        let (shape, _) = primitive_layout(*target);
+        let scalars = parameters
+          .iter()
+          .flat_map(|p| self.primitive(frame, *p).scalars)
+          .collect();
+        InterpreterPrimitive::new(shape, scalars).into()
+      }
+      ShaderNodeExpr::Operator(operator) => match operator {
+        OperatorNode::Unary { one, operator } => {
+          builtin::unary(operator, &self.primitive(frame, *one)).into()
+        }
+        OperatorNode::Binary {
+          left,
+          right,
+          operator,
+        } => {
+          let left = self.primitive(frame, *left);
+          let right = self.primitive(frame, *right);
+          builtin::binary(operator, &left, &right).into()
+        }
+        OperatorNode::Index { array, entry } => {
+          let index = self.primitive(frame, *entry).scalar().as_index();
+          self.get(frame, *array).access(index)
+        }
+        OperatorNode::IndexStatic { array, entry } => {
+          self.get(frame, *array).access(*entry as usize)
+        }
+      },
+      ShaderNodeExpr::FieldGet {
+        field_index,
+        struct_node,
+      } => self.get(frame, *struct_node).access(*field_index),
+      ShaderNodeExpr::StructConstruct { fields, .. } => {
+        InterpreterValue::Composite(fields.iter().map(|f| self.get(frame, *f)).collect())
+      }
+      ShaderNodeExpr::Const { data } => InterpreterPrimitive::from(*data).into(),
+    }
+  }
+}
+
+/// The barrier of the workgroup invocations. The finished invocation leaves the barrier, so the
+/// others will not wait for it forever even if it returns early or panics.
+struct WorkgroupBarrier {
+  /// the running invocation count, the arrived count and the generation of the barrier
+  state: Mutex<(usize, usize, usize)>,
+  condvar: Condvar,
+}
+
+struct BarrierLeaveGuard<'a>(&'a WorkgroupBarrier);
+
+impl<'a> Drop for BarrierLeaveGuard<'a> {
+  fn drop(&mut self) {
+    self.0.leave();
+  }
+}
+
+impl WorkgroupBarrier {
+  fn new(count: usize) -> Self {
+    Self {
+      state: Mutex::new((count, 0, 0)),
+      condvar: Condvar::new(),
+    }
+  }
+
+  fn leave_on_drop(&self) -> BarrierLeaveGuard {
+    BarrierLeaveGuard(self)
+  }
+
+  fn release_if_all_arrived(&self, state: &mut (usize, usize, usize)) -> bool {
+    let (running, arrived, generation) = state;
+    if *arrived >= *running {
+      *arrived = 0;
+      *generation += 1;
+      self.condvar.notify_all();
+      true
+    } else {
+      false
+    }
+  }
+
+  fn wait(&self) {
+    let mut state = self.state.lock().unwrap();
+    state.1 += 1;
+    if self.release_if_all_arrived(&mut state) {
+      return;
+    }
+    let generation = state.2;
+    let _state = self
+      .condvar
+      .wait_while(state, |state| state.2 == generation)
+      .unwrap();
+  }
+
+  fn leave(&self) {
+    let mut state = self.state.lock().unwrap();
+    state.0 -= 1;
+    if state.1 > 0 {
+      self.release_if_all_arrived(&mut state);
+    }
+  }
+}
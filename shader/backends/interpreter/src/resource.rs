@@ -0,0 +1,337 @@
+use crate::*;
+
+/// How the texel stored in the [InterpreterTextureData] is returned to the shader. The integer
+/// texels are stored as the bits of the f32 channels, so they are kept exactly.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InterpreterTexelKind {
+  Float,
+  /// only the first channel is returned
+  Depth,
+  Uint,
+  Sint,
+}
+
+pub enum InterpreterTextureData {
+  /// The layers of the 1d, 2d, 2d array, cube and cube array texture. The 1d texture has the
+  /// height of 1, the cube faces are in the [CUBE_FACES] order.
+  Layers(Vec<Texture2DBuffer<Vec4<f32>>>),
+  D3(Texture3DBuffer<Vec4<f32>>),
+}
+
+/// The texture shared by all the invocations, only the base mip level is provided so the level
+/// of the sampling is ignored.
+#[derive(Clone)]
+pub struct InterpreterTexture {
+  dimension: TextureViewDimension,
+  kind: InterpreterTexelKind,
+  data: Arc<RwLock<InterpreterTextureData>>,
+}
+
+impl InterpreterTexture {
+  pub fn new(
+    dimension: TextureViewDimension,
+    kind: InterpreterTexelKind,
+    data: InterpreterTextureData,
+  ) -> Self {
+    Self {
+      dimension,
+      kind,
+      data: Arc::new(RwLock::new(data)),
+    }
+  }
+
+  pub fn d1(texels: Vec<Vec4<f32>>) -> Self {
+    let size = Size::from_usize_pair_min_one((texels.len(), 1));
+    let layer = Texture2DBuffer::from_raw(texels, size);
+    let data = InterpreterTextureData::Layers(vec![layer]);
+    Self::new(TextureViewDimension::D1, InterpreterTexelKind::Float, data)
+  }
+
+  pub fn d2(texture: Texture2DBuffer<Vec4<f32>>) -> Self {
+    let data = InterpreterTextureData::Layers(vec![texture]);
+    Self::new(TextureViewDimension::D2, InterpreterTexelKind::Float, data)
+  }
+
+  pub fn d2_array(layers: Vec<Texture2DBuffer<Vec4<f32>>>) -> Self {
+    let data = InterpreterTextureData::Layers(layers);
+    Self::new(
+      TextureViewDimension::D2Array,
+      InterpreterTexelKind::Float,
+      data,
+    )
+  }
+
+  pub fn d3(texture: Texture3DBuffer<Vec4<f32>>) -> Self {
+    let data = InterpreterTextureData::D3(texture);
+    Self::new(TextureViewDimension::D3, InterpreterTexelKind::Float, data)
+  }
+
+  pub fn cube(cube: CubeTexture<Vec4<f32>, Texture2DBuffer<Vec4<f32>>>) -> Self {
+    let data = InterpreterTextureData::Layers(cube.into_faces().into());
+    Self::new(
+      TextureViewDimension::Cube,
+      InterpreterTexelKind::Float,
+      data,
+    )
+  }
+
+  pub fn cube_array(cubes: Vec<CubeTexture<Vec4<f32>, Texture2DBuffer<Vec4<f32>>>>) -> Self {
+    let layers = cubes.into_iter().flat_map(|c| c.into_faces()).collect();
+    let data = InterpreterTextureData::Layers(layers);
+    Self::new(
+      TextureViewDimension::CubeArray,
+      InterpreterTexelKind::Float,
+      data,
+    )
+  }
+
+  pub fn depth_2d(texture: &Texture2DBuffer<f32>) -> Self {
+    let data = InterpreterTextureData::Layers(vec![depth_layer(texture)]);
+    Self::new(TextureViewDimension::D2, InterpreterTexelKind::Depth, data)
+  }
+
+  pub fn depth_2d_array(layers: &[Texture2DBuffer<f32>]) -> Self {
+    let data = InterpreterTextureData::Layers(layers.iter().map(depth_layer).collect());
+    Self::new(
+      TextureViewDimension::D2Array,
+      InterpreterTexelKind::Depth,
+      data,
+    )
+  }
+
+  /// The zero initialized 2d storage texture, the kind should match the texel type of the
+  /// storage format.
+  pub fn storage_2d(size: Size, kind: InterpreterTexelKind) -> Self {
+    let layer = Texture2DBuffer::init_not_care(size);
+    let data = InterpreterTextureData::Layers(vec![layer]);
+    Self::new(TextureViewDimension::D2, kind, data)
+  }
+
+  /// Read the texel in the raw storage, the z is the layer index or the depth of the 3d texture.
+  /// Return None if the position is out of bound.
+  pub fn texel(&self, position: Vec3<usize>) -> Option<Vec4<f32>> {
+    let data = self.data.read().unwrap();
+    match &*data {
+      InterpreterTextureData::Layers(layers) => {
+        let layer = layers.get(position.z)?;
+        let (width, height) = layer.size().into_usize();
+        (position.x < width && position.y < height).then(|| layer.read((position.x, position.y)))
+      }
+      InterpreterTextureData::D3(texture) => {
+        let (width, height) = texture.size().into_usize();
+        let inside = position.x < width && position.y < height && position.z < texture.depth();
+        inside.then(|| texture.read(position))
+      }
+    }
+  }
+
+  fn write_texel(&self, position: Vec3<usize>, value: Vec4<f32>) {
+    let mut data = self.data.write().unwrap();
+    match &mut *data {
+      InterpreterTextureData::Layers(layers) => {
+        if let Some(layer) = layers.get_mut(position.z) {
+          let (width, height) = layer.size().into_usize();
+          if position.x < width && position.y < height {
+            layer.write((position.x, position.y), value);
+          }
+        }
+      }
+      InterpreterTextureData::D3(texture) => {
+        let (width, height) = texture.size().into_usize();
+        if position.x < width && position.y < height && position.z < texture.depth() {
+          texture.write(position, value);
+        }
+      }
+    }
+  }
+
+  /// The texel read from the raw storage to the shader value
+  fn to_shader_texel(&self, texel: Vec4<f32>) -> InterpreterPrimitive {
+    let channels = [texel.x, texel.y, texel.z, texel.w];
+    let scalars = match self.kind {
+      InterpreterTexelKind::Float => channels.map(ScalarValue::Float),
+      InterpreterTexelKind::Depth => return ScalarValue::Float(texel.x).into(),
+      InterpreterTexelKind::Uint => channels.map(|c| ScalarValue::Uint(c.to_bits())),
+      InterpreterTexelKind::Sint => channels.map(|c| ScalarValue::Int(c.to_bits() as i32)),
+    };
+    InterpreterPrimitive::new(PrimitiveShape::Vector(4), scalars.to_vec())
+  }
+
+  /// The texel position with the layer index merged in the z component
+  fn texel_position(&self, position: &InterpreterPrimitive, index: Option<usize>) -> Vec3<usize> {
+    let mut p = position.scalars.iter().map(|v| v.as_index());
+    let x = p.next().unwrap();
+    let y = p.next().unwrap_or(0);
+    let z = p.next().or(index).unwrap_or(0);
+    Vec3::new(x, y, z)
+  }
+
+  pub(crate) fn load(
+    &self,
+    position: &InterpreterPrimitive,
+    index: Option<usize>,
+  ) -> InterpreterPrimitive {
+    let texel = self.texel(self.texel_position(position, index));
+    self.to_shader_texel(texel.unwrap_or_else(Vec4::zero))
+  }
+
+  pub(crate) fn store(
+    &self,
+    position: &InterpreterPrimitive,
+    index: Option<usize>,
+    value: &InterpreterPrimitive,
+  ) {
+    let mut channels = value.scalars.iter().map(|v| match v {
+      ScalarValue::Float(v) => *v,
+      ScalarValue::Uint(v) => f32::from_bits(*v),
+      ScalarValue::Int(v) => f32::from_bits(*v as u32),
+      ScalarValue::Bool(_) => panic!("bool texel is not supported"),
+    });
+    let mut channel = || channels.next().unwrap_or(0.);
+    let texel = Vec4::new(channel(), channel(), channel(), channel());
+    self.write_texel(self.texel_position(position, index), texel)
+  }
+
+  pub(crate) fn sample(
+    &self,
+    sampler: &InterpreterSampler,
+    position: &InterpreterPrimitive,
+    index: Option<usize>,
+    reference: Option<f32>,
+    offset: Option<Vec2<i32>>,
+  ) -> InterpreterPrimitive {
+    let p: Vec<f32> = position.scalars.iter().map(|v| v.as_f32()).collect();
+    let data = self.data.read().unwrap();
+
+    let (layer, uv) = match &*data {
+      InterpreterTextureData::D3(texture) => {
+        assert!(
+          reference.is_none(),
+          "the 3d texture does not support comparison"
+        );
+        let texel = sampler
+          .sampler
+          .sample_3d(texture, Vec3::new(p[0], p[1], p[2]));
+        return self.to_shader_texel(texel);
+      }
+      InterpreterTextureData::Layers(layers) => match self.dimension {
+        TextureViewDimension::D1 => (&layers[0], Vec2::new(p[0], 0.5)),
+        TextureViewDimension::Cube | TextureViewDimension::CubeArray => {
+          let (face, uv) = cube_direction_to_face_uv(Vec3::new(p[0], p[1], p[2]));
+          let face = CUBE_FACES.iter().position(|f| *f == face).unwrap();
+          let cube_count = layers.len() / 6;
+          let cube = index.unwrap_or(0).min(cube_count - 1);
+          (&layers[cube * 6 + face], uv)
+        }
+        _ => {
+          let layer = index.unwrap_or(0).min(layers.len() - 1);
+          (&layers[layer], Vec2::new(p[0], p[1]))
+        }
+      },
+    };
+
+    let uv = match offset {
+      Some(offset) => {
+        let (width, height) = layer.size().into_usize();
+        uv + Vec2::new(
+          offset.x as f32 / width as f32,
+          offset.y as f32 / height as f32,
+        )
+      }
+      None => uv,
+    };
+
+    match reference {
+      Some(reference) => {
+        let compare = sampler
+          .compare
+          .expect("the comparison sampling requires the compare sampler");
+        let result = sampler
+          .sampler
+          .sample_2d_compare(layer, uv, reference, compare);
+        ScalarValue::Float(result).into()
+      }
+      None => self.to_shader_texel(sampler.sampler.sample_2d(layer, uv)),
+    }
+  }
+}
+
+fn depth_layer(texture: &Texture2DBuffer<f32>) -> Texture2DBuffer<Vec4<f32>> {
+  let texels = texture
+    .as_buffer()
+    .iter()
+    .map(|d| Vec4::new(*d, 0., 0., 1.))
+    .collect();
+  Texture2DBuffer::from_raw(texels, texture.size())
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct InterpreterSampler {
+  pub sampler: TextureSampler,
+  /// Only the compare sampler has the compare function
+  pub compare: Option<CompareFunction>,
+}
+
+impl From<TextureSampler> for InterpreterSampler {
+  fn from(sampler: TextureSampler) -> Self {
+    Self {
+      sampler,
+      compare: None,
+    }
+  }
+}
+
+/// The binding resources keyed by the bindgroup index and the entry index
+#[derive(Default, Clone)]
+pub struct InterpreterBindings {
+  entries: FastHashMap<(usize, usize), InterpreterValue>,
+}
+
+impl InterpreterBindings {
+  /// Bind the texture, the sampler or the buffer content. The buffer content bound here is copied
+  /// for every invocation, use [Self::bind_buffer] to share and read back the buffer.
+  pub fn bind(
+    &mut self,
+    bindgroup_index: usize,
+    entry_index: usize,
+    value: impl Into<InterpreterValue>,
+  ) -> &mut Self {
+    self
+      .entries
+      .insert((bindgroup_index, entry_index), value.into());
+    self
+  }
+
+  /// Bind the uniform or storage buffer, the returned pointer could be used to read back the
+  /// buffer content after the execution.
+  pub fn bind_buffer(
+    &mut self,
+    bindgroup_index: usize,
+    entry_index: usize,
+    content: impl Into<InterpreterValue>,
+  ) -> InterpreterPointer {
+    let pointer = InterpreterPointer::new(content.into());
+    self.bind(bindgroup_index, entry_index, pointer.clone());
+    pointer
+  }
+
+  pub(crate) fn resolve(
+    &self,
+    desc: &ShaderBindingDescriptor,
+    bindgroup_index: usize,
+    entry_index: usize,
+  ) -> InterpreterValue {
+    let value = self
+      .entries
+      .get(&(bindgroup_index, entry_index))
+      .unwrap_or_else(|| panic!("binding ({bindgroup_index}, {entry_index}) is not provided"))
+      .clone();
+
+    match value {
+      InterpreterValue::Pointer(_) => value,
+      // the buffer is always accessed by pointer
+      value if desc.get_buffer_layout().is_some() => InterpreterPointer::new(value).into(),
+      value => value,
+    }
+  }
+}
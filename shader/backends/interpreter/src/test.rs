@@ -0,0 +1,320 @@
+use crate::*;
+
+struct TestStorageInput;
+
+impl ShaderBindingProvider for TestStorageInput {
+  const SPACE: AddressSpace = AddressSpace::Storage { writeable: false };
+  type Node = [u32; 64];
+}
+
+struct TestStorageOutput;
+
+impl ShaderBindingProvider for TestStorageOutput {
+  const SPACE: AddressSpace = AddressSpace::Storage { writeable: true };
+  type Node = [ShaderAtomic<u32>; 64];
+}
+
+struct TestTexture;
+
+impl ShaderBindingProvider for TestTexture {
+  const SPACE: AddressSpace = AddressSpace::Handle;
+  type Node = ShaderTexture2D;
+}
+
+struct TestSampler;
+
+impl ShaderBindingProvider for TestSampler {
+  const SPACE: AddressSpace = AddressSpace::Handle;
+  type Node = ShaderSampler;
+}
+
+struct TestDepthTextureArray;
+
+impl ShaderBindingProvider for TestDepthTextureArray {
+  const SPACE: AddressSpace = AddressSpace::Handle;
+  type Node = ShaderDepthTexture2DArray;
+}
+
+struct TestCompareSampler;
+
+impl ShaderBindingProvider for TestCompareSampler {
+  const SPACE: AddressSpace = AddressSpace::Handle;
+  type Node = ShaderCompareSampler;
+}
+
+#[repr(C)]
+#[std430_layout]
+#[derive(Clone, Copy, ShaderStruct)]
+pub struct TestLight {
+  pub position: Vec3<f32>,
+  pub intensity: f32,
+}
+
+fn assert_near(a: f32, b: f32) {
+  assert!((a - b).abs() < 1e-5, "{a} != {b}");
+}
+
+fn assert_vec3_near(a: Vec3<f32>, b: Vec3<f32>) {
+  assert_near(a.x, b.x);
+  assert_near(a.y, b.y);
+  assert_near(a.z, b.z);
+}
+
+fn test_reinhard(color: Node<Vec3<f32>>, exposure: Node<f32>) -> Node<Vec3<f32>> {
+  get_shader_fn::<Vec3<f32>>(shader_fn_name(test_reinhard))
+    .or_define(|cx| {
+      let color = cx.push_fn_parameter_by(color);
+      let exposure = cx.push_fn_parameter_by(exposure);
+      let color = exposure * color;
+      let mapped = color / (val(Vec3::one()) + color);
+      cx.do_return(mapped.saturate())
+    })
+    .prepare_parameters()
+    .push(color)
+    .push(exposure)
+    .call()
+}
+
+fn reinhard_reference(color: Vec3<f32>, exposure: f32) -> Vec3<f32> {
+  let color = color * exposure;
+  let map = |c: f32| (c / (1. + c)).clamp(0., 1.);
+  Vec3::new(map(color.x), map(color.y), map(color.z))
+}
+
+#[test]
+fn arithmetic_and_builtin_functions() {
+  let a = Vec3::new(1., -2., 3.5_f32);
+  let b = Vec3::new(0.5, 4., -1.5_f32);
+
+  let r = interpret_by(|_| (val(a) + val(b) * val(2.)).cross(val(b)));
+  assert_vec3_near(r, (a + b * 2.).cross(b));
+
+  let r = interpret_by(|_| val(a).dot(val(b)) + val(a).length() + val(16.).sqrt());
+  assert_near(r, a.dot(b) + a.length() + 4.);
+
+  let r = interpret_by(|_| val(a).clamp(val(Vec3::zero()), val(Vec3::one())));
+  assert_vec3_near(r, Vec3::new(1., 0., 1.));
+
+  let r = interpret_by(|_| val(2.5_f32).pow(2.2) + val(-7_i32).into_f32());
+  assert_near(r, 2.5_f32.powf(2.2) - 7.);
+
+  let r = interpret_by(|_| val(7_u32) / val(0_u32) + val(9_u32) % val(4_u32));
+  assert_eq!(r, 7 + 1);
+
+  let r = interpret_by(|_| val(a).yx());
+  assert_eq!(r, Vec2::new(a.y, a.x));
+}
+
+#[test]
+fn matrix_operations() {
+  let m = Mat3::new(
+    0.59719, 0.07600, 0.02840, 0.35458, 0.90834, 0.13383, 0.04823, 0.01566, 0.83777,
+  );
+  let v = Vec3::new(0.3, 1.2, 4.5_f32);
+
+  let r = interpret_by(|_| {
+    let m: Node<Mat3<f32>> = (
+      (val(m.a1), val(m.a2), val(m.a3)).into(),
+      (val(m.b1), val(m.b2), val(m.b3)).into(),
+      (val(m.c1), val(m.c2), val(m.c3)).into(),
+    )
+      .into();
+    m * val(v)
+  });
+  assert_vec3_near(r, m * v);
+
+  let r = interpret_by(|_| val(m) * val(m));
+  assert_eq!(r, m * m);
+}
+
+#[test]
+fn user_defined_function() {
+  for (color, exposure) in [
+    (Vec3::new(0.1, 0.5, 20.), 1.),
+    (Vec3::new(3., 0., 0.2), 0.25),
+  ] {
+    let r = interpret_by(|_| {
+      // call twice to make sure the function is defined only once
+      test_reinhard(val(color), val(exposure));
+      test_reinhard(val(color), val(exposure))
+    });
+    assert_vec3_near(r, reinhard_reference(color, exposure));
+  }
+}
+
+#[test]
+fn control_flow() {
+  let r = interpret_by(|_| {
+    let sum = val(0_u32).make_local_var();
+    for_by(10, |cx, i, _| {
+      if_by(i.equals(val(7)), || cx.do_break());
+      sum.store(sum.load() + i);
+    });
+    sum.load()
+  });
+  assert_eq!(r, (0..7).sum::<u32>());
+
+  let r = interpret_by(|_| {
+    let sum = val(0_u32).make_local_var();
+    let i = val(0_u32).make_local_var();
+    loop_by(|cx| {
+      let current = i.load();
+      i.store(current + val(1));
+      if_by(current.greater_equal_than(val(10)), || cx.do_break());
+      if_by((current % val(2)).equals(val(0)), || cx.do_continue());
+      sum.store(sum.load() + current);
+    });
+    sum.load()
+  });
+  assert_eq!(r, 1 + 3 + 5 + 7 + 9);
+
+  for (selector, expect) in [(0_u32, 10_u32), (1, 20), (5, 30)] {
+    let r = interpret_by(|_| {
+      let result = val(0_u32).make_local_var();
+      switch_by(val(selector))
+        .case(0, || result.store(val(10)))
+        .case(1, || result.store(val(20)))
+        .end_with_default(|| result.store(val(30)));
+      result.load()
+    });
+    assert_eq!(r, expect);
+  }
+
+  for (input, expect) in [(1., 2.), (-1., -3.)] {
+    let r = interpret_by(|_| {
+      let result = val(0_f32).make_local_var();
+      if_by(val(input).greater_than(val(0.)), || result.store(val(2.)))
+        .else_by(|| result.store(val(-3.)));
+      result.load()
+    });
+    assert_eq!(r, expect);
+  }
+}
+
+#[test]
+fn struct_construct_and_access() {
+  let r = interpret_by(|_| {
+    let light = ENode::<TestLight> {
+      position: val(Vec3::new(1., 2., 3.)),
+      intensity: val(0.5),
+    }
+    .construct();
+
+    let light_var = light.make_local_var();
+    let light = light_var.load().expand();
+    light.position * light.intensity
+  });
+  assert_eq!(r, Vec3::new(0.5, 1., 1.5));
+}
+
+#[test]
+fn texture_sampling() {
+  let texels = vec![
+    Vec4::new(0., 0., 0., 1.),
+    Vec4::new(1., 0., 0., 1.),
+    Vec4::new(0., 1., 0., 1.),
+    Vec4::new(0., 0., 1., 1.),
+  ];
+  let texture = Texture2DBuffer::from_raw(texels, Size::from_usize_pair_min_one((2, 2)));
+  let sampler = TextureSampler {
+    mag_filter: rendiation_texture::FilterMode::Linear,
+    min_filter: rendiation_texture::FilterMode::Linear,
+    ..Default::default()
+  };
+  let uv = Vec2::new(0.4, 0.7);
+
+  let api = ShaderAPIInterpreterImpl::new(ShaderStages::Compute);
+  let mut builder = ShaderComputePipelineBuilder::new(Box::new(api));
+  let shader_texture = builder.binding::<TestTexture>();
+  let shader_sampler = builder.binding::<TestSampler>();
+  let sampled = shader_texture.sample(shader_sampler, val(uv));
+  let loaded = shader_texture.load_texel(val(Vec2::new(1_u32, 0)), val(0));
+
+  let program = ShaderInterpreterProgram::from_built(builder.build().unwrap().shader);
+  let mut bindings = InterpreterBindings::default();
+  bindings
+    .bind(0, 0, InterpreterTexture::d2(texture.clone()))
+    .bind(0, 1, InterpreterSampler::from(sampler));
+  let result = program.invoke(&bindings, &Default::default());
+
+  assert_eq!(result.read(sampled), sampler.sample_2d(&texture, uv));
+  assert_eq!(result.read(loaded), Vec4::new(1., 0., 0., 1.));
+}
+
+#[test]
+fn texture_compare_sampling() {
+  let near = Texture2DBuffer::from_raw(vec![0.2, 0.8], Size::from_usize_pair_min_one((2, 1)));
+  let far = Texture2DBuffer::from_raw(vec![0.9, 0.9], Size::from_usize_pair_min_one((2, 1)));
+  let compare = InterpreterSampler {
+    sampler: TextureSampler {
+      mag_filter: rendiation_texture::FilterMode::Linear,
+      min_filter: rendiation_texture::FilterMode::Linear,
+      ..Default::default()
+    },
+    compare: Some(CompareFunction::Less),
+  };
+
+  let api = ShaderAPIInterpreterImpl::new(ShaderStages::Compute);
+  let mut builder = ShaderComputePipelineBuilder::new(Box::new(api));
+  let map = builder.binding::<TestDepthTextureArray>();
+  let sampler = builder.binding::<TestCompareSampler>();
+  let uv = val(Vec2::new(0.5, 0.5));
+  let near_result = map.sample_compare_index_level(sampler, uv, val(0_u32), val(0.5), None);
+  let far_result = map.sample_compare_index_level(sampler, uv, val(1_u32), val(0.5), None);
+  let offset_result =
+    map.sample_compare_index_level(sampler, uv, val(0_u32), val(0.5), Some(Vec2::new(1, 0)));
+
+  let program = ShaderInterpreterProgram::from_built(builder.build().unwrap().shader);
+  let mut bindings = InterpreterBindings::default();
+  bindings
+    .bind(0, 0, InterpreterTexture::depth_2d_array(&[near, far]))
+    .bind(0, 1, compare);
+  let result = program.invoke(&bindings, &Default::default());
+
+  assert_eq!(result.read(near_result), 0.5);
+  assert_eq!(result.read(far_result), 1.);
+  assert_eq!(result.read(offset_result), 1.);
+}
+
+#[test]
+fn compute_dispatch_with_shared_memory_and_atomics() {
+  let api = ShaderAPIInterpreterImpl::new(ShaderStages::Compute);
+  let mut builder = ShaderComputePipelineBuilder::new(Box::new(api));
+  builder.config_work_group_size((16, 1, 1));
+
+  let input = builder.binding::<TestStorageInput>();
+  let output = builder.binding::<TestStorageOutput>();
+  let shared = builder.define_workgroup_shared_var::<[u32; 16]>();
+  let counter = builder.define_workgroup_shared_var::<ShaderAtomic<u32>>();
+
+  let local_index = builder.local_invocation_index();
+  let global_index = builder.global_invocation_id().x();
+
+  shared
+    .index(local_index)
+    .store(input.index(global_index).load());
+  counter.atomic_add(val(1));
+  workgroup_barrier();
+
+  // every invocation could see the value written by the neighbor and the full counter
+  let neighbor = shared.index((local_index + val(1)) % val(16)).load();
+  output
+    .index(global_index)
+    .atomic_add(neighbor + counter.atomic_load());
+
+  let program = ShaderInterpreterProgram::from_built(builder.build().unwrap().shader);
+  assert_eq!(program.workgroup_size(), (16, 1, 1));
+
+  let input: Vec<u32> = (0..64).map(|i| i * 3).collect();
+  let mut bindings = InterpreterBindings::default();
+  bindings.bind(0, 0, InterpreterValue::from_slice(&input));
+  let output = bindings.bind_buffer(0, 1, InterpreterValue::from_slice(&[0_u32; 64]));
+
+  program.dispatch(&bindings, (4, 1, 1));
+
+  let output = output.load().read::<Vec<u32>>();
+  for (i, v) in output.iter().enumerate() {
+    let neighbor = (i / 16) * 16 + (i + 1) % 16;
+    assert_eq!(*v, input[neighbor] + 16);
+  }
+}
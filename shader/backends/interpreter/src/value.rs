@@ -0,0 +1,505 @@
+use crate::*;
+
+/// The runtime value of the shader node
+#[derive(Clone)]
+pub enum InterpreterValue {
+  Primitive(InterpreterPrimitive),
+  /// The fields of the struct or the elements of the array
+  Composite(Vec<InterpreterValue>),
+  Pointer(InterpreterPointer),
+  Texture(InterpreterTexture),
+  Sampler(InterpreterSampler),
+}
+
+impl InterpreterValue {
+  pub fn primitive(v: &impl PrimitiveShaderNodeType) -> Self {
+    Self::Primitive(v.to_primitive().into())
+  }
+
+  pub fn from_slice<T: PrimitiveShaderNodeType>(values: &[T]) -> Self {
+    Self::Composite(values.iter().map(Self::primitive).collect())
+  }
+
+  /// The value of the function without return value
+  pub fn void() -> Self {
+    Self::Composite(Vec::new())
+  }
+
+  pub fn zeroed(ty: &ShaderSizedValueType) -> Self {
+    match ty {
+      ShaderSizedValueType::Primitive(ty) => Self::Primitive(InterpreterPrimitive::zeroed(*ty)),
+      ShaderSizedValueType::Struct(meta) => {
+        Self::Composite(meta.fields.iter().map(|f| Self::zeroed(&f.ty)).collect())
+      }
+      ShaderSizedValueType::FixedSizeArray((ty, size)) => {
+        Self::Composite(vec![Self::zeroed(ty); *size])
+      }
+      ShaderSizedValueType::Atomic(ShaderAtomicValueType::U32) => {
+        Self::Primitive(ScalarValue::Uint(0).into())
+      }
+      ShaderSizedValueType::Atomic(ShaderAtomicValueType::I32) => {
+        Self::Primitive(ScalarValue::Int(0).into())
+      }
+    }
+  }
+
+  pub fn as_primitive(&self) -> &InterpreterPrimitive {
+    match self {
+      Self::Primitive(v) => v,
+      _ => panic!("expect primitive value"),
+    }
+  }
+
+  pub fn as_pointer(&self) -> &InterpreterPointer {
+    match self {
+      Self::Pointer(v) => v,
+      _ => panic!("expect pointer"),
+    }
+  }
+
+  pub fn as_texture(&self) -> &InterpreterTexture {
+    match self {
+      Self::Texture(v) => v,
+      _ => panic!("expect texture"),
+    }
+  }
+
+  pub fn as_sampler(&self) -> &InterpreterSampler {
+    match self {
+      Self::Sampler(v) => v,
+      _ => panic!("expect sampler"),
+    }
+  }
+
+  /// Read the value as the rust value, panic if the type mismatched.
+  pub fn read<T: ReadInterpreterValue>(&self) -> T {
+    T::read_value(self).expect("the value type mismatched")
+  }
+
+  /// Access the struct field, array element, vector component or matrix column. The
+  /// out of bound array index is clamped into the array, as the robust buffer access does.
+  pub(crate) fn access(self, index: usize) -> Self {
+    match self {
+      Self::Primitive(v) => Self::Primitive(v.component(index)),
+      Self::Composite(mut values) => {
+        assert!(!values.is_empty(), "access the empty array");
+        let index = index.min(values.len() - 1);
+        values.swap_remove(index)
+      }
+      Self::Pointer(ptr) => Self::Pointer(ptr.child(index)),
+      _ => panic!("the value is not accessible by index"),
+    }
+  }
+
+  fn read_path(&self, path: &[usize]) -> Self {
+    match path.split_first() {
+      None => self.clone(),
+      Some((index, rest)) => match self {
+        Self::Composite(values) => {
+          assert!(!values.is_empty(), "access the empty array");
+          values[(*index).min(values.len() - 1)].read_path(rest)
+        }
+        Self::Primitive(v) => Self::Primitive(v.component(*index)).read_path(rest),
+        _ => panic!("the value is not accessible by index"),
+      },
+    }
+  }
+
+  fn write_path(&mut self, path: &[usize], value: Self) {
+    match path.split_first() {
+      None => *self = value,
+      Some((index, rest)) => match self {
+        Self::Composite(values) => {
+          assert!(!values.is_empty(), "access the empty array");
+          let index = (*index).min(values.len() - 1);
+          values[index].write_path(rest, value)
+        }
+        Self::Primitive(v) => {
+          let mut component = Self::Primitive(v.component(*index));
+          component.write_path(rest, value);
+          v.set_component(*index, component.as_primitive());
+        }
+        _ => panic!("the value is not accessible by index"),
+      },
+    }
+  }
+}
+
+impl From<InterpreterPrimitive> for InterpreterValue {
+  fn from(v: InterpreterPrimitive) -> Self {
+    Self::Primitive(v)
+  }
+}
+impl From<InterpreterPointer> for InterpreterValue {
+  fn from(v: InterpreterPointer) -> Self {
+    Self::Pointer(v)
+  }
+}
+impl From<InterpreterTexture> for InterpreterValue {
+  fn from(v: InterpreterTexture) -> Self {
+    Self::Texture(v)
+  }
+}
+impl From<InterpreterSampler> for InterpreterValue {
+  fn from(v: InterpreterSampler) -> Self {
+    Self::Sampler(v)
+  }
+}
+
+/// The pointer is the path into the root variable, the root is shared by all the invocations
+/// that could access it, for example the storage buffer and the workgroup shared variable.
+#[derive(Clone)]
+pub struct InterpreterPointer {
+  root: Arc<Mutex<InterpreterValue>>,
+  path: Vec<usize>,
+}
+
+impl InterpreterPointer {
+  pub fn new(value: InterpreterValue) -> Self {
+    Self {
+      root: Arc::new(Mutex::new(value)),
+      path: Vec::new(),
+    }
+  }
+
+  pub fn load(&self) -> InterpreterValue {
+    self.root.lock().unwrap().read_path(&self.path)
+  }
+
+  pub fn store(&self, value: InterpreterValue) {
+    self.root.lock().unwrap().write_path(&self.path, value)
+  }
+
+  /// Read and write the pointee in one lock, this is how the atomic operations are implemented.
+  pub(crate) fn modify<R>(&self, f: impl FnOnce(&mut InterpreterValue) -> R) -> R {
+    let mut root = self.root.lock().unwrap();
+    let mut value = root.read_path(&self.path);
+    let r = f(&mut value);
+    root.write_path(&self.path, value);
+    r
+  }
+
+  pub(crate) fn child(&self, index: usize) -> Self {
+    let mut path = self.path.clone();
+    path.push(index);
+    Self {
+      root: self.root.clone(),
+      path,
+    }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ScalarValue {
+  Bool(bool),
+  Uint(u32),
+  Int(i32),
+  Float(f32),
+}
+
+impl ScalarValue {
+  pub fn as_f32(self) -> f32 {
+    match self {
+      Self::Float(v) => v,
+      _ => panic!("expect f32"),
+    }
+  }
+  pub fn as_u32(self) -> u32 {
+    match self {
+      Self::Uint(v) => v,
+      _ => panic!("expect u32"),
+    }
+  }
+  pub fn as_i32(self) -> i32 {
+    match self {
+      Self::Int(v) => v,
+      _ => panic!("expect i32"),
+    }
+  }
+  pub fn as_bool(self) -> bool {
+    match self {
+      Self::Bool(v) => v,
+      _ => panic!("expect bool"),
+    }
+  }
+  /// The negative index is treated as out of bound, so it is clamped to the max index later.
+  pub fn as_index(self) -> usize {
+    match self {
+      Self::Uint(v) => v as usize,
+      Self::Int(v) => usize::try_from(v).unwrap_or(usize::MAX),
+      _ => panic!("expect integer index"),
+    }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PrimitiveShape {
+  Scalar,
+  Vector(usize),
+  /// The square matrix with the column count
+  Matrix(usize),
+}
+
+impl PrimitiveShape {
+  pub fn component_count(self) -> usize {
+    match self {
+      Self::Scalar => 1,
+      Self::Vector(n) => n,
+      Self::Matrix(n) => n * n,
+    }
+  }
+}
+
+/// The primitive value decomposed into the scalars, the matrix is in column major order. Unlike
+/// the [PrimitiveShaderValue] the bool vector is expressible, which is the result of the vector
+/// comparison.
+#[derive(Clone, PartialEq, Debug)]
+pub struct InterpreterPrimitive {
+  pub shape: PrimitiveShape,
+  pub scalars: Vec<ScalarValue>,
+}
+
+impl From<ScalarValue> for InterpreterPrimitive {
+  fn from(v: ScalarValue) -> Self {
+    Self {
+      shape: PrimitiveShape::Scalar,
+      scalars: vec![v],
+    }
+  }
+}
+
+impl InterpreterPrimitive {
+  pub fn new(shape: PrimitiveShape, scalars: Vec<ScalarValue>) -> Self {
+    assert_eq!(shape.component_count(), scalars.len());
+    Self { shape, scalars }
+  }
+
+  pub fn zeroed(ty: PrimitiveShaderValueType) -> Self {
+    let (shape, zero) = primitive_layout(ty);
+    Self::new(shape, vec![zero; shape.component_count()])
+  }
+
+  pub fn scalar(&self) -> ScalarValue {
+    assert_eq!(self.shape, PrimitiveShape::Scalar, "expect scalar");
+    self.scalars[0]
+  }
+
+  /// The vector component or the matrix column
+  pub fn component(&self, index: usize) -> Self {
+    match self.shape {
+      PrimitiveShape::Scalar => panic!("the scalar is not accessible by index"),
+      PrimitiveShape::Vector(n) => self.scalars[index.min(n - 1)].into(),
+      PrimitiveShape::Matrix(n) => {
+        let start = index.min(n - 1) * n;
+        Self::new(
+          PrimitiveShape::Vector(n),
+          self.scalars[start..start + n].to_vec(),
+        )
+      }
+    }
+  }
+
+  pub fn set_component(&mut self, index: usize, value: &Self) {
+    match self.shape {
+      PrimitiveShape::Scalar => panic!("the scalar is not accessible by index"),
+      PrimitiveShape::Vector(n) => self.scalars[index.min(n - 1)] = value.scalar(),
+      PrimitiveShape::Matrix(n) => {
+        let start = index.min(n - 1) * n;
+        self.scalars[start..start + n].copy_from_slice(&value.scalars);
+      }
+    }
+  }
+
+  pub fn map(&self, f: impl Fn(ScalarValue) -> ScalarValue) -> Self {
+    Self::new(self.shape, self.scalars.iter().map(|v| f(*v)).collect())
+  }
+
+  pub fn map_f32(&self, f: impl Fn(f32) -> f32) -> Self {
+    self.map(|v| ScalarValue::Float(f(v.as_f32())))
+  }
+
+  /// Return None for the bool vector, which is not a valid shader value type
+  pub fn to_shader_value(&self) -> Option<PrimitiveShaderValue> {
+    use PrimitiveShaderValue as P;
+    use ScalarValue as S;
+
+    let floats = || self.scalars.iter().map(|v| v.as_f32()).collect::<Vec<_>>();
+    let uints = || self.scalars.iter().map(|v| v.as_u32()).collect::<Vec<_>>();
+    let ints = || self.scalars.iter().map(|v| v.as_i32()).collect::<Vec<_>>();
+
+    let value = match (self.shape, self.scalars[0]) {
+      (PrimitiveShape::Scalar, S::Bool(v)) => P::Bool(v),
+      (PrimitiveShape::Scalar, S::Uint(v)) => P::Uint32(v),
+      (PrimitiveShape::Scalar, S::Int(v)) => P::Int32(v),
+      (PrimitiveShape::Scalar, S::Float(v)) => P::Float32(v),
+      (PrimitiveShape::Vector(_), S::Bool(_)) => return None,
+      (PrimitiveShape::Vector(n), S::Float(_)) => {
+        let v = floats();
+        match n {
+          2 => P::Vec2Float32(Vec2::new(v[0], v[1])),
+          3 => P::Vec3Float32(Vec3::new(v[0], v[1], v[2])),
+          _ => P::Vec4Float32(Vec4::new(v[0], v[1], v[2], v[3])),
+        }
+      }
+      (PrimitiveShape::Vector(n), S::Uint(_)) => {
+        let v = uints();
+        match n {
+          2 => P::Vec2Uint32(Vec2::new(v[0], v[1])),
+          3 => P::Vec3Uint32(Vec3::new(v[0], v[1], v[2])),
+          _ => P::Vec4Uint32(Vec4::new(v[0], v[1], v[2], v[3])),
+        }
+      }
+      (PrimitiveShape::Vector(n), S::Int(_)) => {
+        let v = ints();
+        match n {
+          2 => P::Vec2Int32(Vec2::new(v[0], v[1])),
+          3 => P::Vec3Int32(Vec3::new(v[0], v[1], v[2])),
+          _ => P::Vec4Int32(Vec4::new(v[0], v[1], v[2], v[3])),
+        }
+      }
+      (PrimitiveShape::Matrix(n), _) => {
+        let v = floats();
+        match n {
+          2 => P::Mat2Float32(Mat2::new(v[0], v[1], v[2], v[3])),
+          3 => P::Mat3Float32(Mat3::new(
+            v[0], v[1], v[2], v[3], v[4], v[5], v[6], v[7], v[8],
+          )),
+          _ => P::Mat4Float32(mat4(&v)),
+        }
+      }
+    };
+    value.into()
+  }
+}
+
+#[rustfmt::skip]
+fn mat4(v: &[f32]) -> Mat4<f32> {
+  Mat4::new(
+    v[0], v[1], v[2], v[3], v[4], v[5], v[6], v[7],
+    v[8], v[9], v[10], v[11], v[12], v[13], v[14], v[15],
+  )
+}
+
+impl From<PrimitiveShaderValue> for InterpreterPrimitive {
+  fn from(v: PrimitiveShaderValue) -> Self {
+    use PrimitiveShaderValue as P;
+    use ScalarValue as S;
+
+    let vector =
+      |scalars: Vec<ScalarValue>| Self::new(PrimitiveShape::Vector(scalars.len()), scalars);
+    let matrix = |n: usize, scalars: Vec<f32>| {
+      Self::new(
+        PrimitiveShape::Matrix(n),
+        scalars.into_iter().map(S::Float).collect(),
+      )
+    };
+
+    match v {
+      P::Bool(v) => S::Bool(v).into(),
+      P::Uint32(v) => S::Uint(v).into(),
+      P::Int32(v) => S::Int(v).into(),
+      P::Float32(v) => S::Float(v).into(),
+      P::Vec2Float32(v) => vector([v.x, v.y].map(S::Float).to_vec()),
+      P::Vec3Float32(v) => vector([v.x, v.y, v.z].map(S::Float).to_vec()),
+      P::Vec4Float32(v) => vector([v.x, v.y, v.z, v.w].map(S::Float).to_vec()),
+      P::Vec2Uint32(v) => vector([v.x, v.y].map(S::Uint).to_vec()),
+      P::Vec3Uint32(v) => vector([v.x, v.y, v.z].map(S::Uint).to_vec()),
+      P::Vec4Uint32(v) => vector([v.x, v.y, v.z, v.w].map(S::Uint).to_vec()),
+      P::Vec2Int32(v) => vector([v.x, v.y].map(S::Int).to_vec()),
+      P::Vec3Int32(v) => vector([v.x, v.y, v.z].map(S::Int).to_vec()),
+      P::Vec4Int32(v) => vector([v.x, v.y, v.z, v.w].map(S::Int).to_vec()),
+      P::Mat2Float32(m) => matrix(2, vec![m.a1, m.a2, m.b1, m.b2]),
+      #[rustfmt::skip]
+      P::Mat3Float32(m) => matrix(3, vec![
+        m.a1, m.a2, m.a3,
+        m.b1, m.b2, m.b3,
+        m.c1, m.c2, m.c3,
+      ]),
+      #[rustfmt::skip]
+      P::Mat4Float32(m) => matrix(4, vec![
+        m.a1, m.a2, m.a3, m.a4,
+        m.b1, m.b2, m.b3, m.b4,
+        m.c1, m.c2, m.c3, m.c4,
+        m.d1, m.d2, m.d3, m.d4,
+      ]),
+    }
+  }
+}
+
+/// The shape and the zero scalar of the primitive type
+pub(crate) fn primitive_layout(ty: PrimitiveShaderValueType) -> (PrimitiveShape, ScalarValue) {
+  use PrimitiveShaderValueType as P;
+  use PrimitiveShape::*;
+  use ScalarValue as S;
+  match ty {
+    P::Bool => (Scalar, S::Bool(false)),
+    P::Int32 => (Scalar, S::Int(0)),
+    P::Uint32 => (Scalar, S::Uint(0)),
+    P::Float32 => (Scalar, S::Float(0.)),
+    P::Vec2Float32 => (Vector(2), S::Float(0.)),
+    P::Vec3Float32 => (Vector(3), S::Float(0.)),
+    P::Vec4Float32 => (Vector(4), S::Float(0.)),
+    P::Vec2Uint32 => (Vector(2), S::Uint(0)),
+    P::Vec3Uint32 => (Vector(3), S::Uint(0)),
+    P::Vec4Uint32 => (Vector(4), S::Uint(0)),
+    P::Vec2Int32 => (Vector(2), S::Int(0)),
+    P::Vec3Int32 => (Vector(3), S::Int(0)),
+    P::Vec4Int32 => (Vector(4), S::Int(0)),
+    P::Mat2Float32 => (Matrix(2), S::Float(0.)),
+    P::Mat3Float32 => (Matrix(3), S::Float(0.)),
+    P::Mat4Float32 => (Matrix(4), S::Float(0.)),
+  }
+}
+
+/// The rust value that could be read back from the [InterpreterValue]
+pub trait ReadInterpreterValue: Sized {
+  fn read_value(value: &InterpreterValue) -> Option<Self>;
+}
+
+macro_rules! read_primitive {
+  ($ty: ty, $variant: tt) => {
+    impl ReadInterpreterValue for $ty {
+      fn read_value(value: &InterpreterValue) -> Option<Self> {
+        match value {
+          InterpreterValue::Primitive(v) => match v.to_shader_value()? {
+            PrimitiveShaderValue::$variant(v) => Some(v),
+            _ => None,
+          },
+          _ => None,
+        }
+      }
+    }
+  };
+}
+
+read_primitive!(bool, Bool);
+read_primitive!(u32, Uint32);
+read_primitive!(i32, Int32);
+read_primitive!(f32, Float32);
+read_primitive!(Vec2<f32>, Vec2Float32);
+read_primitive!(Vec3<f32>, Vec3Float32);
+read_primitive!(Vec4<f32>, Vec4Float32);
+read_primitive!(Vec2<u32>, Vec2Uint32);
+read_primitive!(Vec3<u32>, Vec3Uint32);
+read_primitive!(Vec4<u32>, Vec4Uint32);
+read_primitive!(Vec2<i32>, Vec2Int32);
+read_primitive!(Vec3<i32>, Vec3Int32);
+read_primitive!(Vec4<i32>, Vec4Int32);
+read_primitive!(Mat2<f32>, Mat2Float32);
+read_primitive!(Mat3<f32>, Mat3Float32);
+read_primitive!(Mat4<f32>, Mat4Float32);
+
+impl<T: ReadInterpreterValue> ReadInterpreterValue for Vec<T> {
+  fn read_value(value: &InterpreterValue) -> Option<Self> {
+    match value {
+      InterpreterValue::Composite(values) => values.iter().map(T::read_value).collect(),
+      _ => None,
+    }
+  }
+}
+
+impl<T: ReadInterpreterValue> ReadInterpreterValue for ShaderAtomic<T> {
+  fn read_value(value: &InterpreterValue) -> Option<Self> {
+    T::read_value(value).map(ShaderAtomic)
+  }
+}
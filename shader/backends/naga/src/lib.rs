@@ -1,3 +1,5 @@
+#![cfg_attr(test, feature(generic_const_exprs))]
+#![cfg_attr(test, allow(incomplete_features))]
 #![allow(clippy::field_reassign_with_default)]
 
 use __core::num::NonZeroU32;
@@ -5,6 +7,9 @@ use fast_hash_collection::*;
 use naga::{Span, StorageAccess};
 use rendiation_shader_api::*;
 
+#[cfg(test)]
+mod test;
+
 pub struct ShaderAPINagaImpl {
   module: naga::Module,
   handle_id: usize,
@@ -35,14 +40,16 @@ impl ShaderAPINagaImpl {
     let stage = match stage {
       ShaderStages::Vertex => naga::ShaderStage::Vertex,
       ShaderStages::Fragment => naga::ShaderStage::Fragment,
+      ShaderStages::Compute => naga::ShaderStage::Compute,
     };
 
     let mut module = naga::Module::default();
     let entry = naga::EntryPoint {
       name: ENTRY_POINT_NAME.to_owned(),
       stage,
-      early_depth_test: None,    // todo expose
-      workgroup_size: [0, 0, 0], // todo expose , why naga not make this an enum??
+      early_depth_test: None, // todo expose
+      // only meaningful for compute stage, see set_workgroup_size
+      workgroup_size: [0, 0, 0],
       function: Default::default(),
     };
     module.entry_points.push(entry);
@@ -142,10 +149,25 @@ impl ShaderAPINagaImpl {
             size: naga::ArraySize::Constant(NonZeroU32::new(size as u32).unwrap()),
             stride: ty.size_of_self(layout.unwrap_or(StructLayoutTarget::Std430)) as u32,
           },
+          ShaderSizedValueType::Atomic(ty) => naga::TypeInner::Atomic {
+            kind: map_atomic_kind(ty),
+            width: 4,
+          },
         },
+        // the unsized type could only be used in the storage buffer, so always std430 layout
         ShaderValueSingleType::Unsized(ty) => match ty {
-          ShaderUnSizedValueType::UnsizedArray(_) => todo!(),
-          ShaderUnSizedValueType::UnsizedStruct(_) => todo!(),
+          ShaderUnSizedValueType::UnsizedArray(ty) => naga::TypeInner::Array {
+            base: self.register_ty_impl(
+              ShaderValueType::Single(ShaderValueSingleType::Sized(*ty)),
+              StructLayoutTarget::Std430.into(),
+            ),
+            size: naga::ArraySize::Dynamic,
+            stride: runtime_array_stride(ty) as u32,
+          },
+          ShaderUnSizedValueType::UnsizedStruct(st) => {
+            name = st.name.to_owned().into();
+            gen_unsized_struct_define(self, st)
+          }
         },
         ShaderValueSingleType::Sampler(sampler) => naga::TypeInner::Sampler {
           comparison: matches!(sampler, SamplerBindingType::Comparison),
@@ -183,10 +205,38 @@ impl ShaderAPINagaImpl {
             class,
           }
         }
+        ShaderValueSingleType::StorageTexture {
+          dimension,
+          format,
+          access,
+        } => {
+          let (dim, arrayed) = match dimension {
+            TextureViewDimension::D1 => (naga::ImageDimension::D1, false),
+            TextureViewDimension::D2 => (naga::ImageDimension::D2, false),
+            TextureViewDimension::D2Array => (naga::ImageDimension::D2, true),
+            TextureViewDimension::D3 => (naga::ImageDimension::D3, false),
+            _ => panic!("cube storage texture is not supported"),
+          };
+          let access = match access {
+            StorageTextureAccess::WriteOnly => StorageAccess::STORE,
+            StorageTextureAccess::ReadOnly => StorageAccess::LOAD,
+            StorageTextureAccess::ReadWrite => StorageAccess::LOAD | StorageAccess::STORE,
+          };
+          naga::TypeInner::Image {
+            dim,
+            arrayed,
+            class: naga::ImageClass::Storage {
+              format: map_storage_format(format),
+              access,
+            },
+          }
+        }
       },
       ShaderValueType::BindingArray { count, ty } => naga::TypeInner::BindingArray {
         base: self.register_ty_impl(ShaderValueType::Single(ty), layout),
-        size: naga::ArraySize::Constant(NonZeroU32::new(count as u32).unwrap()),
+        size: NonZeroU32::new(count as u32)
+          .map(naga::ArraySize::Constant)
+          .unwrap_or(naga::ArraySize::Dynamic),
       },
       ShaderValueType::Never => unreachable!(),
     };
@@ -272,6 +322,18 @@ impl ShaderAPI for ShaderAPINagaImpl {
             kind: naga::ScalarKind::Float,
             width: 4,
           },
+          ShaderBuiltInDecorator::CompLocalInvocationIndex => naga::TypeInner::Scalar {
+            kind: naga::ScalarKind::Uint,
+            width: 4,
+          },
+          ShaderBuiltInDecorator::CompGlobalInvocationId
+          | ShaderBuiltInDecorator::CompLocalInvocationId
+          | ShaderBuiltInDecorator::CompWorkgroupId
+          | ShaderBuiltInDecorator::CompNumWorkgroups => naga::TypeInner::Vector {
+            size: naga::VectorSize::Tri,
+            kind: naga::ScalarKind::Uint,
+            width: 4,
+          },
         };
         let ty = naga::Type {
           name: None,
@@ -294,7 +356,11 @@ impl ShaderAPI for ShaderAPINagaImpl {
         let space = match desc.get_buffer_layout() {
           Some(StructLayoutTarget::Std140) => naga::AddressSpace::Uniform,
           Some(StructLayoutTarget::Std430) => naga::AddressSpace::Storage {
-            access: StorageAccess::LOAD,
+            access: if desc.writeable_if_storage {
+              StorageAccess::LOAD | StorageAccess::STORE
+            } else {
+              StorageAccess::LOAD
+            },
           },
           None => naga::AddressSpace::Handle,
         };
@@ -316,6 +382,25 @@ impl ShaderAPI for ShaderAPINagaImpl {
         self.expression_mapping.insert(return_handle, g);
         return_handle
       }
+      ShaderInputNode::WorkGroupShared { ty } => {
+        let ty = self.register_ty_impl(
+          ShaderValueType::Single(ShaderValueSingleType::Sized(ty)),
+          None,
+        );
+        let g = naga::GlobalVariable {
+          name: None,
+          space: naga::AddressSpace::WorkGroup,
+          binding: None,
+          ty,
+          init: None,
+        };
+        let g = self.module.global_variables.append(g, Span::UNDEFINED);
+        let g = self.make_expression_inner_raw(naga::Expression::GlobalVariable(g));
+
+        let return_handle = self.make_new_handle();
+        self.expression_mapping.insert(return_handle, g);
+        return_handle
+      }
       ShaderInputNode::UserDefinedIn { ty, location } => {
         let ty = self.register_ty_impl(
           ShaderValueType::Single(ShaderValueSingleType::Sized(
@@ -580,6 +665,85 @@ impl ShaderAPI for ShaderAPINagaImpl {
           },
           depth_ref: reference.map(|r| self.get_expression(r)),
         },
+        ShaderNodeExpr::TextureLoad {
+          texture,
+          position,
+          index,
+          level,
+        } => naga::Expression::ImageLoad {
+          image: self.get_expression(texture),
+          coordinate: self.get_expression(position),
+          array_index: index.map(|index| self.get_expression(index)),
+          sample: None,
+          // naga only accept the signed level
+          level: level.map(|level| {
+            let level = self.get_expression(level);
+            self.make_expression_inner_raw(naga::Expression::As {
+              expr: level,
+              kind: naga::ScalarKind::Sint,
+              convert: Some(4),
+            })
+          }),
+        },
+        ShaderNodeExpr::ArrayLength { array } => {
+          naga::Expression::ArrayLength(self.get_expression(array))
+        }
+        ShaderNodeExpr::AtomicCall {
+          ty,
+          pointer,
+          function,
+          value,
+        } => {
+          let kind = map_atomic_kind(ty);
+          let (fun, comparison) = match function {
+            AtomicFunction::Add => (naga::AtomicFunction::Add, false),
+            AtomicFunction::Subtract => (naga::AtomicFunction::Subtract, false),
+            AtomicFunction::And => (naga::AtomicFunction::And, false),
+            AtomicFunction::InclusiveOr => (naga::AtomicFunction::InclusiveOr, false),
+            AtomicFunction::ExclusiveOr => (naga::AtomicFunction::ExclusiveOr, false),
+            AtomicFunction::Min => (naga::AtomicFunction::Min, false),
+            AtomicFunction::Max => (naga::AtomicFunction::Max, false),
+            AtomicFunction::Exchange { compare } => {
+              let compare = compare.map(|c| self.get_expression(c));
+              (
+                naga::AtomicFunction::Exchange { compare },
+                compare.is_some(),
+              )
+            }
+          };
+          let ty = if comparison {
+            self.module.generate_atomic_compare_exchange_result(kind, 4)
+          } else {
+            let ty = match kind {
+              naga::ScalarKind::Sint => PrimitiveShaderValueType::Int32,
+              _ => PrimitiveShaderValueType::Uint32,
+            };
+            self.register_ty_impl(
+              ShaderValueType::Single(ShaderValueSingleType::Sized(
+                ShaderSizedValueType::Primitive(ty),
+              )),
+              None,
+            )
+          };
+
+          // the atomic result is evaluated by the atomic statement, so it should not be emitted.
+          let result = self.building_fn.last_mut().unwrap().expressions.append(
+            naga::Expression::AtomicResult { ty, comparison },
+            Span::UNDEFINED,
+          );
+          let result_handle = self.make_new_handle();
+          self.expression_mapping.insert(result_handle, result);
+
+          let st = naga::Statement::Atomic {
+            pointer: self.get_expression(pointer),
+            fun,
+            value: self.get_expression(value),
+            result,
+          };
+          self.push_top_statement(st);
+
+          return result_handle;
+        }
         ShaderNodeExpr::Swizzle { ty, source } => {
           let source = self.get_expression(source);
 
@@ -820,6 +984,9 @@ impl ShaderAPI for ShaderAPINagaImpl {
     match exp {
       naga::Expression::GlobalVariable(_) => {}
       naga::Expression::LocalVariable(_) => {}
+      // the element or member of the variable, for example the workgroup shared array
+      naga::Expression::Access { .. } => {}
+      naga::Expression::AccessIndex { .. } => {}
       ty => panic!("invalid store {:?}", ty),
     }
 
@@ -1016,6 +1183,28 @@ impl ShaderAPI for ShaderAPINagaImpl {
       .push(naga::Statement::Kill, Span::UNDEFINED)
   }
 
+  fn texture_store(&mut self, store: ShaderTextureStore) {
+    let st = naga::Statement::ImageStore {
+      image: self.get_expression(store.texture),
+      coordinate: self.get_expression(store.position),
+      array_index: store.index.map(|index| self.get_expression(index)),
+      value: self.get_expression(store.value),
+    };
+    self.push_top_statement(st);
+  }
+
+  fn barrier(&mut self, scope: BarrierScope) {
+    let barrier = match scope {
+      BarrierScope::Storage => naga::Barrier::STORAGE,
+      BarrierScope::WorkGroup => naga::Barrier::WORK_GROUP,
+    };
+    self.push_top_statement(naga::Statement::Barrier(barrier));
+  }
+
+  fn set_workgroup_size(&mut self, size: (u32, u32, u32)) {
+    self.module.entry_points[0].workgroup_size = [size.0, size.1, size.2];
+  }
+
   fn get_fn(&mut self, name: String) -> Option<ShaderUserDefinedFunction> {
     self.fn_mapping.get(&name).map(|v| v.1.clone())
   }
@@ -1073,6 +1262,58 @@ impl ShaderAPI for ShaderAPINagaImpl {
   }
 }
 
+fn map_atomic_kind(ty: ShaderAtomicValueType) -> naga::ScalarKind {
+  match ty {
+    ShaderAtomicValueType::I32 => naga::ScalarKind::Sint,
+    ShaderAtomicValueType::U32 => naga::ScalarKind::Uint,
+  }
+}
+
+fn map_storage_format(format: TextureFormat) -> naga::StorageFormat {
+  use naga::StorageFormat as S;
+  match format {
+    TextureFormat::R8Unorm => S::R8Unorm,
+    TextureFormat::R8Snorm => S::R8Snorm,
+    TextureFormat::R8Uint => S::R8Uint,
+    TextureFormat::R8Sint => S::R8Sint,
+    TextureFormat::R16Uint => S::R16Uint,
+    TextureFormat::R16Sint => S::R16Sint,
+    TextureFormat::R16Float => S::R16Float,
+    TextureFormat::R16Unorm => S::R16Unorm,
+    TextureFormat::R16Snorm => S::R16Snorm,
+    TextureFormat::Rg8Unorm => S::Rg8Unorm,
+    TextureFormat::Rg8Snorm => S::Rg8Snorm,
+    TextureFormat::Rg8Uint => S::Rg8Uint,
+    TextureFormat::Rg8Sint => S::Rg8Sint,
+    TextureFormat::R32Uint => S::R32Uint,
+    TextureFormat::R32Sint => S::R32Sint,
+    TextureFormat::R32Float => S::R32Float,
+    TextureFormat::Rg16Uint => S::Rg16Uint,
+    TextureFormat::Rg16Sint => S::Rg16Sint,
+    TextureFormat::Rg16Float => S::Rg16Float,
+    TextureFormat::Rg16Unorm => S::Rg16Unorm,
+    TextureFormat::Rg16Snorm => S::Rg16Snorm,
+    TextureFormat::Rgba8Unorm => S::Rgba8Unorm,
+    TextureFormat::Rgba8Snorm => S::Rgba8Snorm,
+    TextureFormat::Rgba8Uint => S::Rgba8Uint,
+    TextureFormat::Rgba8Sint => S::Rgba8Sint,
+    TextureFormat::Rgb10a2Unorm => S::Rgb10a2Unorm,
+    TextureFormat::Rg11b10Float => S::Rg11b10Float,
+    TextureFormat::Rg32Uint => S::Rg32Uint,
+    TextureFormat::Rg32Sint => S::Rg32Sint,
+    TextureFormat::Rg32Float => S::Rg32Float,
+    TextureFormat::Rgba16Uint => S::Rgba16Uint,
+    TextureFormat::Rgba16Sint => S::Rgba16Sint,
+    TextureFormat::Rgba16Float => S::Rgba16Float,
+    TextureFormat::Rgba16Unorm => S::Rgba16Unorm,
+    TextureFormat::Rgba16Snorm => S::Rgba16Snorm,
+    TextureFormat::Rgba32Uint => S::Rgba32Uint,
+    TextureFormat::Rgba32Sint => S::Rgba32Sint,
+    TextureFormat::Rgba32Float => S::Rgba32Float,
+    _ => panic!("{format:?} is not a storage texture format"),
+  }
+}
+
 fn map_binary_op(o: BinaryOperator) -> naga::BinaryOperator {
   match o {
     BinaryOperator::Add => naga::BinaryOperator::Add,
@@ -1207,6 +1448,65 @@ fn gen_struct_define(
   }
 }
 
+fn runtime_array_stride(ty: &ShaderSizedValueType) -> usize {
+  let layout = StructLayoutTarget::Std430;
+  round_up(ty.align_of_self(layout), ty.size_of_self(layout))
+}
+
+fn gen_unsized_struct_define(
+  api: &mut ShaderAPINagaImpl,
+  meta: &ShaderUnSizedStructMetaInfo,
+) -> naga::TypeInner {
+  let layout = StructLayoutTarget::Std430;
+  let (array_name, array_ty) = meta.last_dynamic_array_field;
+
+  let mut current_byte_used = 0;
+  let mut members = Vec::new();
+  for (index, ShaderStructFieldMetaInfo { name, ty, .. }) in meta.sized_fields.iter().enumerate() {
+    let next_align_requirement = if index + 1 == meta.sized_fields.len() {
+      array_ty.align_of_self(layout)
+    } else {
+      meta.sized_fields[index + 1].ty.align_of_self(layout)
+    };
+
+    let field_offset = current_byte_used;
+    current_byte_used += ty.size_of_self(layout);
+    current_byte_used += align_offset(current_byte_used, next_align_requirement);
+
+    let ty = ShaderValueType::Single(ShaderValueSingleType::Sized(*ty));
+    members.push(naga::StructMember {
+      name: name.to_string().into(),
+      ty: api.register_ty_impl(ty, layout.into()),
+      binding: None,
+      offset: field_offset as u32,
+    });
+  }
+
+  let array_offset = round_up(array_ty.align_of_self(layout), current_byte_used);
+  let ty = ShaderValueType::Single(ShaderValueSingleType::Unsized(
+    ShaderUnSizedValueType::UnsizedArray(array_ty),
+  ));
+  members.push(naga::StructMember {
+    name: array_name.to_string().into(),
+    ty: api.register_ty_impl(ty, layout.into()),
+    binding: None,
+    offset: array_offset as u32,
+  });
+
+  // the dynamic array is treated as one element when computing the struct span
+  let align = meta
+    .sized_fields
+    .iter()
+    .map(|f| f.ty.align_of_self(layout))
+    .fold(array_ty.align_of_self(layout), usize::max);
+  let span = round_up(align, array_offset + runtime_array_stride(array_ty));
+
+  naga::TypeInner::Struct {
+    members,
+    span: span as u32,
+  }
+}
+
 fn match_built_in(bt: ShaderBuiltInDecorator) -> naga::BuiltIn {
   match bt {
     ShaderBuiltInDecorator::VertexIndex => naga::BuiltIn::VertexIndex,
@@ -1217,5 +1517,10 @@ fn match_built_in(bt: ShaderBuiltInDecorator) -> naga::BuiltIn {
     ShaderBuiltInDecorator::FragmentPositionIn => naga::BuiltIn::Position { invariant: false },
     ShaderBuiltInDecorator::VertexPositionOut => naga::BuiltIn::Position { invariant: false },
     ShaderBuiltInDecorator::FragDepth => naga::BuiltIn::FragDepth,
+    ShaderBuiltInDecorator::CompGlobalInvocationId => naga::BuiltIn::GlobalInvocationId,
+    ShaderBuiltInDecorator::CompLocalInvocationId => naga::BuiltIn::LocalInvocationId,
+    ShaderBuiltInDecorator::CompLocalInvocationIndex => naga::BuiltIn::LocalInvocationIndex,
+    ShaderBuiltInDecorator::CompWorkgroupId => naga::BuiltIn::WorkGroupId,
+    ShaderBuiltInDecorator::CompNumWorkgroups => naga::BuiltIn::NumWorkGroups,
   }
 }
@@ -0,0 +1,212 @@
+use crate::*;
+
+struct TestStorageInput;
+
+impl ShaderBindingProvider for TestStorageInput {
+  const SPACE: AddressSpace = AddressSpace::Storage { writeable: false };
+  type Node = [u32; 64];
+}
+
+struct TestStorageOutput;
+
+impl ShaderBindingProvider for TestStorageOutput {
+  const SPACE: AddressSpace = AddressSpace::Storage { writeable: true };
+  type Node = [ShaderAtomic<u32>; 64];
+}
+
+struct TestTexture;
+
+impl ShaderBindingProvider for TestTexture {
+  const SPACE: AddressSpace = AddressSpace::Handle;
+  type Node = ShaderTexture2D;
+}
+
+struct TestStorageTexture;
+
+impl ShaderBindingProvider for TestStorageTexture {
+  const SPACE: AddressSpace = AddressSpace::Handle;
+  type Node = ShaderStorageTexture2D<storage_formats::Rgba8Unorm>;
+}
+
+struct TestRuntimeArray;
+
+impl ShaderBindingProvider for TestRuntimeArray {
+  const SPACE: AddressSpace = AddressSpace::Storage { writeable: false };
+  type Node = [u32];
+}
+
+#[repr(C)]
+#[std430_layout]
+#[derive(Clone, Copy, ShaderStruct)]
+pub struct TestLight {
+  pub position: Vec3<f32>,
+  pub intensity: f32,
+}
+
+#[repr(C)]
+#[derive(ShaderStruct)]
+pub struct TestLightList {
+  pub count: u32,
+  pub lights: [TestLight],
+}
+
+struct TestLightListStorage;
+
+impl ShaderBindingProvider for TestLightListStorage {
+  const SPACE: AddressSpace = AddressSpace::Storage { writeable: true };
+  type Node = TestLightList;
+}
+
+fn build_and_validate(builder: ShaderComputePipelineBuilder) -> (naga::Module, String) {
+  let result = builder.build().unwrap();
+  let module = *result.shader.1.downcast::<naga::Module>().unwrap();
+
+  let info = naga::valid::Validator::new(
+    naga::valid::ValidationFlags::all(),
+    naga::valid::Capabilities::all(),
+  )
+  .validate(&module)
+  .unwrap();
+
+  let wgsl =
+    naga::back::wgsl::write_string(&module, &info, naga::back::wgsl::WriterFlags::empty()).unwrap();
+  (module, wgsl)
+}
+
+#[test]
+fn compute_shader_with_shared_memory_and_barrier() {
+  let api = ShaderAPINagaImpl::new(ShaderStages::Compute);
+  let mut builder = ShaderComputePipelineBuilder::new(Box::new(api));
+  builder.config_work_group_size((64, 1, 1));
+
+  let input = builder.binding::<TestStorageInput>();
+  let shared = builder.define_workgroup_shared_var::<[u32; 64]>();
+
+  let local_index = builder.local_invocation_index();
+  let global_id = builder.global_invocation_id();
+  let value = input.index(global_id.x() % val(64)).load() + builder.workgroup_id().x();
+
+  shared.index(local_index).store(value);
+  workgroup_barrier();
+
+  let neighbor = shared.index((local_index + val(1)) % val(64)).load();
+  let sum = neighbor + builder.local_invocation_id().y() + builder.num_workgroups().z();
+  shared.index(local_index).store(sum);
+  storage_barrier();
+
+  let (module, wgsl) = build_and_validate(builder);
+
+  assert_eq!(module.entry_points[0].stage, naga::ShaderStage::Compute);
+  assert_eq!(module.entry_points[0].workgroup_size, [64, 1, 1]);
+  assert!(wgsl.contains("var<workgroup>"));
+  assert!(wgsl.contains("workgroupBarrier()"));
+  assert!(wgsl.contains("storageBarrier()"));
+}
+
+#[test]
+fn compute_shader_default_workgroup_size() {
+  let api = ShaderAPINagaImpl::new(ShaderStages::Compute);
+  let builder = ShaderComputePipelineBuilder::new(Box::new(api));
+  let _ = builder.global_invocation_id();
+
+  let (module, _) = build_and_validate(builder);
+  assert_eq!(module.entry_points[0].workgroup_size, [256, 1, 1]);
+}
+
+#[test]
+fn compute_shader_with_atomics() {
+  let api = ShaderAPINagaImpl::new(ShaderStages::Compute);
+  let mut builder = ShaderComputePipelineBuilder::new(Box::new(api));
+  builder.config_work_group_size((64, 1, 1));
+
+  let output = builder.binding::<TestStorageOutput>();
+  let counter = builder.define_workgroup_shared_var::<ShaderAtomic<i32>>();
+
+  let local_index = builder.local_invocation_index();
+  counter.atomic_store(val(0));
+  workgroup_barrier();
+
+  let order = counter.atomic_add(val(1));
+  counter.atomic_max(order);
+  counter.atomic_min(order);
+
+  let slot = output.index(local_index);
+  slot.atomic_sub(val(1));
+  slot.atomic_and(val(0xff));
+  slot.atomic_or(val(1));
+  slot.atomic_xor(val(2));
+  let old = slot.atomic_exchange(slot.atomic_load());
+  let (_, exchanged) = slot.atomic_compare_exchange_weak(old, val(3));
+  exchanged.select(val(1_u32), val(0_u32));
+
+  let (module, wgsl) = build_and_validate(builder);
+
+  assert!(matches!(
+    module.global_variables.iter().find_map(|(_, v)| v.binding.as_ref().map(|_| v.space)),
+    Some(naga::AddressSpace::Storage { access }) if access.contains(naga::StorageAccess::STORE)
+  ));
+  assert!(wgsl.contains("var<storage, read_write>"));
+  assert!(wgsl.contains("atomic<i32>"));
+  assert!(wgsl.contains("atomicAdd("));
+  assert!(wgsl.contains("atomicCompareExchangeWeak("));
+}
+
+#[test]
+fn compute_shader_with_texel_load_and_storage_texture() {
+  let api = ShaderAPINagaImpl::new(ShaderStages::Compute);
+  let mut builder = ShaderComputePipelineBuilder::new(Box::new(api));
+  builder.config_work_group_size((8, 8, 1));
+
+  let input = builder.binding::<TestTexture>();
+  let target = builder.binding::<TestStorageTexture>();
+
+  let position = builder.global_invocation_id().xy();
+  let texel = input.load_texel(position, val(0));
+  target.write_texel(position, texel);
+
+  let (_, wgsl) = build_and_validate(builder);
+
+  assert!(wgsl.contains("texture_storage_2d<rgba8unorm,write>"));
+  assert!(wgsl.contains("textureLoad("));
+  assert!(wgsl.contains("textureStore("));
+}
+
+#[test]
+fn compute_shader_with_runtime_sized_array_and_unsized_struct() {
+  let api = ShaderAPINagaImpl::new(ShaderStages::Compute);
+  let mut builder = ShaderComputePipelineBuilder::new(Box::new(api));
+
+  let input = builder.binding::<TestRuntimeArray>();
+  let list = builder.binding::<TestLightListStorage>();
+
+  let id = builder.global_invocation_id().x();
+  let value = input.index(id % input.array_length()).load();
+
+  let lights = TestLightList::lights(list);
+  TestLightList::count(list).store(lights.array_length());
+  let light = lights.index(id).load().expand();
+  let intensity = light.intensity * value.into_f32();
+  lights.index(id).store(
+    ENode::<TestLight> {
+      position: light.position,
+      intensity,
+    }
+    .construct(),
+  );
+
+  let (module, wgsl) = build_and_validate(builder);
+
+  let list_ty = module
+    .types
+    .iter()
+    .find(|(_, ty)| ty.name.as_deref() == Some("TestLightList"))
+    .unwrap()
+    .1;
+  assert!(matches!(
+    &list_ty.inner,
+    naga::TypeInner::Struct { members, .. } if members.len() == 2 && members[1].offset == 16
+  ));
+  assert!(wgsl.contains("array<u32>"));
+  assert!(wgsl.contains("array<TestLight>"));
+  assert!(wgsl.contains("arrayLength("));
+}
@@ -27,6 +27,9 @@ pub fn derive_vertex(input: TokenStream) -> TokenStream {
 ///
 /// Implementation will add static struct meta info for reflection
 /// and define a shader api instance type and convert methods for rendiation_shader_api usage.
+///
+/// If the last field is a slice, the struct is treated as the unsized struct which could only be
+/// used in the storage buffer, and the fields are accessed through the pointer.
 #[proc_macro_derive(ShaderStruct)]
 pub fn derive_shader_struct(input: TokenStream) -> TokenStream {
   let input = parse_macro_input!(input as syn::DeriveInput);
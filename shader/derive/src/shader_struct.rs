@@ -15,6 +15,10 @@ pub fn derive_shader_struct_impl(input: &syn::DeriveInput) -> proc_macro2::Token
 }
 
 fn derive_shader_struct(s: &StructInfo) -> proc_macro2::TokenStream {
+  if let Some((_, syn::Type::Slice(_))) = s.fields_info.last() {
+    return derive_unsized_shader_struct(s);
+  }
+
   let struct_name = &s.struct_name;
   let shader_api_instance_name = format_ident!("{}ShaderAPIInstance", struct_name);
 
@@ -129,3 +133,84 @@ fn derive_shader_struct(s: &StructInfo) -> proc_macro2::TokenStream {
 
   }
 }
+
+/// The struct ends with a runtime sized array, it could only be accessed through the pointer.
+fn derive_unsized_shader_struct(s: &StructInfo) -> proc_macro2::TokenStream {
+  let struct_name = &s.struct_name;
+
+  let struct_name_str = format!("{struct_name}");
+  let meta_info_name = gen_struct_meta_name(struct_name_str.as_str());
+
+  let (sized_fields, last) = s.fields_info.split_at(s.fields_info.len() - 1);
+  let (array_field_name, array_ty) = &last[0];
+  let array_field_str = format!("{array_field_name}");
+  let array_element_ty = match array_ty {
+    syn::Type::Slice(slice) => &slice.elem,
+    _ => unreachable!(),
+  };
+
+  let meta_info_fields = sized_fields.iter().map(|(field_name, ty)| {
+    let field_str = format!("{field_name}");
+    quote! {
+     rendiation_shader_api::ShaderStructFieldMetaInfo {
+       name: #field_str,
+       ty: <<#ty as rendiation_shader_api::ShaderFieldTypeMapper>::ShaderType as rendiation_shader_api::ShaderSizedValueNodeType>::MEMBER_TYPE,
+       ty_deco: None,
+     },
+    }
+  });
+
+  let field_methods = sized_fields
+    .iter()
+    .enumerate()
+    .map(|(idx, (field_name, ty))| {
+      quote! {
+        pub fn #field_name<P: rendiation_shader_api::ShaderStructPtrNode<Pointee = Self>>(
+          ptr: P,
+        ) -> P::FieldPtr<<#ty as rendiation_shader_api::ShaderFieldTypeMapper>::ShaderType> {
+          unsafe { ptr.field_ptr(#idx) }
+        }
+      }
+    });
+  let array_idx = sized_fields.len();
+
+  quote! {
+    #[allow(non_upper_case_globals)]
+    pub const #meta_info_name: &rendiation_shader_api::ShaderUnSizedStructMetaInfo =
+        &rendiation_shader_api::ShaderUnSizedStructMetaInfo {
+          name: #struct_name_str,
+          sized_fields: &[
+            #(#meta_info_fields)*
+          ],
+          last_dynamic_array_field: (
+            #array_field_str,
+            &<<#array_element_ty as rendiation_shader_api::ShaderFieldTypeMapper>::ShaderType as rendiation_shader_api::ShaderSizedValueNodeType>::MEMBER_TYPE,
+          ),
+        };
+
+    impl rendiation_shader_api::ShaderNodeType for #struct_name {
+      const TYPE: rendiation_shader_api::ShaderValueType =
+        rendiation_shader_api::ShaderValueType::Single(<Self as rendiation_shader_api::ShaderNodeSingleType>::SINGLE_TYPE);
+    }
+
+    impl rendiation_shader_api::ShaderNodeSingleType for #struct_name {
+      const SINGLE_TYPE: rendiation_shader_api::ShaderValueSingleType =
+        rendiation_shader_api::ShaderValueSingleType::Unsized(<Self as rendiation_shader_api::ShaderUnsizedValueNodeType>::UNSIZED_TYPE);
+    }
+
+    impl rendiation_shader_api::ShaderUnsizedValueNodeType for #struct_name {
+      const UNSIZED_TYPE: rendiation_shader_api::ShaderUnSizedValueType =
+        rendiation_shader_api::ShaderUnSizedValueType::UnsizedStruct(&#meta_info_name);
+    }
+
+    impl #struct_name {
+      #(#field_methods)*
+
+      pub fn #array_field_name<P: rendiation_shader_api::ShaderStructPtrNode<Pointee = Self>>(
+        ptr: P,
+      ) -> P::FieldPtr<[<#array_element_ty as rendiation_shader_api::ShaderFieldTypeMapper>::ShaderType]> {
+        unsafe { ptr.field_ptr(#array_idx) }
+      }
+    }
+  }
+}